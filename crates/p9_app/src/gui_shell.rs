@@ -15,7 +15,7 @@ use crate::ui_shell::{
 };
 use crate::ui::{UiAction, UiController, UiError, UiScreen, UiSnapshot};
 use p9_core::engine::{Engine, EngineCommand};
-use p9_core::model::{ProjectData, Step, CHAIN_ROW_COUNT, PHRASE_STEP_COUNT, SONG_ROW_COUNT};
+use p9_core::model::{ProjectData, Step, CHAIN_ROW_COUNT};
 use p9_rt::audio::{AudioBackend, NoopAudioBackend};
 use p9_rt::midi::NoopMidiOutput;
 use p9_storage::project::ProjectEnvelope;
@@ -33,6 +33,7 @@ const GUI_HISTORY_LIMIT: usize = 128;
 const SONG_VIEW_ROWS: usize = 8;
 const CHAIN_VIEW_ROWS: usize = 8;
 const RECENT_PROJECT_LIMIT: usize = 6;
+const DEFAULT_SLICE_SENSITIVITY: u8 = 64;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum LoopControl {
@@ -57,6 +58,15 @@ struct SessionHardeningState {
     autosave_status: String,
 }
 
+/// One `/action` request: the command and its query parameters.
+#[derive(Clone, Copy, Debug)]
+struct ActionRequest<'a> {
+    command: &'a str,
+    query: Option<&'a str>,
+    path: Option<&'a str>,
+    force: bool,
+}
+
 #[derive(Clone, Debug)]
 struct ActionOutcome {
     status: String,
@@ -104,6 +114,12 @@ pub fn run_web_shell(
     let mut audio = NoopAudioBackend::default();
     audio.start();
     let mut midi_output = NoopMidiOutput::default();
+    // Enough audio callbacks to cover each sleep, so playback keeps the song tempo.
+    let audio_metrics = audio.metrics();
+    let blocks_per_sleep = (TICK_SLEEP_MS * audio_metrics.sample_rate_hz as u64
+        / 1_000
+        / audio_metrics.buffer_size_frames.max(1) as u64)
+        .max(1);
     let hardening = update_session_hardening(
         engine,
         runtime,
@@ -135,7 +151,9 @@ pub fn run_web_shell(
             Err(err) => return Err(err),
         }
 
-        let _ = runtime.run_tick_safe(engine, &mut audio, &mut midi_output);
+        for _ in 0..blocks_per_sleep {
+            let _ = runtime.run_block_safe(engine, &mut audio, &mut midi_output);
+        }
         let hardening = update_session_hardening(
             engine,
             runtime,
@@ -205,10 +223,12 @@ fn handle_connection(
 
             let outcome = if let Some(name) = cmd {
                 execute_action_command(
-                    name,
-                    query,
-                    path.as_deref(),
-                    force,
+                    ActionRequest {
+                        command: name,
+                        query,
+                        path: path.as_deref(),
+                        force,
+                    },
                     ui,
                     engine,
                     runtime,
@@ -248,16 +268,20 @@ fn handle_connection(
 }
 
 fn execute_action_command(
-    command: &str,
-    query: Option<&str>,
-    path: Option<&str>,
-    force: bool,
+    request: ActionRequest<'_>,
     ui: &mut UiController,
     engine: &mut Engine,
     runtime: &mut RuntimeCoordinator,
     session_state: &mut GuiSessionState,
     dirty_tracker: &mut DirtyStateTracker,
 ) -> ActionOutcome {
+    let ActionRequest {
+        command,
+        query,
+        path,
+        force,
+    } = request;
+
     if requires_dirty_confirmation(command) && session_state.dirty && !force {
         return ActionOutcome {
             status: format!("warn: unsaved changes; confirm '{command}' with force=1"),
@@ -276,6 +300,7 @@ fn execute_action_command(
             engine.replace_project(ProjectData::new("p9_tracker new song"));
             *ui = UiController::default();
             runtime.enqueue_commands([RuntimeCommand::Stop, RuntimeCommand::Rewind]);
+            runtime.set_sample_dir(None);
             session_state.current_project_path = None;
             session_state.dirty = false;
             session_state.autosave_status = String::from("clean");
@@ -301,6 +326,16 @@ fn execute_action_command(
                 Ok(()) => {
                     *ui = UiController::default();
                     runtime.enqueue_commands([RuntimeCommand::Stop, RuntimeCommand::Rewind]);
+                    runtime.set_sample_dir(target_path.parent().map(Path::to_path_buf));
+                    let failed_samples = runtime.sync_samples(engine);
+                    let status = if failed_samples.is_empty() {
+                        String::from("info: project opened")
+                    } else {
+                        format!(
+                            "warn: project opened; sample files failed to load for instruments \
+                             {failed_samples:?}"
+                        )
+                    };
                     dirty_tracker.mark_saved(engine);
                     session_state.dirty = false;
                     session_state.autosave_status = String::from("loaded");
@@ -309,7 +344,7 @@ fn execute_action_command(
                     session_state.current_project_path = Some(target_path.clone());
                     register_recent_path(session_state, target_path);
                     ActionOutcome {
-                        status: String::from("info: project opened"),
+                        status,
                         quit: false,
                         confirm_required: false,
                     }
@@ -349,6 +384,8 @@ fn execute_action_command(
 
             match save_project_to_path(&target_path, engine) {
                 Ok(()) => {
+                    // Relative sample paths now resolve against the file's new folder.
+                    runtime.set_sample_dir(target_path.parent().map(Path::to_path_buf));
                     dirty_tracker.mark_saved(engine);
                     session_state.dirty = false;
                     session_state.current_project_path = Some(target_path.clone());
@@ -603,6 +640,9 @@ fn apply_gui_command_with_query(
                 ui, engine, runtime, history,
             );
         }
+        "edit_detect_slices" => {
+            return apply_detect_slices(query, ui, engine, runtime, history);
+        }
         "edit_write_step" => {
             let custom_edit = query_flag(query, "clear")
                 || query_value(query, "note").is_some()
@@ -686,6 +726,7 @@ fn apply_gui_command_with_query(
 
     let action = match command {
         "toggle_play" => Some(UiAction::TogglePlayStop),
+        "play_cursor" => Some(UiAction::PlayFromCursor),
         "rewind" => Some(UiAction::RewindTransport),
         "screen_next" => Some(UiAction::NextScreen),
         "screen_prev" => Some(UiAction::PrevScreen),
@@ -753,10 +794,11 @@ fn apply_phrase_step_shift(
     runtime: &mut RuntimeCoordinator,
 ) -> String {
     let snapshot = ui.snapshot(engine, runtime);
+    let length = snapshot.selected_phrase_length;
     let next = if delta >= 0 {
-        (snapshot.selected_step + 1) % PHRASE_STEP_COUNT
+        (snapshot.selected_step + 1) % length
     } else {
-        (snapshot.selected_step + PHRASE_STEP_COUNT - 1) % PHRASE_STEP_COUNT
+        (snapshot.selected_step + length - 1) % length
     };
 
     match ui.handle_action(UiAction::SelectStep(next), engine, runtime) {
@@ -828,7 +870,7 @@ fn apply_power_duplicate(
         Err(status) => return status,
     };
     let target_step = selection.end_step + 1;
-    if target_step >= PHRASE_STEP_COUNT {
+    if target_step >= snapshot.selected_phrase_length {
         return String::from("warn: duplicate target out of range; move selection earlier");
     }
 
@@ -1150,6 +1192,48 @@ fn apply_chain_clone_prev(
     )
 }
 
+fn apply_detect_slices(
+    query: Option<&str>,
+    ui: &mut UiController,
+    engine: &mut Engine,
+    runtime: &mut RuntimeCoordinator,
+    history: &mut ProjectHistory,
+) -> String {
+    let snapshot = ui.snapshot(engine, runtime);
+    let instrument_id = query_value(query, "instrument")
+        .and_then(parse_u8_field)
+        .unwrap_or(snapshot.focused_track as u8);
+    let sensitivity = query_value(query, "sensitivity")
+        .and_then(parse_u8_field)
+        .unwrap_or(DEFAULT_SLICE_SENSITIVITY);
+
+    let before = engine.snapshot().clone();
+    if let Err(err) = ui.handle_action(
+        UiAction::DetectSampleSlices {
+            instrument_id,
+            sensitivity,
+        },
+        engine,
+        runtime,
+    ) {
+        return format!(
+            "error: action 'edit_detect_slices' failed: {}",
+            ui_error_label(err)
+        );
+    }
+
+    history.record_change(before);
+    let slices = engine.snapshot().instruments[&instrument_id]
+        .sample
+        .as_ref()
+        .and_then(|sample| sample.slicing.as_ref())
+        .map_or(1, |slicing| slicing.slice_count());
+    format!(
+        "info: slices -> ins {} cut into {} slices at sensitivity {}",
+        instrument_id, slices, sensitivity
+    )
+}
+
 fn normalize_status(message: String) -> String {
     let normalized =
         if message.starts_with("info:") || message.starts_with("warn:") || message.starts_with("error:")
@@ -1184,9 +1268,13 @@ fn polish_status_for_gui(message: String) -> String {
 
 fn cursor_down_action(snapshot: UiSnapshot) -> UiAction {
     match snapshot.screen {
-        UiScreen::Song => UiAction::SelectSongRow((snapshot.selected_song_row + 1) % SONG_ROW_COUNT),
+        UiScreen::Song => {
+            UiAction::SelectSongRow((snapshot.selected_song_row + 1) % snapshot.song_length)
+        }
         UiScreen::Chain => UiAction::SelectChainRow((snapshot.selected_chain_row + 1) % CHAIN_ROW_COUNT),
-        UiScreen::Phrase => UiAction::SelectStep((snapshot.selected_step + 4) % PHRASE_STEP_COUNT),
+        UiScreen::Phrase => {
+            UiAction::SelectStep((snapshot.selected_step + 4) % snapshot.selected_phrase_length)
+        }
         UiScreen::Mixer => UiAction::FocusTrackRight,
     }
 }
@@ -1195,7 +1283,7 @@ fn cursor_up_action(snapshot: UiSnapshot) -> UiAction {
     match snapshot.screen {
         UiScreen::Song => {
             let row = if snapshot.selected_song_row == 0 {
-                SONG_ROW_COUNT - 1
+                snapshot.song_length - 1
             } else {
                 snapshot.selected_song_row - 1
             };
@@ -1210,7 +1298,10 @@ fn cursor_up_action(snapshot: UiSnapshot) -> UiAction {
             UiAction::SelectChainRow(row)
         }
         UiScreen::Phrase => {
-            UiAction::SelectStep((snapshot.selected_step + PHRASE_STEP_COUNT - 4) % PHRASE_STEP_COUNT)
+            // Wraps to the phrase end, including phrases shorter than a row of four.
+            let length = snapshot.selected_phrase_length as isize;
+            let step = (snapshot.selected_step as isize - 4).rem_euclid(length);
+            UiAction::SelectStep(step as usize)
         }
         UiScreen::Mixer => UiAction::FocusTrackLeft,
     }
@@ -1247,7 +1338,7 @@ fn build_state_json(
         screen_label(ui_snapshot.screen),
         transport.tick,
         transport.is_playing,
-        project.song.tempo,
+        runtime.effective_tempo(engine),
         ui_snapshot.focused_track,
         ui_snapshot.selected_song_row,
         ui_snapshot.selected_chain_row,
@@ -1300,8 +1391,8 @@ fn update_session_hardening(
                 let _ = clear_dirty_session_flag(dirty_flag_path);
             }
         }
-        Err(_) => {
-            autosave_status = String::from("error");
+        Err(err) => {
+            autosave_status = format!("error ({err})");
         }
     }
 
@@ -1353,8 +1444,9 @@ fn build_editor_json(project: &ProjectData, snapshot: UiSnapshot, session_state:
 }
 
 fn build_song_view_json(project: &ProjectData, snapshot: UiSnapshot) -> String {
-    let window_start = centered_window_start(snapshot.selected_song_row, SONG_ROW_COUNT, SONG_VIEW_ROWS);
-    let window_end = window_start + SONG_VIEW_ROWS - 1;
+    let window_start =
+        centered_window_start(snapshot.selected_song_row, snapshot.song_length, SONG_VIEW_ROWS);
+    let window_end = (window_start + SONG_VIEW_ROWS).min(snapshot.song_length) - 1;
     let mut rows = String::new();
 
     if let Some(track) = project.song.tracks.get(snapshot.focused_track) {
@@ -1374,8 +1466,8 @@ fn build_song_view_json(project: &ProjectData, snapshot: UiSnapshot) -> String {
     }
 
     format!(
-        "{{\"window_start\":{},\"window_end\":{},\"rows\":[{}]}}",
-        window_start, window_end, rows
+        "{{\"length\":{},\"track_count\":{},\"window_start\":{},\"window_end\":{},\"rows\":[{}]}}",
+        snapshot.song_length, snapshot.track_count, window_start, window_end, rows
     )
 }
 
@@ -1424,7 +1516,7 @@ fn build_phrase_view_json(project: &ProjectData, snapshot: UiSnapshot) -> String
     let phrase = project.phrases.get(&phrase_id);
     let mut rows = String::new();
 
-    for step_index in 0..PHRASE_STEP_COUNT {
+    for step_index in 0..snapshot.selected_phrase_length {
         if !rows.is_empty() {
             rows.push(',');
         }
@@ -1457,10 +1549,11 @@ fn build_phrase_view_json(project: &ProjectData, snapshot: UiSnapshot) -> String
     }
 
     format!(
-        "{{\"selected_phrase_id\":{},\"bound_phrase_id\":{},\"exists\":{},\"rows\":[{}]}}",
+        "{{\"selected_phrase_id\":{},\"bound_phrase_id\":{},\"exists\":{},\"length\":{},\"rows\":[{}]}}",
         phrase_id,
         option_u8_json(bound_phrase),
         phrase.is_some(),
+        snapshot.selected_phrase_length,
         rows,
     )
 }
@@ -1473,9 +1566,10 @@ fn build_mixer_view_json(project: &ProjectData, snapshot: UiSnapshot) -> String
             tracks.push(',');
         }
         tracks.push_str(&format!(
-            "{{\"track\":{},\"level\":{},\"focused\":{}}}",
+            "{{\"track\":{},\"level\":{},\"pan\":{},\"focused\":{}}}",
             track_index,
             level,
+            project.mixer.track_pans[track_index],
             track_index == snapshot.focused_track,
         ));
     }
@@ -1797,13 +1891,14 @@ fn json_escape(value: &str) -> String {
         .replace('\n', "\\n")
 }
 
-fn ui_error_label(error: UiError) -> &'static str {
+fn ui_error_label(error: UiError) -> String {
     match error {
-        UiError::Engine(_) => "engine",
-        UiError::InvalidTrack(_) => "invalid-track",
-        UiError::InvalidSongRow(_) => "invalid-song-row",
-        UiError::InvalidChainRow(_) => "invalid-chain-row",
-        UiError::InvalidStep(_) => "invalid-step",
+        UiError::Engine(err) => format!("engine {err:?}"),
+        UiError::InvalidTrack(track) => format!("invalid-track {track}"),
+        UiError::InvalidSongRow(row) => format!("invalid-song-row {row}"),
+        UiError::InvalidChainRow(row) => format!("invalid-chain-row {row}"),
+        UiError::InvalidStep(step) => format!("invalid-step {step}"),
+        UiError::SampleNotLoaded(instrument) => format!("sample-not-loaded {instrument}"),
     }
 }
 
@@ -1971,6 +2066,7 @@ footer { margin-top: 12px; color: var(--muted); font-size: 0.85rem; }
       <h3>Transport</h3>
       <div class="controls">
         <button onclick="sendCmd('play')">Play</button>
+        <button onclick="sendCmd('play_cursor')">Play Cursor</button>
         <button onclick="sendCmd('stop')">Stop</button>
         <button onclick="sendCmd('toggle_play')">Toggle</button>
         <button onclick="sendCmd('rewind')">Rewind</button>
@@ -2002,7 +2098,7 @@ footer { margin-top: 12px; color: var(--muted); font-size: 0.85rem; }
         <button onclick="sendCmd('step_next_fine')">Step +1 (PgDn)</button>
       </div>
       <div class="small" style="margin-top:10px">
-        Keys: Space/T toggle, G play, S stop, R rewind, arrows or H/J/K/L move, N/P switch screen, 1/2/3/4 direct screens, / quick editor focus, PgUp/PgDn step -/+1, C/F/I/E edit flow, A/Z select, W/V copy-paste, Shift+V force paste, D duplicate, B fill, M clear block, [/] transpose, ,/. rotate, U/Y undo-redo, Ctrl+S save, Q quit.
+        Keys: Space/T toggle, G play, Shift+G play from cursor, S stop, R rewind, arrows or H/J/K/L move, N/P switch screen, 1/2/3/4 direct screens, / quick editor focus, PgUp/PgDn step -/+1, C/F/I/E edit flow, A/Z select, W/V copy-paste, Shift+V force paste, D duplicate, B fill, M clear block, [/] transpose, ,/. rotate, U/Y undo-redo, Ctrl+S save, Q quit.
       </div>
     </div>
 
@@ -2078,6 +2174,7 @@ footer { margin-top: 12px; color: var(--muted); font-size: 0.85rem; }
     <div class="controls" style="margin-top:8px">
       <button onclick="sendCmd('edit_song_clone_prev')">Song Clone Prev Row</button>
       <button onclick="sendCmd('edit_chain_clone_prev')">Chain Clone Prev Row</button>
+      <button onclick="detectSlices()">Detect Slices</button>
     </div>
     <div class="controls" style="margin-top:8px">
       <input id="edit-note" type="text" placeholder="note 0..127 (empty=seeded)" />
@@ -2219,7 +2316,7 @@ function setActiveScreen(screen) {
 }
 
 function renderSong(view) {
-  document.getElementById('song-meta').textContent = `rows ${view.window_start}..${view.window_end}`;
+  document.getElementById('song-meta').textContent = `rows ${view.window_start}..${view.window_end} of ${view.length}`;
   const body = view.rows.map((row) => {
     const selected = row.selected ? 'selected' : '';
     return `<tr class="${selected}"><td>${pad2(row.row)}</td><td>${fmtOptional(row.chain_id, true)}</td></tr>`;
@@ -2358,6 +2455,12 @@ function editWriteStep() {
   });
 }
 
+function detectSlices() {
+  sendCmd('edit_detect_slices', {
+    instrument: readOptionalNumberInput('edit-instrument'),
+  });
+}
+
 function readOptionalSignedInput(id) {
   const value = document.getElementById(id).value.trim();
   if (!value) {
@@ -2508,6 +2611,12 @@ function initKeyboardRouting() {
       return;
     }
 
+    if (event.shiftKey && event.code === 'KeyG') {
+      event.preventDefault();
+      sendCmd('play_cursor');
+      return;
+    }
+
     const cmd = keyMap[event.code];
     if (!cmd) {
       return;
@@ -2537,9 +2646,9 @@ refreshState();
 #[cfg(test)]
 mod tests {
     use super::{
-        apply_gui_command, apply_gui_command_with_query, build_state_json, execute_action_command,
-        parse_request_line, query_value, split_path_and_query, GuiSessionState, ProjectHistory,
-        ShellEditState, GUI_HISTORY_LIMIT,
+        apply_gui_command, apply_gui_command_with_query, build_state_json, cursor_up_action,
+        execute_action_command, parse_request_line, query_value, split_path_and_query,
+        ActionRequest, GuiSessionState, ProjectHistory, ShellEditState, GUI_HISTORY_LIMIT,
     };
     use crate::hardening::{DirtyStateTracker, RecoveryStatus};
     use crate::runtime::RuntimeCoordinator;
@@ -2572,6 +2681,23 @@ mod tests {
         path
     }
 
+    #[test]
+    fn cursor_up_wraps_within_the_phrase_length() {
+        let engine = Engine::new("cursor");
+        let runtime = RuntimeCoordinator::new(24);
+        let mut snapshot = UiController::default().snapshot(&engine, &runtime);
+        snapshot.screen = UiScreen::Phrase;
+
+        for (length, step, expected) in [(16, 6, 2), (16, 1, 13), (6, 2, 4), (3, 1, 0), (1, 0, 0)] {
+            snapshot.selected_phrase_length = length;
+            snapshot.selected_step = step;
+            assert!(matches!(
+                cursor_up_action(snapshot),
+                UiAction::SelectStep(selected) if selected == expected
+            ));
+        }
+    }
+
     #[test]
     fn parse_request_line_extracts_method_and_target() {
         let line = "GET /state HTTP/1.1";
@@ -2617,6 +2743,10 @@ mod tests {
         assert!(play.contains("queued"));
         assert!(stop.contains("queued"));
         assert_eq!(runtime.snapshot().queued_commands, 2);
+
+        let from_cursor = apply_gui_command("play_cursor", &mut ui, &mut engine, &mut runtime);
+        assert!(from_cursor.starts_with("info:"));
+        assert_eq!(runtime.snapshot().queued_commands, 4);
     }
 
     #[test]
@@ -2724,10 +2854,10 @@ mod tests {
                 .snapshot()
                 .song
                 .tracks
-                .get(0)
+                .first()
                 .unwrap()
                 .song_rows
-                .get(0)
+                .first()
                 .copied()
                 .flatten(),
             Some(0)
@@ -2737,7 +2867,7 @@ mod tests {
                 .snapshot()
                 .chains
                 .get(&0)
-                .and_then(|chain| chain.rows.get(0))
+                .and_then(|chain| chain.rows.first())
                 .and_then(|row| row.phrase_id),
             Some(0)
         );
@@ -3107,7 +3237,7 @@ mod tests {
                 .snapshot()
                 .song
                 .tracks
-                .get(0)
+                .first()
                 .unwrap()
                 .song_rows
                 .get(1)
@@ -3147,6 +3277,23 @@ mod tests {
         assert_eq!(chain.rows[1].transpose, 3);
     }
 
+    #[test]
+    fn edit_detect_slices_reports_instruments_without_a_loaded_sample() {
+        let mut ui = UiController::default();
+        let mut engine = Engine::new("gui");
+        let mut runtime = RuntimeCoordinator::new(24);
+
+        let _ = apply_gui_command("edit_ensure_instrument", &mut ui, &mut engine, &mut runtime);
+        let before = engine.snapshot().instruments[&0].clone();
+        let error = apply_gui_command("edit_detect_slices", &mut ui, &mut engine, &mut runtime);
+
+        assert_eq!(
+            error,
+            "error: action 'edit_detect_slices' failed: sample-not-loaded 0"
+        );
+        assert_eq!(engine.snapshot().instruments[&0].sample, before.sample);
+    }
+
     #[test]
     fn edit_write_step_warns_when_bind_context_missing() {
         let mut ui = UiController::default();
@@ -3169,10 +3316,12 @@ mod tests {
         session.dirty = true;
 
         let outcome = execute_action_command(
-            "session_new",
-            None,
-            None,
-            false,
+            ActionRequest {
+                command: "session_new",
+                query: None,
+                path: None,
+                force: false,
+            },
             &mut ui,
             &mut engine,
             &mut runtime,
@@ -3214,10 +3363,12 @@ mod tests {
         session.dirty = true;
 
         let save_outcome = execute_action_command(
-            "session_save_as",
-            None,
-            path.to_str(),
-            false,
+            ActionRequest {
+                command: "session_save_as",
+                query: None,
+                path: path.to_str(),
+                force: false,
+            },
             &mut ui,
             &mut engine,
             &mut runtime,
@@ -3228,12 +3379,15 @@ mod tests {
         assert!(!session.dirty);
         assert_eq!(session.current_project_path.as_deref(), Some(path.as_path()));
         assert_eq!(session.recent_project_paths.first().map(PathBuf::as_path), Some(path.as_path()));
+        assert_eq!(runtime.sample_dir(), path.parent());
 
         let new_outcome = execute_action_command(
-            "session_new",
-            None,
-            None,
-            true,
+            ActionRequest {
+                command: "session_new",
+                query: None,
+                path: None,
+                force: true,
+            },
             &mut ui,
             &mut engine,
             &mut runtime,
@@ -3242,12 +3396,15 @@ mod tests {
         );
         assert!(new_outcome.status.starts_with("info:"));
         assert!(engine.snapshot().phrases.is_empty());
+        assert_eq!(runtime.sample_dir(), None);
 
         let open_outcome = execute_action_command(
-            "session_open",
-            None,
-            path.to_str(),
-            false,
+            ActionRequest {
+                command: "session_open",
+                query: None,
+                path: path.to_str(),
+                force: false,
+            },
             &mut ui,
             &mut engine,
             &mut runtime,
@@ -3263,6 +3420,7 @@ mod tests {
                 .and_then(|phrase| phrase.steps[0].note),
             Some(62)
         );
+        assert_eq!(runtime.sample_dir(), path.parent());
 
         let _ = fs::remove_file(path);
     }
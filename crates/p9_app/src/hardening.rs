@@ -1,3 +1,4 @@
+use std::fmt;
 use std::fs;
 use std::io;
 use std::io::ErrorKind;
@@ -34,6 +35,14 @@ pub enum AutosaveError {
     Io(io::Error),
 }
 
+impl fmt::Display for AutosaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "autosave io: {err}"),
+        }
+    }
+}
+
 impl From<io::Error> for AutosaveError {
     fn from(value: io::Error) -> Self {
         Self::Io(value)
@@ -182,6 +191,7 @@ mod tests {
         DirtyStateTracker, RecoveryStatus,
     };
     use crate::runtime::{SyncMode, TransportSnapshot};
+    use p9_core::scheduler::LaunchQuantize;
     use p9_core::engine::{Engine, EngineCommand};
     use p9_storage::project::ProjectEnvelope;
     use std::fs;
@@ -196,6 +206,9 @@ mod tests {
             queued_commands: 0,
             processed_commands: 0,
             midi_messages_ingested_total: 0,
+            loop_range: None,
+            live_mode: false,
+            launch_quantize: LaunchQuantize::Chain,
         }
     }
 
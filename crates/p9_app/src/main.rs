@@ -4,6 +4,8 @@ mod runtime;
 mod ui;
 mod ui_shell;
 
+use std::io::{self, IsTerminal};
+
 use hardening::{AutosaveManager, AutosavePolicy};
 use p9_core::engine::{Engine, EngineCommand};
 use p9_core::model::{FxCommand, Groove, Instrument, InstrumentType, Scale, Table};
@@ -208,6 +210,10 @@ fn main() {
     let mut started_audio = start_with_noop_fallback(build_preferred_audio_backend(true));
     let audio_backend_name = started_audio.backend().backend_name();
     let audio_used_fallback = started_audio.used_fallback;
+    let failed_samples = runtime.sync_samples(&engine);
+    if !failed_samples.is_empty() {
+        eprintln!("p9_tracker sample files failed to load for instruments {failed_samples:?}");
+    }
 
     let mut midi_input = BufferedMidiInput::default();
     let mut midi_output = BufferedMidiOutput::default();
@@ -243,6 +249,7 @@ fn main() {
         last_audio_metrics = AudioMetrics {
             sample_rate_hz: report.audio_sample_rate_hz,
             buffer_size_frames: report.audio_buffer_size_frames,
+            channels: report.audio_channels,
             callbacks_total: report.audio_callbacks_total,
             xruns_total: report.audio_xruns_total,
             last_callback_us: report.audio_last_callback_us,
@@ -264,10 +271,17 @@ fn main() {
             voice_steal_active_total: report.audio_voice_steal_active_total,
             voice_polyphony_pressure_total: report.audio_voice_polyphony_pressure_total,
             voice_sampler_mode_note_on_total: report.audio_voice_sampler_mode_note_on_total,
+            voice_fm_mode_note_on_total: report.audio_voice_fm_mode_note_on_total,
+            voice_sample_note_on_total: report.audio_voice_sample_note_on_total,
             voice_silent_note_on_total: report.audio_voice_silent_note_on_total,
             voice_mixer_muted_note_on_total: report.audio_voice_mixer_muted_note_on_total,
             voice_send_routed_note_on_total: report.audio_voice_send_routed_note_on_total,
             voice_send_level_total: report.audio_voice_send_level_total,
+            voice_pitch_bend_total: report.audio_voice_pitch_bend_total,
+            voice_volume_change_total: report.audio_voice_volume_change_total,
+            voice_param_change_total: report.audio_voice_param_change_total,
+            voice_panned_note_on_total: report.audio_voice_panned_note_on_total,
+            send_delay_frames: report.audio_send_delay_frames,
         };
         last_voice_steals = report.audio_voices_stolen_total;
     }
@@ -290,6 +304,7 @@ fn main() {
         last_audio_metrics = AudioMetrics {
             sample_rate_hz: report.audio_sample_rate_hz,
             buffer_size_frames: report.audio_buffer_size_frames,
+            channels: report.audio_channels,
             callbacks_total: report.audio_callbacks_total,
             xruns_total: report.audio_xruns_total,
             last_callback_us: report.audio_last_callback_us,
@@ -311,10 +326,17 @@ fn main() {
             voice_steal_active_total: report.audio_voice_steal_active_total,
             voice_polyphony_pressure_total: report.audio_voice_polyphony_pressure_total,
             voice_sampler_mode_note_on_total: report.audio_voice_sampler_mode_note_on_total,
+            voice_fm_mode_note_on_total: report.audio_voice_fm_mode_note_on_total,
+            voice_sample_note_on_total: report.audio_voice_sample_note_on_total,
             voice_silent_note_on_total: report.audio_voice_silent_note_on_total,
             voice_mixer_muted_note_on_total: report.audio_voice_mixer_muted_note_on_total,
             voice_send_routed_note_on_total: report.audio_voice_send_routed_note_on_total,
             voice_send_level_total: report.audio_voice_send_level_total,
+            voice_pitch_bend_total: report.audio_voice_pitch_bend_total,
+            voice_volume_change_total: report.audio_voice_volume_change_total,
+            voice_param_change_total: report.audio_voice_param_change_total,
+            voice_panned_note_on_total: report.audio_voice_panned_note_on_total,
+            send_delay_frames: report.audio_send_delay_frames,
         };
         last_voice_steals = report.audio_voices_stolen_total;
     }
@@ -343,6 +365,7 @@ fn main() {
             sample_rate_hz: 48_000,
             ppq: 24,
             ticks: 96,
+            sample_dir: runtime.sample_dir().map(|dir| dir.to_path_buf()),
         },
     )
     .expect("offline export failed");
@@ -354,7 +377,7 @@ fn main() {
         .expect("autosave failed");
 
     println!(
-        "p9_tracker stage19.3d fx-interaction-safety: tempo={}, restored_tempo={}, ticks={}, playing={}, sync_mode={:?}, external_clock_pending={}, events={}, audio_events={}, midi_events={}, midi_clock_events={}, midi_ingested={}, midi_out_messages={}, processed_commands={}, backend={}, fallback={}, callbacks={}, xruns={}, last_callback_us={}, avg_callback_us={}, sample_rate={}, buffer_size={}, active_voices={}, max_voices={}, voice_steals={}, note_on_total={}, note_off_total={}, note_off_miss_total={}, retrigger_total={}, zero_attack_total={}, short_release_total={}, click_risk_total={}, release_deferred_total={}, release_completed_total={}, release_pending_voices={}, steal_releasing_total={}, steal_active_total={}, polyphony_pressure_total={}, sampler_mode_note_on_total={}, silent_note_on_total={}, mixer_muted_note_on_total={}, send_routed_note_on_total={}, send_level_total={}, ui_screen={:?}, ui_track={}, ui_song_row={}, ui_chain_row={}, ui_phrase={}, ui_step={}, ui_scale_highlight={:?}, ui_track_level={}, export_ticks={}, export_events={}, export_samples={}, export_peak={}, export_path={}, autosave_written={}, autosave_tick={}, autosave_path={}, ui_shell_mode_supported={}",
+        "p9_tracker stage19.3d fx-interaction-safety: tempo={}, restored_tempo={}, ticks={}, playing={}, sync_mode={:?}, external_clock_pending={}, events={}, audio_events={}, midi_events={}, midi_clock_events={}, midi_ingested={}, midi_out_messages={}, processed_commands={}, backend={}, fallback={}, callbacks={}, xruns={}, last_callback_us={}, avg_callback_us={}, sample_rate={}, buffer_size={}, active_voices={}, max_voices={}, voice_steals={}, note_on_total={}, note_off_total={}, note_off_miss_total={}, retrigger_total={}, zero_attack_total={}, short_release_total={}, click_risk_total={}, release_deferred_total={}, release_completed_total={}, release_pending_voices={}, steal_releasing_total={}, steal_active_total={}, polyphony_pressure_total={}, sampler_mode_note_on_total={}, silent_note_on_total={}, mixer_muted_note_on_total={}, send_routed_note_on_total={}, send_level_total={}, ui_screen={:?}, ui_track={}, ui_song_row={}, ui_chain_row={}, ui_phrase={}, ui_step={}, ui_scale_highlight={:?}, ui_track_level={}, export_ticks={}, export_events={}, export_samples={}, export_peak={}, export_failed_samples={:?}, export_path={}, autosave_written={}, autosave_tick={}, autosave_path={}, ui_shell_mode_supported={}",
         envelope.project.song.tempo,
         restored.project.song.tempo,
         transport.tick,
@@ -409,11 +432,13 @@ fn main() {
         export_report.events_rendered,
         export_report.samples_rendered,
         export_report.peak_abs_sample,
+        export_report.failed_samples,
         export_path.display(),
         autosave_written,
         autosave.last_saved_tick(),
         autosave_path.display(),
-        true,
+        // The interactive shell reads commands line by line from a terminal.
+        io::stdin().is_terminal(),
     );
 }
 
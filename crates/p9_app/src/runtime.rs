@@ -1,12 +1,18 @@
 use std::collections::VecDeque;
 use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
-use p9_core::engine::Engine;
-use p9_core::scheduler::Scheduler;
+use p9_core::engine::{Engine, EngineCommand};
+use p9_core::events::RenderEvent;
+use p9_core::model::{InstrumentId, SampleParams, SampleSlicing};
+use p9_core::scheduler::{LaunchQuantize, Scheduler};
 use p9_rt::audio::AudioBackend;
 use p9_rt::midi::{
-    decode_message, forward_render_events, DecodedMidi, MidiInput, MidiMessage, MidiOutput,
+    decode_message, forward_render_events, song_position_clocks, song_position_message,
+    DecodedMidi, MidiInput, MidiMessage, MidiOutput, MIDI_CLOCKS_PER_QUARTER,
 };
+use p9_rt::sample::{detect_slice_points, SampleBank};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum SyncMode {
@@ -20,6 +26,21 @@ pub enum RuntimeCommand {
     Stop,
     Continue,
     Rewind,
+    /// Seeks to the moment `track_index` reaches the position; see `Scheduler::seek`.
+    Seek {
+        track_index: usize,
+        song_row: usize,
+        chain_row: usize,
+        step: usize,
+    },
+    SetLoopRange { start: usize, end: usize },
+    ClearLoopRange,
+    SetLiveMode(bool),
+    SetLaunchQuantize(LaunchQuantize),
+    QueueSongRow { track_index: usize, song_row: usize },
+    CancelQueuedRow { track_index: usize },
+    /// Relocates to an absolute MIDI clock count, as a received song position pointer asks.
+    SeekMidiClocks(u64),
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -29,6 +50,7 @@ pub struct TickReport {
     pub midi_clock_messages_sent: usize,
     pub midi_messages_ingested: u64,
     pub tick: u64,
+    pub tempo: u16,
     pub is_playing: bool,
     pub sync_mode: SyncMode,
     pub external_clock_pending: u32,
@@ -39,6 +61,7 @@ pub struct TickReport {
     pub audio_avg_callback_us: u32,
     pub audio_buffer_size_frames: u32,
     pub audio_sample_rate_hz: u32,
+    pub audio_channels: u16,
     pub audio_active_voices: u32,
     pub audio_max_voices: u32,
     pub audio_voices_stolen_total: u64,
@@ -56,10 +79,17 @@ pub struct TickReport {
     pub audio_voice_steal_active_total: u64,
     pub audio_voice_polyphony_pressure_total: u64,
     pub audio_voice_sampler_mode_note_on_total: u64,
+    pub audio_voice_fm_mode_note_on_total: u64,
+    pub audio_voice_sample_note_on_total: u64,
     pub audio_voice_silent_note_on_total: u64,
     pub audio_voice_mixer_muted_note_on_total: u64,
     pub audio_voice_send_routed_note_on_total: u64,
     pub audio_voice_send_level_total: u64,
+    pub audio_voice_pitch_bend_total: u64,
+    pub audio_voice_volume_change_total: u64,
+    pub audio_voice_param_change_total: u64,
+    pub audio_voice_panned_note_on_total: u64,
+    pub audio_send_delay_frames: u32,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -71,6 +101,9 @@ pub struct TransportSnapshot {
     pub queued_commands: usize,
     pub processed_commands: u64,
     pub midi_messages_ingested_total: u64,
+    pub loop_range: Option<(usize, usize)>,
+    pub live_mode: bool,
+    pub launch_quantize: LaunchQuantize,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -85,6 +118,12 @@ pub struct RuntimeCoordinator {
     command_queue: VecDeque<RuntimeCommand>,
     processed_commands: u64,
     midi_messages_ingested_total: u64,
+    song_position_pending: bool,
+    samples: Arc<SampleBank>,
+    sample_dir: Option<PathBuf>,
+    /// Engine sample revision the bank was last synced at; `None` forces the next sync.
+    samples_revision: Option<u64>,
+    samples_changed: bool,
 }
 
 impl RuntimeCoordinator {
@@ -96,9 +135,71 @@ impl RuntimeCoordinator {
             command_queue: VecDeque::new(),
             processed_commands: 0,
             midi_messages_ingested_total: 0,
+            song_position_pending: false,
+            samples: Arc::default(),
+            sample_dir: None,
+            samples_revision: None,
+            samples_changed: false,
         }
     }
 
+    /// Sets the directory relative sample paths resolve against: the open project file's
+    /// directory, or `None` for a project without a file.
+    pub fn set_sample_dir(&mut self, dir: Option<PathBuf>) {
+        if self.sample_dir != dir {
+            self.sample_dir = dir;
+            self.samples_revision = None;
+        }
+    }
+
+    pub fn sample_dir(&self) -> Option<&Path> {
+        self.sample_dir.as_deref()
+    }
+
+    /// Reloads the samples of instruments whose sample changed since the last sync, for the
+    /// next cycle to hand to the audio backend, and returns the instruments whose files failed
+    /// to load. The bank is only walked after project loads, sample edits or a new sample
+    /// directory, so idle cycles cost nothing.
+    pub fn sync_samples(&mut self, engine: &Engine) -> &[InstrumentId] {
+        let revision = engine.sample_revision();
+        if self.samples_revision != Some(revision) {
+            self.samples_revision = Some(revision);
+            // The backend shares the bank, so an edit copies its index but not the audio.
+            if Arc::make_mut(&mut self.samples)
+                .sync_project(engine.snapshot(), self.sample_dir.as_deref())
+            {
+                self.samples_changed = true;
+            }
+        }
+        self.samples.failed()
+    }
+
+    /// Instruments whose sample file failed to load at the last sync.
+    pub fn failed_samples(&self) -> &[InstrumentId] {
+        self.samples.failed()
+    }
+
+    /// Finds the hits in an instrument's loaded sample and returns the command that slices
+    /// it there, or `None` when the instrument has no loaded sample. The engine never decodes
+    /// files, so detection runs here against the bank playback uses.
+    pub fn detect_sample_slices(
+        &mut self,
+        engine: &Engine,
+        instrument_id: InstrumentId,
+        sensitivity: u8,
+    ) -> Option<EngineCommand> {
+        self.sync_samples(engine);
+        let loaded = self.samples.get(instrument_id)?;
+        let points = detect_slice_points(&loaded.data, &loaded.params, sensitivity);
+        Some(EngineCommand::SetInstrumentSample {
+            instrument_id,
+            sample: Some(SampleParams {
+                slicing: Some(SampleSlicing::Points(points)),
+                ..loaded.params.clone()
+            }),
+        })
+    }
+
     pub fn set_sync_mode(&mut self, mode: SyncMode) {
         self.set_sync_mode_now(mode);
     }
@@ -139,6 +240,14 @@ impl RuntimeCoordinator {
                 DecodedMidi::Clock if matches!(self.sync_mode, SyncMode::ExternalClock) => {
                     self.external_clock_pending = self.external_clock_pending.saturating_add(1);
                 }
+                DecodedMidi::SongPosition(beats)
+                    if matches!(self.sync_mode, SyncMode::ExternalClock) =>
+                {
+                    self.enqueue_command(RuntimeCommand::SeekMidiClocks(song_position_clocks(
+                        beats,
+                    )));
+                    mapped = mapped.saturating_add(1);
+                }
                 _ => {}
             }
         }
@@ -150,6 +259,8 @@ impl RuntimeCoordinator {
         self.enqueue_midi_messages(input.poll())
     }
 
+    /// Polls MIDI input and runs one tick, like `run_tick`. Audio callbacks that clock by
+    /// buffer use `run_block` instead.
     pub fn run_cycle(
         &mut self,
         engine: &Engine,
@@ -180,27 +291,119 @@ impl RuntimeCoordinator {
         audio: &mut dyn AudioBackend,
         midi_output: &mut dyn MidiOutput,
     ) -> TickReport {
-        self.apply_queued_commands();
+        self.apply_queued_commands(engine);
+        self.update_samples(engine, audio);
 
         let events = if self.should_advance_tick() {
             self.scheduler.tick(engine)
         } else {
             Vec::new()
         };
+        let clock_ticks =
+            usize::from(matches!(self.sync_mode, SyncMode::Internal) && self.scheduler.is_playing);
 
+        self.configure_send_effects(engine, audio);
         audio.push_events(&events);
-        let mut midi_messages_sent = forward_render_events(&events, midi_output);
+        self.finish_cycle(engine, audio, midi_output, &events, clock_ticks)
+    }
+
+    pub fn run_tick_safe(
+        &mut self,
+        engine: &Engine,
+        audio: &mut dyn AudioBackend,
+        midi_output: &mut dyn MidiOutput,
+    ) -> Result<TickReport, RuntimeFault> {
+        catch_unwind(AssertUnwindSafe(|| self.run_tick(engine, audio, midi_output)))
+            .map_err(|_| RuntimeFault::TickPanic)
+    }
 
-        let mut midi_clock_messages_sent = 0usize;
-        if matches!(self.sync_mode, SyncMode::Internal) && self.scheduler.is_playing {
+    /// Runs one audio callback: renders the backend's buffer through `Scheduler::render_block`,
+    /// so ticks land on their exact frame at any PPQ. Under external clock the incoming clocks
+    /// drive the ticks, so this falls back to `run_tick`.
+    pub fn run_block(
+        &mut self,
+        engine: &Engine,
+        audio: &mut dyn AudioBackend,
+        midi_output: &mut dyn MidiOutput,
+    ) -> TickReport {
+        if matches!(self.sync_mode, SyncMode::ExternalClock) {
+            return self.run_tick(engine, audio, midi_output);
+        }
+
+        self.apply_queued_commands(engine);
+        self.update_samples(engine, audio);
+
+        let audio_metrics = audio.metrics();
+        let tick_before = self.scheduler.current_tick;
+        let block = self.scheduler.render_block(
+            engine,
+            audio_metrics.sample_rate_hz,
+            audio_metrics.buffer_size_frames,
+        );
+        let clock_ticks = self.scheduler.current_tick.saturating_sub(tick_before) as usize;
+
+        self.configure_send_effects(engine, audio);
+        audio.push_block(&block);
+        let events: Vec<RenderEvent> = block.into_iter().map(|timed| timed.event).collect();
+        self.finish_cycle(engine, audio, midi_output, &events, clock_ticks)
+    }
+
+    pub fn run_block_safe(
+        &mut self,
+        engine: &Engine,
+        audio: &mut dyn AudioBackend,
+        midi_output: &mut dyn MidiOutput,
+    ) -> Result<TickReport, RuntimeFault> {
+        catch_unwind(AssertUnwindSafe(|| self.run_block(engine, audio, midi_output)))
+            .map_err(|_| RuntimeFault::TickPanic)
+    }
+
+    fn update_samples(&mut self, engine: &Engine, audio: &mut dyn AudioBackend) {
+        self.sync_samples(engine);
+        if std::mem::take(&mut self.samples_changed) {
+            audio.load_samples(Arc::clone(&self.samples));
+        }
+    }
+
+    fn configure_send_effects(&self, engine: &Engine, audio: &mut dyn AudioBackend) {
+        let mixer = &engine.snapshot().mixer;
+        audio.set_send_effects(
+            mixer.delay,
+            mixer.reverb,
+            self.scheduler.effective_tempo(engine),
+        );
+    }
+
+    /// Forwards the cycle's events to MIDI, sends one clock per tick run, and reports.
+    fn finish_cycle(
+        &mut self,
+        engine: &Engine,
+        audio: &mut dyn AudioBackend,
+        midi_output: &mut dyn MidiOutput,
+        events: &[RenderEvent],
+        clock_ticks: usize,
+    ) -> TickReport {
+        let mut midi_messages_sent = forward_render_events(events, midi_output);
+
+        // Followers relocate before the next clock so their playhead matches the seek.
+        if std::mem::take(&mut self.song_position_pending)
+            && matches!(self.sync_mode, SyncMode::Internal)
+        {
+            let clocks = self.scheduler.current_tick * MIDI_CLOCKS_PER_QUARTER
+                / self.scheduler.ppq.max(1) as u64;
+            midi_output.send(song_position_message(clocks));
+            midi_messages_sent = midi_messages_sent.saturating_add(1);
+        }
+
+        for _ in 0..clock_ticks {
             midi_output.send(MidiMessage {
                 status: 0xF8,
                 data1: 0,
                 data2: 0,
             });
-            midi_messages_sent = midi_messages_sent.saturating_add(1);
-            midi_clock_messages_sent = 1;
         }
+        midi_messages_sent = midi_messages_sent.saturating_add(clock_ticks);
+        let midi_clock_messages_sent = clock_ticks;
 
         let audio_metrics = audio.metrics();
 
@@ -210,6 +413,7 @@ impl RuntimeCoordinator {
             midi_clock_messages_sent,
             midi_messages_ingested: self.midi_messages_ingested_total,
             tick: self.scheduler.current_tick,
+            tempo: self.scheduler.effective_tempo(engine),
             is_playing: self.scheduler.is_playing,
             sync_mode: self.sync_mode,
             external_clock_pending: self.external_clock_pending,
@@ -220,6 +424,7 @@ impl RuntimeCoordinator {
             audio_avg_callback_us: audio_metrics.avg_callback_us,
             audio_buffer_size_frames: audio_metrics.buffer_size_frames,
             audio_sample_rate_hz: audio_metrics.sample_rate_hz,
+            audio_channels: audio_metrics.channels,
             audio_active_voices: audio_metrics.active_voices,
             audio_max_voices: audio_metrics.max_voices,
             audio_voices_stolen_total: audio_metrics.voices_stolen_total,
@@ -238,23 +443,24 @@ impl RuntimeCoordinator {
             audio_voice_polyphony_pressure_total: audio_metrics.voice_polyphony_pressure_total,
             audio_voice_sampler_mode_note_on_total: audio_metrics
                 .voice_sampler_mode_note_on_total,
+            audio_voice_fm_mode_note_on_total: audio_metrics.voice_fm_mode_note_on_total,
+            audio_voice_sample_note_on_total: audio_metrics.voice_sample_note_on_total,
             audio_voice_silent_note_on_total: audio_metrics.voice_silent_note_on_total,
             audio_voice_mixer_muted_note_on_total: audio_metrics
                 .voice_mixer_muted_note_on_total,
             audio_voice_send_routed_note_on_total: audio_metrics
                 .voice_send_routed_note_on_total,
             audio_voice_send_level_total: audio_metrics.voice_send_level_total,
+            audio_voice_pitch_bend_total: audio_metrics.voice_pitch_bend_total,
+            audio_voice_volume_change_total: audio_metrics.voice_volume_change_total,
+            audio_voice_param_change_total: audio_metrics.voice_param_change_total,
+            audio_voice_panned_note_on_total: audio_metrics.voice_panned_note_on_total,
+            audio_send_delay_frames: audio_metrics.send_delay_frames,
         }
     }
 
-    pub fn run_tick_safe(
-        &mut self,
-        engine: &Engine,
-        audio: &mut dyn AudioBackend,
-        midi_output: &mut dyn MidiOutput,
-    ) -> Result<TickReport, RuntimeFault> {
-        catch_unwind(AssertUnwindSafe(|| self.run_tick(engine, audio, midi_output)))
-            .map_err(|_| RuntimeFault::TickPanic)
+    pub fn effective_tempo(&self, engine: &Engine) -> u16 {
+        self.scheduler.effective_tempo(engine)
     }
 
     pub fn snapshot(&self) -> TransportSnapshot {
@@ -266,22 +472,56 @@ impl RuntimeCoordinator {
             queued_commands: self.command_queue.len(),
             processed_commands: self.processed_commands,
             midi_messages_ingested_total: self.midi_messages_ingested_total,
+            loop_range: self.scheduler.loop_range(),
+            live_mode: self.scheduler.live_mode(),
+            launch_quantize: self.scheduler.launch_quantize(),
         }
     }
 
-    fn apply_queued_commands(&mut self) {
+    fn apply_queued_commands(&mut self, engine: &Engine) {
         while let Some(command) = self.command_queue.pop_front() {
-            self.apply_command(command);
+            self.apply_command(command, engine);
             self.processed_commands = self.processed_commands.saturating_add(1);
         }
     }
 
-    fn apply_command(&mut self, command: RuntimeCommand) {
+    fn apply_command(&mut self, command: RuntimeCommand, engine: &Engine) {
         match command {
             RuntimeCommand::Start => self.scheduler.start(),
             RuntimeCommand::Stop => self.scheduler.stop(),
             RuntimeCommand::Continue => self.scheduler.start(),
             RuntimeCommand::Rewind => self.scheduler.rewind(),
+            RuntimeCommand::Seek {
+                track_index,
+                song_row,
+                chain_row,
+                step,
+            } => {
+                self.scheduler
+                    .seek(engine.snapshot(), track_index, song_row, chain_row, step);
+                self.song_position_pending = true;
+            }
+            RuntimeCommand::SetLoopRange { start, end } => {
+                self.scheduler.set_loop_range(Some((start, end)))
+            }
+            RuntimeCommand::ClearLoopRange => self.scheduler.set_loop_range(None),
+            RuntimeCommand::SetLiveMode(enabled) => self.scheduler.set_live_mode(enabled),
+            RuntimeCommand::SetLaunchQuantize(quantize) => {
+                self.scheduler.set_launch_quantize(quantize)
+            }
+            RuntimeCommand::QueueSongRow {
+                track_index,
+                song_row,
+            } => {
+                self.scheduler.queue_song_row(track_index, Some(song_row));
+            }
+            RuntimeCommand::CancelQueuedRow { track_index } => {
+                self.scheduler.queue_song_row(track_index, None);
+            }
+            RuntimeCommand::SeekMidiClocks(clocks) => {
+                let tick = clocks * self.scheduler.ppq as u64 / MIDI_CLOCKS_PER_QUARTER;
+                self.scheduler.seek_to_tick(engine.snapshot(), tick);
+            }
         }
     }
 
@@ -315,14 +555,19 @@ impl RuntimeCoordinator {
 #[cfg(test)]
 mod tests {
     use super::{RuntimeCommand, RuntimeCoordinator, RuntimeFault, SyncMode};
+    use p9_core::scheduler::LaunchQuantize;
     use p9_core::engine::{Engine, EngineCommand};
     use p9_core::events::RenderEvent;
-    use p9_core::model::{Chain, Phrase};
+    use p9_core::model::{
+        Chain, FxCommand, Instrument, InstrumentType, Phrase, SampleParams, SampleSlicing,
+    };
     use p9_rt::audio::{
         AudioBackend, AudioBackendConfig, AudioMetrics, NativeAudioBackend, NoopAudioBackend,
     };
     use p9_rt::midi::{MidiInput, MidiMessage, MidiOutput, NoopMidiOutput};
+    use p9_rt::sample::SampleBank;
     use std::collections::VecDeque;
+    use std::sync::Arc;
 
     fn setup_engine() -> Engine {
         let mut engine = Engine::new("runtime-test");
@@ -412,6 +657,151 @@ mod tests {
         }
     }
 
+    #[derive(Default)]
+    struct SampleCaptureBackend {
+        banks: Vec<Arc<SampleBank>>,
+    }
+
+    impl AudioBackend for SampleCaptureBackend {
+        fn start(&mut self) {}
+
+        fn stop(&mut self) {}
+
+        fn push_events(&mut self, _events: &[RenderEvent]) {}
+
+        fn events_consumed(&self) -> usize {
+            0
+        }
+
+        fn metrics(&self) -> AudioMetrics {
+            AudioMetrics::default()
+        }
+
+        fn backend_name(&self) -> &'static str {
+            "sample-capture"
+        }
+
+        fn load_samples(&mut self, bank: Arc<SampleBank>) {
+            self.banks.push(bank);
+        }
+    }
+
+    fn mono_wav_bytes(samples: &[i16]) -> Vec<u8> {
+        let data_len = samples.len() as u32 * 2;
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+        bytes.extend_from_slice(b"WAVEfmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&48_000u32.to_le_bytes());
+        bytes.extend_from_slice(&96_000u32.to_le_bytes());
+        bytes.extend_from_slice(&2u16.to_le_bytes());
+        bytes.extend_from_slice(&16u16.to_le_bytes());
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&data_len.to_le_bytes());
+        for sample in samples {
+            bytes.extend_from_slice(&sample.to_le_bytes());
+        }
+        bytes
+    }
+
+    #[test]
+    fn sample_edits_reload_the_backend_bank_from_the_project_dir() {
+        let dir = std::env::temp_dir().join(format!("p9_runtime_samples_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("hit.wav"), mono_wav_bytes(&[0; 8])).unwrap();
+
+        let mut engine = setup_engine();
+        engine
+            .apply_command(EngineCommand::UpsertInstrument {
+                instrument: Instrument::new(1, InstrumentType::Sampler, "Hit"),
+            })
+            .unwrap();
+        let mut runtime = RuntimeCoordinator::new(4);
+        runtime.set_sample_dir(Some(dir.clone()));
+        let mut audio = SampleCaptureBackend::default();
+        let mut midi_out = NoopMidiOutput::default();
+
+        runtime.run_tick(&engine, &mut audio, &mut midi_out);
+        assert!(audio.banks.is_empty());
+
+        let mut sample = SampleParams::new("hit.wav");
+        let set_sample = |engine: &mut Engine, sample: &SampleParams| {
+            engine
+                .apply_command(EngineCommand::SetInstrumentSample {
+                    instrument_id: 1,
+                    sample: Some(sample.clone()),
+                })
+                .unwrap();
+        };
+        set_sample(&mut engine, &sample);
+        runtime.run_tick(&engine, &mut audio, &mut midi_out);
+        assert_eq!(audio.banks.len(), 1);
+        assert_eq!(audio.banks[0].get(1).unwrap().data.frame_count(), 8);
+        // The backend shares the runtime's bank rather than a copy of it.
+        assert_eq!(Arc::strong_count(&audio.banks[0]), 2);
+
+        // Unchanged samples are not handed over again; edited ones are.
+        runtime.run_tick(&engine, &mut audio, &mut midi_out);
+        assert_eq!(audio.banks.len(), 1);
+        sample.root_note = 72;
+        set_sample(&mut engine, &sample);
+        runtime.run_tick(&engine, &mut audio, &mut midi_out);
+        assert_eq!(audio.banks.len(), 2);
+        assert_eq!(audio.banks[1].get(1).unwrap().params.root_note, 72);
+
+        sample.path = String::from("missing.wav");
+        set_sample(&mut engine, &sample);
+        runtime.run_tick(&engine, &mut audio, &mut midi_out);
+        assert!(audio.banks[2].get(1).is_none());
+        assert_eq!(runtime.failed_samples(), &[1]);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn detected_slices_cut_the_loaded_sample_at_its_hits() {
+        let dir = std::env::temp_dir().join(format!("p9_runtime_slices_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        // Decaying noise bursts every 150 ms over silence.
+        let hits = [0u32, 7_200, 14_400];
+        let break_beat: Vec<i16> = (0..21_600u32)
+            .map(|index| {
+                let since_hit = index - hits.iter().rev().find(|hit| **hit <= index).unwrap();
+                let noise = ((index.wrapping_mul(2_654_435_761) >> 16) as f32 / 32_768.0) - 1.0;
+                (noise * (-(since_hit as f32) / 1_200.0).exp() * 20_000.0) as i16
+            })
+            .collect();
+        std::fs::write(dir.join("break.wav"), mono_wav_bytes(&break_beat)).unwrap();
+
+        let mut engine = setup_engine();
+        let mut breaks = Instrument::new(1, InstrumentType::Sampler, "Break");
+        breaks.sample = Some(SampleParams::new("break.wav"));
+        engine
+            .apply_command(EngineCommand::UpsertInstrument { instrument: breaks })
+            .unwrap();
+        let mut runtime = RuntimeCoordinator::new(4);
+        runtime.set_sample_dir(Some(dir.clone()));
+
+        assert!(runtime.detect_sample_slices(&engine, 2, 64).is_none());
+        let command = runtime.detect_sample_slices(&engine, 1, 64).unwrap();
+        engine.apply_command(command).unwrap();
+
+        let sample = engine.snapshot().instruments[&1].sample.clone().unwrap();
+        assert_eq!(sample.path, "break.wav");
+        let Some(SampleSlicing::Points(points)) = sample.slicing else {
+            panic!("expected detected slice points, got {:?}", sample.slicing);
+        };
+        assert_eq!(points.len(), 2);
+        for (point, hit) in points.iter().zip(&hits[1..]) {
+            assert!(point.abs_diff(*hit) <= 240, "{point} is not near {hit}");
+        }
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
     #[test]
     fn command_burst_is_applied_before_tick() {
         let engine = setup_engine();
@@ -488,6 +878,148 @@ mod tests {
         assert!(!runtime.snapshot().is_playing);
     }
 
+    #[test]
+    fn seek_sets_absolute_tick_and_sends_song_position() {
+        let mut engine = setup_engine();
+        for row in 1..=3 {
+            engine
+                .apply_command(EngineCommand::SetSongRowChain {
+                    track_index: 0,
+                    row,
+                    chain_id: Some(0),
+                })
+                .unwrap();
+        }
+        let mut runtime = RuntimeCoordinator::new(24);
+        let mut audio = started_audio();
+        let mut midi_out = CaptureMidiOutput::default();
+
+        runtime.enqueue_commands([
+            RuntimeCommand::Stop,
+            RuntimeCommand::Seek {
+                track_index: 0,
+                song_row: 3,
+                chain_row: 0,
+                step: 2,
+            },
+        ]);
+        let report = runtime.run_tick(&engine, &mut audio, &mut midi_out);
+
+        // Three full 16-step phrases plus two steps at six ticks per step.
+        assert_eq!(report.tick, (3 * 16 + 2) * 6);
+        assert_eq!(
+            midi_out.sent,
+            vec![MidiMessage {
+                status: 0xF2,
+                data1: 50,
+                data2: 0,
+            }]
+        );
+
+        runtime.enqueue_command(RuntimeCommand::Start);
+        runtime.run_tick(&engine, &mut audio, &mut midi_out);
+        assert_eq!(midi_out.sent.last().map(|msg| msg.status), Some(0xF8));
+        assert_eq!(midi_out.sent.iter().filter(|msg| msg.status == 0xF2).count(), 1);
+    }
+
+    #[test]
+    fn song_position_converts_ticks_to_midi_clocks_at_any_ppq() {
+        let mut engine = setup_engine();
+        engine
+            .apply_command(EngineCommand::SetSongRowChain {
+                track_index: 0,
+                row: 1,
+                chain_id: Some(0),
+            })
+            .unwrap();
+        let mut runtime = RuntimeCoordinator::new(96);
+        let mut audio = started_audio();
+        let mut midi_out = CaptureMidiOutput::default();
+
+        runtime.enqueue_commands([
+            RuntimeCommand::Stop,
+            RuntimeCommand::Seek {
+                track_index: 0,
+                song_row: 1,
+                chain_row: 0,
+                step: 0,
+            },
+        ]);
+        let report = runtime.run_tick(&engine, &mut audio, &mut midi_out);
+
+        // Sixteen steps of 24 ticks are four quarter notes, or sixteen MIDI beats.
+        assert_eq!(report.tick, 16 * 24);
+        assert_eq!(
+            midi_out.sent,
+            vec![MidiMessage {
+                status: 0xF2,
+                data1: 16,
+                data2: 0,
+            }]
+        );
+    }
+
+    #[test]
+    fn external_sync_follows_incoming_song_position() {
+        let mut engine = setup_engine();
+        engine
+            .apply_command(EngineCommand::SetSongRowChain {
+                track_index: 0,
+                row: 1,
+                chain_id: Some(0),
+            })
+            .unwrap();
+        let mut runtime = RuntimeCoordinator::new(96);
+        let mut audio = started_audio();
+        let mut midi_out = NoopMidiOutput::default();
+        let song_position = MidiMessage {
+            status: 0xF2,
+            data1: 18,
+            data2: 0,
+        };
+
+        // Ignored while the runtime runs its own clock.
+        runtime.enqueue_midi_messages([song_position]);
+        runtime.run_tick(&engine, &mut audio, &mut midi_out);
+        assert_eq!(runtime.snapshot().tick, 1);
+
+        runtime.set_sync_mode(SyncMode::ExternalClock);
+        assert_eq!(runtime.enqueue_midi_messages([song_position]), 1);
+        let report = runtime.run_tick(&engine, &mut audio, &mut midi_out);
+        // MIDI beat 18 is step 2 of row 1.
+        assert_eq!(report.tick, (16 + 2) * 24);
+    }
+
+    #[test]
+    fn loop_and_live_commands_configure_scheduler() {
+        let engine = setup_engine();
+        let mut runtime = RuntimeCoordinator::new(4);
+        let mut audio = started_audio();
+        let mut midi_out = NoopMidiOutput::default();
+
+        runtime.enqueue_commands([
+            RuntimeCommand::SetLoopRange { start: 4, end: 2 },
+            RuntimeCommand::SetLiveMode(true),
+            RuntimeCommand::SetLaunchQuantize(LaunchQuantize::Phrase),
+            RuntimeCommand::QueueSongRow {
+                track_index: 0,
+                song_row: 2,
+            },
+            RuntimeCommand::CancelQueuedRow { track_index: 0 },
+        ]);
+        runtime.run_tick(&engine, &mut audio, &mut midi_out);
+
+        let snapshot = runtime.snapshot();
+        assert_eq!(snapshot.loop_range, Some((2, 4)));
+        assert!(snapshot.live_mode);
+        assert_eq!(snapshot.launch_quantize, LaunchQuantize::Phrase);
+        assert_eq!(snapshot.processed_commands, 5);
+
+        runtime.enqueue_command(RuntimeCommand::ClearLoopRange);
+        runtime.run_tick(&engine, &mut audio, &mut midi_out);
+        assert_eq!(runtime.snapshot().loop_range, None);
+    }
+
     #[test]
     fn repeated_command_sequences_are_deterministic() {
         fn run_sequence() -> Vec<(usize, bool, u64, SyncMode)> {
@@ -536,6 +1068,71 @@ mod tests {
         assert_eq!(run_sequence(), run_sequence());
     }
 
+    #[test]
+    fn tick_report_follows_tempo_fx() {
+        let mut engine = setup_engine();
+        engine.apply_command(EngineCommand::SetTempo(120)).unwrap();
+        engine
+            .apply_command(EngineCommand::SetStepFx {
+                phrase_id: 0,
+                step_index: 1,
+                fx_slot: 0,
+                fx: Some(FxCommand {
+                    code: "TPO".to_string(),
+                    value: 150,
+                }),
+            })
+            .unwrap();
+        let mut runtime = RuntimeCoordinator::new(4);
+        let mut audio = started_audio();
+        let mut midi_out = NoopMidiOutput::default();
+
+        let first = runtime.run_tick(&engine, &mut audio, &mut midi_out);
+        let second = runtime.run_tick(&engine, &mut audio, &mut midi_out);
+
+        assert_eq!(first.tempo, 120);
+        assert_eq!(second.tempo, 150);
+        assert_eq!(runtime.effective_tempo(&engine), 150);
+
+        runtime.enqueue_command(RuntimeCommand::Rewind);
+        runtime.enqueue_command(RuntimeCommand::Stop);
+        let rewound = runtime.run_tick(&engine, &mut audio, &mut midi_out);
+        assert_eq!(rewound.tempo, 120);
+    }
+
+    #[test]
+    fn native_backend_consumes_lfo_control_events() {
+        let mut engine = setup_engine();
+        for (fx_slot, code, value) in [(0, "VIB", 0x44), (1, "TRM", 0x44), (2, "LEN", 1)] {
+            engine
+                .apply_command(EngineCommand::SetStepFx {
+                    phrase_id: 0,
+                    step_index: 0,
+                    fx_slot,
+                    fx: Some(FxCommand {
+                        code: code.to_string(),
+                        value,
+                    }),
+                })
+                .unwrap();
+        }
+        let mut runtime = RuntimeCoordinator::new(96);
+        let mut audio = NativeAudioBackend::new(AudioBackendConfig::default());
+        audio.start_checked().unwrap();
+        let mut midi_out = CaptureMidiOutput::default();
+
+        let mut report = runtime.run_tick(&engine, &mut audio, &mut midi_out);
+        for _ in 1..12 {
+            report = runtime.run_tick(&engine, &mut audio, &mut midi_out);
+        }
+
+        let bends = midi_out.sent.iter().filter(|msg| msg.status & 0xF0 == 0xE0).count();
+        let pressure = midi_out.sent.iter().filter(|msg| msg.status & 0xF0 == 0xA0).count();
+        assert!(bends > 0 && pressure > 0);
+        assert_eq!(report.audio_voice_pitch_bend_total, bends as u64);
+        assert_eq!(report.audio_voice_volume_change_total, pressure as u64);
+    }
+
     #[test]
     fn tick_report_exposes_audio_metrics() {
         let engine = setup_engine();
@@ -576,6 +1173,8 @@ mod tests {
         assert_eq!(report.audio_voice_mixer_muted_note_on_total, 0);
         assert_eq!(report.audio_voice_send_routed_note_on_total, 0);
         assert_eq!(report.audio_voice_send_level_total, 0);
+        assert_eq!(report.audio_voice_pitch_bend_total, 0);
+        assert_eq!(report.audio_voice_volume_change_total, 0);
     }
 
     #[test]
@@ -648,6 +1247,56 @@ mod tests {
         assert_eq!(second.midi_messages_ingested, 2);
     }
 
+    #[test]
+    fn run_block_runs_every_tick_that_starts_in_the_callback() {
+        let engine = setup_engine();
+        let mut runtime = RuntimeCoordinator::new(96);
+        let mut audio = started_audio();
+        let mut midi_out = NoopMidiOutput::default();
+
+        // 120 BPM at PPQ 96 and 48 kHz is 250 frames per tick; callbacks are 256 frames.
+        let first = runtime.run_block(&engine, &mut audio, &mut midi_out);
+        assert_eq!(first.tick, 2);
+        assert_eq!(first.midi_clock_messages_sent, 2);
+        assert_eq!(first.events_emitted, 1);
+
+        let mut clocks = first.midi_clock_messages_sent;
+        let mut last = first;
+        for _ in 1..125 {
+            last = runtime.run_block(&engine, &mut audio, &mut midi_out);
+            clocks += last.midi_clock_messages_sent;
+        }
+
+        assert_eq!(last.tick, 32_000 / 250);
+        assert_eq!(clocks, 128);
+    }
+
+    #[test]
+    fn stop_and_rewind_drop_a_swung_note_on_still_carried() {
+        let mut engine = setup_engine();
+        engine.apply_command(EngineCommand::SetSongSwing(58)).unwrap();
+        let mut runtime = RuntimeCoordinator::new(4);
+        let mut audio = started_audio();
+        let mut midi_out = CaptureMidiOutput::default();
+
+        // 6000 frames per tick at PPQ 4: step 1 starts at frame 6000 and sounds at 6750.
+        let mut report = runtime.run_block(&engine, &mut audio, &mut midi_out);
+        for _ in 1..24 {
+            report = runtime.run_block(&engine, &mut audio, &mut midi_out);
+        }
+        assert_eq!(report.tick, 2);
+        let note_on = |msg: &MidiMessage| msg.status & 0xF0 == 0x90 && msg.data2 > 0;
+        assert_eq!(midi_out.sent.iter().filter(|msg| note_on(msg)).count(), 1);
+
+        runtime.enqueue_commands([RuntimeCommand::Stop, RuntimeCommand::Rewind]);
+        midi_out.sent.clear();
+        for _ in 0..24 {
+            runtime.run_block(&engine, &mut audio, &mut midi_out);
+        }
+
+        assert!(!midi_out.sent.iter().any(note_on));
+    }
+
     #[test]
     fn run_tick_safe_catches_backend_panics() {
         let engine = setup_engine();
@@ -658,4 +1307,15 @@ mod tests {
         let result = runtime.run_tick_safe(&engine, &mut audio, &mut midi_out);
         assert_eq!(result, Err(RuntimeFault::TickPanic));
     }
+
+    #[test]
+    fn run_block_safe_catches_backend_panics() {
+        let engine = setup_engine();
+        let mut runtime = RuntimeCoordinator::new(4);
+        let mut audio = PanicAudioBackend;
+        let mut midi_out = NoopMidiOutput::default();
+
+        let result = runtime.run_block_safe(&engine, &mut audio, &mut midi_out);
+        assert_eq!(result, Err(RuntimeFault::TickPanic));
+    }
 }
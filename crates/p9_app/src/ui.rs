@@ -1,8 +1,8 @@
 use crate::runtime::{RuntimeCommand, RuntimeCoordinator};
 use p9_core::engine::{Engine, EngineCommand, EngineError};
+use p9_core::scheduler::LaunchQuantize;
 use p9_core::model::{
-    Chain, Instrument, InstrumentId, InstrumentType, Phrase, Scale, CHAIN_ROW_COUNT,
-    SONG_ROW_COUNT, TRACK_COUNT,
+    Chain, Instrument, InstrumentId, InstrumentType, Phrase, ProjectData, Scale, CHAIN_ROW_COUNT,
 };
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -30,8 +30,14 @@ pub struct UiSnapshot {
     pub selected_chain_row: usize,
     pub selected_phrase_id: u8,
     pub selected_step: usize,
+    pub selected_phrase_length: usize,
+    pub track_count: usize,
+    pub song_length: usize,
     pub is_playing: bool,
     pub tick: u64,
+    pub loop_range: Option<(usize, usize)>,
+    pub live_mode: bool,
+    pub launch_quantize: LaunchQuantize,
     pub scale_highlight: ScaleHighlightState,
     pub focused_track_level: u8,
 }
@@ -74,6 +80,14 @@ pub enum UiAction {
     ToggleScaleHighlight,
     TogglePlayStop,
     RewindTransport,
+    PlayFromCursor,
+    SetLoopStart,
+    SetLoopEnd,
+    ClearLoop,
+    ToggleLiveMode,
+    ToggleLaunchQuantize,
+    QueueSelectedSongRow,
+    CancelQueuedSongRow,
     EnsureInstrument {
         instrument_id: InstrumentId,
         instrument_type: InstrumentType,
@@ -104,6 +118,11 @@ pub enum UiAction {
     },
     SetTrackLevel(u8),
     SetMasterLevel(u8),
+    /// Slices the instrument's loaded sample at the hits found in it.
+    DetectSampleSlices {
+        instrument_id: InstrumentId,
+        sensitivity: u8,
+    },
 }
 
 #[derive(Clone, Debug)]
@@ -113,6 +132,7 @@ pub enum UiError {
     InvalidSongRow(usize),
     InvalidChainRow(usize),
     InvalidStep(usize),
+    SampleNotLoaded(InstrumentId),
 }
 
 impl From<EngineError> for UiError {
@@ -128,6 +148,8 @@ impl UiController {
         engine: &mut Engine,
         runtime: &mut RuntimeCoordinator,
     ) -> Result<(), UiError> {
+        self.clamp_cursor(engine.snapshot());
+
         match action {
             UiAction::NextScreen => {
                 self.screen = match self.screen {
@@ -149,18 +171,19 @@ impl UiController {
             }
             UiAction::FocusTrackLeft => {
                 if self.focused_track == 0 {
-                    self.focused_track = TRACK_COUNT - 1;
+                    self.focused_track = engine.snapshot().song.track_count() - 1;
                 } else {
                     self.focused_track -= 1;
                 }
                 Ok(())
             }
             UiAction::FocusTrackRight => {
-                self.focused_track = (self.focused_track + 1) % TRACK_COUNT;
+                self.focused_track =
+                    (self.focused_track + 1) % engine.snapshot().song.track_count();
                 Ok(())
             }
             UiAction::SelectSongRow(row) => {
-                if row >= SONG_ROW_COUNT {
+                if row >= engine.snapshot().song.length() {
                     return Err(UiError::InvalidSongRow(row));
                 }
                 self.selected_song_row = row;
@@ -175,10 +198,12 @@ impl UiController {
             }
             UiAction::SelectPhrase(phrase_id) => {
                 self.selected_phrase_id = phrase_id;
+                let length = self.selected_phrase_length(engine.snapshot());
+                self.selected_step = self.selected_step.min(length - 1);
                 Ok(())
             }
             UiAction::SelectStep(step) => {
-                if step >= p9_core::model::PHRASE_STEP_COUNT {
+                if step >= self.selected_phrase_length(engine.snapshot()) {
                     return Err(UiError::InvalidStep(step));
                 }
                 self.selected_step = step;
@@ -201,6 +226,66 @@ impl UiController {
                 runtime.enqueue_commands([RuntimeCommand::Stop, RuntimeCommand::Rewind]);
                 Ok(())
             }
+            UiAction::PlayFromCursor => {
+                // The screen decides how deep the cursor reaches: song row, chain row or step.
+                let (chain_row, step) = match self.screen {
+                    UiScreen::Song | UiScreen::Mixer => (0, 0),
+                    UiScreen::Chain => (self.selected_chain_row, 0),
+                    UiScreen::Phrase => (self.selected_chain_row, self.selected_step),
+                };
+                runtime.enqueue_commands([
+                    RuntimeCommand::Seek {
+                        track_index: self.focused_track,
+                        song_row: self.selected_song_row,
+                        chain_row,
+                        step,
+                    },
+                    RuntimeCommand::Start,
+                ]);
+                Ok(())
+            }
+            UiAction::SetLoopStart => {
+                let start = self.selected_song_row;
+                let end = runtime.snapshot().loop_range.map_or(start, |(_, end)| end);
+                runtime.enqueue_command(RuntimeCommand::SetLoopRange { start, end });
+                Ok(())
+            }
+            UiAction::SetLoopEnd => {
+                let end = self.selected_song_row;
+                let start = runtime.snapshot().loop_range.map_or(end, |(start, _)| start);
+                runtime.enqueue_command(RuntimeCommand::SetLoopRange { start, end });
+                Ok(())
+            }
+            UiAction::ClearLoop => {
+                runtime.enqueue_command(RuntimeCommand::ClearLoopRange);
+                Ok(())
+            }
+            UiAction::ToggleLiveMode => {
+                let live_mode = runtime.snapshot().live_mode;
+                runtime.enqueue_command(RuntimeCommand::SetLiveMode(!live_mode));
+                Ok(())
+            }
+            UiAction::ToggleLaunchQuantize => {
+                let quantize = match runtime.snapshot().launch_quantize {
+                    LaunchQuantize::Chain => LaunchQuantize::Phrase,
+                    LaunchQuantize::Phrase => LaunchQuantize::Chain,
+                };
+                runtime.enqueue_command(RuntimeCommand::SetLaunchQuantize(quantize));
+                Ok(())
+            }
+            UiAction::QueueSelectedSongRow => {
+                runtime.enqueue_command(RuntimeCommand::QueueSongRow {
+                    track_index: self.focused_track,
+                    song_row: self.selected_song_row,
+                });
+                Ok(())
+            }
+            UiAction::CancelQueuedSongRow => {
+                runtime.enqueue_command(RuntimeCommand::CancelQueuedRow {
+                    track_index: self.focused_track,
+                });
+                Ok(())
+            }
             UiAction::EnsureInstrument {
                 instrument_id,
                 instrument_type,
@@ -266,7 +351,7 @@ impl UiController {
                 Ok(())
             }
             UiAction::SetTrackLevel(level) => {
-                if self.focused_track >= TRACK_COUNT {
+                if self.focused_track >= engine.snapshot().song.track_count() {
                     return Err(UiError::InvalidTrack(self.focused_track));
                 }
                 engine.apply_command(EngineCommand::SetTrackLevel {
@@ -279,13 +364,25 @@ impl UiController {
                 engine.apply_command(EngineCommand::SetMasterLevel { level })?;
                 Ok(())
             }
+            UiAction::DetectSampleSlices {
+                instrument_id,
+                sensitivity,
+            } => {
+                let command = runtime
+                    .detect_sample_slices(engine, instrument_id, sensitivity)
+                    .ok_or(UiError::SampleNotLoaded(instrument_id))?;
+                engine.apply_command(command)?;
+                Ok(())
+            }
         }
     }
 
     pub fn snapshot(&self, engine: &Engine, runtime: &RuntimeCoordinator) -> UiSnapshot {
         let transport = runtime.snapshot();
         let project = engine.snapshot();
-        let focused_track_level = project.mixer.track_levels[self.focused_track];
+        let focused_track = self.focused_track.min(project.song.track_count() - 1);
+        let focused_track_level = project.mixer.track_levels[focused_track];
+        let selected_phrase_length = self.selected_phrase_length(project);
 
         let scale_highlight = if !self.scale_highlight_enabled {
             ScaleHighlightState::Disabled
@@ -295,19 +392,40 @@ impl UiController {
 
         UiSnapshot {
             screen: self.screen,
-            focused_track: self.focused_track,
-            selected_song_row: self.selected_song_row,
+            focused_track,
+            selected_song_row: self.selected_song_row.min(project.song.length() - 1),
             selected_chain_row: self.selected_chain_row,
             selected_phrase_id: self.selected_phrase_id,
-            selected_step: self.selected_step,
+            selected_step: self.selected_step.min(selected_phrase_length - 1),
+            selected_phrase_length,
+            track_count: project.song.track_count(),
+            song_length: project.song.length(),
             is_playing: transport.is_playing,
             tick: transport.tick,
+            loop_range: transport.loop_range,
+            live_mode: transport.live_mode,
+            launch_quantize: transport.launch_quantize,
             scale_highlight,
             focused_track_level,
         }
     }
 
-    fn compute_scale_highlight(&self, project: &p9_core::model::ProjectData) -> ScaleHighlightState {
+    /// Keeps the cursor inside a project whose track count, song length or selected phrase
+    /// length shrank.
+    fn clamp_cursor(&mut self, project: &ProjectData) {
+        self.focused_track = self.focused_track.min(project.song.track_count() - 1);
+        self.selected_song_row = self.selected_song_row.min(project.song.length() - 1);
+        self.selected_step = self.selected_step.min(self.selected_phrase_length(project) - 1);
+    }
+
+    fn selected_phrase_length(&self, project: &ProjectData) -> usize {
+        project
+            .phrases
+            .get(&self.selected_phrase_id)
+            .map_or(p9_core::model::PHRASE_STEP_COUNT, |phrase| phrase.length().max(1))
+    }
+
+    fn compute_scale_highlight(&self, project: &ProjectData) -> ScaleHighlightState {
         let Some(phrase) = project.phrases.get(&self.selected_phrase_id) else {
             return ScaleHighlightState::NoNote;
         };
@@ -318,7 +436,8 @@ impl UiController {
             return ScaleHighlightState::NoNote;
         };
 
-        let track = &project.song.tracks[self.focused_track];
+        let focused_track = self.focused_track.min(project.song.track_count() - 1);
+        let track = &project.song.tracks[focused_track];
         let scale_id = track.scale_override.unwrap_or(project.song.default_scale);
         let Some(scale) = project.scales.get(&scale_id) else {
             return ScaleHighlightState::NoScale;
@@ -344,7 +463,7 @@ fn is_note_in_scale(note: u8, scale: &Scale) -> bool {
 
 #[cfg(test)]
 mod tests {
-    use super::{ScaleHighlightState, UiAction, UiController, UiScreen};
+    use super::{ScaleHighlightState, UiAction, UiController, UiError, UiScreen};
     use crate::runtime::RuntimeCoordinator;
     use p9_core::engine::{Engine, EngineCommand};
     use p9_core::model::{Scale, TRACK_COUNT};
@@ -482,6 +601,78 @@ mod tests {
         assert_eq!(snapshot.selected_chain_row, 2);
     }
 
+    #[test]
+    fn cursor_follows_project_track_count_and_song_length() {
+        let mut ui = UiController::default();
+        let mut engine = Engine::new("layout");
+        let mut runtime = RuntimeCoordinator::new(24);
+
+        ui.handle_action(UiAction::SelectSongRow(200), &mut engine, &mut runtime)
+            .unwrap();
+        ui.handle_action(UiAction::FocusTrackLeft, &mut engine, &mut runtime)
+            .unwrap();
+        engine.apply_command(EngineCommand::SetTrackCount(3)).unwrap();
+        engine.apply_command(EngineCommand::SetSongLength(32)).unwrap();
+
+        let snapshot = ui.snapshot(&engine, &runtime);
+        assert_eq!(snapshot.focused_track, 2);
+        assert_eq!(snapshot.selected_song_row, 31);
+        assert_eq!(snapshot.track_count, 3);
+        assert_eq!(snapshot.song_length, 32);
+
+        ui.handle_action(UiAction::FocusTrackRight, &mut engine, &mut runtime)
+            .unwrap();
+        assert_eq!(ui.snapshot(&engine, &runtime).focused_track, 0);
+        assert!(matches!(
+            ui.handle_action(UiAction::SelectSongRow(32), &mut engine, &mut runtime),
+            Err(UiError::InvalidSongRow(32))
+        ));
+    }
+
+    #[test]
+    fn step_selection_follows_selected_phrase_length() {
+        let mut ui = UiController::default();
+        let mut engine = Engine::new("length");
+        let mut runtime = RuntimeCoordinator::new(24);
+
+        engine
+            .apply_command(EngineCommand::UpsertPhrase {
+                phrase: p9_core::model::Phrase::new(2),
+            })
+            .unwrap();
+        engine
+            .apply_command(EngineCommand::SetPhraseLength {
+                phrase_id: 2,
+                length: 6,
+            })
+            .unwrap();
+
+        ui.handle_action(UiAction::SelectStep(12), &mut engine, &mut runtime)
+            .unwrap();
+        ui.handle_action(UiAction::SelectPhrase(2), &mut engine, &mut runtime)
+            .unwrap();
+
+        let snapshot = ui.snapshot(&engine, &runtime);
+        assert_eq!(snapshot.selected_phrase_length, 6);
+        assert_eq!(snapshot.selected_step, 5);
+        assert!(matches!(
+            ui.handle_action(UiAction::SelectStep(6), &mut engine, &mut runtime),
+            Err(UiError::InvalidStep(6))
+        ));
+
+        // Shrinking the selected phrase pulls the cursor back onto its last step.
+        engine
+            .apply_command(EngineCommand::SetPhraseLength {
+                phrase_id: 2,
+                length: 3,
+            })
+            .unwrap();
+        assert_eq!(ui.snapshot(&engine, &runtime).selected_step, 2);
+        ui.handle_action(UiAction::ToggleScaleHighlight, &mut engine, &mut runtime)
+            .unwrap();
+        assert_eq!(ui.selected_step, 2);
+    }
+
     #[test]
     fn rewind_transport_action_queues_stop_and_rewind() {
         let mut ui = UiController::default();
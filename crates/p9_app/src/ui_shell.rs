@@ -6,18 +6,20 @@ use crate::hardening::{
     mark_dirty_session_flag, recover_from_dirty_session, AutosaveManager, AutosavePolicy,
     DirtyStateTracker,
 };
-use crate::runtime::RuntimeCoordinator;
+use crate::runtime::{RuntimeCoordinator, RuntimeFault, SyncMode, TickReport};
 use crate::ui::{ScaleHighlightState, UiAction, UiController, UiError, UiScreen, UiSnapshot};
 use p9_core::engine::{Engine, EngineCommand};
-use p9_core::model::{ProjectData, Step, PHRASE_STEP_COUNT};
+use p9_core::model::{ProjectData, Step};
 use p9_rt::audio::{AudioBackend, NoopAudioBackend};
-use p9_rt::midi::NoopMidiOutput;
+use p9_rt::midi::{MidiOutput, NoopMidiOutput};
 
 const SONG_VIEW_ROWS: usize = 8;
 const CHAIN_VIEW_ROWS: usize = 8;
 const PHRASE_COLS: usize = 4;
 const HISTORY_LIMIT: usize = 128;
 const SHELL_AUTOSAVE_INTERVAL_TICKS: u64 = 16;
+// Enough 256-frame callbacks for one tick at 20 BPM and 24 PPQ.
+const SHELL_MAX_BLOCKS_PER_STEP: usize = 1_024;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ShellCommandResult {
@@ -190,7 +192,7 @@ pub fn run_interactive_shell(
     });
 
     let mut status = format!(
-        "Shell ready. Commands: n/p/h/l/j/k/t/r/c/f/i/e/a/z/w/v/V/x/+/-/u/y/?/q | recovery={}",
+        "Shell ready. Commands: n/p/h/l/j/k/t/P/r/[/]/o/m/Q/g/G/c/f/i/e/a/z/w/v/V/x/+/-/u/y/?/q | recovery={}",
         recovery_status.label()
     );
     let mut audio = NoopAudioBackend::default();
@@ -222,13 +224,21 @@ pub fn run_interactive_shell(
         ) {
             Ok(ShellCommandResult::Continue(next_status)) => {
                 let (status_level, status_body) = classify_status_message(&next_status);
-                let tick_status = match runtime.run_tick_safe(engine, &mut audio, &mut midi_output) {
+                let clock = run_shell_clock(engine, runtime, &mut audio, &mut midi_output);
+                let tick_status = match clock {
                     Ok(report) => {
-                        format!(
-                            "transport={} tick={}",
+                        let mut tick_status = format!(
+                            "transport={} tick={} tempo={}",
                             transport_label(report.is_playing),
-                            report.tick
-                        )
+                            report.tick,
+                            report.tempo
+                        );
+                        let failed_samples = runtime.failed_samples();
+                        if !failed_samples.is_empty() {
+                            tick_status
+                                .push_str(&format!(" sample-load-failed={failed_samples:?}"));
+                        }
+                        tick_status
                     }
                     Err(_) => String::from("runtime fault"),
                 };
@@ -261,6 +271,31 @@ pub fn run_interactive_shell(
     Ok(())
 }
 
+/// Runs the shell's clock between two commands. The shell blocks on stdin, so while playing
+/// it renders audio callbacks through `run_block`, the clock the GUI shell uses, until the
+/// transport moves by at least one tick. Under external clock the incoming clocks pace the
+/// ticks, so it runs a single tick.
+pub fn run_shell_clock(
+    engine: &Engine,
+    runtime: &mut RuntimeCoordinator,
+    audio: &mut dyn AudioBackend,
+    midi_output: &mut dyn MidiOutput,
+) -> Result<TickReport, RuntimeFault> {
+    if runtime.snapshot().sync_mode == SyncMode::ExternalClock {
+        return runtime.run_tick_safe(engine, audio, midi_output);
+    }
+
+    let tick_before = runtime.snapshot().tick;
+    let mut report = runtime.run_block_safe(engine, audio, midi_output)?;
+    for _ in 1..SHELL_MAX_BLOCKS_PER_STEP {
+        if !report.is_playing || report.tick != tick_before {
+            break;
+        }
+        report = runtime.run_block_safe(engine, audio, midi_output)?;
+    }
+    Ok(report)
+}
+
 #[cfg(test)]
 pub fn apply_shell_command_with_history(
     command: &str,
@@ -381,6 +416,53 @@ pub fn apply_shell_command_with_state(
                 "transport -> stop+rewind",
             )))
         }
+        "P" => {
+            let snapshot = ui.snapshot(engine, runtime);
+            ui.handle_action(UiAction::PlayFromCursor, engine, runtime)?;
+            Ok(ShellCommandResult::Continue(format!(
+                "transport -> play from song row {}",
+                snapshot.selected_song_row
+            )))
+        }
+        "[" | "]" => {
+            let row = ui.snapshot(engine, runtime).selected_song_row;
+            let (action, edge) = if command == "[" {
+                (UiAction::SetLoopStart, "start")
+            } else {
+                (UiAction::SetLoopEnd, "end")
+            };
+            ui.handle_action(action, engine, runtime)?;
+            Ok(ShellCommandResult::Continue(format!("loop -> {edge} at song row {row}")))
+        }
+        "o" => {
+            if runtime.snapshot().loop_range.is_none() {
+                return Ok(ShellCommandResult::Continue(String::from(
+                    "warn: no loop range set",
+                )));
+            }
+            ui.handle_action(UiAction::ClearLoop, engine, runtime)?;
+            Ok(ShellCommandResult::Continue(String::from("loop -> off")))
+        }
+        "m" => {
+            ui.handle_action(UiAction::ToggleLiveMode, engine, runtime)?;
+            Ok(ShellCommandResult::Continue(String::from("live mode -> toggle")))
+        }
+        "Q" => {
+            ui.handle_action(UiAction::ToggleLaunchQuantize, engine, runtime)?;
+            Ok(ShellCommandResult::Continue(String::from("launch quantize -> toggle")))
+        }
+        "g" => {
+            let snapshot = ui.snapshot(engine, runtime);
+            ui.handle_action(UiAction::QueueSelectedSongRow, engine, runtime)?;
+            Ok(ShellCommandResult::Continue(format!(
+                "live -> queue song row {} on track {}",
+                snapshot.selected_song_row, snapshot.focused_track
+            )))
+        }
+        "G" => {
+            ui.handle_action(UiAction::CancelQueuedSongRow, engine, runtime)?;
+            Ok(ShellCommandResult::Continue(String::from("live -> cancel queued row")))
+        }
         "c" => {
             let snapshot = ui.snapshot(engine, runtime);
             let chain_id = snapshot.selected_song_row as u8;
@@ -633,6 +715,14 @@ pub fn render_frame(project: &ProjectData, snapshot: UiSnapshot, status: &str) -
         snapshot.focused_track,
         snapshot.focused_track_level,
     ));
+    out.push_str(&format!(
+        "Loop: {} | Live: {} | Launch: {:?}\n",
+        snapshot
+            .loop_range
+            .map_or(String::from("off"), |(start, end)| format!("{start:03}..{end:03}")),
+        if snapshot.live_mode { "on" } else { "off" },
+        snapshot.launch_quantize,
+    ));
     out.push_str(&format!(
         "Cursor: song_row={} chain_row={} phrase={} step={} scale={:?}\n",
         snapshot.selected_song_row,
@@ -653,7 +743,7 @@ pub fn render_frame(project: &ProjectData, snapshot: UiSnapshot, status: &str) -
     out.push_str("----------------------------------------------------------------\n");
     out.push_str(&format!("Status: {status}\n"));
     out.push_str(
-        "Commands: n/p screen, h/l track, j/k cursor, t play, r rewind, c/f/i/e edit, a/z/w/v/V/x block, +/- level, u/y undo-redo, ? help, q quit\n",
+        "Commands: n/p screen, h/l track, j/k cursor, t play, P play cursor, r rewind, [/]/o loop, m/Q/g/G live, c/f/i/e edit, a/z/w/v/V/x block, +/- level, u/y undo-redo, ? help, q quit\n",
     );
 
     out
@@ -770,7 +860,7 @@ fn apply_paste_from_clipboard(
         )));
     }
 
-    let available = PHRASE_STEP_COUNT.saturating_sub(snapshot.selected_step);
+    let available = snapshot.selected_phrase_length.saturating_sub(snapshot.selected_step);
     let paste_len = clipboard_steps.len().min(available);
     if paste_len == 0 {
         return Ok(ShellCommandResult::Continue(String::from(
@@ -855,7 +945,7 @@ fn step_has_payload(step: &Step) -> bool {
 }
 
 fn command_help() -> &'static str {
-    "help: n/p screen, h/l track, j/k cursor, t play/stop, r stop+rewind, c bind chain, f bind phrase, i ensure instrument, e edit step, a/z selection start/end, w copy, v safe-paste, V force-paste, x clear selection, +/- level, u undo, y redo | status tags: info/warn/error"
+    "help: n/p screen, h/l track, j/k cursor, t play/stop, P play from cursor, r stop+rewind, [/] loop start/end, o loop off, m live mode, Q launch quantize, g/G queue/cancel row, c bind chain, f bind phrase, i ensure instrument, e edit step, a/z selection start/end, w copy, v safe-paste, V force-paste, x clear selection, +/- level, u undo, y redo | status tags: info/warn/error"
 }
 
 fn is_mutating_command(command: &str) -> bool {
@@ -906,8 +996,8 @@ fn update_session_hardening(
                 let _ = clear_dirty_session_flag(dirty_flag_path);
             }
         }
-        Err(_) => {
-            autosave_status = String::from("error");
+        Err(err) => {
+            autosave_status = format!("error ({err})");
         }
     }
 
@@ -930,24 +1020,40 @@ fn wrap_prev(current: usize, len: usize) -> usize {
     }
 }
 
+/// Songs shorter than the view show and cycle through only their own rows.
+fn song_view_rows(snapshot: UiSnapshot) -> usize {
+    snapshot.song_length.min(SONG_VIEW_ROWS)
+}
+
 fn cursor_shift_down(snapshot: UiSnapshot) -> UiAction {
     match snapshot.screen {
-        UiScreen::Song => UiAction::SelectSongRow(wrap_next(snapshot.selected_song_row, SONG_VIEW_ROWS)),
+        UiScreen::Song => {
+            UiAction::SelectSongRow(wrap_next(snapshot.selected_song_row, song_view_rows(snapshot)))
+        }
         UiScreen::Chain => {
             UiAction::SelectChainRow(wrap_next(snapshot.selected_chain_row, CHAIN_VIEW_ROWS))
         }
-        UiScreen::Phrase => UiAction::SelectStep((snapshot.selected_step + PHRASE_COLS) % 16),
+        UiScreen::Phrase => UiAction::SelectStep(
+            (snapshot.selected_step + PHRASE_COLS) % snapshot.selected_phrase_length,
+        ),
         UiScreen::Mixer => UiAction::FocusTrackRight,
     }
 }
 
 fn cursor_shift_up(snapshot: UiSnapshot) -> UiAction {
     match snapshot.screen {
-        UiScreen::Song => UiAction::SelectSongRow(wrap_prev(snapshot.selected_song_row, SONG_VIEW_ROWS)),
+        UiScreen::Song => {
+            UiAction::SelectSongRow(wrap_prev(snapshot.selected_song_row, song_view_rows(snapshot)))
+        }
         UiScreen::Chain => {
             UiAction::SelectChainRow(wrap_prev(snapshot.selected_chain_row, CHAIN_VIEW_ROWS))
         }
-        UiScreen::Phrase => UiAction::SelectStep((snapshot.selected_step + 16 - PHRASE_COLS) % 16),
+        UiScreen::Phrase => {
+            // Wraps to the phrase end, including phrases shorter than a row of four.
+            let length = snapshot.selected_phrase_length as isize;
+            let step = (snapshot.selected_step as isize - PHRASE_COLS as isize).rem_euclid(length);
+            UiAction::SelectStep(step as usize)
+        }
         UiScreen::Mixer => UiAction::FocusTrackLeft,
     }
 }
@@ -956,7 +1062,7 @@ fn render_song_panel(out: &mut String, project: &ProjectData, snapshot: UiSnapsh
     out.push_str("Song Panel\n");
 
     if let Some(track) = project.song.tracks.get(snapshot.focused_track) {
-        for row in 0..SONG_VIEW_ROWS {
+        for row in 0..song_view_rows(snapshot) {
             let marker = if row == snapshot.selected_song_row {
                 ">"
             } else {
@@ -1029,12 +1135,19 @@ fn render_phrase_panel(out: &mut String, project: &ProjectData, snapshot: UiSnap
         return;
     };
 
-    out.push_str(&format!("Phrase ID: {}\n", snapshot.selected_phrase_id));
+    out.push_str(&format!(
+        "Phrase ID: {} | length {}\n",
+        snapshot.selected_phrase_id,
+        phrase.length()
+    ));
 
-    for row in 0..4usize {
+    for row in 0..phrase.length().div_ceil(4) {
         let mut row_line = String::new();
         for col in 0..4usize {
             let step_index = row * 4 + col;
+            if step_index >= phrase.length() {
+                break;
+            }
             let marker = if step_index == snapshot.selected_step {
                 ">"
             } else {
@@ -1072,7 +1185,8 @@ fn render_mixer_panel(out: &mut String, project: &ProjectData, snapshot: UiSnaps
             " "
         };
 
-        out.push_str(&format!("{marker} track {track_index}: level {level}\n"));
+        let pan = project.mixer.track_pans[track_index];
+        out.push_str(&format!("{marker} track {track_index}: level {level} pan {pan}\n"));
     }
 
     out.push_str(&format!("Master: {}\n", project.mixer.master_level));
@@ -1088,7 +1202,7 @@ fn render_mixer_panel(out: &mut String, project: &ProjectData, snapshot: UiSnaps
 mod tests {
     use super::{
         apply_shell_command, apply_shell_command_with_history, apply_shell_command_with_history_state,
-        render_frame, ProjectHistory, ShellCommandResult, ShellEditState,
+        render_frame, run_shell_clock, ProjectHistory, ShellCommandResult, ShellEditState,
     };
     use crate::hardening::{
         mark_dirty_session_flag, recover_from_dirty_session, AutosaveManager, AutosavePolicy,
@@ -1139,7 +1253,7 @@ mod tests {
             match result {
                 ShellCommandResult::Continue(status) => {
                     statuses.push(status);
-                    let _ = runtime.run_tick(&engine, &mut audio, &mut midi);
+                    let _ = run_shell_clock(&engine, &mut runtime, &mut audio, &mut midi);
                 }
                 ShellCommandResult::Exit => break,
             }
@@ -1207,6 +1321,68 @@ mod tests {
         assert_eq!(ui.snapshot(&engine, &runtime).selected_step, 0);
     }
 
+    #[test]
+    fn shell_song_cursor_and_panel_stop_at_a_short_song_end() {
+        let mut ui = UiController::default();
+        let mut engine = Engine::new("shell");
+        let mut runtime = RuntimeCoordinator::new(24);
+        engine.apply_command(EngineCommand::SetSongLength(4)).unwrap();
+
+        let mut rows = Vec::new();
+        for _ in 0..4 {
+            let _ = apply_shell_command("j", &mut ui, &mut engine, &mut runtime).unwrap();
+            rows.push(ui.snapshot(&engine, &runtime).selected_song_row);
+        }
+        assert_eq!(rows, vec![1, 2, 3, 0]);
+        let _ = apply_shell_command("k", &mut ui, &mut engine, &mut runtime).unwrap();
+        assert_eq!(ui.snapshot(&engine, &runtime).selected_song_row, 3);
+
+        let frame = render_frame(engine.snapshot(), ui.snapshot(&engine, &runtime), "");
+        assert!(frame.contains("row 03 -> chain"));
+        assert!(!frame.contains("row 04 -> chain"));
+    }
+
+    #[test]
+    fn shell_phrase_cursor_wraps_at_the_phrase_length() {
+        let mut ui = UiController::default();
+        let mut engine = Engine::new("shell");
+        let mut runtime = RuntimeCoordinator::new(24);
+        engine
+            .apply_command(EngineCommand::UpsertPhrase {
+                phrase: p9_core::model::Phrase::new(0),
+            })
+            .unwrap();
+        let set_length = |engine: &mut Engine, length| {
+            engine
+                .apply_command(EngineCommand::SetPhraseLength {
+                    phrase_id: 0,
+                    length,
+                })
+                .unwrap();
+        };
+        set_length(&mut engine, 6);
+
+        let _ = apply_shell_command("n", &mut ui, &mut engine, &mut runtime).unwrap();
+        let _ = apply_shell_command("n", &mut ui, &mut engine, &mut runtime).unwrap();
+        let mut press = |key, engine: &mut Engine| {
+            let result = apply_shell_command(key, &mut ui, engine, &mut runtime).unwrap();
+            assert!(
+                matches!(result, ShellCommandResult::Continue(ref msg) if !msg.starts_with("error"))
+            );
+            ui.snapshot(engine, &runtime).selected_step
+        };
+
+        assert_eq!(press("j", &mut engine), 4);
+        assert_eq!(press("j", &mut engine), 2);
+        assert_eq!(press("k", &mut engine), 4);
+
+        set_length(&mut engine, 64);
+        let steps: Vec<usize> = (0..4).map(|_| press("j", &mut engine)).collect();
+        assert_eq!(steps, vec![8, 12, 16, 20]);
+        let steps: Vec<usize> = (0..6).map(|_| press("k", &mut engine)).collect();
+        assert_eq!(steps, vec![16, 12, 8, 4, 0, 60]);
+    }
+
     #[test]
     fn shell_transport_commands_queue_runtime_updates() {
         let mut ui = UiController::default();
@@ -1220,6 +1396,63 @@ mod tests {
         assert_eq!(runtime.snapshot().queued_commands, 3);
     }
 
+    #[test]
+    fn shell_loop_and_live_commands_queue_runtime_updates() {
+        let mut ui = UiController::default();
+        let mut engine = Engine::new("shell");
+        let mut runtime = RuntimeCoordinator::new(24);
+        let mut audio = NoopAudioBackend::default();
+        let mut midi_out = NoopMidiOutput::default();
+
+        let result = apply_shell_command("o", &mut ui, &mut engine, &mut runtime).unwrap();
+        assert!(matches!(result, ShellCommandResult::Continue(msg) if msg.starts_with("warn:")));
+
+        let _ = apply_shell_command("j", &mut ui, &mut engine, &mut runtime).unwrap();
+        let _ = apply_shell_command("[", &mut ui, &mut engine, &mut runtime).unwrap();
+        let _ = apply_shell_command("m", &mut ui, &mut engine, &mut runtime).unwrap();
+        let _ = apply_shell_command("g", &mut ui, &mut engine, &mut runtime).unwrap();
+        runtime.run_tick(&engine, &mut audio, &mut midi_out);
+        let _ = apply_shell_command("j", &mut ui, &mut engine, &mut runtime).unwrap();
+        let _ = apply_shell_command("]", &mut ui, &mut engine, &mut runtime).unwrap();
+        runtime.run_tick(&engine, &mut audio, &mut midi_out);
+
+        let transport = runtime.snapshot();
+        assert_eq!(transport.loop_range, Some((1, 2)));
+        assert!(transport.live_mode);
+        let frame = render_frame(engine.snapshot(), ui.snapshot(&engine, &runtime), "ok");
+        assert!(frame.contains("Loop: 001..002 | Live: on | Launch: Chain"));
+
+        let _ = apply_shell_command("o", &mut ui, &mut engine, &mut runtime).unwrap();
+        runtime.run_tick(&engine, &mut audio, &mut midi_out);
+        assert_eq!(runtime.snapshot().loop_range, None);
+    }
+
+    #[test]
+    fn shell_clock_renders_callbacks_until_the_transport_ticks() {
+        let mut ui = UiController::default();
+        let mut engine = Engine::new("shell");
+        let mut runtime = RuntimeCoordinator::new(24);
+        let mut audio = NoopAudioBackend::default();
+        audio.start();
+        let mut midi = NoopMidiOutput::default();
+
+        let first = run_shell_clock(&engine, &mut runtime, &mut audio, &mut midi).unwrap();
+        assert_eq!(first.tick, 1);
+        assert_eq!(audio.metrics().callbacks_total, 1);
+
+        // 120 BPM at PPQ 24 and 48 kHz is 1000 frames per tick; callbacks are 256 frames,
+        // so the next tick starts in the fourth callback of the block clock.
+        let second = run_shell_clock(&engine, &mut runtime, &mut audio, &mut midi).unwrap();
+        assert_eq!(second.tick, 2);
+        assert_eq!(audio.metrics().callbacks_total, 4);
+
+        let _ = apply_shell_command("t", &mut ui, &mut engine, &mut runtime).unwrap();
+        let stopped = run_shell_clock(&engine, &mut runtime, &mut audio, &mut midi).unwrap();
+        assert!(!stopped.is_playing);
+        assert_eq!(stopped.tick, 2);
+        assert_eq!(audio.metrics().callbacks_total, 5);
+    }
+
     #[test]
     fn shell_rewind_warns_when_transport_already_at_start() {
         let mut ui = UiController::default();
@@ -1,6 +1,10 @@
 use crate::model::{
-    Chain, ChainId, FxCommand, Groove, GrooveId, Instrument, InstrumentId, Phrase, PhraseId,
-    ProjectData, Scale, ScaleId, Table, TableId,
+    Chain, ChainId, DelayParams, DrumSynthParams, FilterParams, FmParams, FxCommand, Groove,
+    GrooveId, Instrument, InstrumentId, Phrase, PhraseId, ProjectData, ReverbParams, SampleParams,
+    SampleSlicing, Scale, ScaleId, Table, TableId, DELAY_MAX_STEPS, DELAY_MIN_STEPS,
+    DRUM_FREQ_MAX_HZ, DRUM_FREQ_MIN_HZ, FM_MIN_OPERATORS, FM_OPERATOR_SLOTS,
+    MAX_PHRASE_STEP_COUNT, MAX_SAMPLE_SLICES, MAX_SONG_ROW_COUNT, MAX_TRACK_COUNT, PAN_MAX,
+    SWING_MAX, SWING_STRAIGHT,
 };
 
 #[derive(Clone, Debug)]
@@ -8,6 +12,10 @@ pub enum EngineCommand {
     SetTempo(u16),
     SetDefaultGroove(GrooveId),
     SetDefaultScale(ScaleId),
+    SetSongSeed(u32),
+    SetSongSwing(u8),
+    SetTrackCount(usize),
+    SetSongLength(usize),
     ToggleTrackMute {
         track_index: usize,
     },
@@ -19,6 +27,10 @@ pub enum EngineCommand {
         track_index: usize,
         scale_id: Option<ScaleId>,
     },
+    SetTrackSwingOverride {
+        track_index: usize,
+        swing: Option<u8>,
+    },
     SetSongRowChain {
         track_index: usize,
         row: usize,
@@ -37,6 +49,10 @@ pub enum EngineCommand {
         velocity: u8,
         instrument_id: Option<InstrumentId>,
     },
+    SetPhraseLength {
+        phrase_id: PhraseId,
+        length: usize,
+    },
     SetStepFx {
         phrase_id: PhraseId,
         step_index: usize,
@@ -67,10 +83,43 @@ pub enum EngineCommand {
         fx_slot: usize,
         fx: Option<FxCommand>,
     },
+    SetTablePlayback {
+        table_id: TableId,
+        speed: u8,
+        groove_id: Option<GrooveId>,
+        loop_start: usize,
+    },
+    SetInstrumentEnvelope {
+        instrument_id: InstrumentId,
+        attack_ms: u16,
+        decay_ms: u16,
+        sustain: u8,
+        release_ms: u16,
+    },
+    SetInstrumentFilter {
+        instrument_id: InstrumentId,
+        filter: FilterParams,
+    },
+    SetInstrumentFm {
+        instrument_id: InstrumentId,
+        fm: FmParams,
+    },
+    SetInstrumentSample {
+        instrument_id: InstrumentId,
+        sample: Option<SampleParams>,
+    },
+    SetInstrumentDrums {
+        instrument_id: InstrumentId,
+        drums: DrumSynthParams,
+    },
     SetTrackLevel {
         track_index: usize,
         level: u8,
     },
+    SetTrackPan {
+        track_index: usize,
+        pan: u8,
+    },
     SetMasterLevel {
         level: u8,
     },
@@ -79,6 +128,12 @@ pub enum EngineCommand {
         delay: u8,
         reverb: u8,
     },
+    SetMixerDelay {
+        delay: DelayParams,
+    },
+    SetMixerReverb {
+        reverb: ReverbParams,
+    },
     UpsertGroove {
         groove: Groove,
     },
@@ -91,26 +146,47 @@ pub enum EngineCommand {
 pub enum EngineError {
     InvalidTempo,
     InvalidTrackIndex(usize),
+    InvalidTrackCount(usize),
     InvalidSongRow(usize),
+    InvalidSongLength(usize),
     InvalidChainRow(usize),
     InvalidPhraseStep(usize),
+    InvalidPhraseLength(usize),
     InvalidFxSlot(usize),
     InvalidTableRow(usize),
     InvalidFxCode(String),
     InvalidFxValue(String, u8),
+    InvalidPan(u8),
+    InvalidSwing(u8),
+    InvalidSustain(u8),
+    InvalidFilterLevel(u8),
+    InvalidFmOperatorCount(u8),
+    InvalidFmLevel(u8),
+    InvalidDrumFrequency(u16),
+    InvalidDrumLevel(u8),
+    InvalidDelayTime(u8),
+    InvalidSendFxLevel(u8),
+    InvalidSampleRootNote(u8),
+    InvalidSampleRange { start: u32, end: u32 },
+    InvalidSampleSliceCount(usize),
+    InvalidSampleSlicePoint(u32),
     MissingChain(ChainId),
     MissingPhrase(PhraseId),
     MissingTable(TableId),
+    MissingInstrument(InstrumentId),
+    MissingGroove(GrooveId),
 }
 
 pub struct Engine {
     project: ProjectData,
+    sample_revision: u64,
 }
 
 impl Engine {
     pub fn new(song_name: impl Into<String>) -> Self {
         Self {
             project: ProjectData::new(song_name),
+            sample_revision: 0,
         }
     }
 
@@ -118,8 +194,15 @@ impl Engine {
         &self.project
     }
 
+    /// Moves on whenever a command may change which sample an instrument plays, so sample
+    /// banks only resync after such edits.
+    pub fn sample_revision(&self) -> u64 {
+        self.sample_revision
+    }
+
     pub fn replace_project(&mut self, project: ProjectData) {
         self.project = project;
+        self.sample_revision = self.sample_revision.wrapping_add(1);
     }
 
     pub fn apply_command(&mut self, command: EngineCommand) -> Result<(), EngineError> {
@@ -139,6 +222,29 @@ impl Engine {
                 self.project.song.default_scale = scale_id;
                 Ok(())
             }
+            EngineCommand::SetSongSeed(seed) => {
+                self.project.song.seed = seed;
+                Ok(())
+            }
+            EngineCommand::SetSongSwing(swing) => {
+                validate_swing(swing)?;
+                self.project.song.swing = swing;
+                Ok(())
+            }
+            EngineCommand::SetTrackCount(track_count) => {
+                if !(1..=MAX_TRACK_COUNT).contains(&track_count) {
+                    return Err(EngineError::InvalidTrackCount(track_count));
+                }
+                self.project.set_track_count(track_count);
+                Ok(())
+            }
+            EngineCommand::SetSongLength(length) => {
+                if !(1..=MAX_SONG_ROW_COUNT).contains(&length) {
+                    return Err(EngineError::InvalidSongLength(length));
+                }
+                self.project.song.set_length(length);
+                Ok(())
+            }
             EngineCommand::ToggleTrackMute { track_index } => {
                 let track = self
                     .project
@@ -175,6 +281,19 @@ impl Engine {
                 track.scale_override = scale_id;
                 Ok(())
             }
+            EngineCommand::SetTrackSwingOverride { track_index, swing } => {
+                if let Some(swing) = swing {
+                    validate_swing(swing)?;
+                }
+                let track = self
+                    .project
+                    .song
+                    .tracks
+                    .get_mut(track_index)
+                    .ok_or(EngineError::InvalidTrackIndex(track_index))?;
+                track.swing_override = swing;
+                Ok(())
+            }
             EngineCommand::SetSongRowChain {
                 track_index,
                 row,
@@ -236,12 +355,25 @@ impl Engine {
                 step.instrument_id = instrument_id;
                 Ok(())
             }
+            EngineCommand::SetPhraseLength { phrase_id, length } => {
+                if !(1..=MAX_PHRASE_STEP_COUNT).contains(&length) {
+                    return Err(EngineError::InvalidPhraseLength(length));
+                }
+                let phrase = self
+                    .project
+                    .phrases
+                    .get_mut(&phrase_id)
+                    .ok_or(EngineError::MissingPhrase(phrase_id))?;
+                phrase.set_length(length);
+                Ok(())
+            }
             EngineCommand::SetStepFx {
                 phrase_id,
                 step_index,
                 fx_slot,
                 fx,
             } => {
+                let normalized = normalize_fx(fx, &self.project)?;
                 let phrase = self
                     .project
                     .phrases
@@ -257,8 +389,6 @@ impl Engine {
                     .fx
                     .get_mut(fx_slot)
                     .ok_or(EngineError::InvalidFxSlot(fx_slot))?;
-
-                let normalized = normalize_fx(fx)?;
                 *slot = normalized;
                 Ok(())
             }
@@ -272,6 +402,7 @@ impl Engine {
             }
             EngineCommand::UpsertInstrument { instrument } => {
                 self.project.instruments.insert(instrument.id, instrument);
+                self.sample_revision = self.sample_revision.wrapping_add(1);
                 Ok(())
             }
             EngineCommand::UpsertTable { table } => {
@@ -304,6 +435,7 @@ impl Engine {
                 fx_slot,
                 fx,
             } => {
+                let normalized = normalize_fx(fx, &self.project)?;
                 let table = self
                     .project
                     .tables
@@ -317,11 +449,166 @@ impl Engine {
                     .fx
                     .get_mut(fx_slot)
                     .ok_or(EngineError::InvalidFxSlot(fx_slot))?;
-
-                let normalized = normalize_fx(fx)?;
                 *slot = normalized;
                 Ok(())
             }
+            EngineCommand::SetTablePlayback {
+                table_id,
+                speed,
+                groove_id,
+                loop_start,
+            } => {
+                let table = self
+                    .project
+                    .tables
+                    .get_mut(&table_id)
+                    .ok_or(EngineError::MissingTable(table_id))?;
+                if loop_start >= table.rows.len() {
+                    return Err(EngineError::InvalidTableRow(loop_start));
+                }
+
+                table.speed = speed;
+                table.groove_id = groove_id;
+                table.loop_start = loop_start as u8;
+                Ok(())
+            }
+            EngineCommand::SetInstrumentEnvelope {
+                instrument_id,
+                attack_ms,
+                decay_ms,
+                sustain,
+                release_ms,
+            } => {
+                if sustain > 127 {
+                    return Err(EngineError::InvalidSustain(sustain));
+                }
+                let instrument = self
+                    .project
+                    .instruments
+                    .get_mut(&instrument_id)
+                    .ok_or(EngineError::MissingInstrument(instrument_id))?;
+
+                let synth_params = &mut instrument.synth_params;
+                synth_params.attack_ms = attack_ms;
+                synth_params.decay_ms = decay_ms;
+                synth_params.sustain = sustain;
+                synth_params.release_ms = release_ms;
+                Ok(())
+            }
+            EngineCommand::SetInstrumentFilter {
+                instrument_id,
+                filter,
+            } => {
+                if let Some(level) = [filter.cutoff, filter.resonance]
+                    .into_iter()
+                    .find(|level| *level > 127)
+                {
+                    return Err(EngineError::InvalidFilterLevel(level));
+                }
+                let instrument = self
+                    .project
+                    .instruments
+                    .get_mut(&instrument_id)
+                    .ok_or(EngineError::MissingInstrument(instrument_id))?;
+
+                instrument.synth_params.filter = filter;
+                Ok(())
+            }
+            EngineCommand::SetInstrumentFm { instrument_id, fm } => {
+                if !(FM_MIN_OPERATORS..=FM_OPERATOR_SLOTS as u8).contains(&fm.operator_count) {
+                    return Err(EngineError::InvalidFmOperatorCount(fm.operator_count));
+                }
+                if let Some(level) = fm
+                    .operators
+                    .iter()
+                    .flat_map(|operator| [operator.level, operator.feedback, operator.sustain])
+                    .find(|level| *level > 127)
+                {
+                    return Err(EngineError::InvalidFmLevel(level));
+                }
+                let instrument = self
+                    .project
+                    .instruments
+                    .get_mut(&instrument_id)
+                    .ok_or(EngineError::MissingInstrument(instrument_id))?;
+
+                instrument.fm_params = Some(fm);
+                Ok(())
+            }
+            EngineCommand::SetInstrumentDrums {
+                instrument_id,
+                drums,
+            } => {
+                if let Some(hz) = [drums.kick.start_hz, drums.kick.end_hz, drums.snare.tone_hz]
+                    .into_iter()
+                    .find(|hz| !(DRUM_FREQ_MIN_HZ..=DRUM_FREQ_MAX_HZ).contains(hz))
+                {
+                    return Err(EngineError::InvalidDrumFrequency(hz));
+                }
+                if let Some(level) = [
+                    drums.kick.level,
+                    drums.snare.tone_mix,
+                    drums.snare.level,
+                    drums.hat.cutoff,
+                    drums.hat.level,
+                ]
+                .into_iter()
+                .find(|level| *level > 127)
+                {
+                    return Err(EngineError::InvalidDrumLevel(level));
+                }
+                let instrument = self
+                    .project
+                    .instruments
+                    .get_mut(&instrument_id)
+                    .ok_or(EngineError::MissingInstrument(instrument_id))?;
+
+                instrument.drum_params = Some(drums);
+                Ok(())
+            }
+            EngineCommand::SetInstrumentSample {
+                instrument_id,
+                sample,
+            } => {
+                if let Some(sample) = &sample {
+                    if sample.root_note > 127 {
+                        return Err(EngineError::InvalidSampleRootNote(sample.root_note));
+                    }
+                    for (start, end) in [
+                        (sample.start, sample.end),
+                        (sample.loop_start, sample.loop_end),
+                    ] {
+                        if let Some(end) = end.filter(|end| *end <= start) {
+                            return Err(EngineError::InvalidSampleRange { start, end });
+                        }
+                    }
+                    if let Some(slicing) = &sample.slicing {
+                        let count = slicing.slice_count();
+                        if count == 0 || count > MAX_SAMPLE_SLICES {
+                            return Err(EngineError::InvalidSampleSliceCount(count));
+                        }
+                        if let SampleSlicing::Points(points) = slicing {
+                            let mut previous = sample.start;
+                            for &point in points {
+                                if point <= previous || sample.end.is_some_and(|end| point >= end)
+                                {
+                                    return Err(EngineError::InvalidSampleSlicePoint(point));
+                                }
+                                previous = point;
+                            }
+                        }
+                    }
+                }
+                let instrument = self
+                    .project
+                    .instruments
+                    .get_mut(&instrument_id)
+                    .ok_or(EngineError::MissingInstrument(instrument_id))?;
+
+                instrument.sample = sample;
+                self.sample_revision = self.sample_revision.wrapping_add(1);
+                Ok(())
+            }
             EngineCommand::SetTrackLevel { track_index, level } => {
                 let _track = self
                     .project
@@ -332,6 +619,16 @@ impl Engine {
                 self.project.mixer.track_levels[track_index] = level;
                 Ok(())
             }
+            EngineCommand::SetTrackPan { track_index, pan } => {
+                if track_index >= self.project.song.tracks.len() {
+                    return Err(EngineError::InvalidTrackIndex(track_index));
+                }
+                if pan > PAN_MAX {
+                    return Err(EngineError::InvalidPan(pan));
+                }
+                self.project.mixer.track_pans[track_index] = pan;
+                Ok(())
+            }
             EngineCommand::SetMasterLevel { level } => {
                 self.project.mixer.master_level = level;
                 Ok(())
@@ -342,6 +639,29 @@ impl Engine {
                 self.project.mixer.send_levels.reverb = reverb;
                 Ok(())
             }
+            EngineCommand::SetMixerDelay { delay } => {
+                if !(DELAY_MIN_STEPS..=DELAY_MAX_STEPS).contains(&delay.time_steps) {
+                    return Err(EngineError::InvalidDelayTime(delay.time_steps));
+                }
+                if let Some(level) = [delay.feedback, delay.filter]
+                    .into_iter()
+                    .find(|level| *level > 127)
+                {
+                    return Err(EngineError::InvalidSendFxLevel(level));
+                }
+                self.project.mixer.delay = delay;
+                Ok(())
+            }
+            EngineCommand::SetMixerReverb { reverb } => {
+                if let Some(level) = [reverb.size, reverb.damping, reverb.width]
+                    .into_iter()
+                    .find(|level| *level > 127)
+                {
+                    return Err(EngineError::InvalidSendFxLevel(level));
+                }
+                self.project.mixer.reverb = reverb;
+                Ok(())
+            }
             EngineCommand::UpsertGroove { groove } => {
                 self.project.grooves.insert(groove.id, groove);
                 Ok(())
@@ -354,17 +674,28 @@ impl Engine {
     }
 }
 
-fn normalize_fx(fx: Option<FxCommand>) -> Result<Option<FxCommand>, EngineError> {
+fn validate_swing(swing: u8) -> Result<(), EngineError> {
+    if (SWING_STRAIGHT..=SWING_MAX).contains(&swing) {
+        Ok(())
+    } else {
+        Err(EngineError::InvalidSwing(swing))
+    }
+}
+
+fn normalize_fx(
+    fx: Option<FxCommand>,
+    project: &ProjectData,
+) -> Result<Option<FxCommand>, EngineError> {
     let Some(mut command) = fx else {
         return Ok(None);
     };
 
     command.code = command.code.trim().to_ascii_uppercase();
-    validate_fx_command(&command)?;
+    validate_fx_command(&command, project)?;
     Ok(Some(command))
 }
 
-fn validate_fx_command(command: &FxCommand) -> Result<(), EngineError> {
+fn validate_fx_command(command: &FxCommand, project: &ProjectData) -> Result<(), EngineError> {
     match command.code.as_str() {
         "VOL" => {
             if (1..=127).contains(&command.value) {
@@ -387,6 +718,93 @@ fn validate_fx_command(command: &FxCommand) -> Result<(), EngineError> {
                 Err(EngineError::InvalidFxValue(command.code.clone(), command.value))
             }
         }
+        "GRV" => {
+            if project.grooves.contains_key(&command.value) {
+                Ok(())
+            } else {
+                Err(EngineError::MissingGroove(command.value))
+            }
+        }
+        "CHA" | "ATK" | "REL" => Ok(()),
+        "CHD" => {
+            if command.value != 0 {
+                Ok(())
+            } else {
+                Err(EngineError::InvalidFxValue(command.code.clone(), command.value))
+            }
+        }
+        "VIB" | "TRM" => {
+            if command.value >> 4 != 0 && command.value & 0x0F != 0 {
+                Ok(())
+            } else {
+                Err(EngineError::InvalidFxValue(command.code.clone(), command.value))
+            }
+        }
+        "PAN" => {
+            if command.value <= PAN_MAX {
+                Ok(())
+            } else {
+                Err(EngineError::InvalidFxValue(command.code.clone(), command.value))
+            }
+        }
+        "VSL" => {
+            if (command.value >> 4 == 0) != (command.value & 0x0F == 0) {
+                Ok(())
+            } else {
+                Err(EngineError::InvalidFxValue(command.code.clone(), command.value))
+            }
+        }
+        "TLV" | "GAN" => {
+            if command.value <= 127 {
+                Ok(())
+            } else {
+                Err(EngineError::InvalidFxValue(command.code.clone(), command.value))
+            }
+        }
+        "SND" => {
+            if command.value >> 4 <= 2 {
+                Ok(())
+            } else {
+                Err(EngineError::InvalidFxValue(command.code.clone(), command.value))
+            }
+        }
+        "RNV" => {
+            if (1..=127).contains(&command.value) {
+                Ok(())
+            } else {
+                Err(EngineError::InvalidFxValue(command.code.clone(), command.value))
+            }
+        }
+        "RNN" => {
+            if (1..=48).contains(&command.value) {
+                Ok(())
+            } else {
+                Err(EngineError::InvalidFxValue(command.code.clone(), command.value))
+            }
+        }
+        "HOP" => {
+            if (command.value as usize) < MAX_PHRASE_STEP_COUNT {
+                Ok(())
+            } else {
+                Err(EngineError::InvalidFxValue(command.code.clone(), command.value))
+            }
+        }
+        // The row is the FX byte, so JMP reaches rows 0..=255 even in longer songs.
+        "JMP" => {
+            if (command.value as usize) < project.song.length() {
+                Ok(())
+            } else {
+                Err(EngineError::InvalidFxValue(command.code.clone(), command.value))
+            }
+        }
+        // TPO sets BPM from the FX byte, so it covers 1..=255 while SetTempo goes higher.
+        "SLD" | "ARP" | "RTG" | "DEL" | "KIL" | "TPO" => {
+            if command.value >= 1 {
+                Ok(())
+            } else {
+                Err(EngineError::InvalidFxValue(command.code.clone(), command.value))
+            }
+        }
         _ => Err(EngineError::InvalidFxCode(command.code.clone())),
     }
 }
@@ -394,7 +812,11 @@ fn validate_fx_command(command: &FxCommand) -> Result<(), EngineError> {
 #[cfg(test)]
 mod tests {
     use super::{Engine, EngineCommand, EngineError};
-    use crate::model::{Chain, FxCommand, Phrase, Table};
+    use crate::model::{
+        Chain, DelayParams, DrumSynthParams, FilterMode, FilterParams, FmParams, FxCommand, Groove,
+        Instrument, InstrumentType, Phrase, ReverbParams, SampleLoopMode, SampleParams,
+        SampleSlicing, Table, MAX_PHRASE_STEP_COUNT, MAX_SONG_ROW_COUNT, MAX_TRACK_COUNT,
+    };
 
     fn setup_engine() -> Engine {
         let mut engine = Engine::new("engine");
@@ -452,6 +874,630 @@ mod tests {
         }
     }
 
+    #[test]
+    fn phrase_length_is_bounded_and_resizes_steps() {
+        let mut engine = setup_engine();
+        for length in [0, MAX_PHRASE_STEP_COUNT + 1] {
+            match engine.apply_command(EngineCommand::SetPhraseLength {
+                phrase_id: 0,
+                length,
+            }) {
+                Err(EngineError::InvalidPhraseLength(rejected)) => assert_eq!(rejected, length),
+                other => panic!("unexpected result: {other:?}"),
+            }
+        }
+
+        engine
+            .apply_command(EngineCommand::SetPhraseLength {
+                phrase_id: 0,
+                length: 7,
+            })
+            .unwrap();
+        assert_eq!(engine.snapshot().phrases[&0].length(), 7);
+        assert!(engine
+            .apply_command(EngineCommand::SetPhraseStep {
+                phrase_id: 0,
+                step_index: 7,
+                note: Some(60),
+                velocity: 100,
+                instrument_id: None,
+            })
+            .is_err());
+
+        engine
+            .apply_command(EngineCommand::SetPhraseLength {
+                phrase_id: 0,
+                length: MAX_PHRASE_STEP_COUNT,
+            })
+            .unwrap();
+        engine
+            .apply_command(EngineCommand::SetPhraseStep {
+                phrase_id: 0,
+                step_index: MAX_PHRASE_STEP_COUNT - 1,
+                note: Some(60),
+                velocity: 100,
+                instrument_id: None,
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn track_count_and_song_length_resize_song_and_mixer() {
+        let mut engine = Engine::new("layout");
+        assert!(matches!(
+            engine.apply_command(EngineCommand::SetTrackCount(0)),
+            Err(EngineError::InvalidTrackCount(0))
+        ));
+        assert!(matches!(
+            engine.apply_command(EngineCommand::SetTrackCount(MAX_TRACK_COUNT + 1)),
+            Err(EngineError::InvalidTrackCount(_))
+        ));
+        assert!(matches!(
+            engine.apply_command(EngineCommand::SetSongLength(MAX_SONG_ROW_COUNT + 1)),
+            Err(EngineError::InvalidSongLength(_))
+        ));
+
+        engine.apply_command(EngineCommand::SetSongLength(64)).unwrap();
+        engine.apply_command(EngineCommand::SetTrackCount(12)).unwrap();
+        engine
+            .apply_command(EngineCommand::SetTrackPan {
+                track_index: 11,
+                pan: 0x10,
+            })
+            .unwrap();
+
+        let project = engine.snapshot();
+        assert_eq!(project.song.track_count(), 12);
+        assert_eq!(project.song.length(), 64);
+        assert!(project.song.tracks.iter().all(|track| track.song_rows.len() == 64));
+        assert_eq!(project.song.tracks[11].index, 11);
+        assert_eq!(project.mixer.track_levels.len(), 12);
+        assert_eq!(project.mixer.track_pans[11], 0x10);
+
+        engine.apply_command(EngineCommand::SetTrackCount(2)).unwrap();
+        assert_eq!(engine.snapshot().mixer.track_pans.len(), 2);
+        assert!(matches!(
+            engine.apply_command(EngineCommand::SetSongRowChain {
+                track_index: 0,
+                row: 64,
+                chain_id: Some(0),
+            }),
+            Err(EngineError::InvalidSongRow(64))
+        ));
+        assert!(matches!(
+            engine.apply_command(EngineCommand::SetTrackLevel {
+                track_index: 2,
+                level: 10,
+            }),
+            Err(EngineError::InvalidTrackIndex(2))
+        ));
+    }
+
+    #[test]
+    fn swing_is_bounded_on_song_and_tracks() {
+        let mut engine = Engine::new("swing");
+        assert!(matches!(
+            engine.apply_command(EngineCommand::SetSongSwing(49)),
+            Err(EngineError::InvalidSwing(49))
+        ));
+        assert!(matches!(
+            engine.apply_command(EngineCommand::SetTrackSwingOverride {
+                track_index: 0,
+                swing: Some(76),
+            }),
+            Err(EngineError::InvalidSwing(76))
+        ));
+
+        engine.apply_command(EngineCommand::SetSongSwing(66)).unwrap();
+        engine
+            .apply_command(EngineCommand::SetTrackSwingOverride {
+                track_index: 2,
+                swing: Some(58),
+            })
+            .unwrap();
+        assert_eq!(engine.snapshot().song.swing, 66);
+        assert_eq!(engine.snapshot().song.tracks[2].swing_override, Some(58));
+        assert!(matches!(
+            engine.apply_command(EngineCommand::SetTrackSwingOverride {
+                track_index: 8,
+                swing: None,
+            }),
+            Err(EngineError::InvalidTrackIndex(8))
+        ));
+    }
+
+    #[test]
+    fn flow_fx_targets_are_range_checked() {
+        let mut engine = setup_engine();
+        let rejected = engine.apply_command(EngineCommand::SetStepFx {
+            phrase_id: 0,
+            step_index: 0,
+            fx_slot: 0,
+            fx: Some(FxCommand {
+                code: "HOP".to_string(),
+                value: MAX_PHRASE_STEP_COUNT as u8,
+            }),
+        });
+        match rejected {
+            Err(EngineError::InvalidFxValue(code, _)) => assert_eq!(code, "HOP"),
+            other => panic!("unexpected result: {other:?}"),
+        }
+
+        // Every u8 row fits the default 256-row song, so shorten it to reach the bound.
+        let song_length = 16;
+        engine
+            .apply_command(EngineCommand::SetSongLength(song_length))
+            .unwrap();
+        let rejected = engine.apply_command(EngineCommand::SetStepFx {
+            phrase_id: 0,
+            step_index: 0,
+            fx_slot: 1,
+            fx: Some(FxCommand {
+                code: "JMP".to_string(),
+                value: song_length as u8,
+            }),
+        });
+        match rejected {
+            Err(EngineError::InvalidFxValue(code, _)) => assert_eq!(code, "JMP"),
+            other => panic!("unexpected result: {other:?}"),
+        }
+
+        let last_row = (song_length - 1) as u8;
+        for (fx_slot, code, value) in [(0, "hop", 15), (1, "JMP", last_row)] {
+            engine
+                .apply_command(EngineCommand::SetStepFx {
+                    phrase_id: 0,
+                    step_index: 0,
+                    fx_slot,
+                    fx: Some(FxCommand {
+                        code: code.to_string(),
+                        value,
+                    }),
+                })
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn jmp_fx_reaches_rows_up_to_255_in_longer_songs() {
+        let mut engine = setup_engine();
+        engine
+            .apply_command(EngineCommand::SetSongLength(MAX_SONG_ROW_COUNT))
+            .unwrap();
+
+        let jmp = |value| EngineCommand::SetStepFx {
+            phrase_id: 0,
+            step_index: 0,
+            fx_slot: 0,
+            fx: Some(FxCommand {
+                code: "JMP".to_string(),
+                value,
+            }),
+        };
+        // The highest row JMP can name is 255; rows past it stay valid song rows.
+        engine.apply_command(jmp(u8::MAX)).unwrap();
+        assert_eq!(engine.snapshot().song.length(), MAX_SONG_ROW_COUNT);
+        assert!(u8::MAX as usize + 1 < MAX_SONG_ROW_COUNT);
+    }
+
+    #[test]
+    fn tpo_fx_covers_1_to_255_bpm() {
+        let mut engine = setup_engine();
+        let tpo = |value| EngineCommand::SetStepFx {
+            phrase_id: 0,
+            step_index: 0,
+            fx_slot: 0,
+            fx: Some(FxCommand {
+                code: "TPO".to_string(),
+                value,
+            }),
+        };
+
+        match engine.apply_command(tpo(0)) {
+            Err(EngineError::InvalidFxValue(code, 0)) => assert_eq!(code, "TPO"),
+            other => panic!("unexpected result: {other:?}"),
+        }
+        engine.apply_command(tpo(1)).unwrap();
+        engine.apply_command(tpo(u8::MAX)).unwrap();
+
+        // Tempos past the FX range stay reachable from the song itself.
+        engine.apply_command(EngineCommand::SetTempo(300)).unwrap();
+        assert_eq!(engine.snapshot().song.tempo, 300);
+    }
+
+    #[test]
+    fn groove_fx_requires_an_existing_groove() {
+        let mut engine = setup_engine();
+        let groove_fx = |engine: &mut Engine| {
+            engine.apply_command(EngineCommand::SetStepFx {
+                phrase_id: 0,
+                step_index: 0,
+                fx_slot: 0,
+                fx: Some(FxCommand {
+                    code: "GRV".to_string(),
+                    value: 3,
+                }),
+            })
+        };
+
+        assert!(matches!(groove_fx(&mut engine), Err(EngineError::MissingGroove(3))));
+        engine
+            .apply_command(EngineCommand::UpsertGroove {
+                groove: Groove {
+                    id: 3,
+                    ticks_pattern: vec![6, 4],
+                },
+            })
+            .unwrap();
+        groove_fx(&mut engine).unwrap();
+    }
+
+    #[test]
+    fn instrument_envelope_and_filter_are_validated() {
+        let mut engine = setup_engine();
+        let envelope = |sustain| EngineCommand::SetInstrumentEnvelope {
+            instrument_id: 3,
+            attack_ms: 2,
+            decay_ms: 300,
+            sustain,
+            release_ms: 400,
+        };
+        assert!(matches!(
+            engine.apply_command(envelope(64)),
+            Err(EngineError::MissingInstrument(3))
+        ));
+
+        engine
+            .apply_command(EngineCommand::UpsertInstrument {
+                instrument: Instrument::new(3, InstrumentType::Synth, "Pad"),
+            })
+            .unwrap();
+        assert!(matches!(
+            engine.apply_command(envelope(200)),
+            Err(EngineError::InvalidSustain(200))
+        ));
+        engine.apply_command(envelope(64)).unwrap();
+
+        let filter = FilterParams {
+            mode: FilterMode::BandPass,
+            cutoff: 60,
+            resonance: 128,
+            env_amount: -20,
+        };
+        assert!(matches!(
+            engine.apply_command(EngineCommand::SetInstrumentFilter {
+                instrument_id: 3,
+                filter,
+            }),
+            Err(EngineError::InvalidFilterLevel(128))
+        ));
+        let filter = FilterParams {
+            resonance: 90,
+            ..filter
+        };
+        engine
+            .apply_command(EngineCommand::SetInstrumentFilter {
+                instrument_id: 3,
+                filter,
+            })
+            .unwrap();
+
+        let synth_params = engine.snapshot().instruments[&3].synth_params;
+        assert_eq!((synth_params.decay_ms, synth_params.sustain), (300, 64));
+        assert_eq!(synth_params.filter, filter);
+    }
+
+    #[test]
+    fn fm_params_are_validated() {
+        let mut engine = Engine::new("fm");
+        engine
+            .apply_command(EngineCommand::UpsertInstrument {
+                instrument: Instrument::new(1, InstrumentType::FmSynth, "Bell"),
+            })
+            .unwrap();
+        let set_fm = |fm| EngineCommand::SetInstrumentFm {
+            instrument_id: 1,
+            fm,
+        };
+
+        let mut fm = FmParams {
+            operator_count: 5,
+            ..FmParams::default()
+        };
+        assert!(matches!(
+            engine.apply_command(set_fm(fm)),
+            Err(EngineError::InvalidFmOperatorCount(5))
+        ));
+        fm.operator_count = 3;
+        fm.operators[2].feedback = 200;
+        assert!(matches!(
+            engine.apply_command(set_fm(fm)),
+            Err(EngineError::InvalidFmLevel(200))
+        ));
+        fm.operators[2].feedback = 100;
+        engine.apply_command(set_fm(fm)).unwrap();
+
+        assert_eq!(engine.snapshot().instruments[&1].fm_params, Some(fm));
+    }
+
+    #[test]
+    fn drum_params_are_validated() {
+        let mut engine = Engine::new("drums");
+        engine
+            .apply_command(EngineCommand::UpsertInstrument {
+                instrument: Instrument::new(4, InstrumentType::DrumSynth, "Kit"),
+            })
+            .unwrap();
+        let set_drums = |drums| EngineCommand::SetInstrumentDrums {
+            instrument_id: 4,
+            drums,
+        };
+
+        let mut drums = DrumSynthParams::default();
+        drums.kick.end_hz = 10;
+        assert!(matches!(
+            engine.apply_command(set_drums(drums)),
+            Err(EngineError::InvalidDrumFrequency(10))
+        ));
+        drums.kick.end_hz = 45;
+        drums.hat.cutoff = 140;
+        assert!(matches!(
+            engine.apply_command(set_drums(drums)),
+            Err(EngineError::InvalidDrumLevel(140))
+        ));
+        drums.hat.cutoff = 120;
+        engine.apply_command(set_drums(drums)).unwrap();
+
+        assert_eq!(engine.snapshot().instruments[&4].drum_params, Some(drums));
+    }
+
+    #[test]
+    fn sample_regions_are_validated() {
+        let mut engine = Engine::new("sample");
+        engine
+            .apply_command(EngineCommand::UpsertInstrument {
+                instrument: Instrument::new(2, InstrumentType::Sampler, "Kit"),
+            })
+            .unwrap();
+        let set_sample = |sample| EngineCommand::SetInstrumentSample {
+            instrument_id: 2,
+            sample,
+        };
+
+        let mut sample = SampleParams::new("kit/kick.wav");
+        sample.root_note = 130;
+        assert!(matches!(
+            engine.apply_command(set_sample(Some(sample.clone()))),
+            Err(EngineError::InvalidSampleRootNote(130))
+        ));
+        sample.root_note = 36;
+        sample.loop_start = 400;
+        sample.loop_end = Some(400);
+        assert!(matches!(
+            engine.apply_command(set_sample(Some(sample.clone()))),
+            Err(EngineError::InvalidSampleRange {
+                start: 400,
+                end: 400
+            })
+        ));
+        sample.loop_end = Some(900);
+        sample.loop_mode = SampleLoopMode::Forward;
+        engine
+            .apply_command(set_sample(Some(sample.clone())))
+            .unwrap();
+        assert_eq!(engine.snapshot().instruments[&2].sample, Some(sample));
+
+        engine.apply_command(set_sample(None)).unwrap();
+        assert_eq!(engine.snapshot().instruments[&2].sample, None);
+    }
+
+    #[test]
+    fn sample_revision_moves_on_edits_that_can_change_samples() {
+        let mut engine = Engine::new("revision");
+        engine
+            .apply_command(EngineCommand::UpsertInstrument {
+                instrument: Instrument::new(2, InstrumentType::Sampler, "Kit"),
+            })
+            .unwrap();
+        let upserted = engine.sample_revision();
+        assert_eq!(upserted, 1);
+
+        engine.apply_command(EngineCommand::SetTempo(140)).unwrap();
+        let mut sample = SampleParams::new("kit/kick.wav");
+        sample.root_note = 130;
+        let rejected = engine.apply_command(EngineCommand::SetInstrumentSample {
+            instrument_id: 2,
+            sample: Some(sample),
+        });
+        assert!(rejected.is_err());
+        assert_eq!(engine.sample_revision(), upserted);
+
+        engine
+            .apply_command(EngineCommand::SetInstrumentSample {
+                instrument_id: 2,
+                sample: Some(SampleParams::new("kit/kick.wav")),
+            })
+            .unwrap();
+        assert_eq!(engine.sample_revision(), 2);
+        engine.replace_project(engine.snapshot().clone());
+        assert_eq!(engine.sample_revision(), 3);
+    }
+
+    #[test]
+    fn sample_slices_are_validated() {
+        let mut engine = Engine::new("slices");
+        engine
+            .apply_command(EngineCommand::UpsertInstrument {
+                instrument: Instrument::new(3, InstrumentType::Sampler, "Break"),
+            })
+            .unwrap();
+        let sliced = |slicing| {
+            let mut sample = SampleParams::new("breaks/amen.wav");
+            sample.start = 100;
+            sample.end = Some(10_000);
+            sample.slicing = Some(slicing);
+            EngineCommand::SetInstrumentSample {
+                instrument_id: 3,
+                sample: Some(sample),
+            }
+        };
+
+        assert!(matches!(
+            engine.apply_command(sliced(SampleSlicing::Equal(0))),
+            Err(EngineError::InvalidSampleSliceCount(0))
+        ));
+        assert!(matches!(
+            engine.apply_command(sliced(SampleSlicing::Equal(65))),
+            Err(EngineError::InvalidSampleSliceCount(65))
+        ));
+        let invalid = [(vec![100, 400], 100), (vec![900, 400], 400), (vec![10_000], 10_000)];
+        for (points, bad) in invalid {
+            assert!(matches!(
+                engine.apply_command(sliced(SampleSlicing::Points(points))),
+                Err(EngineError::InvalidSampleSlicePoint(point)) if point == bad
+            ));
+        }
+
+        let points = SampleSlicing::Points(vec![2_500, 5_000, 7_500]);
+        engine.apply_command(sliced(points.clone())).unwrap();
+        let sample = engine.snapshot().instruments[&3].sample.clone().unwrap();
+        assert_eq!(sample.slicing, Some(points));
+    }
+
+    #[test]
+    fn automation_fx_values_are_bounded() {
+        let mut engine = setup_engine();
+        for (code, value) in [("TLV", 128), ("GAN", 200), ("SND", 0x30)] {
+            let rejected = engine.apply_command(EngineCommand::SetStepFx {
+                phrase_id: 0,
+                step_index: 0,
+                fx_slot: 0,
+                fx: Some(FxCommand {
+                    code: code.to_string(),
+                    value,
+                }),
+            });
+            assert!(matches!(rejected, Err(EngineError::InvalidFxValue(_, _))));
+        }
+
+        for (code, value) in [("TLV", 0), ("GAN", 127), ("SND", 0x2F), ("ATK", 0), ("REL", 255)] {
+            engine
+                .apply_command(EngineCommand::SetStepFx {
+                    phrase_id: 0,
+                    step_index: 0,
+                    fx_slot: 0,
+                    fx: Some(FxCommand {
+                        code: code.to_string(),
+                        value,
+                    }),
+                })
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn volume_slide_requires_a_single_direction() {
+        let mut engine = setup_engine();
+        for value in [0x00, 0x44] {
+            let rejected = engine.apply_command(EngineCommand::SetStepFx {
+                phrase_id: 0,
+                step_index: 0,
+                fx_slot: 0,
+                fx: Some(FxCommand {
+                    code: "VSL".to_string(),
+                    value,
+                }),
+            });
+            match rejected {
+                Err(EngineError::InvalidFxValue(code, _)) => assert_eq!(code, "VSL"),
+                other => panic!("unexpected result: {other:?}"),
+            }
+        }
+
+        for value in [0x40, 0x04] {
+            engine
+                .apply_command(EngineCommand::SetStepFx {
+                    phrase_id: 0,
+                    step_index: 0,
+                    fx_slot: 0,
+                    fx: Some(FxCommand {
+                        code: "VSL".to_string(),
+                        value,
+                    }),
+                })
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn table_playback_settings_validate_loop_start() {
+        let mut engine = setup_engine();
+        engine
+            .apply_command(EngineCommand::UpsertTable {
+                table: Table::new(1),
+            })
+            .unwrap();
+
+        let rejected = engine.apply_command(EngineCommand::SetTablePlayback {
+            table_id: 1,
+            speed: 2,
+            groove_id: None,
+            loop_start: 16,
+        });
+        assert!(matches!(rejected, Err(EngineError::InvalidTableRow(16))));
+        let missing = engine.apply_command(EngineCommand::SetTablePlayback {
+            table_id: 9,
+            speed: 2,
+            groove_id: None,
+            loop_start: 0,
+        });
+        assert!(matches!(missing, Err(EngineError::MissingTable(9))));
+
+        engine
+            .apply_command(EngineCommand::SetTablePlayback {
+                table_id: 1,
+                speed: 2,
+                groove_id: Some(3),
+                loop_start: 4,
+            })
+            .unwrap();
+        let table = engine.snapshot().tables.get(&1).unwrap();
+        assert_eq!((table.speed, table.groove_id, table.loop_start), (2, Some(3), 4));
+        assert!(table.runs_per_tick());
+    }
+
+    #[test]
+    fn slide_fx_requires_nonzero_tick_count() {
+        let mut engine = setup_engine();
+        let rejected = engine.apply_command(EngineCommand::SetStepFx {
+            phrase_id: 0,
+            step_index: 0,
+            fx_slot: 0,
+            fx: Some(FxCommand {
+                code: "SLD".to_string(),
+                value: 0,
+            }),
+        });
+        match rejected {
+            Err(EngineError::InvalidFxValue(code, 0)) => assert_eq!(code, "SLD"),
+            other => panic!("unexpected result: {other:?}"),
+        }
+
+        engine
+            .apply_command(EngineCommand::SetStepFx {
+                phrase_id: 0,
+                step_index: 0,
+                fx_slot: 0,
+                fx: Some(FxCommand {
+                    code: "sld".to_string(),
+                    value: 6,
+                }),
+            })
+            .unwrap();
+        let step = &engine.snapshot().phrases.get(&0).unwrap().steps[0];
+        assert_eq!(step.fx[0].as_ref().unwrap().code, "SLD");
+    }
+
     #[test]
     fn table_row_and_mixer_commands_update_state() {
         let mut engine = setup_engine();
@@ -494,4 +1540,44 @@ mod tests {
         assert_eq!(project.mixer.send_levels.delay, 20);
         assert_eq!(project.mixer.send_levels.reverb, 30);
     }
+
+    #[test]
+    fn send_effect_params_are_validated() {
+        let mut engine = setup_engine();
+        let mut delay = DelayParams {
+            time_steps: 0,
+            ..DelayParams::default()
+        };
+        assert!(matches!(
+            engine.apply_command(EngineCommand::SetMixerDelay { delay }),
+            Err(EngineError::InvalidDelayTime(0))
+        ));
+        delay.time_steps = 6;
+        delay.feedback = 130;
+        assert!(matches!(
+            engine.apply_command(EngineCommand::SetMixerDelay { delay }),
+            Err(EngineError::InvalidSendFxLevel(130))
+        ));
+        delay.feedback = 90;
+        delay.ping_pong = true;
+        engine
+            .apply_command(EngineCommand::SetMixerDelay { delay })
+            .unwrap();
+
+        let mut reverb = ReverbParams {
+            width: 200,
+            ..ReverbParams::default()
+        };
+        assert!(matches!(
+            engine.apply_command(EngineCommand::SetMixerReverb { reverb }),
+            Err(EngineError::InvalidSendFxLevel(200))
+        ));
+        reverb.width = 40;
+        engine
+            .apply_command(EngineCommand::SetMixerReverb { reverb })
+            .unwrap();
+
+        assert_eq!(engine.snapshot().mixer.delay, delay);
+        assert_eq!(engine.snapshot().mixer.reverb, reverb);
+    }
 }
@@ -1,13 +1,33 @@
-use crate::model::{InstrumentId, SamplerRenderVariant, SynthWaveform};
+use crate::model::{
+    DrumSynthParams, FilterParams, FmParams, InstrumentId, SamplerRenderVariant, SynthWaveform,
+};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum RenderMode {
     Synth,
     SamplerV1,
+    /// Plays the instrument's sample file; the renderer looks it up by `instrument_id`.
+    Sample,
+    Fm,
+    /// Kick, snare or hat picked by the note; see `DrumSound::for_note`.
+    Drum,
     ExternalMuted,
 }
 
-#[derive(Clone, Debug)]
+/// Mix and synth values a `NoteOn` snapshots; `TrackParams` updates them mid-note.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VoiceParams {
+    pub track_level: u8,
+    pub master_level: u8,
+    pub send_mfx: u8,
+    pub send_delay: u8,
+    pub send_reverb: u8,
+    pub attack_ms: u16,
+    pub release_ms: u16,
+    pub gain: u8,
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub enum RenderEvent {
     NoteOn {
         track_id: u8,
@@ -16,6 +36,7 @@ pub enum RenderEvent {
         render_mode: RenderMode,
         track_level: u8,
         master_level: u8,
+        pan: u8,
         send_mfx: u8,
         send_delay: u8,
         send_reverb: u8,
@@ -23,15 +44,49 @@ pub enum RenderEvent {
         waveform: SynthWaveform,
         attack_ms: u16,
         release_ms: u16,
+        decay_ms: u16,
+        sustain: u8,
+        filter: FilterParams,
         gain: u8,
         sampler_variant: SamplerRenderVariant,
         sampler_transient_level: u8,
         sampler_body_level: u8,
+        fm: FmParams,
+        drum: DrumSynthParams,
     },
     NoteOff {
         track_id: u8,
         note: u8,
     },
+    PitchBend {
+        track_id: u8,
+        note: u8,
+        cents: i16,
+    },
+    NoteVolume {
+        track_id: u8,
+        note: u8,
+        velocity: u8,
+    },
+    /// Applies to every voice sounding on the track.
+    TrackParams {
+        track_id: u8,
+        params: VoiceParams,
+    },
+}
+
+/// A render event from `Scheduler::tick_timed`, due `subtick` sub-ticks into its tick.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TimedRenderEvent {
+    pub subtick: u8,
+    pub event: RenderEvent,
+}
+
+/// A render event from `Scheduler::render_block`, due `frame_offset` frames into the block.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlockRenderEvent {
+    pub frame_offset: u32,
+    pub event: RenderEvent,
 }
 
 #[derive(Clone, Debug, Default)]
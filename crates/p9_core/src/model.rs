@@ -1,9 +1,21 @@
 use std::collections::HashMap;
 
+/// Layout of a new song; `ProjectData::set_track_count` and `Song::set_length` resize it.
 pub const TRACK_COUNT: usize = 8;
+pub const MAX_TRACK_COUNT: usize = 32;
 pub const SONG_ROW_COUNT: usize = 256;
+pub const MAX_SONG_ROW_COUNT: usize = 1024;
 pub const CHAIN_ROW_COUNT: usize = 16;
+/// Length of a new phrase; `Phrase::set_length` allows up to `MAX_PHRASE_STEP_COUNT`.
 pub const PHRASE_STEP_COUNT: usize = 16;
+pub const MAX_PHRASE_STEP_COUNT: usize = 64;
+/// Share of each step pair given to the on-beat step, in percent: 50 plays straight,
+/// 66 is a triplet shuffle and `SWING_MAX` a dotted feel.
+pub const SWING_STRAIGHT: u8 = 50;
+pub const SWING_MAX: u8 = 75;
+/// Pan runs from hard left (0) through centre to hard right (`PAN_MAX`).
+pub const PAN_CENTER: u8 = 0x40;
+pub const PAN_MAX: u8 = 0x80;
 
 pub type ChainId = u8;
 pub type PhraseId = u8;
@@ -19,6 +31,8 @@ pub enum InstrumentType {
     Sampler,
     MidiOut,
     External,
+    FmSynth,
+    DrumSynth,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -27,8 +41,14 @@ pub enum SynthWaveform {
     Square,
     Saw,
     Triangle,
+    Noise,
+    /// Pulse with a duty cycle of `width`/128; 64 is a square.
+    Pulse { width: u8 },
 }
 
+pub const PULSE_WIDTH_MIN: u8 = 1;
+pub const PULSE_WIDTH_MAX: u8 = 127;
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum SamplerRenderVariant {
     Classic,
@@ -53,12 +73,243 @@ impl Default for SamplerRenderParams {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SampleLoopMode {
+    Off,
+    Forward,
+    PingPong,
+}
+
+pub const MAX_SAMPLE_SLICES: usize = 64;
+
+/// Splits the play region into slices that notes pick instead of transposing; the root note
+/// plays slice 0 and each semitone above it the next slice.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SampleSlicing {
+    Equal(u8),
+    /// Cut points inside the play region, ascending; N points make N + 1 slices.
+    Points(Vec<u32>),
+}
+
+impl SampleSlicing {
+    pub fn slice_count(&self) -> usize {
+        match self {
+            Self::Equal(count) => *count as usize,
+            Self::Points(points) => points.len() + 1,
+        }
+    }
+}
+
+/// A WAV file played by a Sampler instrument. Positions are frames of the decoded file; `end`
+/// and `loop_end` are exclusive and `None` means the end of the file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SampleParams {
+    pub path: String,
+    /// The note that plays the file at its recorded pitch.
+    pub root_note: u8,
+    pub start: u32,
+    pub end: Option<u32>,
+    pub loop_start: u32,
+    pub loop_end: Option<u32>,
+    pub loop_mode: SampleLoopMode,
+    pub slicing: Option<SampleSlicing>,
+}
+
+impl SampleParams {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            root_note: 60,
+            start: 0,
+            end: None,
+            loop_start: 0,
+            loop_end: None,
+            loop_mode: SampleLoopMode::Off,
+            slicing: None,
+        }
+    }
+}
+
+pub const FM_OPERATOR_SLOTS: usize = 4;
+pub const FM_MIN_OPERATORS: u8 = 2;
+
+/// One sine operator; `ratio` is in quarters of the note frequency, so 4 plays the note itself.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FmOperator {
+    pub ratio: u8,
+    pub level: u8,
+    pub feedback: u8,
+    pub attack_ms: u16,
+    pub decay_ms: u16,
+    pub sustain: u8,
+    pub release_ms: u16,
+}
+
+impl Default for FmOperator {
+    fn default() -> Self {
+        Self {
+            ratio: 4,
+            level: 127,
+            feedback: 0,
+            attack_ms: 2,
+            decay_ms: 200,
+            sustain: 96,
+            release_ms: 120,
+        }
+    }
+}
+
+/// Operators form a stack: each one phase-modulates the one before it and operator 0 is the
+/// carrier. Only the first `operator_count` slots sound.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FmParams {
+    pub operator_count: u8,
+    pub operators: [FmOperator; FM_OPERATOR_SLOTS],
+}
+
+impl Default for FmParams {
+    fn default() -> Self {
+        let modulator = FmOperator {
+            level: 64,
+            ..FmOperator::default()
+        };
+        Self {
+            operator_count: FM_MIN_OPERATORS,
+            operators: [FmOperator::default(), modulator, modulator, modulator],
+        }
+    }
+}
+
+/// Range of the drum synth's tuned oscillators.
+pub const DRUM_FREQ_MIN_HZ: u16 = 20;
+pub const DRUM_FREQ_MAX_HZ: u16 = 5_000;
+
+/// A sine that sweeps from `start_hz` down to `end_hz` over `sweep_ms` and fades over
+/// `decay_ms`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DrumKickParams {
+    pub start_hz: u16,
+    pub end_hz: u16,
+    pub sweep_ms: u16,
+    pub decay_ms: u16,
+    pub level: u8,
+}
+
+/// A sine body under a noise burst; `tone_mix` 0 is all noise and 127 all tone.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DrumSnareParams {
+    pub tone_hz: u16,
+    pub tone_decay_ms: u16,
+    pub noise_decay_ms: u16,
+    pub tone_mix: u8,
+    pub level: u8,
+}
+
+/// High-passed noise; `cutoff` uses the same 0..=127 scale as `FilterParams`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DrumHatParams {
+    pub cutoff: u8,
+    pub decay_ms: u16,
+    pub level: u8,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DrumSynthParams {
+    pub kick: DrumKickParams,
+    pub snare: DrumSnareParams,
+    pub hat: DrumHatParams,
+}
+
+impl Default for DrumSynthParams {
+    fn default() -> Self {
+        Self {
+            kick: DrumKickParams {
+                start_hz: 180,
+                end_hz: 50,
+                sweep_ms: 40,
+                decay_ms: 350,
+                level: 127,
+            },
+            snare: DrumSnareParams {
+                tone_hz: 190,
+                tone_decay_ms: 90,
+                noise_decay_ms: 180,
+                tone_mix: 48,
+                level: 110,
+            },
+            hat: DrumHatParams {
+                cutoff: 110,
+                decay_ms: 60,
+                level: 90,
+            },
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DrumSound {
+    Kick,
+    Snare,
+    Hat,
+}
+
+impl DrumSound {
+    /// Picks the sound by pitch class in every octave: C and C# kick, D to F snare and F# to
+    /// B hat, so General MIDI's 36, 38 and 42 land where a drummer expects.
+    pub fn for_note(note: u8) -> Self {
+        match note % 12 {
+            0 | 1 => Self::Kick,
+            2..=5 => Self::Snare,
+            _ => Self::Hat,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FilterMode {
+    LowPass,
+    HighPass,
+    BandPass,
+}
+
+/// Per-voice state-variable filter. `cutoff` maps 0..=127 exponentially onto 20 Hz..20 kHz
+/// and `env_amount` moves it by up to that many steps at full envelope; an open low-pass
+/// with no envelope amount is bypassed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FilterParams {
+    pub mode: FilterMode,
+    pub cutoff: u8,
+    pub resonance: u8,
+    pub env_amount: i8,
+}
+
+impl FilterParams {
+    pub fn is_bypassed(&self) -> bool {
+        self.mode == FilterMode::LowPass && self.cutoff >= 127 && self.env_amount == 0
+    }
+}
+
+impl Default for FilterParams {
+    fn default() -> Self {
+        Self {
+            mode: FilterMode::LowPass,
+            cutoff: 127,
+            resonance: 0,
+            env_amount: 0,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct SynthParams {
     pub waveform: SynthWaveform,
     pub attack_ms: u16,
+    /// Time to fall from peak to `sustain`, a level in 0..=127.
+    pub decay_ms: u16,
+    pub sustain: u8,
     pub release_ms: u16,
     pub gain: u8,
+    pub filter: FilterParams,
 }
 
 impl Default for SynthParams {
@@ -66,8 +317,11 @@ impl Default for SynthParams {
         Self {
             waveform: SynthWaveform::Saw,
             attack_ms: 5,
+            decay_ms: 120,
+            sustain: 127,
             release_ms: 80,
             gain: 100,
+            filter: FilterParams::default(),
         }
     }
 }
@@ -78,6 +332,8 @@ pub struct Song {
     pub tempo: u16,
     pub default_groove: GrooveId,
     pub default_scale: ScaleId,
+    pub seed: u32,
+    pub swing: u8,
     pub tracks: Vec<Track>,
 }
 
@@ -93,9 +349,43 @@ impl Song {
             tempo: 120,
             default_groove: 0,
             default_scale: 0,
+            seed: 0,
+            swing: SWING_STRAIGHT,
             tracks,
         }
     }
+
+    pub fn track_count(&self) -> usize {
+        self.tracks.len()
+    }
+
+    /// Every track holds the same number of song rows.
+    pub fn length(&self) -> usize {
+        self.tracks
+            .first()
+            .map_or(SONG_ROW_COUNT, |track| track.song_rows.len())
+    }
+
+    /// Grows with empty rows or truncates; callers validate `1..=MAX_SONG_ROW_COUNT`.
+    pub fn set_length(&mut self, length: usize) {
+        for track in &mut self.tracks {
+            track.song_rows.resize(length, None);
+        }
+    }
+
+    fn set_track_count(&mut self, track_count: usize) {
+        let length = self.length();
+        if track_count < self.tracks.len() {
+            self.tracks.truncate(track_count);
+            return;
+        }
+
+        for index in self.tracks.len()..track_count {
+            let mut track = Track::new(index as u8);
+            track.song_rows.resize(length, None);
+            self.tracks.push(track);
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -106,6 +396,7 @@ pub struct Track {
     pub solo: bool,
     pub groove_override: Option<GrooveId>,
     pub scale_override: Option<ScaleId>,
+    pub swing_override: Option<u8>,
 }
 
 impl Track {
@@ -117,6 +408,7 @@ impl Track {
             solo: false,
             groove_override: None,
             scale_override: None,
+            swing_override: None,
         }
     }
 }
@@ -155,6 +447,15 @@ impl Phrase {
             steps: vec![Step::default(); PHRASE_STEP_COUNT],
         }
     }
+
+    pub fn length(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Grows with empty steps or truncates; callers validate `1..=MAX_PHRASE_STEP_COUNT`.
+    pub fn set_length(&mut self, length: usize) {
+        self.steps.resize(length, Step::default());
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -182,10 +483,14 @@ pub struct Instrument {
     pub instrument_type: InstrumentType,
     pub name: String,
     pub send_levels: SendLevels,
+    pub pan: u8,
     pub table_id: Option<TableId>,
     pub note_length_steps: u8,
     pub synth_params: SynthParams,
     pub sampler_render: Option<SamplerRenderParams>,
+    pub fm_params: Option<FmParams>,
+    pub sample: Option<SampleParams>,
+    pub drum_params: Option<DrumSynthParams>,
 }
 
 impl Instrument {
@@ -195,10 +500,14 @@ impl Instrument {
             instrument_type,
             name: name.into(),
             send_levels: SendLevels::default(),
+            pan: PAN_CENTER,
             table_id: None,
             note_length_steps: 1,
             synth_params: SynthParams::default(),
             sampler_render: None,
+            fm_params: None,
+            sample: None,
+            drum_params: None,
         }
     }
 }
@@ -207,6 +516,10 @@ impl Instrument {
 pub struct Table {
     pub id: TableId,
     pub rows: Vec<TableRow>,
+    /// Ticks per row; 0 keeps the table locked to phrase steps.
+    pub speed: u8,
+    pub groove_id: Option<GrooveId>,
+    pub loop_start: u8,
 }
 
 impl Table {
@@ -214,8 +527,15 @@ impl Table {
         Self {
             id,
             rows: vec![TableRow::default(); CHAIN_ROW_COUNT],
+            speed: 0,
+            groove_id: None,
+            loop_start: 0,
         }
     }
+
+    pub fn runs_per_tick(&self) -> bool {
+        self.speed > 0 || self.groove_id.is_some()
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -248,19 +568,77 @@ pub struct Scale {
     pub interval_mask: u16,
 }
 
+/// Holds one level and pan per song track; `ProjectData::set_track_count` keeps them in step.
 #[derive(Clone, Debug)]
 pub struct Mixer {
-    pub track_levels: [u8; TRACK_COUNT],
+    pub track_levels: Vec<u8>,
+    pub track_pans: Vec<u8>,
     pub master_level: u8,
     pub send_levels: SendLevels,
+    pub delay: DelayParams,
+    pub reverb: ReverbParams,
+}
+
+impl Mixer {
+    fn set_track_count(&mut self, track_count: usize) {
+        self.track_levels.resize(track_count, 0x80);
+        self.track_pans.resize(track_count, PAN_CENTER);
+    }
 }
 
 impl Default for Mixer {
     fn default() -> Self {
         Self {
-            track_levels: [0x80; TRACK_COUNT],
+            track_levels: vec![0x80; TRACK_COUNT],
+            track_pans: vec![PAN_CENTER; TRACK_COUNT],
             master_level: 0x80,
             send_levels: SendLevels::default(),
+            delay: DelayParams::default(),
+            reverb: ReverbParams::default(),
+        }
+    }
+}
+
+pub const DELAY_MIN_STEPS: u8 = 1;
+pub const DELAY_MAX_STEPS: u8 = 32;
+
+/// The send delay. `time_steps` counts sixteenth-note steps at the current tempo, capped at
+/// four seconds, `filter` is a low-pass in the feedback path on the `FilterParams` cutoff
+/// scale, and ping-pong bounces the repeats between the left and right channels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DelayParams {
+    pub time_steps: u8,
+    pub feedback: u8,
+    pub filter: u8,
+    pub ping_pong: bool,
+}
+
+impl Default for DelayParams {
+    fn default() -> Self {
+        Self {
+            time_steps: 3,
+            feedback: 56,
+            filter: 100,
+            ping_pong: false,
+        }
+    }
+}
+
+/// The send reverb, a Freeverb-style network; every value runs 0..=127.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReverbParams {
+    pub size: u8,
+    pub damping: u8,
+    /// Stereo spread of the tail; 0 is mono.
+    pub width: u8,
+}
+
+impl Default for ReverbParams {
+    fn default() -> Self {
+        Self {
+            size: 88,
+            damping: 64,
+            width: 127,
         }
     }
 }
@@ -303,4 +681,10 @@ impl ProjectData {
             mixer: Mixer::default(),
         }
     }
+
+    /// Resizes song tracks and mixer strips together; callers validate `1..=MAX_TRACK_COUNT`.
+    pub fn set_track_count(&mut self, track_count: usize) {
+        self.song.set_track_count(track_count);
+        self.mixer.set_track_count(track_count);
+    }
 }
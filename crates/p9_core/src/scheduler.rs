@@ -1,10 +1,24 @@
 use crate::engine::Engine;
-use crate::events::{RenderEvent, RenderMode};
+use crate::events::{BlockRenderEvent, RenderEvent, RenderMode, TimedRenderEvent, VoiceParams};
 use crate::model::{
-    ChainId, FxCommand, InstrumentId, InstrumentType, ProjectData, SamplerRenderParams, Scale,
-    SynthParams, PHRASE_STEP_COUNT, SONG_ROW_COUNT, TRACK_COUNT,
+    ChainId, DrumSynthParams, FmParams, FxCommand, GrooveId, InstrumentId, InstrumentType,
+    ProjectData, SamplerRenderParams, Scale, Step, SynthParams, SynthWaveform, Table, TableId,
+    TableRow, CHAIN_ROW_COUNT, DRUM_FREQ_MAX_HZ, DRUM_FREQ_MIN_HZ, FM_MIN_OPERATORS,
+    FM_OPERATOR_SLOTS, PAN_CENTER, PAN_MAX, PHRASE_STEP_COUNT, SWING_MAX, SWING_STRAIGHT,
+    TRACK_COUNT,
 };
 
+/// Swing places steps on a grid this much finer than a tick.
+pub const SUBTICKS_PER_TICK: u32 = 16;
+
+/// Audio frames per tick, unrounded so block rendering does not drift.
+pub fn frames_per_tick(sample_rate_hz: u32, tempo: u16, ppq: u16) -> f64 {
+    let ticks_per_second = tempo.max(1) as f64 * ppq.max(1) as f64 / 60.0;
+    sample_rate_hz as f64 / ticks_per_second
+}
+
+const VIBRATO_CENTS_PER_DEPTH: i32 = 10;
+
 #[derive(Clone, Debug, Default)]
 pub struct TrackPlaybackState {
     pub song_row: usize,
@@ -12,32 +26,295 @@ pub struct TrackPlaybackState {
     pub phrase_step: usize,
     pub tick_in_step: u8,
     pub active_note: Option<u8>,
+    /// Bit n set: a chord voice sounds n semitones above `active_note`.
+    pub chord_mask: u16,
     pub note_steps_remaining: Option<u8>,
+    pub last_note: Option<u8>,
+    pub pitch_slide: Option<PitchSlideState>,
+    pub arpeggio: Option<ArpeggioState>,
+    pub pitch_cents: i16,
+    pub note_delay_ticks: Option<u8>,
+    pub retrigger_ticks: Option<u8>,
+    pub kill_ticks: Option<u8>,
+    pub hop_to_step: Option<u8>,
+    pub song_jump_row: Option<u8>,
+    pub groove_fx: Option<GrooveId>,
+    pub table: Option<TablePlaybackState>,
+    pub vibrato: Option<LfoState>,
+    pub tremolo: Option<LfoState>,
+    pub volume_slide: Option<VolumeSlideState>,
+    /// Sub-ticks the current step trails its first tick by, left over from swing timing.
+    pub swing_delay_subticks: u8,
+    /// Song row this track launches at its next `LaunchQuantize` boundary.
+    pub queued_song_row: Option<usize>,
+    pub param_automation: ParamAutomation,
+    pub active_instrument: Option<InstrumentId>,
+    /// What the sounding voices were last told, by `NoteOn` or `TrackParams`.
+    pub voice_params: Option<VoiceParams>,
+    pub note_velocity: u8,
+    pub current_velocity: u8,
+    /// The last triggered note after random FX, replayed unchanged by `RTG`.
+    retrigger_step: Option<StepPlaybackData>,
+}
+
+/// Boundary at which a queued song row takes over a track.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LaunchQuantize {
+    #[default]
+    Chain,
+    Phrase,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PitchSlideState {
+    pub from_cents: i16,
+    pub ticks_total: u8,
+    pub ticks_elapsed: u8,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LfoState {
+    pub speed: u8,
+    pub depth: u8,
+    pub phase: u8,
+}
+
+impl LfoState {
+    fn from_fx_value(value: u8) -> Self {
+        Self {
+            speed: value >> 4,
+            depth: value & 0x0F,
+            phase: 0,
+        }
+    }
+
+    /// Triangle in -128..=128, starting at zero.
+    fn bipolar(&self) -> i32 {
+        lfo_triangle(self.phase)
+    }
+
+    /// Triangle in 0..=256, starting at zero.
+    fn unipolar(&self) -> i32 {
+        128 - lfo_triangle(self.phase.wrapping_add(64))
+    }
+
+    fn advance(&mut self) {
+        self.phase = self.phase.wrapping_add(self.speed.wrapping_mul(4));
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct VolumeSlideState {
+    pub per_tick: i8,
+    pub offset: i16,
+}
+
+impl VolumeSlideState {
+    /// High nibble slides up, low nibble slides down, in velocity units per tick.
+    fn from_fx_value(value: u8) -> Self {
+        Self {
+            per_tick: (value >> 4) as i8 - (value & 0x0F) as i8,
+            offset: 0,
+        }
+    }
+
+    fn advance(&mut self) {
+        self.offset = (self.offset + self.per_tick as i16).clamp(-127, 127);
+    }
+}
+
+/// Values set by the TLV, SND, ATK, REL and GAN FX. They stay in force for later notes
+/// until playback rewinds or seeks.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ParamAutomation {
+    pub track_level: Option<u8>,
+    pub send_mfx: Option<u8>,
+    pub send_delay: Option<u8>,
+    pub send_reverb: Option<u8>,
+    pub attack_ms: Option<u16>,
+    pub release_ms: Option<u16>,
+    pub gain: Option<u8>,
+}
+
+impl ParamAutomation {
+    fn apply_fx_commands(&mut self, commands: &[Option<FxCommand>]) {
+        for command in commands.iter().flatten() {
+            match command.code.as_str() {
+                "TLV" => self.track_level = Some(command.value.min(127)),
+                // High nibble picks MFX, delay or reverb; low nibble is the level in 16ths.
+                "SND" => {
+                    let level = Some(((command.value & 0x0F) as u16 * 127 / 15) as u8);
+                    match command.value >> 4 {
+                        0 => self.send_mfx = level,
+                        1 => self.send_delay = level,
+                        _ => self.send_reverb = level,
+                    }
+                }
+                "ATK" => self.attack_ms = Some(command.value as u16),
+                "REL" => self.release_ms = Some(command.value as u16),
+                "GAN" => self.gain = Some(command.value.min(127)),
+                _ => {}
+            }
+        }
+    }
+
+    fn apply(&self, params: VoiceParams) -> VoiceParams {
+        VoiceParams {
+            track_level: self.track_level.unwrap_or(params.track_level),
+            master_level: params.master_level,
+            send_mfx: self.send_mfx.unwrap_or(params.send_mfx),
+            send_delay: self.send_delay.unwrap_or(params.send_delay),
+            send_reverb: self.send_reverb.unwrap_or(params.send_reverb),
+            attack_ms: self.attack_ms.unwrap_or(params.attack_ms),
+            release_ms: self.release_ms.unwrap_or(params.release_ms),
+            gain: self.gain.unwrap_or(params.gain),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TablePlaybackState {
+    pub table_id: TableId,
+    pub row: u8,
+    pub ticks_in_row: u8,
+    pub base_note: i16,
+    pub base_velocity: u8,
+    pub origin_note: i16,
+    pub cents: i16,
+    pub velocity: u8,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ArpeggioState {
+    pub offsets: [u8; 2],
+    pub phase: u8,
+}
+
+impl ArpeggioState {
+    fn from_fx_value(value: u8) -> Self {
+        Self {
+            offsets: [value >> 4, value & 0x0F],
+            phase: 0,
+        }
+    }
+
+    fn current_offset_semitones(&self) -> u8 {
+        match self.phase % 3 {
+            0 => 0,
+            1 => self.offsets[0],
+            _ => self.offsets[1],
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
 struct StepPlaybackData {
     track_id: u8,
     note: u8,
+    chord_mask: u16,
     velocity: u8,
     render_mode: RenderMode,
-    track_level: u8,
-    master_level: u8,
-    send_mfx: u8,
-    send_delay: u8,
-    send_reverb: u8,
+    pan: u8,
+    voice_params: VoiceParams,
     sampler_render: SamplerRenderParams,
+    fm: FmParams,
+    drum: DrumSynthParams,
     instrument_id: Option<InstrumentId>,
     note_length_steps: u8,
+    slide_ticks: Option<u8>,
+    arpeggio: Option<u8>,
+    delay_ticks: Option<u8>,
+    retrigger_ticks: Option<u8>,
+    kill_ticks: Option<u8>,
+    chance: Option<u8>,
+    random_velocity: Option<u8>,
+    random_note: Option<u8>,
+    tick_table: Option<TickTableStart>,
+    vibrato: Option<u8>,
+    tremolo: Option<u8>,
+    volume_slide: Option<u8>,
     synth_params: SynthParams,
 }
 
+#[derive(Clone, Copy, Debug)]
+struct TickTableStart {
+    table_id: TableId,
+    base_note: i16,
+    base_velocity: u8,
+    origin_note: i16,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct StepFxOutcome {
+    note_i16: i16,
+    chord_mask: u16,
+    velocity: u8,
+    note_length_steps: u8,
+    slide_ticks: Option<u8>,
+    arpeggio: Option<u8>,
+    delay_ticks: Option<u8>,
+    retrigger_ticks: Option<u8>,
+    kill_ticks: Option<u8>,
+    chance: Option<u8>,
+    random_velocity: Option<u8>,
+    random_note: Option<u8>,
+    vibrato: Option<u8>,
+    tremolo: Option<u8>,
+    volume_slide: Option<u8>,
+    pan: Option<u8>,
+}
+
+/// SplitMix32-style generator; every seed, including zero, yields a usable stream.
+#[derive(Clone, Copy, Debug)]
+struct StepRng {
+    state: u32,
+}
+
+impl StepRng {
+    fn new(seed: u32) -> Self {
+        Self { state: seed }
+    }
+
+    /// Hashes the track index into the seed, so each track draws from its own stream.
+    fn for_track(seed: u32, track_index: usize) -> Self {
+        let mut mixer = Self::new(seed ^ (track_index as u32).wrapping_mul(0x85EB_CA6B));
+        Self::new(mixer.next_u32())
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        self.state = self.state.wrapping_add(0x9E37_79B9);
+        let mut z = self.state;
+        z = (z ^ (z >> 16)).wrapping_mul(0x85EB_CA6B);
+        z = (z ^ (z >> 13)).wrapping_mul(0xC2B2_AE35);
+        z ^ (z >> 16)
+    }
+
+    fn roll_chance(&mut self, chance: u8) -> bool {
+        (self.next_u32() % 255) < chance as u32
+    }
+
+    fn offset_within(&mut self, range: u8) -> i16 {
+        let span = range as u32 * 2 + 1;
+        (self.next_u32() % span) as i16 - range as i16
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct StepControlFx {
+    hop_to_step: Option<u8>,
+    song_jump_row: Option<u8>,
+    tempo: Option<u16>,
+    groove: Option<GrooveId>,
+}
+
 #[derive(Clone, Copy, Debug)]
 struct InstrumentPlaybackProfile {
     note_length_steps: u8,
     synth_params: SynthParams,
     render_mode: RenderMode,
     sampler_render: SamplerRenderParams,
+    fm: FmParams,
+    drum: DrumSynthParams,
 }
 
 pub struct Scheduler {
@@ -46,6 +323,19 @@ pub struct Scheduler {
     pub current_tick: u64,
     pub is_playing: bool,
     pub track_state: Vec<TrackPlaybackState>,
+    pending_song_jump: Option<usize>,
+    tempo_override: Option<u16>,
+    /// Seed the streams were built from, and one stream per track.
+    rng: Option<(u32, Vec<StepRng>)>,
+    loop_range: Option<(usize, usize)>,
+    live_mode: bool,
+    launch_quantize: LaunchQuantize,
+    pending_releases: Vec<RenderEvent>,
+    /// Frames from the start of the next `render_block` call until the next tick fires.
+    block_next_tick_at: f64,
+    /// Events whose sub-tick offset fell past the end of the last block, in frames from the
+    /// start of the next one.
+    block_carry: Vec<(f64, RenderEvent)>,
 }
 
 impl Scheduler {
@@ -58,6 +348,15 @@ impl Scheduler {
             current_tick: 0,
             is_playing: true,
             track_state: vec![TrackPlaybackState::default(); TRACK_COUNT],
+            pending_song_jump: None,
+            tempo_override: None,
+            rng: None,
+            loop_range: None,
+            live_mode: false,
+            launch_quantize: LaunchQuantize::Chain,
+            pending_releases: Vec::new(),
+            block_next_tick_at: 0.0,
+            block_carry: Vec::new(),
         }
     }
 
@@ -65,29 +364,137 @@ impl Scheduler {
         self.is_playing = true;
     }
 
+    /// Stops ticking. Swung NoteOns still carried toward a later block are dropped so they
+    /// cannot sound after the stop; carried releases still drain.
     pub fn stop(&mut self) {
         self.is_playing = false;
+        self.block_carry
+            .retain(|(_, event)| !matches!(event, RenderEvent::NoteOn { .. }));
     }
 
     pub fn rewind(&mut self) {
+        self.reset_block_clock();
         self.current_tick = 0;
+        self.pending_song_jump = None;
+        self.tempo_override = None;
+        self.rng = None;
         for state in &mut self.track_state {
             *state = TrackPlaybackState::default();
         }
     }
 
+    /// Seeks to the moment a straight playthrough brings `track_index` to `song_row`/
+    /// `chain_row`/`step` and sets `current_tick` to it. Tracks advance independently, so every
+    /// other track lands where its own rows have taken it by then. When `track_index` does not
+    /// play the position, the first track that does sets the time. Flow FX such as HOP, JMP and
+    /// TPO are not replayed.
+    pub fn seek(
+        &mut self,
+        project: &ProjectData,
+        track_index: usize,
+        song_row: usize,
+        chain_row: usize,
+        step: usize,
+    ) {
+        let song_row = song_row.min(project.song.length() - 1);
+        let chain_row = chain_row.min(CHAIN_ROW_COUNT - 1);
+        let timing_track = std::iter::once(track_index)
+            .chain(0..project.song.tracks.len())
+            .find(|track| self.is_chain_row_playable(project, *track, song_row, chain_row))
+            .unwrap_or(track_index);
+
+        let target =
+            self.track_subticks_to_position(project, timing_track, song_row, chain_row, step);
+        self.seek_subticks(project, target, (song_row, chain_row, step));
+    }
+
+    /// Seeks to `tick`, as an incoming song position pointer asks for, placing each track on
+    /// the step it plays at that tick. Returns false, leaving playback alone, when `tick` lies
+    /// past the end of the song's longest track.
+    pub fn seek_to_tick(&mut self, project: &ProjectData, tick: u64) -> bool {
+        let target = tick.saturating_mul(SUBTICKS_PER_TICK as u64);
+        let song_subticks = (0..project.song.tracks.len())
+            .map(|track_index| self.track_song_subticks(project, track_index))
+            .max()
+            .unwrap_or(0);
+        if target >= song_subticks {
+            return false;
+        }
+        self.seek_subticks(project, target, (0, 0, 0));
+        true
+    }
+
+    /// Restricts song playback to rows `start..=end`; rows past the song end are ignored.
+    pub fn set_loop_range(&mut self, range: Option<(usize, usize)>) {
+        self.loop_range = range.map(|(start, end)| (start.min(end), start.max(end)));
+    }
+
+    pub fn loop_range(&self) -> Option<(usize, usize)> {
+        self.loop_range
+    }
+
+    /// In live mode a track loops its current song row until another row is queued.
+    pub fn set_live_mode(&mut self, enabled: bool) {
+        self.live_mode = enabled;
+    }
+
+    pub fn live_mode(&self) -> bool {
+        self.live_mode
+    }
+
+    pub fn set_launch_quantize(&mut self, quantize: LaunchQuantize) {
+        self.launch_quantize = quantize;
+    }
+
+    pub fn launch_quantize(&self) -> LaunchQuantize {
+        self.launch_quantize
+    }
+
+    /// Returns false when the track does not exist yet.
+    pub fn queue_song_row(&mut self, track_index: usize, song_row: Option<usize>) -> bool {
+        let Some(state) = self.track_state.get_mut(track_index) else {
+            return false;
+        };
+        state.queued_song_row = song_row;
+        true
+    }
+
+    pub fn effective_tempo(&self, engine: &Engine) -> u16 {
+        self.tempo_override.unwrap_or(engine.snapshot().song.tempo)
+    }
+
     pub fn tick(&mut self, engine: &Engine) -> Vec<RenderEvent> {
+        self.tick_timed(engine)
+            .into_iter()
+            .map(|timed| timed.event)
+            .collect()
+    }
+
+    /// Runs one tick like `tick`, tagging every event with the swing delay of the step that
+    /// produced it. Events keep `tick` order, which is not sorted by offset.
+    pub fn tick_timed(&mut self, engine: &Engine) -> Vec<TimedRenderEvent> {
         if !self.is_playing {
             return Vec::new();
         }
 
         let project = engine.snapshot();
-        let mut out = Vec::new();
+        let mut out = std::mem::take(&mut self.pending_releases);
+        let mut subticks = Vec::new();
+
+        let seed = project.song.seed;
+        if !matches!(self.rng, Some((rng_seed, _)) if rng_seed == seed) {
+            self.rng = Some((seed, Vec::new()));
+        }
+
+        self.sync_track_count(project.song.tracks.len(), &mut out);
+        subticks.resize(out.len(), 0);
 
         for track_index in 0..project.song.tracks.len() {
+            let swing_delay = self.track_state[track_index].swing_delay_subticks;
             if !self.track_is_audible(project, track_index) {
                 self.force_note_off_if_active(project, track_index, &mut out);
                 self.advance_one_tick(project, track_index);
+                subticks.resize(out.len(), swing_delay);
                 continue;
             }
 
@@ -95,15 +502,95 @@ impl Scheduler {
 
             if self.track_state[track_index].tick_in_step == 0 {
                 self.process_step_boundary(project, track_index, &mut out);
+            } else {
+                self.process_tick_offset_fx(project, track_index, &mut out);
             }
 
+            self.process_tick_table(project, track_index);
+            self.process_param_automation(project, track_index, &mut out);
+            self.process_pitch_modulation(project, track_index, &mut out);
+            self.process_volume_modulation(project, track_index, &mut out);
             self.advance_one_tick(project, track_index);
+            subticks.resize(out.len(), swing_delay);
+        }
+
+        if let Some(song_row) = self.pending_song_jump.take() {
+            let song_row = song_row.min(project.song.length() - 1);
+            self.jump_all_tracks_to_song_row(project, song_row, &mut out);
+            subticks.resize(out.len(), 0);
         }
 
         self.current_tick = self.current_tick.saturating_add(1);
+        subticks
+            .into_iter()
+            .zip(out)
+            .map(|(subtick, event)| TimedRenderEvent { subtick, event })
+            .collect()
+    }
+
+    /// Advances playback by `frames` audio frames, running every tick that starts inside the
+    /// block, and returns the due events sorted by frame. Tick length follows the effective
+    /// tempo, so the block size is independent of the PPQ. A zero sample rate has no tick
+    /// length, so nothing plays.
+    pub fn render_block(
+        &mut self,
+        engine: &Engine,
+        sample_rate_hz: u32,
+        frames: u32,
+    ) -> Vec<BlockRenderEvent> {
+        if sample_rate_hz == 0 {
+            return Vec::new();
+        }
+
+        let block_end = frames as f64;
+        let mut due = std::mem::take(&mut self.block_carry);
+
+        while self.block_next_tick_at < block_end {
+            let tick_start = self.block_next_tick_at;
+            let events = self.tick_timed(engine);
+            // As in offline export, TPO FX take effect from the tick that sets them.
+            let tempo = self.effective_tempo(engine);
+            let tick_frames = frames_per_tick(sample_rate_hz, tempo, self.ppq);
+            for timed in events {
+                let offset = timed.subtick as f64 * tick_frames / SUBTICKS_PER_TICK as f64;
+                due.push((tick_start + offset, timed.event));
+            }
+            self.block_next_tick_at += tick_frames;
+        }
+        self.block_next_tick_at -= block_end;
+
+        let mut out = Vec::new();
+        for (at, event) in due {
+            if at < block_end {
+                out.push(BlockRenderEvent {
+                    frame_offset: at as u32,
+                    event,
+                });
+            } else {
+                self.block_carry.push((at - block_end, event));
+            }
+        }
+        out.sort_by_key(|event| event.frame_offset);
         out
     }
 
+    /// Frames from the start of the next `render_block` call until the next tick fires.
+    pub fn frames_until_next_tick(&self) -> f64 {
+        self.block_next_tick_at
+    }
+
+    /// Restarts the block clock for a jump in the transport: the next tick fires at the start
+    /// of the next block, and carried events from the old position are dropped except for
+    /// releases, which go out with the next tick.
+    fn reset_block_clock(&mut self) {
+        self.block_next_tick_at = 0.0;
+        for (_, event) in std::mem::take(&mut self.block_carry) {
+            if matches!(event, RenderEvent::NoteOff { .. }) {
+                self.pending_releases.push(event);
+            }
+        }
+    }
+
     fn track_is_audible(&self, project: &ProjectData, track_index: usize) -> bool {
         let has_solo = project.song.tracks.iter().any(|track| track.solo);
         let track = &project.song.tracks[track_index];
@@ -137,155 +624,564 @@ impl Scheduler {
         track_index: usize,
         out: &mut Vec<RenderEvent>,
     ) {
+        // Arpeggios and tick-offset FX only apply within the step that set them.
+        let state = &mut self.track_state[track_index];
+        state.arpeggio = None;
+        state.note_delay_ticks = None;
+        state.retrigger_ticks = None;
+        state.kill_ticks = None;
+        let control = self.resolve_control_fx(project, track_index);
+        if control.tempo.is_some() {
+            self.tempo_override = control.tempo;
+        }
+        let state = &mut self.track_state[track_index];
+        state.hop_to_step = control.hop_to_step;
+        state.song_jump_row = control.song_jump_row;
+        if control.groove.is_some() {
+            state.groove_fx = control.groove;
+        }
+        if let Some(step) = self.current_step(project, track_index) {
+            self.track_state[track_index]
+                .param_automation
+                .apply_fx_commands(&step.fx);
+        }
         self.emit_scheduled_note_off(project, track_index, out);
 
         let Some(step_data) = self.resolve_step_data(project, track_index) else {
             return;
         };
 
-        self.force_note_off_if_active(project, track_index, out);
-
-        out.push(RenderEvent::NoteOn {
-            track_id: step_data.track_id,
-            note: step_data.note,
-            velocity: step_data.velocity,
-            render_mode: step_data.render_mode,
-            track_level: step_data.track_level,
-            master_level: step_data.master_level,
-            send_mfx: step_data.send_mfx,
-            send_delay: step_data.send_delay,
-            send_reverb: step_data.send_reverb,
-            instrument_id: step_data.instrument_id,
-            waveform: step_data.synth_params.waveform,
-            attack_ms: step_data.synth_params.attack_ms,
-            release_ms: step_data.synth_params.release_ms,
-            gain: step_data.synth_params.gain,
-            sampler_variant: step_data.sampler_render.variant,
-            sampler_transient_level: step_data.sampler_render.transient_level,
-            sampler_body_level: step_data.sampler_render.body_level,
-        });
+        if let Some(chance) = step_data.chance {
+            if !self.rng_mut(track_index).roll_chance(chance) {
+                return;
+            }
+        }
 
+        // A delay past the step's last tick would never fire, so it lands on that tick.
+        let (step_ticks, _) = self.current_step_timing(project, track_index);
+        let delay = step_data
+            .delay_ticks
+            .map(|delay| delay.min(step_ticks.saturating_sub(1)))
+            .filter(|delay| *delay > 0);
         let state = &mut self.track_state[track_index];
-        state.active_note = Some(step_data.note);
-        state.note_steps_remaining = Some(step_data.note_length_steps.max(1));
+        state.retrigger_ticks = step_data.retrigger_ticks;
+        state.kill_ticks = step_data.kill_ticks;
+        if delay.is_some() {
+            state.note_delay_ticks = delay;
+            return;
+        }
+
+        self.trigger_step_note(project, track_index, step_data, out);
     }
 
-    fn emit_scheduled_note_off(
+    /// Streams are created on first use; a track's draws never depend on other tracks.
+    fn rng_mut(&mut self, track_index: usize) -> &mut StepRng {
+        let (seed, streams) = self.rng.get_or_insert((0, Vec::new()));
+        while streams.len() <= track_index {
+            streams.push(StepRng::for_track(*seed, streams.len()));
+        }
+        &mut streams[track_index]
+    }
+
+    fn apply_random_fx(
+        &mut self,
+        project: &ProjectData,
+        track_index: usize,
+        mut step_data: StepPlaybackData,
+    ) -> StepPlaybackData {
+        if let Some(range) = step_data.random_velocity {
+            let offset = self.rng_mut(track_index).offset_within(range);
+            step_data.velocity = (step_data.velocity as i16 + offset).clamp(1, 127) as u8;
+        }
+
+        if let Some(range) = step_data.random_note {
+            let offset = self.rng_mut(track_index).offset_within(range);
+            let note = (step_data.note as i16 + offset).clamp(0, 127) as u8;
+            step_data.note = self.apply_scale(project, track_index, note);
+        }
+
+        step_data
+    }
+
+    fn process_tick_offset_fx(
         &mut self,
         project: &ProjectData,
         track_index: usize,
         out: &mut Vec<RenderEvent>,
     ) {
-        let (track_id, active_note, note_steps_remaining) = {
-            let Some(track) = project.song.tracks.get(track_index) else {
-                return;
-            };
+        let state = &self.track_state[track_index];
+        let tick = state.tick_in_step;
+
+        let delayed_note_due = state.note_delay_ticks == Some(tick);
+        let retrigger_due = state.active_note.is_some()
+            && state.note_delay_ticks.is_none()
+            && state
+                .retrigger_ticks
+                .is_some_and(|interval| tick.is_multiple_of(interval));
+
+        if delayed_note_due {
+            self.track_state[track_index].note_delay_ticks = None;
+            if let Some(step_data) = self.resolve_step_data(project, track_index) {
+                self.trigger_step_note(project, track_index, step_data, out);
+            }
+        } else if retrigger_due {
+            // Retriggers repeat the resolved note instead of re-rolling RNV and RNN.
             let state = &self.track_state[track_index];
-            (track.index, state.active_note, state.note_steps_remaining)
+            if let Some(mut step_data) = state.retrigger_step {
+                // Level and send automation since the first hit still applies.
+                step_data.voice_params = state.voice_params.unwrap_or(step_data.voice_params);
+                self.start_note(project, track_index, step_data, out);
+            }
+        }
+
+        if self.track_state[track_index].kill_ticks == Some(tick) {
+            self.force_note_off_if_active(project, track_index, out);
+        }
+    }
+
+    fn trigger_step_note(
+        &mut self,
+        project: &ProjectData,
+        track_index: usize,
+        step_data: StepPlaybackData,
+        out: &mut Vec<RenderEvent>,
+    ) {
+        let mut step_data = self.apply_random_fx(project, track_index, step_data);
+        step_data.chord_mask =
+            self.scale_chord_mask(project, track_index, step_data.note, step_data.chord_mask);
+        self.start_note(project, track_index, step_data, out);
+    }
+
+    fn start_note(
+        &mut self,
+        project: &ProjectData,
+        track_index: usize,
+        step_data: StepPlaybackData,
+        out: &mut Vec<RenderEvent>,
+    ) {
+        self.force_note_off_if_active(project, track_index, out);
+
+        for note in chord_notes(step_data.note, step_data.chord_mask) {
+            out.push(RenderEvent::NoteOn {
+                track_id: step_data.track_id,
+                note,
+                velocity: step_data.velocity,
+                render_mode: step_data.render_mode,
+                track_level: step_data.voice_params.track_level,
+                master_level: step_data.voice_params.master_level,
+                pan: step_data.pan,
+                send_mfx: step_data.voice_params.send_mfx,
+                send_delay: step_data.voice_params.send_delay,
+                send_reverb: step_data.voice_params.send_reverb,
+                instrument_id: step_data.instrument_id,
+                waveform: step_data.synth_params.waveform,
+                attack_ms: step_data.voice_params.attack_ms,
+                release_ms: step_data.voice_params.release_ms,
+                decay_ms: step_data.synth_params.decay_ms,
+                sustain: step_data.synth_params.sustain,
+                filter: step_data.synth_params.filter,
+                gain: step_data.voice_params.gain,
+                sampler_variant: step_data.sampler_render.variant,
+                sampler_transient_level: step_data.sampler_render.transient_level,
+                sampler_body_level: step_data.sampler_render.body_level,
+                fm: step_data.fm,
+                drum: step_data.drum,
+            });
+        }
+
+        let state = &mut self.track_state[track_index];
+        let previous_note = state.last_note;
+        state.active_note = Some(step_data.note);
+        state.chord_mask = step_data.chord_mask;
+        state.note_steps_remaining = Some(step_data.note_length_steps.max(1));
+        state.last_note = Some(step_data.note);
+        state.pitch_cents = 0;
+        state.pitch_slide = match (step_data.slide_ticks, previous_note) {
+            (Some(ticks), Some(from_note)) if from_note != step_data.note => Some(PitchSlideState {
+                from_cents: (from_note as i16 - step_data.note as i16) * 100,
+                ticks_total: ticks.max(1),
+                ticks_elapsed: 0,
+            }),
+            _ => None,
         };
+        state.arpeggio = step_data.arpeggio.map(ArpeggioState::from_fx_value);
+        state.table = step_data.tick_table.map(|start| TablePlaybackState {
+            table_id: start.table_id,
+            row: 0,
+            ticks_in_row: 0,
+            base_note: start.base_note,
+            base_velocity: start.base_velocity,
+            origin_note: start.origin_note,
+            cents: 0,
+            velocity: step_data.velocity,
+        });
+        state.vibrato = step_data.vibrato.map(LfoState::from_fx_value);
+        state.tremolo = step_data.tremolo.map(LfoState::from_fx_value);
+        state.volume_slide = step_data.volume_slide.map(VolumeSlideState::from_fx_value);
+        state.note_velocity = step_data.velocity;
+        state.current_velocity = step_data.velocity;
+        state.active_instrument = step_data.instrument_id;
+        state.voice_params = Some(step_data.voice_params);
+        state.retrigger_step = Some(step_data);
+    }
 
-        let (Some(note), Some(remaining)) = (active_note, note_steps_remaining) else {
+    fn process_tick_table(&mut self, project: &ProjectData, track_index: usize) {
+        let state = &mut self.track_state[track_index];
+        let (Some(_), Some(mut playback)) = (state.active_note, state.table) else {
+            return;
+        };
+        let Some(table) = project.tables.get(&playback.table_id).filter(|table| !table.rows.is_empty()) else {
+            state.table = None;
             return;
         };
 
-        if remaining <= 1 {
-            out.push(RenderEvent::NoteOff { track_id, note });
-            let state = &mut self.track_state[track_index];
-            state.active_note = None;
-            state.note_steps_remaining = None;
-        } else {
-            self.track_state[track_index].note_steps_remaining = Some(remaining - 1);
+        if playback.ticks_in_row >= Self::table_row_ticks(project, table, playback.row) {
+            let next_row = playback.row as usize + 1;
+            playback.row = if next_row >= table.rows.len() {
+                (table.loop_start as usize).min(table.rows.len() - 1) as u8
+            } else {
+                next_row as u8
+            };
+            playback.ticks_in_row = 0;
+
+            let row = &table.rows[playback.row as usize];
+            state.param_automation.apply_fx_commands(&row.fx);
+            let fx = Self::apply_fx_commands(
+                StepFxOutcome {
+                    note_i16: playback.base_note + row.note_offset as i16,
+                    velocity: ((playback.base_velocity as u16 * row.volume as u16) / 127) as u8,
+                    ..StepFxOutcome::default()
+                },
+                &row.fx,
+            );
+            playback.cents = ((fx.note_i16 - playback.origin_note) * 100).clamp(-12_700, 12_700);
+            playback.velocity = fx.velocity;
         }
+
+        playback.ticks_in_row = playback.ticks_in_row.saturating_add(1);
+        state.table = Some(playback);
     }
 
-    fn force_note_off_if_active(
+    fn table_row_ticks(project: &ProjectData, table: &Table, row: u8) -> u8 {
+        let groove_ticks = table
+            .groove_id
+            .and_then(|groove_id| project.grooves.get(&groove_id))
+            .filter(|groove| !groove.ticks_pattern.is_empty())
+            .map(|groove| groove.ticks_pattern[row as usize % groove.ticks_pattern.len()]);
+
+        groove_ticks.unwrap_or(table.speed).max(1)
+    }
+
+    /// Sends `TrackParams` when automation or the mixer changes what a held note was started
+    /// with.
+    fn process_param_automation(
         &mut self,
         project: &ProjectData,
         track_index: usize,
         out: &mut Vec<RenderEvent>,
     ) {
-        let active_note = self.track_state[track_index].active_note;
-        let Some(note) = active_note else {
+        let state = &self.track_state[track_index];
+        let (Some(_), Some(sent)) = (state.active_note, state.voice_params) else {
             return;
         };
 
+        let params = self.resolve_voice_params(project, track_index, state.active_instrument);
+        if params != sent {
+            out.push(RenderEvent::TrackParams {
+                track_id: project.song.tracks[track_index].index,
+                params,
+            });
+            self.track_state[track_index].voice_params = Some(params);
+        }
+    }
+
+    fn process_pitch_modulation(
+        &mut self,
+        project: &ProjectData,
+        track_index: usize,
+        out: &mut Vec<RenderEvent>,
+    ) {
         let Some(track) = project.song.tracks.get(track_index) else {
             return;
         };
-
-        out.push(RenderEvent::NoteOff {
-            track_id: track.index,
-            note,
-        });
         let state = &mut self.track_state[track_index];
-        state.active_note = None;
-        state.note_steps_remaining = None;
-    }
+        let Some(note) = state.active_note else {
+            return;
+        };
 
-    fn resolve_step_data(&self, project: &ProjectData, track_index: usize) -> Option<StepPlaybackData> {
-        let track = project.song.tracks.get(track_index)?;
-        let state = self.track_state.get(track_index)?;
+        let mut cents = 0i32;
 
-        let chain_id = track.song_rows.get(state.song_row).and_then(|slot| *slot)?;
-        let chain = project.chains.get(&chain_id)?;
-        let chain_row = chain.rows.get(state.chain_row)?;
-        let phrase_id = chain_row.phrase_id?;
-        let phrase = project.phrases.get(&phrase_id)?;
-        let step = phrase.steps.get(state.phrase_step)?;
+        if let Some(mut slide) = state.pitch_slide {
+            let remaining = (slide.ticks_total - slide.ticks_elapsed) as i32;
+            cents += slide.from_cents as i32 * remaining / slide.ticks_total as i32;
 
-        let base_note = step.note.map(|raw_note| Self::apply_transpose(raw_note, chain_row.transpose))?;
-        let mut note_i16 = base_note as i16;
-        let mut velocity = step.velocity;
-        let profile = self.resolve_instrument_profile(project, step.instrument_id);
-        let render_mode = profile.render_mode;
-        let sampler_render = profile.sampler_render;
-        let (send_mfx, send_delay, send_reverb) = self.resolve_effective_send_levels(project, step.instrument_id);
-        let track_level = project.mixer.track_levels[track_index].min(127);
-        let master_level = project.mixer.master_level.min(127);
-        let mut note_length_steps = profile.note_length_steps;
-        let synth_params = profile.synth_params;
+            if slide.ticks_elapsed >= slide.ticks_total {
+                state.pitch_slide = None;
+            } else {
+                slide.ticks_elapsed += 1;
+                state.pitch_slide = Some(slide);
+            }
+        }
 
-        let (fx_note, fx_velocity, fx_length) = Self::apply_fx_commands(
-            note_i16,
-            velocity,
-            note_length_steps,
-            &step.fx,
-        );
-        note_i16 = fx_note;
-        velocity = fx_velocity;
-        note_length_steps = fx_length;
-
-        if let Some(table_row) = self.resolve_table_row(project, step.instrument_id, state.phrase_step) {
-            note_i16 += table_row.note_offset as i16;
-            velocity = ((velocity as u16 * table_row.volume as u16) / 127) as u8;
-
-            let (tbl_note, tbl_velocity, tbl_length) = Self::apply_fx_commands(
-                note_i16,
-                velocity,
-                note_length_steps,
-                &table_row.fx,
-            );
-            note_i16 = tbl_note;
-            velocity = tbl_velocity;
-            note_length_steps = tbl_length;
+        if let Some(table) = state.table {
+            cents += table.cents as i32;
         }
 
-        let note = note_i16.clamp(0, 127) as u8;
-        let note = self.apply_scale(project, track_index, note);
+        if let Some(mut arpeggio) = state.arpeggio {
+            cents += arpeggio.current_offset_semitones() as i32 * 100;
+            arpeggio.phase = (arpeggio.phase + 1) % 3;
+            state.arpeggio = Some(arpeggio);
+        }
+
+        if let Some(mut vibrato) = state.vibrato {
+            cents += vibrato.bipolar() * vibrato.depth as i32 * VIBRATO_CENTS_PER_DEPTH / 128;
+            vibrato.advance();
+            state.vibrato = Some(vibrato);
+        }
+
+        let cents = cents.clamp(i16::MIN as i32, i16::MAX as i32) as i16;
+        if cents != state.pitch_cents {
+            for note in chord_notes(note, state.chord_mask) {
+                out.push(RenderEvent::PitchBend {
+                    track_id: track.index,
+                    note,
+                    cents,
+                });
+            }
+            state.pitch_cents = cents;
+        }
+    }
+
+    fn process_volume_modulation(
+        &mut self,
+        project: &ProjectData,
+        track_index: usize,
+        out: &mut Vec<RenderEvent>,
+    ) {
+        let Some(track) = project.song.tracks.get(track_index) else {
+            return;
+        };
+        let state = &mut self.track_state[track_index];
+        let Some(note) = state.active_note else {
+            return;
+        };
+
+        let mut velocity = state
+            .table
+            .map_or(state.note_velocity, |table| table.velocity) as i32;
+
+        if let Some(mut slide) = state.volume_slide {
+            velocity += slide.offset as i32;
+            slide.advance();
+            state.volume_slide = Some(slide);
+        }
+
+        if let Some(mut tremolo) = state.tremolo {
+            // Full depth swings the level from unchanged down to silence.
+            velocity -= velocity * tremolo.depth as i32 * tremolo.unipolar() / (15 * 256);
+            tremolo.advance();
+            state.tremolo = Some(tremolo);
+        }
+
+        let velocity = velocity.clamp(0, 127) as u8;
+        if velocity != state.current_velocity {
+            for note in chord_notes(note, state.chord_mask) {
+                out.push(RenderEvent::NoteVolume {
+                    track_id: track.index,
+                    note,
+                    velocity,
+                });
+            }
+            state.current_velocity = velocity;
+        }
+    }
+
+    fn emit_scheduled_note_off(
+        &mut self,
+        project: &ProjectData,
+        track_index: usize,
+        out: &mut Vec<RenderEvent>,
+    ) {
+        let (track_id, active_note, chord_mask, note_steps_remaining) = {
+            let Some(track) = project.song.tracks.get(track_index) else {
+                return;
+            };
+            let state = &self.track_state[track_index];
+            (
+                track.index,
+                state.active_note,
+                state.chord_mask,
+                state.note_steps_remaining,
+            )
+        };
+
+        let (Some(note), Some(remaining)) = (active_note, note_steps_remaining) else {
+            return;
+        };
+
+        if remaining <= 1 {
+            for note in chord_notes(note, chord_mask) {
+                out.push(RenderEvent::NoteOff { track_id, note });
+            }
+            let state = &mut self.track_state[track_index];
+            state.active_note = None;
+            state.chord_mask = 0;
+            state.note_steps_remaining = None;
+            state.pitch_slide = None;
+            state.arpeggio = None;
+            state.table = None;
+            state.vibrato = None;
+            state.tremolo = None;
+            state.volume_slide = None;
+            state.pitch_cents = 0;
+            state.voice_params = None;
+        } else {
+            self.track_state[track_index].note_steps_remaining = Some(remaining - 1);
+        }
+    }
+
+    fn force_note_off_if_active(
+        &mut self,
+        project: &ProjectData,
+        track_index: usize,
+        out: &mut Vec<RenderEvent>,
+    ) {
+        let active_note = self.track_state[track_index].active_note;
+        let Some(note) = active_note else {
+            return;
+        };
+
+        let Some(track) = project.song.tracks.get(track_index) else {
+            return;
+        };
+
+        for note in chord_notes(note, self.track_state[track_index].chord_mask) {
+            out.push(RenderEvent::NoteOff {
+                track_id: track.index,
+                note,
+            });
+        }
+        let state = &mut self.track_state[track_index];
+        state.active_note = None;
+        state.chord_mask = 0;
+        state.note_steps_remaining = None;
+        state.pitch_slide = None;
+        state.arpeggio = None;
+        state.table = None;
+        state.vibrato = None;
+        state.tremolo = None;
+        state.volume_slide = None;
+        state.pitch_cents = 0;
+        state.voice_params = None;
+    }
+
+    fn resolve_control_fx(&self, project: &ProjectData, track_index: usize) -> StepControlFx {
+        let mut control = StepControlFx::default();
+
+        let Some(step) = self.current_step(project, track_index) else {
+            return control;
+        };
+
+        for command in step.fx.iter().flatten() {
+            match command.code.as_str() {
+                "HOP" => control.hop_to_step = Some(command.value),
+                "JMP" => control.song_jump_row = Some(command.value),
+                "TPO" => control.tempo = Some(command.value as u16),
+                "GRV" => control.groove = Some(command.value),
+                _ => {}
+            }
+        }
+
+        control
+    }
+
+    fn current_step<'a>(&self, project: &'a ProjectData, track_index: usize) -> Option<&'a Step> {
+        let track = project.song.tracks.get(track_index)?;
+        let state = self.track_state.get(track_index)?;
+
+        let chain_id = track.song_rows.get(state.song_row).and_then(|slot| *slot)?;
+        let chain = project.chains.get(&chain_id)?;
+        let phrase_id = chain.rows.get(state.chain_row)?.phrase_id?;
+        let phrase = project.phrases.get(&phrase_id)?;
+        phrase.steps.get(state.phrase_step)
+    }
+
+    fn resolve_step_data(&self, project: &ProjectData, track_index: usize) -> Option<StepPlaybackData> {
+        let track = project.song.tracks.get(track_index)?;
+        let state = self.track_state.get(track_index)?;
+
+        let chain_id = track.song_rows.get(state.song_row).and_then(|slot| *slot)?;
+        let chain = project.chains.get(&chain_id)?;
+        let chain_row = chain.rows.get(state.chain_row)?;
+        let phrase_id = chain_row.phrase_id?;
+        let phrase = project.phrases.get(&phrase_id)?;
+        let step = phrase.steps.get(state.phrase_step)?;
+
+        let base_note = step.note.map(|raw_note| Self::apply_transpose(raw_note, chain_row.transpose))?;
+        let profile = self.resolve_instrument_profile(project, step.instrument_id);
+        let render_mode = profile.render_mode;
+        let sampler_render = profile.sampler_render;
+        let fm = profile.fm;
+        let drum = profile.drum;
+        let voice_params = self.resolve_voice_params(project, track_index, step.instrument_id);
+        let synth_params = profile.synth_params;
+
+        let mut fx = Self::apply_fx_commands(
+            StepFxOutcome {
+                note_i16: base_note as i16,
+                velocity: step.velocity,
+                note_length_steps: profile.note_length_steps,
+                ..StepFxOutcome::default()
+            },
+            &step.fx,
+        );
+
+        let mut tick_table = None;
+        if let Some((table, table_row)) = self.resolve_table_row(project, step.instrument_id, state.phrase_step) {
+            let (base_note, base_velocity) = (fx.note_i16, fx.velocity);
+            fx.note_i16 += table_row.note_offset as i16;
+            fx.velocity = ((fx.velocity as u16 * table_row.volume as u16) / 127) as u8;
+            fx = Self::apply_fx_commands(fx, &table_row.fx);
+
+            if table.runs_per_tick() {
+                tick_table = Some(TickTableStart {
+                    table_id: table.id,
+                    base_note,
+                    base_velocity,
+                    origin_note: fx.note_i16,
+                });
+            }
+        }
+
+        let note = fx.note_i16.clamp(0, 127) as u8;
+        let note = self.apply_scale(project, track_index, note);
+        let pan = self.resolve_effective_pan(project, track_index, step.instrument_id, fx.pan);
 
         Some(StepPlaybackData {
             track_id: track.index,
             note,
-            velocity,
+            chord_mask: fx.chord_mask,
+            velocity: fx.velocity,
             render_mode,
-            track_level,
-            master_level,
-            send_mfx,
-            send_delay,
-            send_reverb,
+            pan,
+            voice_params,
             sampler_render,
+            fm,
+            drum,
             instrument_id: step.instrument_id,
-            note_length_steps,
+            note_length_steps: fx.note_length_steps,
+            slide_ticks: fx.slide_ticks,
+            arpeggio: fx.arpeggio,
+            delay_ticks: fx.delay_ticks,
+            retrigger_ticks: fx.retrigger_ticks,
+            kill_ticks: fx.kill_ticks,
+            chance: fx.chance,
+            random_velocity: fx.random_velocity,
+            random_note: fx.random_note,
+            tick_table,
+            vibrato: fx.vibrato,
+            tremolo: fx.tremolo,
+            volume_slide: fx.volume_slide,
             synth_params,
         })
     }
@@ -301,15 +1197,22 @@ impl Scheduler {
                 synth_params: SynthParams::default(),
                 render_mode: RenderMode::Synth,
                 sampler_render: SamplerRenderParams::default(),
+                fm: FmParams::default(),
+                drum: DrumSynthParams::default(),
             };
         };
 
         let mut note_length_steps = instrument.note_length_steps.max(1);
         let mut synth_params = instrument.synth_params;
         let mut sampler_render = instrument.sampler_render.unwrap_or_default();
+        let mut fm = instrument.fm_params.unwrap_or_default();
+        let mut drum = instrument.drum_params.unwrap_or_default();
         let render_mode = match instrument.instrument_type {
             InstrumentType::Synth | InstrumentType::None => RenderMode::Synth,
+            InstrumentType::Sampler if instrument.sample.is_some() => RenderMode::Sample,
             InstrumentType::Sampler => RenderMode::SamplerV1,
+            InstrumentType::FmSynth => RenderMode::Fm,
+            InstrumentType::DrumSynth => RenderMode::Drum,
             InstrumentType::MidiOut | InstrumentType::External => RenderMode::ExternalMuted,
         };
 
@@ -323,6 +1226,41 @@ impl Scheduler {
                 sampler_render.transient_level = sampler_render.transient_level.min(127);
                 sampler_render.body_level = sampler_render.body_level.min(127);
             }
+            InstrumentType::FmSynth => {
+                fm.operator_count = fm
+                    .operator_count
+                    .clamp(FM_MIN_OPERATORS, FM_OPERATOR_SLOTS as u8);
+                for operator in fm.operators.iter_mut() {
+                    operator.level = operator.level.min(127);
+                    operator.feedback = operator.feedback.min(127);
+                    operator.sustain = operator.sustain.min(127);
+                }
+                // The carrier's envelope is the voice envelope, so note length, ATK and REL
+                // automation treat FM voices like any other synth.
+                let carrier = fm.operators[0];
+                synth_params.waveform = SynthWaveform::Sine;
+                synth_params.attack_ms = carrier.attack_ms;
+                synth_params.decay_ms = carrier.decay_ms;
+                synth_params.sustain = carrier.sustain;
+                synth_params.release_ms = carrier.release_ms;
+            }
+            InstrumentType::DrumSynth => {
+                let clamp_hz = |hz: u16| hz.clamp(DRUM_FREQ_MIN_HZ, DRUM_FREQ_MAX_HZ);
+                drum.kick.start_hz = clamp_hz(drum.kick.start_hz);
+                drum.kick.end_hz = clamp_hz(drum.kick.end_hz);
+                drum.kick.level = drum.kick.level.min(127);
+                drum.snare.tone_hz = clamp_hz(drum.snare.tone_hz);
+                drum.snare.tone_mix = drum.snare.tone_mix.min(127);
+                drum.snare.level = drum.snare.level.min(127);
+                drum.hat.cutoff = drum.hat.cutoff.min(127);
+                drum.hat.level = drum.hat.level.min(127);
+                // Hits shape themselves and ring out past note off, so the voice envelope
+                // only has to open instantly.
+                synth_params.waveform = SynthWaveform::Sine;
+                synth_params.attack_ms = 0;
+                synth_params.decay_ms = 0;
+                synth_params.sustain = 127;
+            }
             InstrumentType::MidiOut | InstrumentType::External => {
                 // External destinations should not produce duplicated internal voice output.
                 synth_params.gain = 0;
@@ -340,23 +1278,53 @@ impl Scheduler {
             synth_params,
             render_mode,
             sampler_render,
+            fm,
+            drum,
         }
     }
 
+    /// Mixer and instrument values with the track's FX automation on top.
+    fn resolve_voice_params(
+        &self,
+        project: &ProjectData,
+        track_index: usize,
+        instrument_id: Option<InstrumentId>,
+    ) -> VoiceParams {
+        let synth_params = self.resolve_instrument_profile(project, instrument_id).synth_params;
+        let (send_mfx, send_delay, send_reverb) =
+            self.resolve_effective_send_levels(project, instrument_id);
+
+        self.track_state[track_index].param_automation.apply(VoiceParams {
+            track_level: project.mixer.track_levels[track_index].min(127),
+            master_level: project.mixer.master_level.min(127),
+            send_mfx,
+            send_delay,
+            send_reverb,
+            attack_ms: synth_params.attack_ms,
+            release_ms: synth_params.release_ms,
+            gain: synth_params.gain,
+        })
+    }
+
     fn resolve_table_row<'a>(
         &self,
         project: &'a ProjectData,
         instrument_id: Option<InstrumentId>,
         phrase_step: usize,
-    ) -> Option<&'a crate::model::TableRow> {
+    ) -> Option<(&'a Table, &'a TableRow)> {
         let instrument = instrument_id.and_then(|id| project.instruments.get(&id))?;
         let table_id = instrument.table_id?;
         let table = project.tables.get(&table_id)?;
         if table.rows.is_empty() {
             return None;
         }
-        let index = phrase_step % table.rows.len();
-        table.rows.get(index)
+        // Tick-driven tables always start from their first row on note-on.
+        let index = if table.runs_per_tick() {
+            0
+        } else {
+            phrase_step % table.rows.len()
+        };
+        table.rows.get(index).map(|row| (table, row))
     }
 
     fn resolve_effective_send_levels(
@@ -382,29 +1350,82 @@ impl Scheduler {
         )
     }
 
-    fn apply_fx_commands(
-        mut note_i16: i16,
-        mut velocity: u8,
-        mut note_length_steps: u8,
-        commands: &[Option<FxCommand>],
-    ) -> (i16, u8, u8) {
+    /// Step `PAN` overrides the instrument default; the mixer track pan then offsets it.
+    fn resolve_effective_pan(
+        &self,
+        project: &ProjectData,
+        track_index: usize,
+        instrument_id: Option<InstrumentId>,
+        step_pan: Option<u8>,
+    ) -> u8 {
+        let instrument_pan = instrument_id
+            .and_then(|id| project.instruments.get(&id))
+            .map_or(PAN_CENTER, |inst| inst.pan);
+        let note_pan = step_pan.unwrap_or(instrument_pan).min(PAN_MAX) as i16;
+        let track_pan = project.mixer.track_pans[track_index].min(PAN_MAX) as i16;
+
+        (note_pan + track_pan - PAN_CENTER as i16).clamp(0, PAN_MAX as i16) as u8
+    }
+
+    fn apply_fx_commands(mut fx: StepFxOutcome, commands: &[Option<FxCommand>]) -> StepFxOutcome {
         for command in commands.iter().flatten() {
             match command.code.as_str() {
                 "VOL" => {
-                    velocity = command.value;
+                    fx.velocity = command.value;
                 }
                 "TRN" => {
                     let transpose = command.value as i16 - 48;
-                    note_i16 += transpose;
+                    fx.note_i16 += transpose;
                 }
                 "LEN" => {
-                    note_length_steps = command.value.clamp(1, 16);
+                    fx.note_length_steps = command.value.clamp(1, 16);
+                }
+                "SLD" => {
+                    fx.slide_ticks = Some(command.value.max(1));
+                }
+                "ARP" => {
+                    fx.arpeggio = Some(command.value);
+                }
+                "RTG" => {
+                    fx.retrigger_ticks = Some(command.value.max(1));
+                }
+                "DEL" => {
+                    fx.delay_ticks = Some(command.value).filter(|ticks| *ticks > 0);
+                }
+                "KIL" => {
+                    fx.kill_ticks = Some(command.value).filter(|ticks| *ticks > 0);
+                }
+                "CHA" => {
+                    fx.chance = Some(command.value);
+                }
+                "RNV" => {
+                    fx.random_velocity = Some(command.value);
+                }
+                "RNN" => {
+                    fx.random_note = Some(command.value);
+                }
+                "VIB" => {
+                    fx.vibrato = Some(command.value);
+                }
+                "TRM" => {
+                    fx.tremolo = Some(command.value);
+                }
+                "VSL" => {
+                    fx.volume_slide = Some(command.value);
+                }
+                "PAN" => {
+                    fx.pan = Some(command.value.min(PAN_MAX));
+                }
+                "CHD" => {
+                    // Each nibble adds a chord voice that many semitones above the root.
+                    let intervals = (1u16 << (command.value >> 4)) | (1 << (command.value & 0x0F));
+                    fx.chord_mask |= intervals & !1;
                 }
                 _ => {}
             }
         }
 
-        (note_i16, velocity, note_length_steps)
+        fx
     }
 
     fn apply_transpose(note: u8, transpose: i8) -> u8 {
@@ -420,6 +1441,26 @@ impl Scheduler {
         Self::quantize_to_scale(note, scale)
     }
 
+    /// Quantizes each chord voice like the root. Voices that land on the root or on another
+    /// voice merge, and any pushed past the mask's 15-semitone reach are dropped.
+    fn scale_chord_mask(
+        &self,
+        project: &ProjectData,
+        track_index: usize,
+        root: u8,
+        chord_mask: u16,
+    ) -> u16 {
+        let Some(scale) = self.effective_scale(project, track_index) else {
+            return chord_mask;
+        };
+
+        chord_notes(root, chord_mask)
+            .skip(1)
+            .map(|note| Self::quantize_to_scale(note, scale).saturating_sub(root))
+            .filter(|interval| (1..16).contains(interval))
+            .fold(0, |mask, interval| mask | (1 << interval))
+    }
+
     fn effective_scale<'a>(&self, project: &'a ProjectData, track_index: usize) -> Option<&'a Scale> {
         let track = project.song.tracks.get(track_index)?;
         let scale_id = track.scale_override.unwrap_or(project.song.default_scale);
@@ -461,39 +1502,244 @@ impl Scheduler {
         note
     }
 
-    fn ticks_for_current_step(&self, project: &ProjectData, track_index: usize) -> u8 {
+    /// Releases held notes and places every track where a straight playthrough has it after
+    /// `target` sub-ticks. Tracks without any rows take `fallback`, which they cannot play.
+    fn seek_subticks(
+        &mut self,
+        project: &ProjectData,
+        target: u64,
+        fallback: (usize, usize, usize),
+    ) {
+        self.reset_block_clock();
+        for (track_index, state) in self.track_state.iter().enumerate() {
+            let Some(root) = state.active_note else {
+                continue;
+            };
+            for note in chord_notes(root, state.chord_mask) {
+                self.pending_releases.push(RenderEvent::NoteOff {
+                    track_id: track_index as u8,
+                    note,
+                });
+            }
+        }
+
+        self.current_tick = target / SUBTICKS_PER_TICK as u64;
+        self.pending_song_jump = None;
+        self.tempo_override = None;
+        self.rng = None;
+        self.track_state
+            .resize(project.song.tracks.len(), TrackPlaybackState::default());
+        for track_index in 0..self.track_state.len() {
+            let (song_row, chain_row, step, tick_in_step) = self
+                .track_position_at(project, track_index, target)
+                .unwrap_or_else(|| {
+                    let (song_row, chain_row, step) = fallback;
+                    let length = self.phrase_length_at(project, track_index, song_row, chain_row);
+                    (song_row, chain_row, step.min(length - 1), 0)
+                });
+            self.track_state[track_index] = TrackPlaybackState {
+                song_row,
+                chain_row,
+                phrase_step: step,
+                tick_in_step,
+                ..TrackPlaybackState::default()
+            };
+        }
+    }
+
+    /// Sub-ticks a straight playthrough takes to bring the track to the position, counting
+    /// only the rows it plays. A position the track does not play times the start of
+    /// `song_row` instead.
+    fn track_subticks_to_position(
+        &self,
+        project: &ProjectData,
+        track_index: usize,
+        song_row: usize,
+        chain_row: usize,
+        step: usize,
+    ) -> u64 {
+        let mut subticks: u64 = (0..song_row)
+            .map(|row| self.chain_rows_subticks(project, track_index, row, CHAIN_ROW_COUNT))
+            .sum();
+
+        if self.is_chain_row_playable(project, track_index, song_row, chain_row) {
+            subticks += self.chain_rows_subticks(project, track_index, song_row, chain_row);
+            let length = self.phrase_length_at(project, track_index, song_row, chain_row);
+            subticks += self.steps_subticks(project, track_index, step.min(length - 1), length);
+        }
+        subticks
+    }
+
+    /// Sub-ticks of one pass over every row the track plays.
+    fn track_song_subticks(&self, project: &ProjectData, track_index: usize) -> u64 {
+        (0..project.song.length())
+            .map(|row| self.chain_rows_subticks(project, track_index, row, CHAIN_ROW_COUNT))
+            .sum()
+    }
+
+    /// Song row, chain row, step and whole ticks into that step where a straight playthrough
+    /// has the track after `target` sub-ticks. Tracks loop their own rows, so a track shorter
+    /// than the song wraps; a track without rows has no position.
+    fn track_position_at(
+        &self,
+        project: &ProjectData,
+        track_index: usize,
+        target: u64,
+    ) -> Option<(usize, usize, usize, u8)> {
+        let song_subticks = self.track_song_subticks(project, track_index);
+        if song_subticks == 0 {
+            return None;
+        }
+        let target = target % song_subticks;
+        let (groove, swing) = self.seek_timing(project, track_index);
+        let mut elapsed = 0u64;
+
+        for song_row in 0..project.song.length() {
+            for chain_row in 0..CHAIN_ROW_COUNT {
+                if !self.is_chain_row_playable(project, track_index, song_row, chain_row) {
+                    break;
+                }
+                let length = self.phrase_length_at(project, track_index, song_row, chain_row);
+                for step in 0..length {
+                    let step_subticks = self.step_subticks(groove, swing, step, length) as u64;
+                    if elapsed + step_subticks > target {
+                        let ticks_in = (target - elapsed) / SUBTICKS_PER_TICK as u64;
+                        return Some((song_row, chain_row, step, ticks_in as u8));
+                    }
+                    elapsed += step_subticks;
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Sub-ticks the track spends on the first `chain_rows` playable rows of its chain.
+    fn chain_rows_subticks(
+        &self,
+        project: &ProjectData,
+        track_index: usize,
+        song_row: usize,
+        chain_rows: usize,
+    ) -> u64 {
+        (0..chain_rows)
+            .take_while(|chain_row| {
+                self.is_chain_row_playable(project, track_index, song_row, *chain_row)
+            })
+            .map(|chain_row| {
+                let length = self.phrase_length_at(project, track_index, song_row, chain_row);
+                self.steps_subticks(project, track_index, length, length)
+            })
+            .sum()
+    }
+
+    /// Sub-ticks the first `steps` steps of a phrase take on the track's groove and swing.
+    fn steps_subticks(
+        &self,
+        project: &ProjectData,
+        track_index: usize,
+        steps: usize,
+        phrase_length: usize,
+    ) -> u64 {
+        let (groove, swing) = self.seek_timing(project, track_index);
+        (0..steps)
+            .map(|phrase_step| self.step_subticks(groove, swing, phrase_step, phrase_length) as u64)
+            .sum()
+    }
+
+    /// Groove and swing of a straight playthrough, ignoring GRV FX.
+    fn seek_timing<'a>(
+        &self,
+        project: &'a ProjectData,
+        track_index: usize,
+    ) -> (Option<&'a crate::model::Groove>, u8) {
+        let groove = project.song.tracks.get(track_index).and_then(|track| {
+            let groove_id = track.groove_override.unwrap_or(project.song.default_groove);
+            project.grooves.get(&groove_id)
+        });
+        (groove, self.effective_swing(project, track_index))
+    }
+
+    /// Swing moves the boundary inside each on-beat/off-beat step pair by `swing - 50`
+    /// percent of the pair, so groove patterns keep their shape and pairs keep their length.
+    fn step_subticks(
+        &self,
+        groove: Option<&crate::model::Groove>,
+        swing: u8,
+        phrase_step: usize,
+        phrase_length: usize,
+    ) -> u32 {
+        let subticks =
+            |step: usize| self.groove_step_ticks(groove, step) as u32 * SUBTICKS_PER_TICK;
+        let (on_beat, off_beat) = if phrase_step.is_multiple_of(2) {
+            (phrase_step, phrase_step + 1)
+        } else {
+            (phrase_step - 1, phrase_step)
+        };
+        // A trailing on-beat step without its partner stays straight so phrases keep length.
+        if swing <= SWING_STRAIGHT || off_beat >= phrase_length {
+            return subticks(phrase_step);
+        }
+
+        let pair = subticks(on_beat) + subticks(off_beat);
+        let shift = (pair * (swing - SWING_STRAIGHT) as u32 / 100).min(subticks(off_beat) - 1);
+        if phrase_step == on_beat {
+            subticks(on_beat) + shift
+        } else {
+            subticks(off_beat) - shift
+        }
+    }
+
+    fn groove_step_ticks(&self, groove: Option<&crate::model::Groove>, phrase_step: usize) -> u8 {
+        let Some(groove) = groove.filter(|groove| !groove.ticks_pattern.is_empty()) else {
+            return self.ticks_per_step;
+        };
+
+        groove.ticks_pattern[phrase_step % groove.ticks_pattern.len()].max(1)
+    }
+
+    /// Whole ticks the current step lasts and the sub-tick delay it hands to the next step.
+    fn current_step_timing(&self, project: &ProjectData, track_index: usize) -> (u8, u8) {
         let state = &self.track_state[track_index];
+        let straight = (self.ticks_per_step, 0);
         let Some(track) = project.song.tracks.get(track_index) else {
-            return self.ticks_per_step;
+            return straight;
         };
 
         let Some(chain_id) = track.song_rows.get(state.song_row).and_then(|slot| *slot) else {
-            return self.ticks_per_step;
+            return straight;
         };
 
         let Some(chain) = project.chains.get(&chain_id) else {
-            return self.ticks_per_step;
+            return straight;
         };
 
         if chain.rows.get(state.chain_row).is_none() {
-            return self.ticks_per_step;
+            return straight;
         }
 
-        let Some(groove) = self.effective_groove(project, track_index) else {
-            return self.ticks_per_step;
-        };
-
-        if groove.ticks_pattern.is_empty() {
-            return self.ticks_per_step;
-        }
+        let length = self.step_subticks(
+            self.effective_groove(project, track_index),
+            self.effective_swing(project, track_index),
+            state.phrase_step,
+            self.phrase_length_at(project, track_index, state.song_row, state.chain_row),
+        );
+        let total = state.swing_delay_subticks as u32 + length;
+        let ticks = (total / SUBTICKS_PER_TICK).clamp(1, u8::MAX as u32);
+        let carry = total
+            .saturating_sub(ticks * SUBTICKS_PER_TICK)
+            .min(SUBTICKS_PER_TICK - 1);
+        (ticks as u8, carry as u8)
+    }
 
-        let pattern_index = state.phrase_step % groove.ticks_pattern.len();
-        let value = groove.ticks_pattern[pattern_index];
-        if value == 0 {
-            1
-        } else {
-            value
-        }
+    fn effective_swing(&self, project: &ProjectData, track_index: usize) -> u8 {
+        project
+            .song
+            .tracks
+            .get(track_index)
+            .and_then(|track| track.swing_override)
+            .unwrap_or(project.song.swing)
+            .clamp(SWING_STRAIGHT, SWING_MAX)
     }
 
     fn effective_groove<'a>(
@@ -502,12 +1748,19 @@ impl Scheduler {
         track_index: usize,
     ) -> Option<&'a crate::model::Groove> {
         let track = project.song.tracks.get(track_index)?;
+        if let Some(groove) = self.track_state[track_index]
+            .groove_fx
+            .and_then(|groove_id| project.grooves.get(&groove_id))
+        {
+            return Some(groove);
+        }
+
         let groove_id = track.groove_override.unwrap_or(project.song.default_groove);
         project.grooves.get(&groove_id)
     }
 
     fn advance_one_tick(&mut self, project: &ProjectData, track_index: usize) {
-        let ticks_needed = self.ticks_for_current_step(project, track_index).max(1);
+        let (ticks_needed, next_swing_delay) = self.current_step_timing(project, track_index);
         let mut song_row = self.track_state[track_index].song_row;
         let mut chain_row = self.track_state[track_index].chain_row;
         let mut phrase_step = self.track_state[track_index].phrase_step;
@@ -517,13 +1770,36 @@ impl Scheduler {
             tick_in_step = 0;
             phrase_step += 1;
 
-            if phrase_step >= PHRASE_STEP_COUNT {
-                phrase_step = 0;
-                chain_row += 1;
+            let state = &mut self.track_state[track_index];
+            state.swing_delay_subticks = next_swing_delay;
+            let hop_to_step = state.hop_to_step.take();
+            if let Some(row) = state.song_jump_row.take() {
+                self.pending_song_jump = Some(row as usize);
+            }
 
-                if !self.is_chain_row_playable(project, track_index, song_row, chain_row) {
+            let phrase_length = self.phrase_length_at(project, track_index, song_row, chain_row);
+            if hop_to_step.is_some() || phrase_step >= phrase_length {
+                chain_row += 1;
+                let chain_ended =
+                    !self.is_chain_row_playable(project, track_index, song_row, chain_row);
+
+                if let Some(row) = self.take_launch(project, track_index, chain_ended) {
                     chain_row = 0;
-                    song_row = self.next_song_row_with_chain(project, track_index, song_row);
+                    song_row = row;
+                    phrase_step = 0;
+                } else {
+                    if chain_ended {
+                        chain_row = 0;
+                        if !self.live_mode {
+                            song_row =
+                                self.next_song_row_with_chain(project, track_index, song_row);
+                        }
+                    }
+
+                    let next_length =
+                        self.phrase_length_at(project, track_index, song_row, chain_row);
+                    phrase_step =
+                        hop_to_step.map_or(0, |step| (step as usize).min(next_length - 1));
                 }
             }
         }
@@ -535,6 +1811,84 @@ impl Scheduler {
         state.tick_in_step = tick_in_step;
     }
 
+    /// Hands out the queued row once the track reaches the configured launch boundary.
+    fn take_launch(
+        &mut self,
+        project: &ProjectData,
+        track_index: usize,
+        chain_ended: bool,
+    ) -> Option<usize> {
+        let at_boundary = match self.launch_quantize {
+            LaunchQuantize::Chain => chain_ended,
+            LaunchQuantize::Phrase => true,
+        };
+        if !at_boundary {
+            return None;
+        }
+
+        let row = self.track_state[track_index].queued_song_row.take()?;
+        (row < project.song.length()).then_some(row)
+    }
+
+    /// Releases notes held by tracks the project no longer has; track ids match indices.
+    fn sync_track_count(&mut self, track_count: usize, out: &mut Vec<RenderEvent>) {
+        for (track_index, state) in self.track_state.iter().enumerate().skip(track_count) {
+            let Some(root) = state.active_note else {
+                continue;
+            };
+            for note in chord_notes(root, state.chord_mask) {
+                out.push(RenderEvent::NoteOff {
+                    track_id: track_index as u8,
+                    note,
+                });
+            }
+        }
+        self.track_state
+            .resize(track_count, TrackPlaybackState::default());
+    }
+
+    /// Releases and moves every track with a chain on `song_row`; tracks whose row is empty
+    /// keep playing where they are.
+    fn jump_all_tracks_to_song_row(
+        &mut self,
+        project: &ProjectData,
+        song_row: usize,
+        out: &mut Vec<RenderEvent>,
+    ) {
+        for track_index in 0..self.track_state.len() {
+            if !self.is_chain_row_playable(project, track_index, song_row, 0) {
+                continue;
+            }
+            self.force_note_off_if_active(project, track_index, out);
+            let state = &mut self.track_state[track_index];
+            state.song_row = song_row;
+            state.chain_row = 0;
+            state.phrase_step = 0;
+            state.tick_in_step = 0;
+            state.hop_to_step = None;
+            state.song_jump_row = None;
+        }
+    }
+
+    /// Falls back to the default length when the position has no phrase.
+    fn phrase_length_at(
+        &self,
+        project: &ProjectData,
+        track_index: usize,
+        song_row: usize,
+        chain_row: usize,
+    ) -> usize {
+        project
+            .song
+            .tracks
+            .get(track_index)
+            .and_then(|track| track.song_rows.get(song_row).copied().flatten())
+            .and_then(|chain_id| project.chains.get(&chain_id))
+            .and_then(|chain| chain.rows.get(chain_row)?.phrase_id)
+            .and_then(|phrase_id| project.phrases.get(&phrase_id))
+            .map_or(PHRASE_STEP_COUNT, |phrase| phrase.steps.len().max(1))
+    }
+
     fn is_chain_row_playable(
         &self,
         project: &ProjectData,
@@ -577,7 +1931,23 @@ impl Scheduler {
 
         let valid_chain = |chain_id: ChainId| project.chains.contains_key(&chain_id);
 
-        for row in (from_row + 1)..SONG_ROW_COUNT {
+        let song_length = track.song_rows.len();
+        if let Some((start, end)) = self.loop_range {
+            let end = end.min(song_length.saturating_sub(1));
+            let first = if (start..end).contains(&from_row) {
+                from_row + 1
+            } else {
+                start
+            };
+            let in_range = (first..=end).chain(start..first);
+            for row in in_range {
+                if track.song_rows[row].is_some_and(valid_chain) {
+                    return row;
+                }
+            }
+        }
+
+        for row in (from_row + 1)..song_length {
             if let Some(chain_id) = track.song_rows[row] {
                 if valid_chain(chain_id) {
                     return row;
@@ -585,7 +1955,7 @@ impl Scheduler {
             }
         }
 
-        for row in 0..=from_row.min(SONG_ROW_COUNT.saturating_sub(1)) {
+        for row in 0..=from_row.min(song_length.saturating_sub(1)) {
             if let Some(chain_id) = track.song_rows[row] {
                 if valid_chain(chain_id) {
                     return row;
@@ -597,6 +1967,28 @@ impl Scheduler {
     }
 }
 
+// Integer waveform so live playback and export stay bit-identical on every platform.
+fn lfo_triangle(phase: u8) -> i32 {
+    let phase = phase as i32;
+    if phase < 64 {
+        phase * 2
+    } else if phase < 192 {
+        256 - phase * 2
+    } else {
+        phase * 2 - 512
+    }
+}
+
+/// The root first, then one note per interval bit set in `chord_mask`.
+fn chord_notes(root: u8, chord_mask: u16) -> impl Iterator<Item = u8> {
+    let intervals = (1..16u8).filter(move |interval| chord_mask & (1 << interval) != 0);
+    std::iter::once(root).chain(
+        intervals
+            .map(move |interval| root.saturating_add(interval))
+            .filter(|note| *note <= 127),
+    )
+}
+
 fn scale_send(instrument_send: u8, global_send: u8) -> u8 {
     let instrument = instrument_send.min(127) as u16;
     let global = global_send.min(127) as u16;
@@ -605,12 +1997,12 @@ fn scale_send(instrument_send: u8, global_send: u8) -> u8 {
 
 #[cfg(test)]
 mod tests {
-    use super::Scheduler;
+    use super::{LaunchQuantize, Scheduler};
     use crate::engine::{Engine, EngineCommand};
     use crate::events::{RenderEvent, RenderMode};
     use crate::model::{
-        Chain, FxCommand, Groove, Instrument, InstrumentType, Phrase, SamplerRenderParams,
-        SamplerRenderVariant, Scale, Table,
+        Chain, DrumSynthParams, FmParams, FxCommand, Groove, Instrument, InstrumentType, Phrase,
+        SampleParams, SamplerRenderParams, SamplerRenderVariant, Scale, Table, PHRASE_STEP_COUNT,
     };
 
     fn setup_engine() -> Engine {
@@ -669,225 +2061,526 @@ mod tests {
     }
 
     #[test]
-    fn groove_changes_step_timing() {
+    fn chd_fx_plays_a_chord_and_releases_every_voice() {
         let mut engine = setup_engine();
-        engine
-            .apply_command(EngineCommand::SetPhraseStep {
-                phrase_id: 0,
-                step_index: 1,
-                note: None,
-                velocity: 90,
-                instrument_id: Some(0),
-            })
-            .unwrap();
-        engine
-            .apply_command(EngineCommand::SetPhraseStep {
+        for (fx_slot, value) in [(0, 0x47), (1, 0x0C)] {
+            engine
+                .apply_command(EngineCommand::SetStepFx {
+                    phrase_id: 0,
+                    step_index: 0,
+                    fx_slot,
+                    fx: Some(FxCommand {
+                        code: "CHD".to_string(),
+                        value,
+                    }),
+                })
+                .unwrap();
+        }
+        assert!(engine
+            .apply_command(EngineCommand::SetStepFx {
                 phrase_id: 0,
-                step_index: 2,
-                note: Some(62),
-                velocity: 90,
-                instrument_id: Some(0),
+                step_index: 0,
+                fx_slot: 2,
+                fx: Some(FxCommand {
+                    code: "CHD".to_string(),
+                    value: 0,
+                }),
             })
-            .unwrap();
-
-        let groove = Groove {
-            id: 1,
-            ticks_pattern: vec![1, 2, 1, 1],
-        };
-        engine
-            .apply_command(EngineCommand::UpsertGroove { groove })
-            .unwrap();
-        engine
-            .apply_command(EngineCommand::SetDefaultGroove(1))
-            .unwrap();
+            .is_err());
 
         let mut scheduler = Scheduler::new(4);
-        let t1 = scheduler.tick(&engine);
-        let t2 = scheduler.tick(&engine);
-        let t3 = scheduler.tick(&engine);
-        let t4 = scheduler.tick(&engine);
+        let first = scheduler.tick(&engine);
+        assert_eq!(note_ons(&first), vec![60, 64, 67, 72]);
 
-        assert_eq!(t1.len(), 1);
-        assert_eq!(count_note_on(&t2), 0);
-        assert_eq!(count_note_off(&t2), 1);
-        assert_eq!(t3.len(), 0);
-        assert_eq!(count_note_on(&t4), 1);
+        let second = scheduler.tick(&engine);
+        assert_eq!(count_note_off(&second), 4);
+        assert_eq!(note_ons(&second), vec![61]);
+
+        // Muting mid-chord releases the whole chord, not just the root.
+        let mut scheduler = Scheduler::new(16);
+        assert_eq!(count_note_on(&scheduler.tick(&engine)), 4);
+        engine
+            .apply_command(EngineCommand::ToggleTrackMute { track_index: 0 })
+            .unwrap();
+        let muted = scheduler.tick(&engine);
+        assert_eq!(count_note_off(&muted), 4);
+        assert_eq!(scheduler.track_state[0].chord_mask, 0);
     }
 
     #[test]
-    fn track_groove_override_has_priority() {
+    fn phrase_length_drives_polymetric_tracks() {
         let mut engine = setup_engine();
-
-        let default_groove = Groove {
-            id: 10,
-            ticks_pattern: vec![2, 2, 2, 2],
-        };
-        let fast_groove = Groove {
-            id: 11,
-            ticks_pattern: vec![1, 1, 1, 1],
-        };
         engine
-            .apply_command(EngineCommand::UpsertGroove {
-                groove: default_groove,
+            .apply_command(EngineCommand::SetPhraseLength {
+                phrase_id: 0,
+                length: 3,
             })
             .unwrap();
+
+        let mut chain = Chain::new(1);
+        chain.rows[0].phrase_id = Some(1);
         engine
-            .apply_command(EngineCommand::UpsertGroove { groove: fast_groove })
+            .apply_command(EngineCommand::UpsertChain { chain })
             .unwrap();
+        let mut phrase = Phrase::new(1);
+        phrase.set_length(4);
+        phrase.steps[0].note = Some(48);
         engine
-            .apply_command(EngineCommand::SetDefaultGroove(10))
+            .apply_command(EngineCommand::UpsertPhrase { phrase })
             .unwrap();
         engine
-            .apply_command(EngineCommand::SetTrackGrooveOverride {
-                track_index: 0,
-                groove_id: Some(11),
+            .apply_command(EngineCommand::SetSongRowChain {
+                track_index: 1,
+                row: 0,
+                chain_id: Some(1),
             })
             .unwrap();
 
         let mut scheduler = Scheduler::new(4);
-        let t1 = scheduler.tick(&engine);
-        let t2 = scheduler.tick(&engine);
+        let mut track_0_ticks = Vec::new();
+        let mut track_1_ticks = Vec::new();
+        for tick in 0..12 {
+            for event in scheduler.tick(&engine) {
+                match event {
+                    RenderEvent::NoteOn { track_id: 0, note: 60, .. } => track_0_ticks.push(tick),
+                    RenderEvent::NoteOn { track_id: 1, .. } => track_1_ticks.push(tick),
+                    _ => {}
+                }
+            }
+        }
 
-        assert_eq!(t1.len(), 1);
-        assert_eq!(count_note_on(&t2), 1);
-        assert_eq!(count_note_off(&t2), 1);
+        assert_eq!(track_0_ticks, vec![0, 3, 6, 9]);
+        assert_eq!(track_1_ticks, vec![0, 4, 8]);
     }
 
-    #[test]
-    fn scale_quantizes_out_of_scale_note() {
-        let mut engine = setup_engine();
-
-        let scale = Scale {
-            id: 2,
-            key: 0,
-            interval_mask: major_scale_mask(),
-        };
-        engine
-            .apply_command(EngineCommand::UpsertScale { scale })
-            .unwrap();
+    /// Song row `r` on track 0 plays a two-row chain of one-step phrases: notes `40+10r`, `41+10r`.
+    fn sectioned_engine(rows: u8) -> Engine {
+        let mut engine = Engine::new("sections");
+        for row in 0..rows {
+            let mut chain = Chain::new(row);
+            for half in 0..2 {
+                let phrase_id = row * 2 + half;
+                let mut phrase = Phrase::new(phrase_id);
+                phrase.set_length(1);
+                phrase.steps[0].note = Some(40 + row * 10 + half);
+                engine
+                    .apply_command(EngineCommand::UpsertPhrase { phrase })
+                    .unwrap();
+                chain.rows[half as usize].phrase_id = Some(phrase_id);
+            }
+            engine
+                .apply_command(EngineCommand::UpsertChain { chain })
+                .unwrap();
+            engine
+                .apply_command(EngineCommand::SetSongRowChain {
+                    track_index: 0,
+                    row: row as usize,
+                    chain_id: Some(row),
+                })
+                .unwrap();
+        }
         engine
-            .apply_command(EngineCommand::SetDefaultScale(2))
-            .unwrap();
+    }
 
-        engine
-            .apply_command(EngineCommand::SetPhraseStep {
-                phrase_id: 0,
-                step_index: 0,
-                note: Some(61), // C#
-                velocity: 100,
-                instrument_id: Some(0),
-            })
-            .unwrap();
+    fn played_notes(scheduler: &mut Scheduler, engine: &Engine, ticks: usize) -> Vec<u8> {
+        let mut notes = Vec::new();
+        for _ in 0..ticks {
+            for event in scheduler.tick(engine) {
+                if let RenderEvent::NoteOn { note, .. } = event {
+                    notes.push(note);
+                }
+            }
+        }
+        notes
+    }
 
+    #[test]
+    fn loop_range_wraps_song_playback() {
+        let engine = sectioned_engine(4);
         let mut scheduler = Scheduler::new(4);
-        let events = scheduler.tick(&engine);
+        scheduler.set_loop_range(Some((2, 1)));
+        assert_eq!(scheduler.loop_range(), Some((1, 2)));
 
-        match &events[0] {
-            RenderEvent::NoteOn { note, .. } => assert_eq!(*note, 60),
-            _ => panic!("expected note on"),
-        }
+        assert_eq!(
+            played_notes(&mut scheduler, &engine, 8),
+            vec![40, 41, 50, 51, 60, 61, 50, 51]
+        );
+
+        scheduler.set_loop_range(None);
+        assert_eq!(played_notes(&mut scheduler, &engine, 4), vec![60, 61, 70, 71]);
     }
 
     #[test]
-    fn track_scale_override_has_priority() {
-        let mut engine = setup_engine();
+    fn live_mode_loops_row_until_queued_row_launches_at_chain_end() {
+        let engine = sectioned_engine(3);
+        let mut scheduler = Scheduler::new(4);
+        scheduler.set_live_mode(true);
+
+        assert_eq!(played_notes(&mut scheduler, &engine, 3), vec![40, 41, 40]);
+        assert!(scheduler.queue_song_row(0, Some(2)));
+        assert_eq!(scheduler.track_state[0].queued_song_row, Some(2));
+        assert_eq!(played_notes(&mut scheduler, &engine, 5), vec![41, 60, 61, 60, 61]);
+        assert_eq!(scheduler.track_state[0].queued_song_row, None);
+        assert!(!scheduler.queue_song_row(40, Some(1)));
+    }
 
-        let major = Scale {
-            id: 20,
-            key: 0,
-            interval_mask: major_scale_mask(),
-        };
-        let chromatic = Scale {
-            id: 21,
-            key: 0,
-            interval_mask: 0x0FFF,
-        };
-        engine
-            .apply_command(EngineCommand::UpsertScale { scale: major })
-            .unwrap();
-        engine
-            .apply_command(EngineCommand::UpsertScale { scale: chromatic })
-            .unwrap();
-        engine
-            .apply_command(EngineCommand::SetDefaultScale(20))
-            .unwrap();
-        engine
-            .apply_command(EngineCommand::SetTrackScaleOverride {
-                track_index: 0,
-                scale_id: Some(21),
-            })
-            .unwrap();
-        engine
-            .apply_command(EngineCommand::SetPhraseStep {
-                phrase_id: 0,
-                step_index: 0,
-                note: Some(61), // C#
-                velocity: 100,
-                instrument_id: Some(0),
-            })
-            .unwrap();
+    #[test]
+    fn phrase_quantized_launch_cuts_chain_short() {
+        let engine = sectioned_engine(3);
+        let mut scheduler = Scheduler::new(4);
+        scheduler.set_launch_quantize(LaunchQuantize::Phrase);
+
+        scheduler.queue_song_row(0, Some(2));
+        assert_eq!(played_notes(&mut scheduler, &engine, 4), vec![40, 60, 61, 40]);
+
+        scheduler.queue_song_row(0, Some(300));
+        assert_eq!(played_notes(&mut scheduler, &engine, 2), vec![41, 50]);
+    }
 
+    #[test]
+    fn seek_positions_every_track_and_releases_held_notes() {
+        let engine = sectioned_engine(4);
         let mut scheduler = Scheduler::new(4);
-        let events = scheduler.tick(&engine);
+        assert_eq!(played_notes(&mut scheduler, &engine, 1), vec![40]);
 
-        match &events[0] {
-            RenderEvent::NoteOn { note, .. } => assert_eq!(*note, 61),
-            _ => panic!("expected note on"),
-        }
+        scheduler.seek(engine.snapshot(), 0, 2, 1, 7);
+        assert_eq!(scheduler.current_tick, 5);
+        assert!(scheduler
+            .track_state
+            .iter()
+            .all(|state| state.song_row == 2 && state.chain_row == 1));
+        assert_eq!(scheduler.track_state[0].phrase_step, 0);
+        assert_eq!(scheduler.track_state[1].phrase_step, 7);
+
+        let events = scheduler.tick(&engine);
+        assert!(matches!(events[0], RenderEvent::NoteOff { track_id: 0, note: 40 }));
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, RenderEvent::NoteOn { note: 61, .. })));
+        assert_eq!(played_notes(&mut scheduler, &engine, 2), vec![70, 71]);
     }
 
     #[test]
-    fn emits_note_off_after_one_step_by_default() {
+    fn seek_tick_follows_groove_timing() {
         let mut engine = setup_engine();
         engine
-            .apply_command(EngineCommand::SetPhraseStep {
-                phrase_id: 0,
-                step_index: 1,
-                note: None,
-                velocity: 90,
-                instrument_id: None,
+            .apply_command(EngineCommand::UpsertGroove {
+                groove: Groove {
+                    id: 0,
+                    ticks_pattern: vec![3, 1],
+                },
             })
             .unwrap();
 
         let mut scheduler = Scheduler::new(4);
-        let t1 = scheduler.tick(&engine);
-        let t2 = scheduler.tick(&engine);
+        scheduler.seek(engine.snapshot(), 0, 0, 0, 5);
+        assert_eq!(scheduler.current_tick, 11);
 
-        assert_eq!(count_note_on(&t1), 1);
-        assert_eq!(count_note_off(&t1), 0);
-        assert_eq!(count_note_off(&t2), 1);
+        // Rows no track plays take no time, so row 9 starts where row 0 ends.
+        scheduler.seek(engine.snapshot(), 0, 9, 0, 0);
+        assert_eq!(scheduler.current_tick, 32);
     }
 
     #[test]
-    fn respects_instrument_note_length_steps() {
-        let mut engine = setup_engine();
-        let mut instrument = Instrument::new(0, InstrumentType::Synth, "Long");
-        instrument.note_length_steps = 3;
+    fn seek_times_the_position_on_a_track_that_plays_it() {
+        let mut engine = sectioned_engine(3);
+        // Track 1 leaves row 1 empty; track 0 plays it for two ticks.
+        for row in [0, 2] {
+            engine
+                .apply_command(EngineCommand::SetSongRowChain {
+                    track_index: 1,
+                    row,
+                    chain_id: Some(row as u8),
+                })
+                .unwrap();
+        }
         engine
-            .apply_command(EngineCommand::UpsertInstrument { instrument })
+            .apply_command(EngineCommand::SetSongRowChain {
+                track_index: 0,
+                row: 2,
+                chain_id: None,
+            })
             .unwrap();
+
+        let mut scheduler = Scheduler::new(4);
+        scheduler.seek(engine.snapshot(), 0, 2, 1, 0);
+        // Track 0 has no row 2, so track 1 times it: it skips row 1 and gets there on tick 3,
+        // while track 0 is still on its second row.
+        assert_eq!(scheduler.current_tick, 3);
+        let position = |track_index: usize| {
+            let state = &scheduler.track_state[track_index];
+            (state.song_row, state.chain_row)
+        };
+        assert_eq!(position(0), (1, 1));
+        assert_eq!(position(1), (2, 1));
+    }
+
+    #[test]
+    fn seek_places_tracks_with_uneven_chains_where_playback_has_them() {
+        let mut engine = Engine::new("uneven");
+        for (chain_id, phrase_ids) in [(0u8, &[0u8][..]), (1, &[1, 2][..])] {
+            let mut chain = Chain::new(chain_id);
+            for (chain_row, phrase_id) in phrase_ids.iter().enumerate() {
+                let mut phrase = Phrase::new(*phrase_id);
+                phrase.set_length(4);
+                for (step, note) in phrase.steps.iter_mut().take(4).zip(0..) {
+                    step.note = Some(40 + phrase_id * 10 + note);
+                }
+                engine
+                    .apply_command(EngineCommand::UpsertPhrase { phrase })
+                    .unwrap();
+                chain.rows[chain_row].phrase_id = Some(*phrase_id);
+            }
+            engine
+                .apply_command(EngineCommand::UpsertChain { chain })
+                .unwrap();
+            // Track 0 plays a 4-step chain per row and track 1 an 8-step one.
+            for row in 0..2 {
+                engine
+                    .apply_command(EngineCommand::SetSongRowChain {
+                        track_index: chain_id as usize,
+                        row,
+                        chain_id: Some(chain_id),
+                    })
+                    .unwrap();
+            }
+        }
+        let position = |scheduler: &Scheduler, track_index: usize| {
+            let state = &scheduler.track_state[track_index];
+            (state.song_row, state.chain_row, state.phrase_step)
+        };
+
+        let mut playback = Scheduler::new(4);
+        for tick in 0..16 {
+            let mut seeked = Scheduler::new(4);
+            assert!(seeked.seek_to_tick(engine.snapshot(), tick));
+            assert_eq!(seeked.current_tick, tick);
+            for track_index in 0..2 {
+                assert_eq!(
+                    position(&seeked, track_index),
+                    position(&playback, track_index),
+                    "track {track_index} at tick {tick}"
+                );
+            }
+            playback.tick(&engine);
+        }
+        assert!(!Scheduler::new(4).seek_to_tick(engine.snapshot(), 16));
+
+        // Play-from-cursor on track 1's second row starts it there; track 0, half as long, is
+        // halfway through its second pass by then.
+        let mut scheduler = Scheduler::new(4);
+        scheduler.seek(engine.snapshot(), 1, 1, 1, 2);
+        assert_eq!(scheduler.current_tick, 14);
+        assert_eq!(position(&scheduler, 1), (1, 1, 2));
+        assert_eq!(position(&scheduler, 0), (1, 0, 2));
+        assert_eq!(played_notes(&mut scheduler, &engine, 1), vec![42, 62]);
+    }
+
+    #[test]
+    fn seek_to_tick_lands_on_the_step_holding_the_tick() {
+        let engine = sectioned_engine(4);
+        let mut scheduler = Scheduler::new(4);
+
+        assert!(scheduler.seek_to_tick(engine.snapshot(), 5));
+        assert_eq!(scheduler.current_tick, 5);
+        assert_eq!(
+            (scheduler.track_state[0].song_row, scheduler.track_state[0].chain_row),
+            (2, 1)
+        );
+        assert_eq!(played_notes(&mut scheduler, &engine, 1), vec![61]);
+
+        assert!(!scheduler.seek_to_tick(engine.snapshot(), 8));
+        assert_eq!(scheduler.current_tick, 6);
+    }
+
+    fn note_on_ticks(scheduler: &mut Scheduler, engine: &Engine, ticks: u64) -> Vec<u64> {
+        (0..ticks)
+            .filter(|_| count_note_on(&scheduler.tick(engine)) > 0)
+            .collect()
+    }
+
+    fn four_note_engine() -> Engine {
+        let mut engine = setup_engine();
+        let mut phrase = Phrase::new(0);
+        phrase.set_length(4);
+        for (step, note) in phrase.steps.iter_mut().take(4).zip(60..) {
+            step.note = Some(note);
+            step.velocity = 100;
+        }
         engine
-            .apply_command(EngineCommand::SetPhraseStep {
+            .apply_command(EngineCommand::UpsertPhrase { phrase })
+            .unwrap();
+        engine
+    }
+
+    #[test]
+    fn swing_delays_off_beat_steps_and_tracks_can_override_it() {
+        let mut engine = four_note_engine();
+        engine.apply_command(EngineCommand::SetSongSwing(75)).unwrap();
+
+        let mut scheduler = Scheduler::new(24); // 6 ticks per step
+        assert_eq!(note_on_ticks(&mut scheduler, &engine, 24), vec![0, 9, 12, 21]);
+
+        engine
+            .apply_command(EngineCommand::SetTrackSwingOverride {
+                track_index: 0,
+                swing: Some(50),
+            })
+            .unwrap();
+        let mut scheduler = Scheduler::new(24);
+        assert_eq!(note_on_ticks(&mut scheduler, &engine, 24), vec![0, 6, 12, 18]);
+    }
+
+    #[test]
+    fn swing_finer_than_a_tick_carries_as_sub_tick_delay() {
+        let mut engine = four_note_engine();
+        engine.apply_command(EngineCommand::SetSongSwing(58)).unwrap();
+
+        let mut scheduler = Scheduler::new(4);
+        scheduler.tick(&engine);
+        assert_eq!(scheduler.track_state[0].phrase_step, 1);
+        assert_eq!(scheduler.track_state[0].swing_delay_subticks, 2);
+
+        scheduler.tick(&engine);
+        assert_eq!(scheduler.track_state[0].phrase_step, 2);
+        assert_eq!(scheduler.track_state[0].swing_delay_subticks, 0);
+    }
+
+    #[test]
+    fn timed_ticks_tag_swung_steps_with_their_sub_tick_delay() {
+        let mut engine = four_note_engine();
+        engine.apply_command(EngineCommand::SetSongSwing(58)).unwrap();
+
+        let mut scheduler = Scheduler::new(4);
+        let first = scheduler.tick_timed(&engine);
+        assert!(first.iter().all(|timed| timed.subtick == 0));
+
+        let second = scheduler.tick_timed(&engine);
+        assert_eq!(second.len(), 2);
+        assert!(second.iter().all(|timed| timed.subtick == 2));
+        assert!(matches!(second[1].event, RenderEvent::NoteOn { note: 61, .. }));
+    }
+
+    #[test]
+    fn render_block_places_events_inside_blocks_independent_of_ppq() {
+        let mut engine = four_note_engine();
+        engine.apply_command(EngineCommand::SetSongSwing(58)).unwrap();
+
+        // 120 BPM at PPQ 4 and 48 kHz gives 6000 frames per tick.
+        let mut scheduler = Scheduler::new(4);
+        let mut note_on_frames = Vec::new();
+        for block in 0u32..80 {
+            for timed in scheduler.render_block(&engine, 48_000, 256) {
+                assert!(timed.frame_offset < 256);
+                if matches!(timed.event, RenderEvent::NoteOn { .. }) {
+                    note_on_frames.push(block * 256 + timed.frame_offset);
+                }
+            }
+        }
+
+        assert_eq!(note_on_frames, vec![0, 6750, 12_000, 18_750]);
+        assert_eq!(scheduler.current_tick, 4);
+    }
+
+    #[test]
+    fn rewind_and_seek_drop_swung_note_ons_carried_past_the_block() {
+        let mut engine = four_note_engine();
+        engine.apply_command(EngineCommand::SetSongSwing(58)).unwrap();
+        let project = engine.snapshot().clone();
+
+        // 6000 frames per tick at PPQ 4: step 1 starts at frame 6000 and sounds at 6750.
+        let mut scheduler = Scheduler::new(4);
+        let note_ons = |scheduler: &mut Scheduler, blocks: u32| {
+            let mut notes = Vec::new();
+            for _ in 0..blocks {
+                for timed in scheduler.render_block(&engine, 48_000, 256) {
+                    if let RenderEvent::NoteOn { note, .. } = timed.event {
+                        notes.push(note);
+                    }
+                }
+            }
+            notes
+        };
+
+        assert_eq!(note_ons(&mut scheduler, 24), vec![60]);
+        scheduler.rewind();
+        assert_eq!(note_ons(&mut scheduler, 24), vec![60]);
+        scheduler.seek(&project, 0, 0, 0, 0);
+        assert_eq!(note_ons(&mut scheduler, 24), vec![60]);
+    }
+
+    #[test]
+    fn render_block_without_a_sample_rate_plays_nothing() {
+        let engine = four_note_engine();
+        let mut scheduler = Scheduler::new(4);
+
+        assert!(scheduler.render_block(&engine, 0, 256).is_empty());
+        assert_eq!(scheduler.current_tick, 0);
+        assert_eq!(scheduler.frames_until_next_tick(), 0.0);
+    }
+
+    #[test]
+    fn track_count_changes_follow_the_project() {
+        let mut engine = setup_engine();
+        engine.apply_command(EngineCommand::SetTrackCount(12)).unwrap();
+        engine
+            .apply_command(EngineCommand::SetSongRowChain {
+                track_index: 11,
+                row: 0,
+                chain_id: Some(0),
+            })
+            .unwrap();
+
+        let mut scheduler = Scheduler::new(4);
+        let events = scheduler.tick(&engine);
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, RenderEvent::NoteOn { track_id: 11, note: 60, .. })));
+        assert_eq!(scheduler.track_state.len(), 12);
+
+        engine.apply_command(EngineCommand::SetTrackCount(4)).unwrap();
+        let events = scheduler.tick(&engine);
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, RenderEvent::NoteOff { track_id: 11, note: 60 })));
+        assert_eq!(scheduler.track_state.len(), 4);
+    }
+
+    #[test]
+    fn hop_target_is_clamped_to_next_phrase_length() {
+        let mut engine = setup_engine();
+        engine
+            .apply_command(EngineCommand::SetStepFx {
                 phrase_id: 0,
                 step_index: 0,
-                note: Some(60),
-                velocity: 100,
-                instrument_id: Some(0),
+                fx_slot: 0,
+                fx: Some(FxCommand {
+                    code: "HOP".to_string(),
+                    value: 40,
+                }),
             })
             .unwrap();
         engine
-            .apply_command(EngineCommand::SetPhraseStep {
+            .apply_command(EngineCommand::SetPhraseLength {
                 phrase_id: 0,
-                step_index: 1,
-                note: None,
-                velocity: 90,
-                instrument_id: Some(0),
+                length: 2,
             })
             .unwrap();
+
+        let mut scheduler = Scheduler::new(4);
+        scheduler.tick(&engine);
+        assert_eq!(scheduler.track_state[0].phrase_step, 1);
+        let events = scheduler.tick(&engine);
+        assert_eq!(note_ons(&events), vec![61]);
+    }
+
+    #[test]
+    fn groove_changes_step_timing() {
+        let mut engine = setup_engine();
         engine
             .apply_command(EngineCommand::SetPhraseStep {
                 phrase_id: 0,
-                step_index: 2,
+                step_index: 1,
                 note: None,
                 velocity: 90,
                 instrument_id: Some(0),
@@ -896,84 +2589,97 @@ mod tests {
         engine
             .apply_command(EngineCommand::SetPhraseStep {
                 phrase_id: 0,
-                step_index: 3,
-                note: None,
+                step_index: 2,
+                note: Some(62),
                 velocity: 90,
                 instrument_id: Some(0),
             })
             .unwrap();
 
+        let groove = Groove {
+            id: 1,
+            ticks_pattern: vec![1, 2, 1, 1],
+        };
+        engine
+            .apply_command(EngineCommand::UpsertGroove { groove })
+            .unwrap();
+        engine
+            .apply_command(EngineCommand::SetDefaultGroove(1))
+            .unwrap();
+
         let mut scheduler = Scheduler::new(4);
         let t1 = scheduler.tick(&engine);
         let t2 = scheduler.tick(&engine);
         let t3 = scheduler.tick(&engine);
         let t4 = scheduler.tick(&engine);
 
-        assert_eq!(count_note_on(&t1), 1);
-        assert_eq!(count_note_off(&t2), 0);
-        assert_eq!(count_note_off(&t3), 0);
-        assert_eq!(count_note_off(&t4), 1);
+        assert_eq!(t1.len(), 1);
+        assert_eq!(count_note_on(&t2), 0);
+        assert_eq!(count_note_off(&t2), 1);
+        assert_eq!(t3.len(), 0);
+        assert_eq!(count_note_on(&t4), 1);
     }
 
     #[test]
-    fn step_fx_transpose_and_volume_are_applied() {
+    fn track_groove_override_has_priority() {
         let mut engine = setup_engine();
+
+        let default_groove = Groove {
+            id: 10,
+            ticks_pattern: vec![2, 2, 2, 2],
+        };
+        let fast_groove = Groove {
+            id: 11,
+            ticks_pattern: vec![1, 1, 1, 1],
+        };
         engine
-            .apply_command(EngineCommand::SetStepFx {
-                phrase_id: 0,
-                step_index: 0,
-                fx_slot: 0,
-                fx: Some(FxCommand {
-                    code: "TRN".to_string(),
-                    value: 52, // +4 semitones (center=48)
-                }),
+            .apply_command(EngineCommand::UpsertGroove {
+                groove: default_groove,
             })
             .unwrap();
         engine
-            .apply_command(EngineCommand::SetStepFx {
-                phrase_id: 0,
-                step_index: 0,
-                fx_slot: 1,
-                fx: Some(FxCommand {
-                    code: "VOL".to_string(),
-                    value: 80,
-                }),
+            .apply_command(EngineCommand::UpsertGroove { groove: fast_groove })
+            .unwrap();
+        engine
+            .apply_command(EngineCommand::SetDefaultGroove(10))
+            .unwrap();
+        engine
+            .apply_command(EngineCommand::SetTrackGrooveOverride {
+                track_index: 0,
+                groove_id: Some(11),
             })
             .unwrap();
 
         let mut scheduler = Scheduler::new(4);
-        let events = scheduler.tick(&engine);
+        let t1 = scheduler.tick(&engine);
+        let t2 = scheduler.tick(&engine);
 
-        let note_on = events
-            .iter()
-            .find_map(|event| match event {
-                RenderEvent::NoteOn { note, velocity, .. } => Some((*note, *velocity)),
-                _ => None,
-            })
-            .expect("expected note on");
-        assert_eq!(note_on, (64, 80));
+        assert_eq!(t1.len(), 1);
+        assert_eq!(count_note_on(&t2), 1);
+        assert_eq!(count_note_off(&t2), 1);
     }
 
     #[test]
-    fn table_row_modifies_note_and_velocity() {
+    fn scale_quantizes_out_of_scale_note() {
         let mut engine = setup_engine();
-        let mut instrument = Instrument::new(0, InstrumentType::Synth, "Tbl");
-        instrument.table_id = Some(0);
+
+        let scale = Scale {
+            id: 2,
+            key: 0,
+            interval_mask: major_scale_mask(),
+        };
         engine
-            .apply_command(EngineCommand::UpsertInstrument { instrument })
+            .apply_command(EngineCommand::UpsertScale { scale })
             .unwrap();
-        let mut table = Table::new(0);
-        table.rows[0].note_offset = 2;
-        table.rows[0].volume = 64;
         engine
-            .apply_command(EngineCommand::UpsertTable { table })
+            .apply_command(EngineCommand::SetDefaultScale(2))
             .unwrap();
 
         engine
             .apply_command(EngineCommand::SetPhraseStep {
                 phrase_id: 0,
                 step_index: 0,
-                note: Some(60),
+                note: Some(61), // C#
                 velocity: 100,
                 instrument_id: Some(0),
             })
@@ -981,71 +2687,189 @@ mod tests {
 
         let mut scheduler = Scheduler::new(4);
         let events = scheduler.tick(&engine);
-        let note_on = events
-            .iter()
-            .find_map(|event| match event {
-                RenderEvent::NoteOn { note, velocity, .. } => Some((*note, *velocity)),
-                _ => None,
-            })
-            .expect("expected note on");
 
-        assert_eq!(note_on.0, 62);
-        assert_eq!(note_on.1, 50);
+        match &events[0] {
+            RenderEvent::NoteOn { note, .. } => assert_eq!(*note, 60),
+            _ => panic!("expected note on"),
+        }
     }
 
     #[test]
-    fn len_fx_overrides_note_length() {
+    fn chord_voices_are_quantized_to_the_scale() {
         let mut engine = setup_engine();
         engine
-            .apply_command(EngineCommand::SetStepFx {
-                phrase_id: 0,
-                step_index: 0,
-                fx_slot: 0,
-                fx: Some(FxCommand {
-                    code: "LEN".to_string(),
-                    value: 4,
-                }),
+            .apply_command(EngineCommand::UpsertScale {
+                scale: Scale {
+                    id: 2,
+                    key: 0,
+                    interval_mask: major_scale_mask(),
+                },
             })
             .unwrap();
-        for step in 1..=4 {
+        engine
+            .apply_command(EngineCommand::SetDefaultScale(2))
+            .unwrap();
+        for (step_index, note, chord) in [(0, 62, 0x47), (1, 60, 0x12)] {
             engine
                 .apply_command(EngineCommand::SetPhraseStep {
                     phrase_id: 0,
-                    step_index: step,
-                    note: None,
-                    velocity: 90,
-                    instrument_id: Some(0),
+                    step_index,
+                    note: Some(note),
+                    velocity: 100,
+                    instrument_id: None,
+                })
+                .unwrap();
+            engine
+                .apply_command(EngineCommand::SetStepFx {
+                    phrase_id: 0,
+                    step_index,
+                    fx_slot: 0,
+                    fx: Some(FxCommand {
+                        code: "CHD".to_string(),
+                        value: chord,
+                    }),
                 })
                 .unwrap();
         }
 
         let mut scheduler = Scheduler::new(4);
-        let t1 = scheduler.tick(&engine);
-        let t2 = scheduler.tick(&engine);
-        let t3 = scheduler.tick(&engine);
-        let t4 = scheduler.tick(&engine);
-        let t5 = scheduler.tick(&engine);
+        // D major over C major becomes D minor: F# falls to F.
+        assert_eq!(note_ons(&scheduler.tick(&engine)), vec![62, 65, 69]);
+        // C# merges into the root and only D is added.
+        let second = scheduler.tick(&engine);
+        assert_eq!(count_note_off(&second), 3);
+        assert_eq!(note_ons(&second), vec![60, 62]);
+    }
 
-        assert_eq!(count_note_on(&t1), 1);
-        assert_eq!(count_note_off(&t2), 0);
-        assert_eq!(count_note_off(&t3), 0);
-        assert_eq!(count_note_off(&t4), 0);
-        assert_eq!(count_note_off(&t5), 1);
+    #[test]
+    fn track_scale_override_has_priority() {
+        let mut engine = setup_engine();
+
+        let major = Scale {
+            id: 20,
+            key: 0,
+            interval_mask: major_scale_mask(),
+        };
+        let chromatic = Scale {
+            id: 21,
+            key: 0,
+            interval_mask: 0x0FFF,
+        };
+        engine
+            .apply_command(EngineCommand::UpsertScale { scale: major })
+            .unwrap();
+        engine
+            .apply_command(EngineCommand::UpsertScale { scale: chromatic })
+            .unwrap();
+        engine
+            .apply_command(EngineCommand::SetDefaultScale(20))
+            .unwrap();
+        engine
+            .apply_command(EngineCommand::SetTrackScaleOverride {
+                track_index: 0,
+                scale_id: Some(21),
+            })
+            .unwrap();
+        engine
+            .apply_command(EngineCommand::SetPhraseStep {
+                phrase_id: 0,
+                step_index: 0,
+                note: Some(61), // C#
+                velocity: 100,
+                instrument_id: Some(0),
+            })
+            .unwrap();
+
+        let mut scheduler = Scheduler::new(4);
+        let events = scheduler.tick(&engine);
+
+        match &events[0] {
+            RenderEvent::NoteOn { note, .. } => assert_eq!(*note, 61),
+            _ => panic!("expected note on"),
+        }
     }
 
     #[test]
-    fn sampler_profile_shapes_envelope_and_note_length() {
+    fn emits_note_off_after_one_step_by_default() {
         let mut engine = setup_engine();
+        engine
+            .apply_command(EngineCommand::SetPhraseStep {
+                phrase_id: 0,
+                step_index: 1,
+                note: None,
+                velocity: 90,
+                instrument_id: None,
+            })
+            .unwrap();
 
-        let mut sampler = Instrument::new(0, InstrumentType::Sampler, "Sampler");
-        sampler.note_length_steps = 1;
-        sampler.synth_params.attack_ms = 9;
-        sampler.synth_params.release_ms = 8;
-        sampler.synth_params.gain = 96;
+        let mut scheduler = Scheduler::new(4);
+        let t1 = scheduler.tick(&engine);
+        let t2 = scheduler.tick(&engine);
+
+        assert_eq!(count_note_on(&t1), 1);
+        assert_eq!(count_note_off(&t1), 0);
+        assert_eq!(count_note_off(&t2), 1);
+    }
+
+    #[test]
+    fn automation_fx_update_held_notes_and_later_note_ons() {
+        let mut engine = setup_engine();
+        let mut instrument = Instrument::new(0, InstrumentType::Synth, "Long");
+        instrument.note_length_steps = 4;
         engine
-            .apply_command(EngineCommand::UpsertInstrument { instrument: sampler })
+            .apply_command(EngineCommand::UpsertInstrument { instrument })
+            .unwrap();
+        let mut phrase = Phrase::new(0);
+        phrase.steps[0].note = Some(60);
+        phrase.steps[0].instrument_id = Some(0);
+        phrase.steps[0].fx[0] = Some(FxCommand {
+            code: "TLV".to_string(),
+            value: 40,
+        });
+        phrase.steps[1].fx[0] = Some(FxCommand {
+            code: "GAN".to_string(),
+            value: 20,
+        });
+        phrase.steps[1].fx[1] = Some(FxCommand {
+            code: "SND".to_string(),
+            value: 0x1F,
+        });
+        phrase.steps[4].note = Some(62);
+        phrase.steps[4].instrument_id = Some(0);
+        engine
+            .apply_command(EngineCommand::UpsertPhrase { phrase })
             .unwrap();
 
+        let mut scheduler = Scheduler::new(4);
+        let first = scheduler.tick(&engine);
+        assert!(matches!(first[0], RenderEvent::NoteOn { track_level: 40, gain: 100, .. }));
+
+        let second = scheduler.tick(&engine);
+        let [RenderEvent::TrackParams { track_id: 0, params }] = second.as_slice() else {
+            panic!("expected one parameter change: {second:?}");
+        };
+        assert_eq!((params.track_level, params.gain, params.send_delay), (40, 20, 127));
+        assert!(scheduler.tick(&engine).is_empty());
+
+        scheduler.tick(&engine);
+        let fifth = scheduler.tick(&engine);
+        assert!(fifth.iter().any(|event| matches!(
+            event,
+            RenderEvent::NoteOn { note: 62, track_level: 40, gain: 20, send_delay: 127, .. }
+        )));
+
+        scheduler.rewind();
+        assert_eq!(scheduler.track_state[0].param_automation, Default::default());
+    }
+
+    #[test]
+    fn respects_instrument_note_length_steps() {
+        let mut engine = setup_engine();
+        let mut instrument = Instrument::new(0, InstrumentType::Synth, "Long");
+        instrument.note_length_steps = 3;
+        engine
+            .apply_command(EngineCommand::UpsertInstrument { instrument })
+            .unwrap();
         engine
             .apply_command(EngineCommand::SetPhraseStep {
                 phrase_id: 0,
@@ -1060,7 +2884,7 @@ mod tests {
                 phrase_id: 0,
                 step_index: 1,
                 note: None,
-                velocity: 80,
+                velocity: 90,
                 instrument_id: Some(0),
             })
             .unwrap();
@@ -1069,7 +2893,16 @@ mod tests {
                 phrase_id: 0,
                 step_index: 2,
                 note: None,
-                velocity: 80,
+                velocity: 90,
+                instrument_id: Some(0),
+            })
+            .unwrap();
+        engine
+            .apply_command(EngineCommand::SetPhraseStep {
+                phrase_id: 0,
+                step_index: 3,
+                note: None,
+                velocity: 90,
                 instrument_id: Some(0),
             })
             .unwrap();
@@ -1078,89 +2911,68 @@ mod tests {
         let t1 = scheduler.tick(&engine);
         let t2 = scheduler.tick(&engine);
         let t3 = scheduler.tick(&engine);
+        let t4 = scheduler.tick(&engine);
 
-        let note_on = t1
-            .iter()
-            .find_map(|event| match event {
-                RenderEvent::NoteOn {
-                    render_mode,
-                    attack_ms,
-                    release_ms,
-                    gain,
-                    ..
-                } => Some((*render_mode, *attack_ms, *release_ms, *gain)),
-                _ => None,
-            })
-            .expect("expected sampler note on");
-
-        assert_eq!(note_on.0, RenderMode::SamplerV1);
-        assert_eq!(note_on.1, 1);
-        assert_eq!(note_on.2, 24);
-        assert_eq!(note_on.3, 96);
+        assert_eq!(count_note_on(&t1), 1);
         assert_eq!(count_note_off(&t2), 0);
-        assert_eq!(count_note_off(&t3), 1);
+        assert_eq!(count_note_off(&t3), 0);
+        assert_eq!(count_note_off(&t4), 1);
     }
 
     #[test]
-    fn midiout_profile_mutes_internal_gain() {
+    fn step_fx_transpose_and_volume_are_applied() {
         let mut engine = setup_engine();
-
-        let mut midi_out = Instrument::new(0, InstrumentType::MidiOut, "MIDI Out");
-        midi_out.synth_params.attack_ms = 12;
-        midi_out.synth_params.release_ms = 48;
-        midi_out.synth_params.gain = 100;
         engine
-            .apply_command(EngineCommand::UpsertInstrument {
-                instrument: midi_out,
+            .apply_command(EngineCommand::SetStepFx {
+                phrase_id: 0,
+                step_index: 0,
+                fx_slot: 0,
+                fx: Some(FxCommand {
+                    code: "TRN".to_string(),
+                    value: 52, // +4 semitones (center=48)
+                }),
             })
             .unwrap();
-
         engine
-            .apply_command(EngineCommand::SetPhraseStep {
+            .apply_command(EngineCommand::SetStepFx {
                 phrase_id: 0,
                 step_index: 0,
-                note: Some(60),
-                velocity: 100,
-                instrument_id: Some(0),
+                fx_slot: 1,
+                fx: Some(FxCommand {
+                    code: "VOL".to_string(),
+                    value: 80,
+                }),
             })
             .unwrap();
 
         let mut scheduler = Scheduler::new(4);
         let events = scheduler.tick(&engine);
+
         let note_on = events
             .iter()
             .find_map(|event| match event {
-                RenderEvent::NoteOn {
-                    render_mode,
-                    attack_ms,
-                    release_ms,
-                    gain,
-                    ..
-                } => Some((*render_mode, *attack_ms, *release_ms, *gain)),
+                RenderEvent::NoteOn { note, velocity, .. } => Some((*note, *velocity)),
                 _ => None,
             })
-            .expect("expected midi out note on");
-
-        assert_eq!(note_on.0, RenderMode::ExternalMuted);
-        assert_eq!(note_on.1, 1);
-        assert_eq!(note_on.2, 16);
-        assert_eq!(note_on.3, 0);
+            .expect("expected note on");
+        assert_eq!(note_on, (64, 80));
     }
 
     #[test]
-    fn sampler_render_params_are_forwarded_to_render_event() {
+    fn table_row_modifies_note_and_velocity() {
         let mut engine = setup_engine();
-        let mut sampler = Instrument::new(0, InstrumentType::Sampler, "Sampler");
-        sampler.sampler_render = Some(SamplerRenderParams {
-            variant: SamplerRenderVariant::Air,
-            transient_level: 99,
-            body_level: 31,
-        });
+        let mut instrument = Instrument::new(0, InstrumentType::Synth, "Tbl");
+        instrument.table_id = Some(0);
         engine
-            .apply_command(EngineCommand::UpsertInstrument {
-                instrument: sampler,
-            })
+            .apply_command(EngineCommand::UpsertInstrument { instrument })
+            .unwrap();
+        let mut table = Table::new(0);
+        table.rows[0].note_offset = 2;
+        table.rows[0].volume = 64;
+        engine
+            .apply_command(EngineCommand::UpsertTable { table })
             .unwrap();
+
         engine
             .apply_command(EngineCommand::SetPhraseStep {
                 phrase_id: 0,
@@ -1176,52 +2988,43 @@ mod tests {
         let note_on = events
             .iter()
             .find_map(|event| match event {
-                RenderEvent::NoteOn {
-                    render_mode,
-                    sampler_variant,
-                    sampler_transient_level,
-                    sampler_body_level,
-                    ..
-                } => Some((
-                    *render_mode,
-                    *sampler_variant,
-                    *sampler_transient_level,
-                    *sampler_body_level,
-                )),
+                RenderEvent::NoteOn { note, velocity, .. } => Some((*note, *velocity)),
                 _ => None,
             })
-            .expect("expected sampler note on");
+            .expect("expected note on");
 
-        assert_eq!(note_on.0, RenderMode::SamplerV1);
-        assert_eq!(note_on.1, SamplerRenderVariant::Air);
-        assert_eq!(note_on.2, 99);
-        assert_eq!(note_on.3, 31);
+        assert_eq!(note_on.0, 62);
+        assert_eq!(note_on.1, 50);
     }
 
     #[test]
-    fn mixer_routing_levels_are_forwarded_to_render_event() {
+    fn tick_table_advances_per_tick_and_loops() {
         let mut engine = setup_engine();
-        let mut instrument = Instrument::new(0, InstrumentType::Synth, "Mix");
-        instrument.send_levels.mfx = 100;
-        instrument.send_levels.delay = 80;
-        instrument.send_levels.reverb = 60;
+        let mut instrument = Instrument::new(0, InstrumentType::Synth, "Tbl");
+        instrument.table_id = Some(0);
         engine
             .apply_command(EngineCommand::UpsertInstrument { instrument })
             .unwrap();
+        let mut table = Table::new(0);
+        for (row, note_offset, volume) in [
+            (0, 0, 127),
+            (1, 12, 64),
+            (2, 7, 127),
+            (14, 3, 127),
+            (15, 5, 127),
+        ] {
+            table.rows[row].note_offset = note_offset;
+            table.rows[row].volume = volume;
+        }
         engine
-            .apply_command(EngineCommand::SetTrackLevel {
-                track_index: 0,
-                level: 101,
-            })
-            .unwrap();
-        engine
-            .apply_command(EngineCommand::SetMasterLevel { level: 109 })
+            .apply_command(EngineCommand::UpsertTable { table })
             .unwrap();
         engine
-            .apply_command(EngineCommand::SetMixerSends {
-                mfx: 64,
-                delay: 32,
-                reverb: 96,
+            .apply_command(EngineCommand::SetTablePlayback {
+                table_id: 0,
+                speed: 1,
+                groove_id: None,
+                loop_start: 14,
             })
             .unwrap();
         engine
@@ -1234,60 +3037,48 @@ mod tests {
             })
             .unwrap();
 
-        let mut scheduler = Scheduler::new(4);
-        let events = scheduler.tick(&engine);
-        let routed = events
-            .iter()
-            .find_map(|event| match event {
-                RenderEvent::NoteOn {
-                    track_level,
-                    master_level,
-                    send_mfx,
-                    send_delay,
-                    send_reverb,
-                    ..
-                } => Some((
-                    *track_level,
-                    *master_level,
-                    *send_mfx,
-                    *send_delay,
-                    *send_reverb,
-                )),
-                _ => None,
-            })
-            .expect("expected note on");
-
-        assert_eq!(routed.0, 101);
-        assert_eq!(routed.1, 109);
-        assert_eq!(routed.2, 50);
-        assert_eq!(routed.3, 20);
-        assert_eq!(routed.4, 45);
+        let mut scheduler = Scheduler::new(96); // 24 ticks per step
+        let ticks: Vec<Vec<RenderEvent>> = (0..18).map(|_| scheduler.tick(&engine)).collect();
+
+        assert_eq!(note_ons(&ticks[0]), vec![60]);
+        assert!(pitch_bends(&ticks[0]).is_empty());
+        assert_eq!(pitch_bends(&ticks[1]), vec![1200]);
+        assert_eq!(note_volumes(&ticks[1]), vec![50]);
+        assert_eq!(pitch_bends(&ticks[2]), vec![700]);
+        assert_eq!(note_volumes(&ticks[2]), vec![100]);
+        assert_eq!(pitch_bends(&ticks[3]), vec![0]);
+        assert_eq!(note_volumes(&ticks[3]), vec![50]);
+        assert!(ticks[4..14].iter().all(|events| events.is_empty()));
+        assert_eq!(pitch_bends(&ticks[14]), vec![300]);
+        assert_eq!(pitch_bends(&ticks[15]), vec![500]);
+        assert_eq!(pitch_bends(&ticks[16]), vec![300]);
+        assert_eq!(pitch_bends(&ticks[17]), vec![500]);
     }
 
     #[test]
-    fn routing_levels_are_clamped_for_safety() {
+    fn tick_table_row_length_follows_table_groove() {
         let mut engine = setup_engine();
-        let mut instrument = Instrument::new(0, InstrumentType::Synth, "Clamp");
-        instrument.send_levels.mfx = 255;
-        instrument.send_levels.delay = 200;
-        instrument.send_levels.reverb = 180;
+        let mut instrument = Instrument::new(0, InstrumentType::Synth, "Tbl");
+        instrument.table_id = Some(0);
         engine
             .apply_command(EngineCommand::UpsertInstrument { instrument })
             .unwrap();
+        let mut table = Table::new(0);
+        for row in 1..4 {
+            table.rows[row].note_offset = row as i8;
+            table.rows[row].volume = 0x40;
+        }
+        table.rows[0].volume = 0x40;
+        table.groove_id = Some(2);
         engine
-            .apply_command(EngineCommand::SetTrackLevel {
-                track_index: 0,
-                level: 240,
-            })
-            .unwrap();
-        engine
-            .apply_command(EngineCommand::SetMasterLevel { level: 250 })
+            .apply_command(EngineCommand::UpsertTable { table })
             .unwrap();
         engine
-            .apply_command(EngineCommand::SetMixerSends {
-                mfx: 255,
-                delay: 255,
-                reverb: 255,
+            .apply_command(EngineCommand::UpsertGroove {
+                groove: Groove {
+                    id: 2,
+                    ticks_pattern: vec![2, 1],
+                },
             })
             .unwrap();
         engine
@@ -1300,110 +3091,70 @@ mod tests {
             })
             .unwrap();
 
-        let mut scheduler = Scheduler::new(4);
-        let events = scheduler.tick(&engine);
-        let routed = events
-            .iter()
-            .find_map(|event| match event {
-                RenderEvent::NoteOn {
-                    track_level,
-                    master_level,
-                    send_mfx,
-                    send_delay,
-                    send_reverb,
-                    ..
-                } => Some((
-                    *track_level,
-                    *master_level,
-                    *send_mfx,
-                    *send_delay,
-                    *send_reverb,
-                )),
-                _ => None,
-            })
-            .expect("expected note on");
+        let mut scheduler = Scheduler::new(96);
+        let ticks: Vec<Vec<RenderEvent>> = (0..7).map(|_| scheduler.tick(&engine)).collect();
+        let bends: Vec<Vec<i16>> = ticks.iter().map(|events| pitch_bends(events)).collect();
 
-        assert_eq!(routed.0, 127);
-        assert_eq!(routed.1, 127);
-        assert_eq!(routed.2, 127);
-        assert_eq!(routed.3, 127);
-        assert_eq!(routed.4, 127);
+        assert_eq!(
+            bends,
+            vec![vec![], vec![], vec![100], vec![200], vec![], vec![300], vec![0]]
+        );
+        assert!(ticks.iter().all(|events| note_volumes(events).is_empty()));
     }
 
     #[test]
-    fn complex_fx_stack_and_routing_stays_deterministic() {
+    fn len_fx_overrides_note_length() {
         let mut engine = setup_engine();
-        let mut instrument = Instrument::new(0, InstrumentType::Synth, "Stack");
-        instrument.table_id = Some(0);
-        instrument.note_length_steps = 5;
-        instrument.send_levels.mfx = 64;
-        engine
-            .apply_command(EngineCommand::UpsertInstrument { instrument })
-            .unwrap();
-
-        let mut table = Table::new(0);
-        table.rows[0].note_offset = -1;
-        table.rows[0].volume = 64;
-        table.rows[0].fx[0] = Some(FxCommand {
-            code: "TRN".to_string(),
-            value: 49,
-        });
-        table.rows[0].fx[1] = Some(FxCommand {
-            code: "LEN".to_string(),
-            value: 2,
-        });
-        engine
-            .apply_command(EngineCommand::UpsertTable { table })
-            .unwrap();
-        engine
-            .apply_command(EngineCommand::SetMixerSends {
-                mfx: 64,
-                delay: 0,
-                reverb: 0,
-            })
-            .unwrap();
-        engine
-            .apply_command(EngineCommand::SetTrackLevel {
-                track_index: 0,
-                level: 96,
-            })
-            .unwrap();
-        engine
-            .apply_command(EngineCommand::SetMasterLevel { level: 112 })
-            .unwrap();
         engine
             .apply_command(EngineCommand::SetStepFx {
                 phrase_id: 0,
                 step_index: 0,
                 fx_slot: 0,
                 fx: Some(FxCommand {
-                    code: "TRN".to_string(),
-                    value: 50,
-                }),
-            })
-            .unwrap();
-        engine
-            .apply_command(EngineCommand::SetStepFx {
-                phrase_id: 0,
-                step_index: 0,
-                fx_slot: 1,
-                fx: Some(FxCommand {
-                    code: "VOL".to_string(),
-                    value: 90,
+                    code: "LEN".to_string(),
+                    value: 4,
                 }),
             })
             .unwrap();
+        for step in 1..=4 {
+            engine
+                .apply_command(EngineCommand::SetPhraseStep {
+                    phrase_id: 0,
+                    step_index: step,
+                    note: None,
+                    velocity: 90,
+                    instrument_id: Some(0),
+                })
+                .unwrap();
+        }
+
+        let mut scheduler = Scheduler::new(4);
+        let t1 = scheduler.tick(&engine);
+        let t2 = scheduler.tick(&engine);
+        let t3 = scheduler.tick(&engine);
+        let t4 = scheduler.tick(&engine);
+        let t5 = scheduler.tick(&engine);
+
+        assert_eq!(count_note_on(&t1), 1);
+        assert_eq!(count_note_off(&t2), 0);
+        assert_eq!(count_note_off(&t3), 0);
+        assert_eq!(count_note_off(&t4), 0);
+        assert_eq!(count_note_off(&t5), 1);
+    }
+
+    #[test]
+    fn sampler_profile_shapes_envelope_and_note_length() {
+        let mut engine = setup_engine();
+
+        let mut sampler = Instrument::new(0, InstrumentType::Sampler, "Sampler");
+        sampler.note_length_steps = 1;
+        sampler.synth_params.attack_ms = 9;
+        sampler.synth_params.release_ms = 8;
+        sampler.synth_params.gain = 96;
         engine
-            .apply_command(EngineCommand::SetStepFx {
-                phrase_id: 0,
-                step_index: 0,
-                fx_slot: 2,
-                fx: Some(FxCommand {
-                    code: "LEN".to_string(),
-                    value: 3,
-                }),
-            })
+            .apply_command(EngineCommand::UpsertInstrument { instrument: sampler })
             .unwrap();
+
         engine
             .apply_command(EngineCommand::SetPhraseStep {
                 phrase_id: 0,
@@ -1441,26 +3192,1344 @@ mod tests {
             .iter()
             .find_map(|event| match event {
                 RenderEvent::NoteOn {
-                    note,
-                    velocity,
-                    track_level,
-                    master_level,
-                    send_mfx,
+                    render_mode,
+                    attack_ms,
+                    release_ms,
+                    gain,
                     ..
-                } => Some((*note, *velocity, *track_level, *master_level, *send_mfx)),
+                } => Some((*render_mode, *attack_ms, *release_ms, *gain)),
                 _ => None,
             })
-            .expect("expected note on");
+            .expect("expected sampler note on");
 
-        assert_eq!(note_on.0, 62);
-        assert_eq!(note_on.1, 45);
-        assert_eq!(note_on.2, 96);
-        assert_eq!(note_on.3, 112);
-        assert_eq!(note_on.4, 32);
+        assert_eq!(note_on.0, RenderMode::SamplerV1);
+        assert_eq!(note_on.1, 1);
+        assert_eq!(note_on.2, 24);
+        assert_eq!(note_on.3, 96);
         assert_eq!(count_note_off(&t2), 0);
         assert_eq!(count_note_off(&t3), 1);
     }
 
+    #[test]
+    fn sampler_with_a_sample_file_uses_the_sample_render_mode() {
+        let mut engine = setup_engine();
+        let mut drums = Instrument::new(0, InstrumentType::Sampler, "Drums");
+        drums.sample = Some(SampleParams::new("kick.wav"));
+        engine
+            .apply_command(EngineCommand::UpsertInstrument { instrument: drums })
+            .unwrap();
+        engine
+            .apply_command(EngineCommand::SetPhraseStep {
+                phrase_id: 0,
+                step_index: 0,
+                note: Some(36),
+                velocity: 100,
+                instrument_id: Some(0),
+            })
+            .unwrap();
+
+        let mut scheduler = Scheduler::new(4);
+        let render_mode = scheduler
+            .tick(&engine)
+            .iter()
+            .find_map(|event| match event {
+                RenderEvent::NoteOn { render_mode, .. } => Some(*render_mode),
+                _ => None,
+            });
+
+        assert_eq!(render_mode, Some(RenderMode::Sample));
+    }
+
+    #[test]
+    fn fm_profile_uses_the_carrier_envelope_and_clamps_operators() {
+        let mut engine = setup_engine();
+
+        let mut fm = FmParams {
+            operator_count: 9,
+            ..FmParams::default()
+        };
+        fm.operators[0].attack_ms = 3;
+        fm.operators[0].decay_ms = 400;
+        fm.operators[0].sustain = 50;
+        fm.operators[0].release_ms = 700;
+        fm.operators[1].level = 200;
+        let mut bell = Instrument::new(0, InstrumentType::FmSynth, "Bell");
+        bell.synth_params.attack_ms = 90;
+        bell.fm_params = Some(fm);
+        engine
+            .apply_command(EngineCommand::UpsertInstrument { instrument: bell })
+            .unwrap();
+        engine
+            .apply_command(EngineCommand::SetPhraseStep {
+                phrase_id: 0,
+                step_index: 0,
+                note: Some(60),
+                velocity: 100,
+                instrument_id: Some(0),
+            })
+            .unwrap();
+
+        let mut scheduler = Scheduler::new(4);
+        let events = scheduler.tick(&engine);
+        let note_on = events
+            .iter()
+            .find_map(|event| match event {
+                RenderEvent::NoteOn {
+                    render_mode,
+                    attack_ms,
+                    decay_ms,
+                    sustain,
+                    release_ms,
+                    fm,
+                    ..
+                } => Some((*render_mode, (*attack_ms, *decay_ms, *sustain, *release_ms), *fm)),
+                _ => None,
+            })
+            .expect("expected fm note on");
+
+        assert_eq!(note_on.0, RenderMode::Fm);
+        assert_eq!(note_on.1, (3, 400, 50, 700));
+        assert_eq!(note_on.2.operator_count, 4);
+        assert_eq!(note_on.2.operators[1].level, 127);
+    }
+
+    #[test]
+    fn drum_synth_profile_opens_instantly_and_clamps_params() {
+        let mut engine = setup_engine();
+
+        let mut drums = DrumSynthParams::default();
+        drums.kick.start_hz = 9_000;
+        drums.hat.level = 200;
+        let mut kit = Instrument::new(0, InstrumentType::DrumSynth, "Kit");
+        kit.synth_params.attack_ms = 40;
+        kit.drum_params = Some(drums);
+        engine
+            .apply_command(EngineCommand::UpsertInstrument { instrument: kit })
+            .unwrap();
+        engine
+            .apply_command(EngineCommand::SetPhraseStep {
+                phrase_id: 0,
+                step_index: 0,
+                note: Some(42),
+                velocity: 100,
+                instrument_id: Some(0),
+            })
+            .unwrap();
+
+        let mut scheduler = Scheduler::new(4);
+        let note_on = scheduler
+            .tick(&engine)
+            .iter()
+            .find_map(|event| match event {
+                RenderEvent::NoteOn {
+                    render_mode,
+                    attack_ms,
+                    drum,
+                    ..
+                } => Some((*render_mode, *attack_ms, *drum)),
+                _ => None,
+            })
+            .expect("expected drum note on");
+
+        assert_eq!(note_on.0, RenderMode::Drum);
+        assert_eq!(note_on.1, 0);
+        assert_eq!(note_on.2.kick.start_hz, 5_000);
+        assert_eq!(note_on.2.hat.level, 127);
+    }
+
+    #[test]
+    fn midiout_profile_mutes_internal_gain() {
+        let mut engine = setup_engine();
+
+        let mut midi_out = Instrument::new(0, InstrumentType::MidiOut, "MIDI Out");
+        midi_out.synth_params.attack_ms = 12;
+        midi_out.synth_params.release_ms = 48;
+        midi_out.synth_params.gain = 100;
+        engine
+            .apply_command(EngineCommand::UpsertInstrument {
+                instrument: midi_out,
+            })
+            .unwrap();
+
+        engine
+            .apply_command(EngineCommand::SetPhraseStep {
+                phrase_id: 0,
+                step_index: 0,
+                note: Some(60),
+                velocity: 100,
+                instrument_id: Some(0),
+            })
+            .unwrap();
+
+        let mut scheduler = Scheduler::new(4);
+        let events = scheduler.tick(&engine);
+        let note_on = events
+            .iter()
+            .find_map(|event| match event {
+                RenderEvent::NoteOn {
+                    render_mode,
+                    attack_ms,
+                    release_ms,
+                    gain,
+                    ..
+                } => Some((*render_mode, *attack_ms, *release_ms, *gain)),
+                _ => None,
+            })
+            .expect("expected midi out note on");
+
+        assert_eq!(note_on.0, RenderMode::ExternalMuted);
+        assert_eq!(note_on.1, 1);
+        assert_eq!(note_on.2, 16);
+        assert_eq!(note_on.3, 0);
+    }
+
+    #[test]
+    fn sampler_render_params_are_forwarded_to_render_event() {
+        let mut engine = setup_engine();
+        let mut sampler = Instrument::new(0, InstrumentType::Sampler, "Sampler");
+        sampler.sampler_render = Some(SamplerRenderParams {
+            variant: SamplerRenderVariant::Air,
+            transient_level: 99,
+            body_level: 31,
+        });
+        engine
+            .apply_command(EngineCommand::UpsertInstrument {
+                instrument: sampler,
+            })
+            .unwrap();
+        engine
+            .apply_command(EngineCommand::SetPhraseStep {
+                phrase_id: 0,
+                step_index: 0,
+                note: Some(60),
+                velocity: 100,
+                instrument_id: Some(0),
+            })
+            .unwrap();
+
+        let mut scheduler = Scheduler::new(4);
+        let events = scheduler.tick(&engine);
+        let note_on = events
+            .iter()
+            .find_map(|event| match event {
+                RenderEvent::NoteOn {
+                    render_mode,
+                    sampler_variant,
+                    sampler_transient_level,
+                    sampler_body_level,
+                    ..
+                } => Some((
+                    *render_mode,
+                    *sampler_variant,
+                    *sampler_transient_level,
+                    *sampler_body_level,
+                )),
+                _ => None,
+            })
+            .expect("expected sampler note on");
+
+        assert_eq!(note_on.0, RenderMode::SamplerV1);
+        assert_eq!(note_on.1, SamplerRenderVariant::Air);
+        assert_eq!(note_on.2, 99);
+        assert_eq!(note_on.3, 31);
+    }
+
+    #[test]
+    fn mixer_routing_levels_are_forwarded_to_render_event() {
+        let mut engine = setup_engine();
+        let mut instrument = Instrument::new(0, InstrumentType::Synth, "Mix");
+        instrument.send_levels.mfx = 100;
+        instrument.send_levels.delay = 80;
+        instrument.send_levels.reverb = 60;
+        engine
+            .apply_command(EngineCommand::UpsertInstrument { instrument })
+            .unwrap();
+        engine
+            .apply_command(EngineCommand::SetTrackLevel {
+                track_index: 0,
+                level: 101,
+            })
+            .unwrap();
+        engine
+            .apply_command(EngineCommand::SetMasterLevel { level: 109 })
+            .unwrap();
+        engine
+            .apply_command(EngineCommand::SetMixerSends {
+                mfx: 64,
+                delay: 32,
+                reverb: 96,
+            })
+            .unwrap();
+        engine
+            .apply_command(EngineCommand::SetPhraseStep {
+                phrase_id: 0,
+                step_index: 0,
+                note: Some(60),
+                velocity: 100,
+                instrument_id: Some(0),
+            })
+            .unwrap();
+
+        let mut scheduler = Scheduler::new(4);
+        let events = scheduler.tick(&engine);
+        let routed = events
+            .iter()
+            .find_map(|event| match event {
+                RenderEvent::NoteOn {
+                    track_level,
+                    master_level,
+                    send_mfx,
+                    send_delay,
+                    send_reverb,
+                    ..
+                } => Some((
+                    *track_level,
+                    *master_level,
+                    *send_mfx,
+                    *send_delay,
+                    *send_reverb,
+                )),
+                _ => None,
+            })
+            .expect("expected note on");
+
+        assert_eq!(routed.0, 101);
+        assert_eq!(routed.1, 109);
+        assert_eq!(routed.2, 50);
+        assert_eq!(routed.3, 20);
+        assert_eq!(routed.4, 45);
+    }
+
+    #[test]
+    fn pan_combines_step_instrument_and_mixer_track() {
+        let mut engine = setup_engine();
+        let mut instrument = Instrument::new(0, InstrumentType::Synth, "Pan");
+        instrument.pan = 0x20;
+        engine
+            .apply_command(EngineCommand::UpsertInstrument { instrument })
+            .unwrap();
+        engine
+            .apply_command(EngineCommand::SetTrackPan {
+                track_index: 0,
+                pan: 0x50,
+            })
+            .unwrap();
+        for step_index in [0, 1] {
+            engine
+                .apply_command(EngineCommand::SetPhraseStep {
+                    phrase_id: 0,
+                    step_index,
+                    note: Some(60 + step_index as u8),
+                    velocity: 100,
+                    instrument_id: Some(0),
+                })
+                .unwrap();
+        }
+        engine
+            .apply_command(EngineCommand::SetStepFx {
+                phrase_id: 0,
+                step_index: 1,
+                fx_slot: 0,
+                fx: Some(FxCommand {
+                    code: "PAN".to_string(),
+                    value: 0x78,
+                }),
+            })
+            .unwrap();
+
+        let mut scheduler = Scheduler::new(4);
+        let pans: Vec<u8> = (0..2)
+            .flat_map(|_| scheduler.tick(&engine))
+            .filter_map(|event| match event {
+                RenderEvent::NoteOn { pan, .. } => Some(pan),
+                _ => None,
+            })
+            .collect();
+
+        // Instrument 0x20 offset by track 0x50, then step PAN 0x78 clamped at hard right.
+        assert_eq!(pans, vec![0x30, 0x80]);
+        assert!(engine
+            .apply_command(EngineCommand::SetTrackPan {
+                track_index: 0,
+                pan: 0x81,
+            })
+            .is_err());
+    }
+
+    #[test]
+    fn routing_levels_are_clamped_for_safety() {
+        let mut engine = setup_engine();
+        let mut instrument = Instrument::new(0, InstrumentType::Synth, "Clamp");
+        instrument.send_levels.mfx = 255;
+        instrument.send_levels.delay = 200;
+        instrument.send_levels.reverb = 180;
+        engine
+            .apply_command(EngineCommand::UpsertInstrument { instrument })
+            .unwrap();
+        engine
+            .apply_command(EngineCommand::SetTrackLevel {
+                track_index: 0,
+                level: 240,
+            })
+            .unwrap();
+        engine
+            .apply_command(EngineCommand::SetMasterLevel { level: 250 })
+            .unwrap();
+        engine
+            .apply_command(EngineCommand::SetMixerSends {
+                mfx: 255,
+                delay: 255,
+                reverb: 255,
+            })
+            .unwrap();
+        engine
+            .apply_command(EngineCommand::SetPhraseStep {
+                phrase_id: 0,
+                step_index: 0,
+                note: Some(60),
+                velocity: 100,
+                instrument_id: Some(0),
+            })
+            .unwrap();
+
+        let mut scheduler = Scheduler::new(4);
+        let events = scheduler.tick(&engine);
+        let routed = events
+            .iter()
+            .find_map(|event| match event {
+                RenderEvent::NoteOn {
+                    track_level,
+                    master_level,
+                    send_mfx,
+                    send_delay,
+                    send_reverb,
+                    ..
+                } => Some((
+                    *track_level,
+                    *master_level,
+                    *send_mfx,
+                    *send_delay,
+                    *send_reverb,
+                )),
+                _ => None,
+            })
+            .expect("expected note on");
+
+        assert_eq!(routed.0, 127);
+        assert_eq!(routed.1, 127);
+        assert_eq!(routed.2, 127);
+        assert_eq!(routed.3, 127);
+        assert_eq!(routed.4, 127);
+    }
+
+    #[test]
+    fn complex_fx_stack_and_routing_stays_deterministic() {
+        let mut engine = setup_engine();
+        let mut instrument = Instrument::new(0, InstrumentType::Synth, "Stack");
+        instrument.table_id = Some(0);
+        instrument.note_length_steps = 5;
+        instrument.send_levels.mfx = 64;
+        engine
+            .apply_command(EngineCommand::UpsertInstrument { instrument })
+            .unwrap();
+
+        let mut table = Table::new(0);
+        table.rows[0].note_offset = -1;
+        table.rows[0].volume = 64;
+        table.rows[0].fx[0] = Some(FxCommand {
+            code: "TRN".to_string(),
+            value: 49,
+        });
+        table.rows[0].fx[1] = Some(FxCommand {
+            code: "LEN".to_string(),
+            value: 2,
+        });
+        engine
+            .apply_command(EngineCommand::UpsertTable { table })
+            .unwrap();
+        engine
+            .apply_command(EngineCommand::SetMixerSends {
+                mfx: 64,
+                delay: 0,
+                reverb: 0,
+            })
+            .unwrap();
+        engine
+            .apply_command(EngineCommand::SetTrackLevel {
+                track_index: 0,
+                level: 96,
+            })
+            .unwrap();
+        engine
+            .apply_command(EngineCommand::SetMasterLevel { level: 112 })
+            .unwrap();
+        engine
+            .apply_command(EngineCommand::SetStepFx {
+                phrase_id: 0,
+                step_index: 0,
+                fx_slot: 0,
+                fx: Some(FxCommand {
+                    code: "TRN".to_string(),
+                    value: 50,
+                }),
+            })
+            .unwrap();
+        engine
+            .apply_command(EngineCommand::SetStepFx {
+                phrase_id: 0,
+                step_index: 0,
+                fx_slot: 1,
+                fx: Some(FxCommand {
+                    code: "VOL".to_string(),
+                    value: 90,
+                }),
+            })
+            .unwrap();
+        engine
+            .apply_command(EngineCommand::SetStepFx {
+                phrase_id: 0,
+                step_index: 0,
+                fx_slot: 2,
+                fx: Some(FxCommand {
+                    code: "LEN".to_string(),
+                    value: 3,
+                }),
+            })
+            .unwrap();
+        engine
+            .apply_command(EngineCommand::SetPhraseStep {
+                phrase_id: 0,
+                step_index: 0,
+                note: Some(60),
+                velocity: 100,
+                instrument_id: Some(0),
+            })
+            .unwrap();
+        engine
+            .apply_command(EngineCommand::SetPhraseStep {
+                phrase_id: 0,
+                step_index: 1,
+                note: None,
+                velocity: 80,
+                instrument_id: Some(0),
+            })
+            .unwrap();
+        engine
+            .apply_command(EngineCommand::SetPhraseStep {
+                phrase_id: 0,
+                step_index: 2,
+                note: None,
+                velocity: 80,
+                instrument_id: Some(0),
+            })
+            .unwrap();
+
+        let mut scheduler = Scheduler::new(4);
+        let t1 = scheduler.tick(&engine);
+        let t2 = scheduler.tick(&engine);
+        let t3 = scheduler.tick(&engine);
+
+        let note_on = t1
+            .iter()
+            .find_map(|event| match event {
+                RenderEvent::NoteOn {
+                    note,
+                    velocity,
+                    track_level,
+                    master_level,
+                    send_mfx,
+                    ..
+                } => Some((*note, *velocity, *track_level, *master_level, *send_mfx)),
+                _ => None,
+            })
+            .expect("expected note on");
+
+        assert_eq!(note_on.0, 62);
+        assert_eq!(note_on.1, 45);
+        assert_eq!(note_on.2, 96);
+        assert_eq!(note_on.3, 112);
+        assert_eq!(note_on.4, 32);
+        assert_eq!(count_note_off(&t2), 0);
+        assert_eq!(count_note_off(&t3), 1);
+    }
+
+    #[test]
+    fn rtg_fx_retriggers_note_every_n_ticks() {
+        let mut engine = setup_engine();
+        engine
+            .apply_command(EngineCommand::SetStepFx {
+                phrase_id: 0,
+                step_index: 0,
+                fx_slot: 0,
+                fx: Some(FxCommand {
+                    code: "RTG".to_string(),
+                    value: 2,
+                }),
+            })
+            .unwrap();
+
+        let mut scheduler = Scheduler::new(16); // 4 ticks per step
+        let ticks: Vec<Vec<RenderEvent>> = (0..5).map(|_| scheduler.tick(&engine)).collect();
+
+        assert_eq!(count_note_on(&ticks[0]), 1);
+        assert_eq!(count_note_on(&ticks[1]), 0);
+        assert_eq!(count_note_off(&ticks[2]), 1);
+        assert_eq!(count_note_on(&ticks[2]), 1);
+        assert_eq!(count_note_on(&ticks[3]), 0);
+        assert_eq!(note_ons(&ticks[4]), vec![61]);
+        assert_eq!(count_note_off(&ticks[4]), 1);
+    }
+
+    #[test]
+    fn del_fx_delays_note_on_within_step() {
+        let mut engine = setup_engine();
+        engine
+            .apply_command(EngineCommand::SetStepFx {
+                phrase_id: 0,
+                step_index: 1,
+                fx_slot: 0,
+                fx: Some(FxCommand {
+                    code: "DEL".to_string(),
+                    value: 2,
+                }),
+            })
+            .unwrap();
+
+        let mut scheduler = Scheduler::new(16);
+        let ticks: Vec<Vec<RenderEvent>> = (0..8).map(|_| scheduler.tick(&engine)).collect();
+
+        assert_eq!(note_ons(&ticks[0]), vec![60]);
+        assert_eq!(count_note_off(&ticks[4]), 1);
+        assert_eq!(count_note_on(&ticks[4]), 0);
+        assert_eq!(count_note_on(&ticks[5]), 0);
+        assert_eq!(note_ons(&ticks[6]), vec![61]);
+        assert_eq!(count_note_on(&ticks[7]), 0);
+    }
+
+    #[test]
+    fn del_fx_past_the_step_lands_on_its_last_tick() {
+        for delay in [3, 4, 200] {
+            let mut engine = setup_engine();
+            engine
+                .apply_command(EngineCommand::SetStepFx {
+                    phrase_id: 0,
+                    step_index: 1,
+                    fx_slot: 0,
+                    fx: Some(FxCommand {
+                        code: "DEL".to_string(),
+                        value: delay,
+                    }),
+                })
+                .unwrap();
+
+            let mut scheduler = Scheduler::new(16); // 4 ticks per step
+            let ticks: Vec<Vec<RenderEvent>> = (0..9).map(|_| scheduler.tick(&engine)).collect();
+
+            assert_eq!(count_note_on(&ticks[4..7].concat()), 0, "DEL {delay}");
+            assert_eq!(note_ons(&ticks[7]), vec![61], "DEL {delay}");
+        }
+
+        // With one tick per step there is no later tick, so the note plays on time.
+        let mut engine = setup_engine();
+        engine
+            .apply_command(EngineCommand::SetStepFx {
+                phrase_id: 0,
+                step_index: 1,
+                fx_slot: 0,
+                fx: Some(FxCommand {
+                    code: "DEL".to_string(),
+                    value: 2,
+                }),
+            })
+            .unwrap();
+        let mut scheduler = Scheduler::new(4);
+        scheduler.tick(&engine);
+        assert_eq!(note_ons(&scheduler.tick(&engine)), vec![61]);
+    }
+
+    #[test]
+    fn kil_fx_cuts_note_after_n_ticks() {
+        let mut engine = setup_engine();
+        engine
+            .apply_command(EngineCommand::SetStepFx {
+                phrase_id: 0,
+                step_index: 0,
+                fx_slot: 0,
+                fx: Some(FxCommand {
+                    code: "KIL".to_string(),
+                    value: 3,
+                }),
+            })
+            .unwrap();
+        engine
+            .apply_command(EngineCommand::SetStepFx {
+                phrase_id: 0,
+                step_index: 0,
+                fx_slot: 1,
+                fx: Some(FxCommand {
+                    code: "LEN".to_string(),
+                    value: 4,
+                }),
+            })
+            .unwrap();
+
+        let mut scheduler = Scheduler::new(16);
+        let ticks: Vec<Vec<RenderEvent>> = (0..5).map(|_| scheduler.tick(&engine)).collect();
+
+        assert_eq!(count_note_on(&ticks[0]), 1);
+        assert_eq!(count_note_off(&ticks[..3].concat()), 0);
+        assert_eq!(count_note_off(&ticks[3]), 1);
+        assert_eq!(note_ons(&ticks[4]), vec![61]);
+        assert_eq!(count_note_off(&ticks[4]), 0);
+    }
+
+    #[test]
+    fn tick_offset_fx_stack_stays_deterministic() {
+        let mut engine = setup_engine();
+        for (step_index, fx_slot, code, value) in [
+            (0, 0, "RTG", 2),
+            (0, 1, "KIL", 3),
+            (1, 0, "DEL", 1),
+            (1, 1, "RTG", 2),
+        ] {
+            engine
+                .apply_command(EngineCommand::SetStepFx {
+                    phrase_id: 0,
+                    step_index,
+                    fx_slot,
+                    fx: Some(FxCommand {
+                        code: code.to_string(),
+                        value,
+                    }),
+                })
+                .unwrap();
+        }
+
+        let run = || {
+            let mut scheduler = Scheduler::new(16);
+            (0..12).map(|_| scheduler.tick(&engine)).collect::<Vec<_>>()
+        };
+        let first = run();
+        let second = run();
+
+        assert_eq!(first, second);
+        assert_eq!(count_note_on(&first[..4].concat()), 2);
+        assert_eq!(count_note_off(&first[3]), 1);
+        assert!(first[4].is_empty());
+        assert_eq!(note_ons(&first[5]), vec![61]);
+        assert_eq!(note_ons(&first[6]), vec![61]);
+    }
+
+    #[test]
+    fn hop_fx_jumps_to_step_of_next_phrase() {
+        let mut engine = setup_engine();
+        let mut chain = Chain::new(0);
+        chain.rows[0].phrase_id = Some(0);
+        chain.rows[1].phrase_id = Some(1);
+        engine
+            .apply_command(EngineCommand::UpsertChain { chain })
+            .unwrap();
+        let mut phrase = Phrase::new(1);
+        phrase.steps[5].note = Some(72);
+        phrase.steps[5].velocity = 100;
+        engine
+            .apply_command(EngineCommand::UpsertPhrase { phrase })
+            .unwrap();
+        engine
+            .apply_command(EngineCommand::SetStepFx {
+                phrase_id: 0,
+                step_index: 2,
+                fx_slot: 0,
+                fx: Some(FxCommand {
+                    code: "HOP".to_string(),
+                    value: 5,
+                }),
+            })
+            .unwrap();
+
+        let mut scheduler = Scheduler::new(4);
+        let ticks: Vec<Vec<RenderEvent>> = (0..4).map(|_| scheduler.tick(&engine)).collect();
+
+        assert_eq!(note_ons(&ticks[0]), vec![60]);
+        assert_eq!(note_ons(&ticks[1]), vec![61]);
+        assert!(note_ons(&ticks[2]).is_empty());
+        assert_eq!(note_ons(&ticks[3]), vec![72]);
+        assert_eq!(scheduler.track_state[0].chain_row, 1);
+        assert_eq!(scheduler.track_state[0].phrase_step, 6);
+    }
+
+    #[test]
+    fn jmp_fx_releases_and_moves_tracks_with_a_chain_on_the_row() {
+        let mut engine = setup_engine();
+        let mut chain = Chain::new(1);
+        chain.rows[0].phrase_id = Some(1);
+        engine
+            .apply_command(EngineCommand::UpsertChain { chain })
+            .unwrap();
+        let mut phrase = Phrase::new(1);
+        phrase.steps[0].note = Some(72);
+        phrase.steps[0].velocity = 100;
+        engine
+            .apply_command(EngineCommand::UpsertPhrase { phrase })
+            .unwrap();
+        for (track_index, row, chain_id) in [(0, 3, 1), (1, 0, 0), (1, 3, 1), (2, 0, 0)] {
+            engine
+                .apply_command(EngineCommand::SetSongRowChain {
+                    track_index,
+                    row,
+                    chain_id: Some(chain_id),
+                })
+                .unwrap();
+        }
+        engine
+            .apply_command(EngineCommand::SetStepFx {
+                phrase_id: 0,
+                step_index: 1,
+                fx_slot: 0,
+                fx: Some(FxCommand {
+                    code: "JMP".to_string(),
+                    value: 3,
+                }),
+            })
+            .unwrap();
+
+        let run = || {
+            let mut scheduler = Scheduler::new(4);
+            let ticks = (0..3).map(|_| scheduler.tick(&engine)).collect::<Vec<_>>();
+            let rows = scheduler.track_state.iter().map(|state| state.song_row).collect();
+            (ticks, rows)
+        };
+        let (ticks, rows): (_, Vec<usize>) = run();
+
+        assert_eq!(note_ons(&ticks[0]), vec![60, 60, 60]);
+        assert_eq!(note_ons(&ticks[1]), vec![61, 61, 61]);
+        // Jumping tracks release their notes with the jump; track 2 has no chain on row 3.
+        let released: Vec<u8> = ticks[1]
+            .iter()
+            .filter_map(|event| match event {
+                RenderEvent::NoteOff { track_id, note: 61 } => Some(*track_id),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(released, vec![0, 1]);
+        assert_eq!(note_ons(&ticks[2]), vec![72, 72]);
+        assert_eq!(&rows[..3], &[3, 3, 0]);
+        assert_eq!(run().0, ticks);
+    }
+
+    #[test]
+    fn tpo_fx_overrides_tempo_until_rewind() {
+        let mut engine = setup_engine();
+        engine
+            .apply_command(EngineCommand::SetStepFx {
+                phrase_id: 0,
+                step_index: 1,
+                fx_slot: 0,
+                fx: Some(FxCommand {
+                    code: "TPO".to_string(),
+                    value: 150,
+                }),
+            })
+            .unwrap();
+
+        let mut scheduler = Scheduler::new(4);
+        scheduler.tick(&engine);
+        assert_eq!(scheduler.effective_tempo(&engine), 120);
+        scheduler.tick(&engine);
+        assert_eq!(scheduler.effective_tempo(&engine), 150);
+        scheduler.tick(&engine);
+        assert_eq!(scheduler.effective_tempo(&engine), 150);
+
+        scheduler.rewind();
+        assert_eq!(scheduler.effective_tempo(&engine), 120);
+    }
+
+    #[test]
+    fn grv_fx_switches_track_groove_from_that_step() {
+        let mut engine = setup_engine();
+        engine
+            .apply_command(EngineCommand::SetPhraseStep {
+                phrase_id: 0,
+                step_index: 2,
+                note: Some(64),
+                velocity: 90,
+                instrument_id: None,
+            })
+            .unwrap();
+        engine
+            .apply_command(EngineCommand::UpsertGroove {
+                groove: Groove {
+                    id: 1,
+                    ticks_pattern: vec![2],
+                },
+            })
+            .unwrap();
+        engine
+            .apply_command(EngineCommand::SetStepFx {
+                phrase_id: 0,
+                step_index: 1,
+                fx_slot: 0,
+                fx: Some(FxCommand {
+                    code: "GRV".to_string(),
+                    value: 1,
+                }),
+            })
+            .unwrap();
+
+        let mut scheduler = Scheduler::new(16); // 4 ticks per step before GRV
+        let ticks: Vec<Vec<RenderEvent>> = (0..9).map(|_| scheduler.tick(&engine)).collect();
+
+        assert_eq!(note_ons(&ticks[0]), vec![60]);
+        assert_eq!(note_ons(&ticks[4]), vec![61]);
+        assert_eq!(note_ons(&ticks[6]), vec![64]);
+        assert_eq!(count_note_off(&ticks[8]), 1);
+        assert_eq!(scheduler.track_state[0].groove_fx, Some(1));
+    }
+
+    #[test]
+    fn cha_fx_uses_seeded_rng_deterministically() {
+        fn chance_engine(seed: u32, chance: u8) -> Engine {
+            let mut engine = setup_engine();
+            engine.apply_command(EngineCommand::SetSongSeed(seed)).unwrap();
+            for step_index in 0..PHRASE_STEP_COUNT {
+                engine
+                    .apply_command(EngineCommand::SetPhraseStep {
+                        phrase_id: 0,
+                        step_index,
+                        note: Some(60),
+                        velocity: 100,
+                        instrument_id: None,
+                    })
+                    .unwrap();
+                engine
+                    .apply_command(EngineCommand::SetStepFx {
+                        phrase_id: 0,
+                        step_index,
+                        fx_slot: 0,
+                        fx: Some(FxCommand {
+                            code: "CHA".to_string(),
+                            value: chance,
+                        }),
+                    })
+                    .unwrap();
+            }
+            engine
+        }
+
+        fn played_steps(engine: &Engine, scheduler: &mut Scheduler) -> Vec<bool> {
+            (0..64)
+                .map(|_| count_note_on(&scheduler.tick(engine)) == 1)
+                .collect()
+        }
+
+        let engine = chance_engine(7, 128);
+        let mut scheduler = Scheduler::new(4);
+        let first = played_steps(&engine, &mut scheduler);
+        scheduler.rewind();
+        let replay = played_steps(&engine, &mut scheduler);
+        let other_seed = played_steps(&chance_engine(8, 128), &mut Scheduler::new(4));
+        let played = first.iter().filter(|played| **played).count();
+
+        assert_eq!(first, replay);
+        assert_ne!(first, other_seed);
+        assert!(played > 16 && played < 48, "played {played} of 64");
+
+        let always = played_steps(&chance_engine(7, 255), &mut Scheduler::new(4));
+        let never = played_steps(&chance_engine(7, 0), &mut Scheduler::new(4));
+        assert!(always.iter().all(|played| *played));
+        assert!(never.iter().all(|played| !*played));
+    }
+
+    #[test]
+    fn rnv_and_rnn_fx_stay_within_range_for_seed() {
+        let mut engine = setup_engine();
+        engine.apply_command(EngineCommand::SetSongSeed(42)).unwrap();
+        for (fx_slot, code, value) in [(0, "RNV", 20), (1, "RNN", 3), (2, "RTG", 1)] {
+            engine
+                .apply_command(EngineCommand::SetStepFx {
+                    phrase_id: 0,
+                    step_index: 0,
+                    fx_slot,
+                    fx: Some(FxCommand {
+                        code: code.to_string(),
+                        value,
+                    }),
+                })
+                .unwrap();
+        }
+
+        // Four passes over the phrase, each hitting step 0 with four retriggers.
+        let collect = || {
+            let mut scheduler = Scheduler::new(16);
+            (0..4)
+                .map(|_| {
+                    let pass: Vec<_> = (0..64).map(|_| scheduler.tick(&engine)).collect();
+                    pass[..4]
+                        .concat()
+                        .into_iter()
+                        .filter_map(|event| match event {
+                            RenderEvent::NoteOn { note, velocity, .. } => Some((note, velocity)),
+                            _ => None,
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>()
+        };
+        let passes = collect();
+
+        assert_eq!(passes, collect());
+        for hits in &passes {
+            assert_eq!(hits.len(), 4);
+            assert!(hits.iter().all(|hit| *hit == hits[0]), "retriggers re-rolled: {hits:?}");
+            let (note, velocity) = hits[0];
+            assert!((57..=63).contains(&note) && (80..=120).contains(&velocity));
+        }
+        assert!(passes.iter().any(|hits| hits[0] != passes[0][0]));
+    }
+
+    #[test]
+    fn random_fx_streams_are_independent_per_track() {
+        let mut engine = setup_engine();
+        engine.apply_command(EngineCommand::SetSongSeed(9)).unwrap();
+        engine
+            .apply_command(EngineCommand::SetStepFx {
+                phrase_id: 0,
+                step_index: 0,
+                fx_slot: 0,
+                fx: Some(FxCommand {
+                    code: "RNN".to_string(),
+                    value: 12,
+                }),
+            })
+            .unwrap();
+        let notes_by_track = |engine: &Engine| {
+            let mut scheduler = Scheduler::new(4);
+            let mut notes = [Vec::new(), Vec::new()];
+            for event in (0..64).flat_map(|_| scheduler.tick(engine)) {
+                if let RenderEvent::NoteOn { track_id, note, .. } = event {
+                    notes[track_id as usize].push(note);
+                }
+            }
+            notes
+        };
+
+        let solo = notes_by_track(&engine);
+        engine
+            .apply_command(EngineCommand::SetSongRowChain {
+                track_index: 1,
+                row: 0,
+                chain_id: Some(0),
+            })
+            .unwrap();
+        let both = notes_by_track(&engine);
+
+        assert_eq!(both[0], solo[0]);
+        assert_ne!(both[1], both[0]);
+        engine
+            .apply_command(EngineCommand::ToggleTrackMute { track_index: 1 })
+            .unwrap();
+        assert_eq!(notes_by_track(&engine)[0], solo[0]);
+    }
+
+    #[test]
+    fn vib_fx_modulates_pitch_with_triangle_lfo() {
+        let mut engine = setup_engine();
+        engine
+            .apply_command(EngineCommand::SetStepFx {
+                phrase_id: 0,
+                step_index: 0,
+                fx_slot: 0,
+                fx: Some(FxCommand {
+                    code: "VIB".to_string(),
+                    value: 0x44,
+                }),
+            })
+            .unwrap();
+
+        let mut scheduler = Scheduler::new(96); // 24 ticks per step
+        let ticks: Vec<Vec<RenderEvent>> = (0..25).map(|_| scheduler.tick(&engine)).collect();
+        let bends: Vec<Vec<i16>> = ticks[..10].iter().map(|events| pitch_bends(events)).collect();
+
+        assert_eq!(
+            bends,
+            vec![
+                vec![],
+                vec![10],
+                vec![20],
+                vec![30],
+                vec![40],
+                vec![30],
+                vec![20],
+                vec![10],
+                vec![0],
+                vec![-10],
+            ]
+        );
+        assert_eq!(count_note_off(&ticks[24]), 1);
+        assert!(pitch_bends(&ticks[24]).is_empty());
+        assert!(scheduler.track_state[0].vibrato.is_none());
+    }
+
+    #[test]
+    fn trm_fx_modulates_volume_with_lfo() {
+        let mut engine = setup_engine();
+        engine
+            .apply_command(EngineCommand::SetStepFx {
+                phrase_id: 0,
+                step_index: 0,
+                fx_slot: 0,
+                fx: Some(FxCommand {
+                    code: "TRM".to_string(),
+                    value: 0x8F,
+                }),
+            })
+            .unwrap();
+
+        let mut scheduler = Scheduler::new(96);
+        let volumes: Vec<Vec<u8>> = (0..9)
+            .map(|_| note_volumes(&scheduler.tick(&engine)))
+            .collect();
+
+        assert_eq!(
+            volumes,
+            vec![
+                vec![],
+                vec![75],
+                vec![50],
+                vec![25],
+                vec![0],
+                vec![25],
+                vec![50],
+                vec![75],
+                vec![100],
+            ]
+        );
+    }
+
+    #[test]
+    fn vsl_fx_ramps_volume_until_next_note() {
+        let mut engine = setup_engine();
+        engine
+            .apply_command(EngineCommand::SetStepFx {
+                phrase_id: 0,
+                step_index: 0,
+                fx_slot: 0,
+                fx: Some(FxCommand {
+                    code: "VSL".to_string(),
+                    value: 0x0F,
+                }),
+            })
+            .unwrap();
+
+        let mut scheduler = Scheduler::new(96);
+        let volumes: Vec<Vec<u8>> = (0..24)
+            .map(|_| note_volumes(&scheduler.tick(&engine)))
+            .collect();
+
+        assert_eq!(volumes[0], Vec::<u8>::new());
+        assert_eq!(volumes[1], vec![85]);
+        assert_eq!(volumes[6], vec![10]);
+        assert_eq!(volumes[7], vec![0]);
+        assert!(volumes[8..].iter().all(|tick| tick.is_empty()));
+
+        // The next note starts at its own velocity with the slide cleared.
+        let next_step = scheduler.tick(&engine);
+        assert_eq!(count_note_on(&next_step), 1);
+        assert!(note_volumes(&next_step).is_empty());
+        assert!(note_volumes(&scheduler.tick(&engine)).is_empty());
+        assert_eq!(scheduler.track_state[0].volume_slide, None);
+    }
+
+    #[test]
+    fn slide_fx_glides_from_previous_note_over_ticks() {
+        let mut engine = setup_engine();
+        engine
+            .apply_command(EngineCommand::SetPhraseStep {
+                phrase_id: 0,
+                step_index: 1,
+                note: Some(64),
+                velocity: 90,
+                instrument_id: None,
+            })
+            .unwrap();
+        engine
+            .apply_command(EngineCommand::SetStepFx {
+                phrase_id: 0,
+                step_index: 1,
+                fx_slot: 0,
+                fx: Some(FxCommand {
+                    code: "SLD".to_string(),
+                    value: 4,
+                }),
+            })
+            .unwrap();
+        engine
+            .apply_command(EngineCommand::SetStepFx {
+                phrase_id: 0,
+                step_index: 1,
+                fx_slot: 1,
+                fx: Some(FxCommand {
+                    code: "LEN".to_string(),
+                    value: 2,
+                }),
+            })
+            .unwrap();
+
+        let mut scheduler = Scheduler::new(16); // 4 ticks per step
+        let ticks: Vec<Vec<RenderEvent>> = (0..10).map(|_| scheduler.tick(&engine)).collect();
+
+        assert!(ticks[..4].iter().all(|events| pitch_bends(events).is_empty()));
+        assert_eq!(count_note_on(&ticks[4]), 1);
+        assert_eq!(pitch_bends(&ticks[4]), vec![-400]);
+        assert_eq!(pitch_bends(&ticks[5]), vec![-300]);
+        assert_eq!(pitch_bends(&ticks[6]), vec![-200]);
+        assert_eq!(pitch_bends(&ticks[7]), vec![-100]);
+        assert_eq!(pitch_bends(&ticks[8]), vec![0]);
+        assert!(pitch_bends(&ticks[9]).is_empty());
+        assert!(ticks.iter().flatten().all(|event| match event {
+            RenderEvent::PitchBend { note, .. } => *note == 64,
+            _ => true,
+        }));
+    }
+
+    #[test]
+    fn slide_fx_without_previous_note_plays_straight() {
+        let mut engine = setup_engine();
+        engine
+            .apply_command(EngineCommand::SetStepFx {
+                phrase_id: 0,
+                step_index: 0,
+                fx_slot: 0,
+                fx: Some(FxCommand {
+                    code: "SLD".to_string(),
+                    value: 3,
+                }),
+            })
+            .unwrap();
+
+        let mut scheduler = Scheduler::new(16);
+        let events: Vec<RenderEvent> = (0..4).flat_map(|_| scheduler.tick(&engine)).collect();
+
+        assert_eq!(count_note_on(&events), 1);
+        assert!(pitch_bends(&events).is_empty());
+    }
+
+    #[test]
+    fn arp_fx_cycles_offsets_every_tick_within_step() {
+        let mut engine = setup_engine();
+        engine
+            .apply_command(EngineCommand::SetPhraseStep {
+                phrase_id: 0,
+                step_index: 1,
+                note: None,
+                velocity: 0,
+                instrument_id: None,
+            })
+            .unwrap();
+        engine
+            .apply_command(EngineCommand::SetStepFx {
+                phrase_id: 0,
+                step_index: 0,
+                fx_slot: 0,
+                fx: Some(FxCommand {
+                    code: "ARP".to_string(),
+                    value: 0x47,
+                }),
+            })
+            .unwrap();
+        engine
+            .apply_command(EngineCommand::SetStepFx {
+                phrase_id: 0,
+                step_index: 0,
+                fx_slot: 1,
+                fx: Some(FxCommand {
+                    code: "LEN".to_string(),
+                    value: 2,
+                }),
+            })
+            .unwrap();
+
+        let mut scheduler = Scheduler::new(24); // 6 ticks per step
+        let ticks: Vec<Vec<RenderEvent>> = (0..8).map(|_| scheduler.tick(&engine)).collect();
+
+        assert_eq!(count_note_on(&ticks[0]), 1);
+        assert!(pitch_bends(&ticks[0]).is_empty());
+        assert_eq!(pitch_bends(&ticks[1]), vec![400]);
+        assert_eq!(pitch_bends(&ticks[2]), vec![700]);
+        assert_eq!(pitch_bends(&ticks[3]), vec![0]);
+        assert_eq!(pitch_bends(&ticks[4]), vec![400]);
+        assert_eq!(pitch_bends(&ticks[5]), vec![700]);
+        // The held note returns to its root once the arpeggiated step ends.
+        assert_eq!(pitch_bends(&ticks[6]), vec![0]);
+        assert!(pitch_bends(&ticks[7]).is_empty());
+        assert_eq!(count_note_off(&ticks.concat()), 0);
+    }
+
+    #[test]
+    fn arp_fx_composes_with_slide() {
+        let mut engine = setup_engine();
+        engine
+            .apply_command(EngineCommand::SetPhraseStep {
+                phrase_id: 0,
+                step_index: 1,
+                note: Some(62),
+                velocity: 90,
+                instrument_id: None,
+            })
+            .unwrap();
+        for (fx_slot, code, value) in [(0, "SLD", 2), (1, "ARP", 0x34)] {
+            engine
+                .apply_command(EngineCommand::SetStepFx {
+                    phrase_id: 0,
+                    step_index: 1,
+                    fx_slot,
+                    fx: Some(FxCommand {
+                        code: code.to_string(),
+                        value,
+                    }),
+                })
+                .unwrap();
+        }
+
+        let mut scheduler = Scheduler::new(16);
+        let ticks: Vec<Vec<RenderEvent>> = (0..8).map(|_| scheduler.tick(&engine)).collect();
+
+        assert_eq!(pitch_bends(&ticks[4]), vec![-200]);
+        assert_eq!(pitch_bends(&ticks[5]), vec![200]);
+        assert_eq!(pitch_bends(&ticks[6]), vec![400]);
+        assert_eq!(pitch_bends(&ticks[7]), vec![0]);
+    }
+
+    fn pitch_bends(events: &[RenderEvent]) -> Vec<i16> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                RenderEvent::PitchBend { cents, .. } => Some(*cents),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn note_volumes(events: &[RenderEvent]) -> Vec<u8> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                RenderEvent::NoteVolume { velocity, .. } => Some(*velocity),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn note_ons(events: &[RenderEvent]) -> Vec<u8> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                RenderEvent::NoteOn { note, .. } => Some(*note),
+                _ => None,
+            })
+            .collect()
+    }
+
     fn count_note_on(events: &[RenderEvent]) -> usize {
         events
             .iter()
@@ -1,11 +1,15 @@
-use crate::dsp::DspPipeline;
-use crate::voice::VoiceAllocator;
-use p9_core::events::{RenderEvent, RenderMode};
+use crate::dsp::{DspPipeline, SendEffects};
+use crate::sample::{SampleBank, SamplePlayhead};
+use crate::voice::{NoteOnParams, VoiceAllocator};
+use p9_core::events::{BlockRenderEvent, RenderEvent, RenderMode};
+use p9_core::model::{DelayParams, DrumSound, DrumSynthParams, ReverbParams, SynthParams};
+use std::sync::Arc;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct AudioMetrics {
     pub sample_rate_hz: u32,
     pub buffer_size_frames: u32,
+    pub channels: u16,
     pub callbacks_total: u64,
     pub xruns_total: u64,
     pub last_callback_us: u32,
@@ -27,10 +31,17 @@ pub struct AudioMetrics {
     pub voice_steal_active_total: u64,
     pub voice_polyphony_pressure_total: u64,
     pub voice_sampler_mode_note_on_total: u64,
+    pub voice_fm_mode_note_on_total: u64,
+    pub voice_sample_note_on_total: u64,
     pub voice_silent_note_on_total: u64,
     pub voice_mixer_muted_note_on_total: u64,
     pub voice_send_routed_note_on_total: u64,
     pub voice_send_level_total: u64,
+    pub voice_pitch_bend_total: u64,
+    pub voice_volume_change_total: u64,
+    pub voice_param_change_total: u64,
+    pub voice_panned_note_on_total: u64,
+    pub send_delay_frames: u32,
 }
 
 impl Default for AudioMetrics {
@@ -38,6 +49,7 @@ impl Default for AudioMetrics {
         Self {
             sample_rate_hz: 48_000,
             buffer_size_frames: 256,
+            channels: 2,
             callbacks_total: 0,
             xruns_total: 0,
             last_callback_us: 0,
@@ -59,10 +71,17 @@ impl Default for AudioMetrics {
             voice_steal_active_total: 0,
             voice_polyphony_pressure_total: 0,
             voice_sampler_mode_note_on_total: 0,
+            voice_fm_mode_note_on_total: 0,
+            voice_sample_note_on_total: 0,
             voice_silent_note_on_total: 0,
             voice_mixer_muted_note_on_total: 0,
             voice_send_routed_note_on_total: 0,
             voice_send_level_total: 0,
+            voice_pitch_bend_total: 0,
+            voice_volume_change_total: 0,
+            voice_param_change_total: 0,
+            voice_panned_note_on_total: 0,
+            send_delay_frames: 0,
         }
     }
 }
@@ -71,6 +90,7 @@ impl Default for AudioMetrics {
 pub struct AudioBackendConfig {
     pub sample_rate_hz: u32,
     pub buffer_size_frames: u32,
+    pub channels: u16,
     pub base_callback_us: u32,
     pub per_event_us: u32,
     pub max_callback_us: u32,
@@ -83,6 +103,7 @@ impl Default for AudioBackendConfig {
         Self {
             sample_rate_hz: 48_000,
             buffer_size_frames: 256,
+            channels: 2,
             base_callback_us: 220,
             per_event_us: 35,
             max_callback_us: 1_200,
@@ -105,9 +126,21 @@ pub trait AudioBackend {
     }
     fn stop(&mut self);
     fn push_events(&mut self, events: &[RenderEvent]);
+    /// Plays one callback of events from `Scheduler::render_block`. Backends without
+    /// sample-accurate scheduling apply every event at the start of the callback.
+    fn push_block(&mut self, events: &[BlockRenderEvent]) {
+        let events: Vec<RenderEvent> = events.iter().map(|timed| timed.event.clone()).collect();
+        self.push_events(&events);
+    }
     fn events_consumed(&self) -> usize;
     fn metrics(&self) -> AudioMetrics;
     fn backend_name(&self) -> &'static str;
+    /// Hands over the shared bank of decoded sample files that `RenderMode::Sample` notes
+    /// play from.
+    fn load_samples(&mut self, _bank: Arc<SampleBank>) {}
+    /// Mirrors the mixer's delay and reverb settings; the delay time follows `tempo`. Only
+    /// export renders the effects.
+    fn set_send_effects(&mut self, _delay: DelayParams, _reverb: ReverbParams, _tempo: u16) {}
 }
 
 #[derive(Default)]
@@ -154,11 +187,18 @@ pub struct NativeAudioBackend {
     callback_us_total: u64,
     dsp: DspPipeline,
     voices: VoiceAllocator,
+    sample_bank: Arc<SampleBank>,
+    sends: SendEffects,
     sampler_mode_note_on_total: u64,
+    fm_mode_note_on_total: u64,
+    sample_note_on_total: u64,
     silent_note_on_total: u64,
     mixer_muted_note_on_total: u64,
     send_routed_note_on_total: u64,
     send_level_total: u64,
+    pitch_bend_total: u64,
+    volume_change_total: u64,
+    param_change_total: u64,
 }
 
 impl NativeAudioBackend {
@@ -169,17 +209,25 @@ impl NativeAudioBackend {
             metrics: AudioMetrics {
                 sample_rate_hz: config.sample_rate_hz,
                 buffer_size_frames: config.buffer_size_frames,
+                channels: config.channels.clamp(1, 2),
                 max_voices: config.max_voices as u32,
                 ..AudioMetrics::default()
             },
             callback_us_total: 0,
             dsp: DspPipeline::new(config.max_callback_us),
             voices: VoiceAllocator::new(config.max_voices),
+            sample_bank: Arc::default(),
+            sends: SendEffects::new(config.sample_rate_hz),
             sampler_mode_note_on_total: 0,
+            fm_mode_note_on_total: 0,
+            sample_note_on_total: 0,
             silent_note_on_total: 0,
             mixer_muted_note_on_total: 0,
             send_routed_note_on_total: 0,
             send_level_total: 0,
+            pitch_bend_total: 0,
+            volume_change_total: 0,
+            param_change_total: 0,
             config,
         }
     }
@@ -210,79 +258,211 @@ impl AudioBackend for NativeAudioBackend {
         self.running = false;
     }
 
+    fn load_samples(&mut self, bank: Arc<SampleBank>) {
+        self.sample_bank = bank;
+    }
+
+    fn set_send_effects(&mut self, delay: DelayParams, reverb: ReverbParams, tempo: u16) {
+        self.sends.configure(delay, reverb, tempo);
+    }
+
     fn push_events(&mut self, events: &[RenderEvent]) {
         if !self.running {
             return;
         }
 
         self.voices.advance_release_envelopes();
+        self.voices.advance_velocity_smoothing();
+        self.voices.advance_sample_playheads(self.config.buffer_size_frames);
 
         for event in events {
-            match event {
-                RenderEvent::NoteOn {
-                    track_id,
-                    note,
-                    velocity,
-                    render_mode,
-                    track_level,
-                    master_level,
-                    send_mfx,
-                    send_delay,
-                    send_reverb,
-                    instrument_id,
-                    waveform,
-                    attack_ms,
-                    release_ms,
-                    gain,
-                    ..
-                } => {
-                    let effective_gain = routed_gain(*gain, *track_level, *master_level);
-                    if effective_gain == 0 || matches!(render_mode, RenderMode::ExternalMuted) {
-                        if *gain > 0
-                            && !matches!(render_mode, RenderMode::ExternalMuted)
-                            && (*track_level == 0 || *master_level == 0)
-                        {
-                            self.mixer_muted_note_on_total =
-                                self.mixer_muted_note_on_total.saturating_add(1);
-                        }
-                        self.silent_note_on_total = self.silent_note_on_total.saturating_add(1);
-                        continue;
-                    }
+            self.apply_event(event);
+        }
+        self.finish_callback(events.len());
+    }
+
+    fn push_block(&mut self, events: &[BlockRenderEvent]) {
+        if !self.running {
+            return;
+        }
 
-                    let send_total = *send_mfx as u64 + *send_delay as u64 + *send_reverb as u64;
-                    if send_total > 0 {
-                        self.send_routed_note_on_total =
-                            self.send_routed_note_on_total.saturating_add(1);
-                        self.send_level_total = self.send_level_total.saturating_add(send_total);
+        self.voices.advance_release_envelopes();
+        self.voices.advance_velocity_smoothing();
+
+        // Render the block in segments so each event starts at its own frame.
+        let frames = self.config.buffer_size_frames;
+        let mut rendered = 0;
+        for timed in events {
+            let offset = timed.frame_offset.min(frames);
+            if offset > rendered {
+                self.voices.advance_sample_playheads(offset - rendered);
+                rendered = offset;
+            }
+            self.apply_event(&timed.event);
+        }
+        self.voices.advance_sample_playheads(frames - rendered);
+        self.finish_callback(events.len());
+    }
+
+    fn events_consumed(&self) -> usize {
+        self.events_total
+    }
+
+    fn metrics(&self) -> AudioMetrics {
+        self.metrics
+    }
+
+    fn backend_name(&self) -> &'static str {
+        "native-simulated-linux"
+    }
+}
+
+impl NativeAudioBackend {
+    fn apply_event(&mut self, event: &RenderEvent) {
+        match event {
+            RenderEvent::NoteOn {
+                track_id,
+                note,
+                velocity,
+                render_mode,
+                track_level,
+                master_level,
+                pan,
+                send_mfx,
+                send_delay,
+                send_reverb,
+                instrument_id,
+                waveform,
+                attack_ms,
+                decay_ms,
+                sustain,
+                release_ms,
+                filter,
+                gain,
+                fm,
+                drum,
+                ..
+            } => {
+                let effective_gain = routed_gain(*gain, *track_level, *master_level);
+                if effective_gain == 0 || matches!(render_mode, RenderMode::ExternalMuted) {
+                    if *gain > 0
+                        && !matches!(render_mode, RenderMode::ExternalMuted)
+                        && (*track_level == 0 || *master_level == 0)
+                    {
+                        self.mixer_muted_note_on_total =
+                            self.mixer_muted_note_on_total.saturating_add(1);
                     }
+                    self.silent_note_on_total = self.silent_note_on_total.saturating_add(1);
+                    return;
+                }
+
+                let send_total = *send_mfx as u64 + *send_delay as u64 + *send_reverb as u64;
+                if send_total > 0 {
+                    self.send_routed_note_on_total =
+                        self.send_routed_note_on_total.saturating_add(1);
+                    self.send_level_total = self.send_level_total.saturating_add(send_total);
+                }
 
-                    if matches!(render_mode, RenderMode::SamplerV1) {
-                        self.sampler_mode_note_on_total =
-                            self.sampler_mode_note_on_total.saturating_add(1);
+                let sample = if matches!(render_mode, RenderMode::Sample) {
+                    let playhead = instrument_id
+                        .and_then(|id| self.sample_bank.get(id))
+                        .map(|loaded| {
+                            SamplePlayhead::new(loaded, *note, self.config.sample_rate_hz as f32)
+                        })
+                        .filter(|playhead| !playhead.is_finished());
+                    // Missing files and notes outside a sliced sample stay silent.
+                    let Some(playhead) = playhead else {
+                        self.silent_note_on_total = self.silent_note_on_total.saturating_add(1);
+                        return;
+                    };
+                    Some(playhead)
+                } else {
+                    None
+                };
+
+                if matches!(render_mode, RenderMode::SamplerV1) {
+                    self.sampler_mode_note_on_total =
+                        self.sampler_mode_note_on_total.saturating_add(1);
+                }
+                let fm = matches!(render_mode, RenderMode::Fm).then_some(*fm);
+                if fm.is_some() {
+                    self.fm_mode_note_on_total = self.fm_mode_note_on_total.saturating_add(1);
+                }
+                self.voices.note_on(
+                    *track_id,
+                    *note,
+                    *velocity,
+                    NoteOnParams {
+                        instrument_id: *instrument_id,
+                        synth: SynthParams {
+                            waveform: *waveform,
+                            attack_ms: *attack_ms,
+                            decay_ms: *decay_ms,
+                            sustain: *sustain,
+                            release_ms: *release_ms,
+                            gain: effective_gain,
+                            filter: *filter,
+                        },
+                        fm,
+                        pan: *pan,
+                    },
+                );
+                if let Some(playhead) = sample {
+                    if self.voices.attach_sample(*track_id, *note, playhead) {
+                        self.sample_note_on_total = self.sample_note_on_total.saturating_add(1);
                     }
-                    self.voices.note_on(
-                        *track_id,
-                        *note,
-                        *velocity,
-                        *instrument_id,
-                        *waveform,
-                        *attack_ms,
-                        *release_ms,
-                        effective_gain,
-                    );
                 }
-                RenderEvent::NoteOff { track_id, note } => {
-                    let _ = self.voices.note_off(*track_id, *note);
+                // Drum hits ring out over their own decay, as in export.
+                if matches!(render_mode, RenderMode::Drum) {
+                    let _ = self
+                        .voices
+                        .ring_out(*track_id, *note, drum_decay_ms(drum, *note));
+                }
+            }
+            RenderEvent::NoteOff { track_id, note } => {
+                let _ = self.voices.note_off(*track_id, *note);
+            }
+            RenderEvent::PitchBend {
+                track_id,
+                note,
+                cents,
+            } => {
+                if self.voices.set_pitch_cents(*track_id, *note, *cents) {
+                    self.pitch_bend_total = self.pitch_bend_total.saturating_add(1);
+                }
+            }
+            RenderEvent::NoteVolume {
+                track_id,
+                note,
+                velocity,
+            } => {
+                if self.voices.set_velocity(*track_id, *note, *velocity) {
+                    self.volume_change_total = self.volume_change_total.saturating_add(1);
+                }
+            }
+            RenderEvent::TrackParams { track_id, params } => {
+                let gain = routed_gain(params.gain, params.track_level, params.master_level);
+                let updated = self.voices.set_track_params(
+                    *track_id,
+                    gain,
+                    params.attack_ms,
+                    params.release_ms,
+                );
+                if updated > 0 {
+                    self.param_change_total = self.param_change_total.saturating_add(1);
                 }
             }
         }
+    }
 
-        let simulated_callback_us = self.config.base_callback_us.saturating_add(
-            (events.len() as u32).saturating_mul(self.config.per_event_us),
-        );
+    fn finish_callback(&mut self, event_count: usize) {
+        let simulated_callback_us = self
+            .config
+            .base_callback_us
+            .saturating_add((event_count as u32).saturating_mul(self.config.per_event_us));
         let dsp_stats = self.dsp.process_block(simulated_callback_us);
 
-        self.events_total = self.events_total.saturating_add(events.len());
+        self.events_total = self.events_total.saturating_add(event_count);
         self.metrics.callbacks_total = self.metrics.callbacks_total.saturating_add(1);
         self.metrics.last_callback_us = dsp_stats.block_us;
 
@@ -313,22 +493,26 @@ impl AudioBackend for NativeAudioBackend {
         self.metrics.voice_steal_active_total = lifecycle.steal_active_total;
         self.metrics.voice_polyphony_pressure_total = lifecycle.polyphony_pressure_total;
         self.metrics.voice_sampler_mode_note_on_total = self.sampler_mode_note_on_total;
+        self.metrics.voice_fm_mode_note_on_total = self.fm_mode_note_on_total;
+        self.metrics.voice_sample_note_on_total = self.sample_note_on_total;
         self.metrics.voice_silent_note_on_total = self.silent_note_on_total;
         self.metrics.voice_mixer_muted_note_on_total = self.mixer_muted_note_on_total;
         self.metrics.voice_send_routed_note_on_total = self.send_routed_note_on_total;
         self.metrics.voice_send_level_total = self.send_level_total;
+        self.metrics.voice_pitch_bend_total = self.pitch_bend_total;
+        self.metrics.voice_volume_change_total = self.volume_change_total;
+        self.metrics.voice_param_change_total = self.param_change_total;
+        self.metrics.voice_panned_note_on_total = lifecycle.panned_note_on_total;
+        self.metrics.send_delay_frames = self.sends.delay_frames() as u32;
     }
+}
 
-    fn events_consumed(&self) -> usize {
-        self.events_total
-    }
-
-    fn metrics(&self) -> AudioMetrics {
-        self.metrics
-    }
-
-    fn backend_name(&self) -> &'static str {
-        "native-simulated-linux"
+/// How long the sound `note` picks rings; a snare lasts as long as its longer layer.
+fn drum_decay_ms(drum: &DrumSynthParams, note: u8) -> u16 {
+    match DrumSound::for_note(note) {
+        DrumSound::Kick => drum.kick.decay_ms,
+        DrumSound::Snare => drum.snare.tone_decay_ms.max(drum.snare.noise_decay_ms),
+        DrumSound::Hat => drum.hat.decay_ms,
     }
 }
 
@@ -381,8 +565,10 @@ mod tests {
     use super::{
         start_with_noop_fallback, AudioBackend, AudioBackendConfig, NativeAudioBackend,
     };
-    use p9_core::events::{RenderEvent, RenderMode};
-    use p9_core::model::SynthWaveform;
+    use p9_core::events::{BlockRenderEvent, RenderEvent, RenderMode, VoiceParams};
+    use crate::sample::{SampleBank, SampleData};
+    use p9_core::model::{DelayParams, ReverbParams, SynthWaveform};
+    use std::sync::Arc;
 
     fn note_on(track_id: u8, note: u8) -> RenderEvent {
         RenderEvent::NoteOn {
@@ -392,6 +578,7 @@ mod tests {
             render_mode: RenderMode::Synth,
             track_level: 127,
             master_level: 127,
+            pan: 0x40,
             send_mfx: 0,
             send_delay: 0,
             send_reverb: 0,
@@ -399,10 +586,15 @@ mod tests {
             waveform: SynthWaveform::Saw,
             attack_ms: 5,
             release_ms: 80,
+            decay_ms: 0,
+            sustain: 127,
+            filter: p9_core::model::FilterParams::default(),
             gain: 100,
             sampler_variant: p9_core::model::SamplerRenderVariant::Classic,
             sampler_transient_level: 64,
             sampler_body_level: 96,
+            fm: p9_core::model::FmParams::default(),
+            drum: p9_core::model::DrumSynthParams::default(),
         }
     }
 
@@ -439,6 +631,24 @@ mod tests {
         assert_eq!(metrics.voice_silent_note_on_total, 0);
     }
 
+    #[test]
+    fn native_backend_syncs_send_delay_to_tempo() {
+        let mut backend = NativeAudioBackend::default();
+        backend.start_checked().unwrap();
+        let delay = DelayParams {
+            time_steps: 2,
+            ..DelayParams::default()
+        };
+
+        backend.set_send_effects(delay, ReverbParams::default(), 120);
+        backend.push_events(&[]);
+        assert_eq!(backend.metrics().send_delay_frames, 12_000);
+
+        backend.set_send_effects(delay, ReverbParams::default(), 150);
+        backend.push_events(&[]);
+        assert_eq!(backend.metrics().send_delay_frames, 9_600);
+    }
+
     #[test]
     fn start_with_fallback_uses_noop_when_native_start_fails() {
         let primary = Box::new(NativeAudioBackend::new(AudioBackendConfig {
@@ -489,6 +699,7 @@ mod tests {
             render_mode: RenderMode::Synth,
             track_level: 127,
             master_level: 127,
+            pan: 0x40,
             send_mfx: 0,
             send_delay: 0,
             send_reverb: 0,
@@ -496,10 +707,15 @@ mod tests {
             waveform: SynthWaveform::Saw,
             attack_ms: 0,
             release_ms: 1,
+            decay_ms: 0,
+            sustain: 127,
+            filter: p9_core::model::FilterParams::default(),
             gain: 100,
             sampler_variant: p9_core::model::SamplerRenderVariant::Classic,
             sampler_transient_level: 64,
             sampler_body_level: 96,
+            fm: p9_core::model::FmParams::default(),
+            drum: p9_core::model::DrumSynthParams::default(),
         }]);
         backend.push_events(&[RenderEvent::NoteOn {
             track_id: 0,
@@ -508,6 +724,7 @@ mod tests {
             render_mode: RenderMode::Synth,
             track_level: 127,
             master_level: 127,
+            pan: 0x40,
             send_mfx: 0,
             send_delay: 0,
             send_reverb: 0,
@@ -515,10 +732,15 @@ mod tests {
             waveform: SynthWaveform::Saw,
             attack_ms: 4,
             release_ms: 1,
+            decay_ms: 0,
+            sustain: 127,
+            filter: p9_core::model::FilterParams::default(),
             gain: 100,
             sampler_variant: p9_core::model::SamplerRenderVariant::Classic,
             sampler_transient_level: 64,
             sampler_body_level: 96,
+            fm: p9_core::model::FmParams::default(),
+            drum: p9_core::model::DrumSynthParams::default(),
         }]);
         backend.push_events(&[RenderEvent::NoteOff {
             track_id: 0,
@@ -559,6 +781,7 @@ mod tests {
             render_mode: RenderMode::Synth,
             track_level: 127,
             master_level: 127,
+            pan: 0x40,
             send_mfx: 0,
             send_delay: 0,
             send_reverb: 0,
@@ -566,10 +789,15 @@ mod tests {
             waveform: SynthWaveform::Saw,
             attack_ms: 5,
             release_ms: 40,
+            decay_ms: 0,
+            sustain: 127,
+            filter: p9_core::model::FilterParams::default(),
             gain: 100,
             sampler_variant: p9_core::model::SamplerRenderVariant::Classic,
             sampler_transient_level: 64,
             sampler_body_level: 96,
+            fm: p9_core::model::FmParams::default(),
+            drum: p9_core::model::DrumSynthParams::default(),
         }]);
         backend.push_events(&[RenderEvent::NoteOff {
             track_id: 0,
@@ -637,6 +865,7 @@ mod tests {
             render_mode: RenderMode::ExternalMuted,
             track_level: 127,
             master_level: 127,
+            pan: 0x40,
             send_mfx: 0,
             send_delay: 0,
             send_reverb: 0,
@@ -644,10 +873,15 @@ mod tests {
             waveform: SynthWaveform::Saw,
             attack_ms: 1,
             release_ms: 24,
+            decay_ms: 0,
+            sustain: 127,
+            filter: p9_core::model::FilterParams::default(),
             gain: 0,
             sampler_variant: p9_core::model::SamplerRenderVariant::Classic,
             sampler_transient_level: 64,
             sampler_body_level: 96,
+            fm: p9_core::model::FmParams::default(),
+            drum: p9_core::model::DrumSynthParams::default(),
         }]);
 
         let metrics = backend.metrics();
@@ -669,6 +903,7 @@ mod tests {
             render_mode: RenderMode::SamplerV1,
             track_level: 127,
             master_level: 127,
+            pan: 0x40,
             send_mfx: 0,
             send_delay: 0,
             send_reverb: 0,
@@ -676,10 +911,15 @@ mod tests {
             waveform: SynthWaveform::Saw,
             attack_ms: 1,
             release_ms: 32,
+            decay_ms: 0,
+            sustain: 127,
+            filter: p9_core::model::FilterParams::default(),
             gain: 100,
             sampler_variant: p9_core::model::SamplerRenderVariant::Punch,
             sampler_transient_level: 110,
             sampler_body_level: 40,
+            fm: p9_core::model::FmParams::default(),
+            drum: p9_core::model::DrumSynthParams::default(),
         }]);
 
         let metrics = backend.metrics();
@@ -687,6 +927,127 @@ mod tests {
         assert_eq!(metrics.voice_note_on_total, 1);
     }
 
+    #[test]
+    fn fm_render_mode_note_on_keeps_operators_on_the_voice() {
+        let mut backend = NativeAudioBackend::new(AudioBackendConfig::default());
+        backend.start_checked().unwrap();
+        let fm = p9_core::model::FmParams {
+            operator_count: 4,
+            ..p9_core::model::FmParams::default()
+        };
+
+        backend.push_events(&[RenderEvent::NoteOn {
+            track_id: 2,
+            note: 57,
+            velocity: 100,
+            render_mode: RenderMode::Fm,
+            track_level: 127,
+            master_level: 127,
+            pan: 0x40,
+            send_mfx: 0,
+            send_delay: 0,
+            send_reverb: 0,
+            instrument_id: Some(0),
+            waveform: SynthWaveform::Sine,
+            attack_ms: 2,
+            release_ms: 120,
+            decay_ms: 200,
+            sustain: 96,
+            filter: p9_core::model::FilterParams::default(),
+            gain: 100,
+            sampler_variant: p9_core::model::SamplerRenderVariant::Classic,
+            sampler_transient_level: 64,
+            sampler_body_level: 96,
+            fm,
+            drum: p9_core::model::DrumSynthParams::default(),
+        }]);
+
+        let metrics = backend.metrics();
+        assert_eq!(metrics.voice_fm_mode_note_on_total, 1);
+        assert_eq!(metrics.voice_sampler_mode_note_on_total, 0);
+        assert_eq!(backend.voices.voice_fm(2, 57), Some(fm));
+    }
+
+    #[test]
+    fn sample_render_mode_plays_loaded_files_and_frees_finished_voices() {
+        let mut backend = NativeAudioBackend::new(AudioBackendConfig::default());
+        backend.start_checked().unwrap();
+        let mut bank = SampleBank::default();
+        bank.insert(
+            0,
+            p9_core::model::SampleParams::new("hit.wav"),
+            Arc::new(SampleData::new(48_000, 1, vec![[0.5, 0.5]; 1_024])),
+        );
+        backend.load_samples(Arc::new(bank));
+        let sample_note_on = |id| {
+            let mut event = note_on(1, 72);
+            if let RenderEvent::NoteOn {
+                render_mode,
+                instrument_id,
+                ..
+            } = &mut event
+            {
+                *render_mode = RenderMode::Sample;
+                *instrument_id = Some(id);
+            }
+            event
+        };
+
+        backend.push_events(&[sample_note_on(0)]);
+        assert_eq!(backend.metrics().voice_sample_note_on_total, 1);
+        assert_eq!(backend.voices.voice_sample(1, 72).unwrap().frame_position(), 0);
+
+        // An octave above the root note reads two file frames per output frame.
+        backend.push_events(&[]);
+        assert_eq!(backend.voices.voice_sample(1, 72).unwrap().frame_position(), 512);
+        backend.push_events(&[]);
+        assert!(backend.voices.voice_sample(1, 72).is_none());
+        assert_eq!(backend.metrics().active_voices, 0);
+
+        backend.push_events(&[sample_note_on(3)]);
+        let metrics = backend.metrics();
+        assert_eq!(metrics.voice_sample_note_on_total, 1);
+        assert_eq!(metrics.voice_silent_note_on_total, 1);
+    }
+
+    #[test]
+    fn push_block_starts_each_event_at_its_frame_offset() {
+        let mut backend = NativeAudioBackend::new(AudioBackendConfig::default());
+        backend.start_checked().unwrap();
+        let mut bank = SampleBank::default();
+        bank.insert(
+            0,
+            p9_core::model::SampleParams::new("hit.wav"),
+            Arc::new(SampleData::new(48_000, 1, vec![[0.5, 0.5]; 4_096])),
+        );
+        backend.load_samples(Arc::new(bank));
+        let sample_note_on = |note| {
+            let mut event = note_on(1, note);
+            if let RenderEvent::NoteOn { render_mode, .. } = &mut event {
+                *render_mode = RenderMode::Sample;
+            }
+            event
+        };
+
+        backend.push_block(&[
+            BlockRenderEvent {
+                frame_offset: 0,
+                event: sample_note_on(60),
+            },
+            BlockRenderEvent {
+                frame_offset: 192,
+                event: sample_note_on(72),
+            },
+        ]);
+
+        // Each voice plays only the frames after its offset in the 256-frame block.
+        let position = |note| backend.voices.voice_sample(1, note).unwrap().frame_position();
+        assert_eq!(position(60), 256);
+        assert_eq!(position(72), 128);
+        assert_eq!(backend.metrics().callbacks_total, 1);
+        assert_eq!(backend.events_consumed(), 2);
+    }
+
     #[test]
     fn mixer_zero_level_mutes_note_on_and_counts_routing_mute() {
         let mut backend = NativeAudioBackend::new(AudioBackendConfig::default());
@@ -699,6 +1060,7 @@ mod tests {
             render_mode: RenderMode::Synth,
             track_level: 0,
             master_level: 127,
+            pan: 0x40,
             send_mfx: 0,
             send_delay: 0,
             send_reverb: 0,
@@ -706,10 +1068,15 @@ mod tests {
             waveform: SynthWaveform::Saw,
             attack_ms: 1,
             release_ms: 24,
+            decay_ms: 0,
+            sustain: 127,
+            filter: p9_core::model::FilterParams::default(),
             gain: 100,
             sampler_variant: p9_core::model::SamplerRenderVariant::Classic,
             sampler_transient_level: 64,
             sampler_body_level: 96,
+            fm: p9_core::model::FmParams::default(),
+            drum: p9_core::model::DrumSynthParams::default(),
         }]);
 
         let metrics = backend.metrics();
@@ -719,6 +1086,108 @@ mod tests {
         assert_eq!(metrics.active_voices, 0);
     }
 
+    #[test]
+    fn pitch_bend_events_retune_active_voices_only() {
+        let mut backend = NativeAudioBackend::new(AudioBackendConfig::default());
+        backend.start_checked().unwrap();
+
+        backend.push_events(&[
+            note_on(0, 64),
+            RenderEvent::PitchBend {
+                track_id: 0,
+                note: 64,
+                cents: -400,
+            },
+        ]);
+        backend.push_events(&[RenderEvent::PitchBend {
+            track_id: 0,
+            note: 64,
+            cents: -200,
+        }]);
+        backend.push_events(&[RenderEvent::PitchBend {
+            track_id: 3,
+            note: 64,
+            cents: 100,
+        }]);
+
+        let metrics = backend.metrics();
+        assert_eq!(metrics.voice_note_on_total, 1);
+        assert_eq!(metrics.voice_pitch_bend_total, 2);
+        assert_eq!(metrics.active_voices, 1);
+    }
+
+    #[test]
+    fn note_volume_events_update_active_voices_only() {
+        let mut backend = NativeAudioBackend::new(AudioBackendConfig::default());
+        backend.start_checked().unwrap();
+
+        backend.push_events(&[
+            note_on(1, 60),
+            RenderEvent::NoteVolume {
+                track_id: 1,
+                note: 60,
+                velocity: 40,
+            },
+            RenderEvent::NoteVolume {
+                track_id: 1,
+                note: 61,
+                velocity: 40,
+            },
+        ]);
+
+        assert_eq!(backend.metrics().voice_volume_change_total, 1);
+    }
+
+    #[test]
+    fn track_params_update_every_voice_on_the_track() {
+        let mut backend = NativeAudioBackend::new(AudioBackendConfig::default());
+        backend.start_checked().unwrap();
+        let params = VoiceParams {
+            track_level: 64,
+            master_level: 127,
+            send_mfx: 0,
+            send_delay: 0,
+            send_reverb: 0,
+            attack_ms: 20,
+            release_ms: 200,
+            gain: 127,
+        };
+
+        backend.push_events(&[
+            note_on(2, 60),
+            note_on(2, 64),
+            RenderEvent::TrackParams {
+                track_id: 2,
+                params,
+            },
+            RenderEvent::TrackParams {
+                track_id: 5,
+                params,
+            },
+        ]);
+
+        let metrics = backend.metrics();
+        assert_eq!(metrics.voice_param_change_total, 1);
+        assert_eq!(metrics.active_voices, 2);
+    }
+
+    #[test]
+    fn stereo_backend_tracks_voice_pan() {
+        let mut backend = NativeAudioBackend::new(AudioBackendConfig::default());
+        backend.start_checked().unwrap();
+
+        let mut panned = note_on(2, 64);
+        if let RenderEvent::NoteOn { pan, .. } = &mut panned {
+            *pan = 0x10;
+        }
+        backend.push_events(&[note_on(1, 60), panned]);
+
+        let metrics = backend.metrics();
+        assert_eq!(metrics.channels, 2);
+        assert_eq!(metrics.voice_note_on_total, 2);
+        assert_eq!(metrics.voice_panned_note_on_total, 1);
+    }
+
     #[test]
     fn send_routing_activity_is_tracked() {
         let mut backend = NativeAudioBackend::new(AudioBackendConfig::default());
@@ -731,6 +1200,7 @@ mod tests {
             render_mode: RenderMode::Synth,
             track_level: 127,
             master_level: 127,
+            pan: 0x40,
             send_mfx: 10,
             send_delay: 20,
             send_reverb: 30,
@@ -738,10 +1208,15 @@ mod tests {
             waveform: SynthWaveform::Saw,
             attack_ms: 1,
             release_ms: 24,
+            decay_ms: 0,
+            sustain: 127,
+            filter: p9_core::model::FilterParams::default(),
             gain: 100,
             sampler_variant: p9_core::model::SamplerRenderVariant::Classic,
             sampler_transient_level: 64,
             sampler_body_level: 96,
+            fm: p9_core::model::FmParams::default(),
+            drum: p9_core::model::DrumSynthParams::default(),
         }]);
 
         let metrics = backend.metrics();
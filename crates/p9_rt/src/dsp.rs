@@ -1,3 +1,5 @@
+use p9_core::model::{DelayParams, ReverbParams, DELAY_MAX_STEPS, DELAY_MIN_STEPS};
+
 #[derive(Clone, Copy, Debug)]
 pub struct DspBudget {
     pub max_block_us: u32,
@@ -34,3 +36,355 @@ impl DspPipeline {
         self.last_stats
     }
 }
+
+const FILTER_MIN_HZ: f32 = 20.0;
+const FILTER_MAX_HZ: f32 = 20_000.0;
+/// Keeps full delay feedback just short of runaway.
+const DELAY_MAX_FEEDBACK: f32 = 0.95;
+/// Longest delay time, whatever the steps and tempo ask for; 32 steps at 120 BPM. The lines
+/// are allocated this long up front so tempo changes never resize them.
+const DELAY_MAX_SECONDS: f32 = 4.0;
+const DELAY_RETURN_GAIN: f32 = 0.34;
+const REVERB_RETURN_GAIN: f32 = 0.28;
+/// Freeverb's tunings in samples at 44.1 kHz; the right channel is offset by the spread.
+const COMB_TUNINGS: [usize; 8] = [1116, 1188, 1277, 1356, 1422, 1491, 1557, 1617];
+const ALLPASS_TUNINGS: [usize; 4] = [556, 441, 341, 225];
+const STEREO_SPREAD: usize = 23;
+const TUNING_RATE_HZ: f32 = 44_100.0;
+const REVERB_INPUT_GAIN: f32 = 0.015;
+const REVERB_WET_SCALE: f32 = 3.0;
+const ALLPASS_FEEDBACK: f32 = 0.5;
+
+/// Maps the 0..=127 cutoff scale exponentially onto 20 Hz..20 kHz, kept below Nyquist.
+pub fn cutoff_steps_to_hz(steps: f32, sample_rate_hz: f32) -> f32 {
+    (FILTER_MIN_HZ * (FILTER_MAX_HZ / FILTER_MIN_HZ).powf(steps / 127.0)).min(sample_rate_hz * 0.45)
+}
+
+/// The mixer's delay and reverb as offline export runs them: fed the mono send buses, they
+/// return stereo. The simulated realtime backend renders no audio, so it only configures a
+/// copy to mirror the parameters and report the delay length.
+#[derive(Clone, Debug)]
+pub struct SendEffects {
+    sample_rate_hz: f32,
+    settings: Option<(DelayParams, ReverbParams, u16)>,
+    delay: StereoDelay,
+    reverb: Freeverb,
+}
+
+impl SendEffects {
+    pub fn new(sample_rate_hz: u32) -> Self {
+        let sample_rate_hz = sample_rate_hz.max(1) as f32;
+        let mut effects = Self {
+            sample_rate_hz,
+            settings: None,
+            delay: StereoDelay::new(sample_rate_hz),
+            reverb: Freeverb::new(sample_rate_hz),
+        };
+        effects.configure(DelayParams::default(), ReverbParams::default(), 120);
+        effects
+    }
+
+    /// Applies new parameters; cheap to call every tick since unchanged settings are skipped.
+    /// Tails keep ringing through changes.
+    pub fn configure(&mut self, delay: DelayParams, reverb: ReverbParams, tempo: u16) {
+        if self.settings == Some((delay, reverb, tempo)) {
+            return;
+        }
+        self.settings = Some((delay, reverb, tempo));
+        self.delay.configure(&delay, tempo, self.sample_rate_hz);
+        self.reverb.configure(&reverb);
+    }
+
+    pub fn delay_frames(&self) -> usize {
+        self.delay.length
+    }
+
+    pub fn process(&mut self, send_delay: f32, send_reverb: f32) -> (f32, f32) {
+        let (delay_left, delay_right) = self.delay.process(send_delay);
+        let (reverb_left, reverb_right) = self.reverb.process(send_reverb);
+        (
+            delay_left * DELAY_RETURN_GAIN + reverb_left * REVERB_RETURN_GAIN,
+            delay_right * DELAY_RETURN_GAIN + reverb_right * REVERB_RETURN_GAIN,
+        )
+    }
+}
+
+/// Two circular lines that loop over the first `length` frames, the delay time, with a
+/// one-pole low-pass on each feedback.
+#[derive(Clone, Debug)]
+struct StereoDelay {
+    left: Vec<f32>,
+    right: Vec<f32>,
+    length: usize,
+    index: usize,
+    feedback: f32,
+    damping: f32,
+    ping_pong: bool,
+    lowpass: [f32; 2],
+}
+
+impl StereoDelay {
+    fn new(sample_rate_hz: f32) -> Self {
+        let capacity = ((DELAY_MAX_SECONDS * sample_rate_hz).round() as usize).max(1);
+        Self {
+            left: vec![0.0; capacity],
+            right: vec![0.0; capacity],
+            length: capacity,
+            index: 0,
+            feedback: 0.0,
+            damping: 0.0,
+            ping_pong: false,
+            lowpass: [0.0; 2],
+        }
+    }
+
+    fn configure(&mut self, params: &DelayParams, tempo: u16, sample_rate_hz: f32) {
+        // A step is a sixteenth note: 15 seconds divided by the tempo.
+        let steps = params.time_steps.clamp(DELAY_MIN_STEPS, DELAY_MAX_STEPS) as f32;
+        let frames = (steps * 15.0 * sample_rate_hz / tempo.max(1) as f32).round().max(1.0);
+        self.length = (frames as usize).min(self.left.len());
+        self.index %= self.length;
+        self.feedback = params.feedback.min(127) as f32 / 127.0 * DELAY_MAX_FEEDBACK;
+        let cutoff_hz = cutoff_steps_to_hz(params.filter.min(127) as f32, sample_rate_hz);
+        self.damping = 1.0 - (-std::f32::consts::TAU * cutoff_hz / sample_rate_hz).exp();
+        self.ping_pong = params.ping_pong;
+    }
+
+    fn process(&mut self, input: f32) -> (f32, f32) {
+        let out_left = self.left[self.index];
+        let out_right = self.right[self.index];
+        self.lowpass[0] += (out_left - self.lowpass[0]) * self.damping;
+        self.lowpass[1] += (out_right - self.lowpass[1]) * self.damping;
+        let [fed_left, fed_right] = self.lowpass.map(|sample| sample * self.feedback);
+
+        // Ping-pong enters on the left and crosses sides on every repeat.
+        let (write_left, write_right) = if self.ping_pong {
+            (input + fed_right, fed_left)
+        } else {
+            (input + fed_left, input + fed_right)
+        };
+        self.left[self.index] = write_left;
+        self.right[self.index] = write_right;
+        self.index = (self.index + 1) % self.length;
+        (out_left, out_right)
+    }
+}
+
+/// Jezar's Freeverb: eight damped combs in parallel into four allpasses in series, per side.
+#[derive(Clone, Debug)]
+struct Freeverb {
+    combs: [Vec<CombFilter>; 2],
+    allpasses: [Vec<AllpassFilter>; 2],
+    wet_direct: f32,
+    wet_cross: f32,
+}
+
+impl Freeverb {
+    fn new(sample_rate_hz: f32) -> Self {
+        let scale = |tuning: usize, spread: usize| {
+            (((tuning + spread) as f32 * sample_rate_hz / TUNING_RATE_HZ).round() as usize).max(1)
+        };
+        let combs = |spread| {
+            COMB_TUNINGS
+                .iter()
+                .map(|tuning| CombFilter::new(scale(*tuning, spread)))
+                .collect()
+        };
+        let allpasses = |spread| {
+            ALLPASS_TUNINGS
+                .iter()
+                .map(|tuning| AllpassFilter::new(scale(*tuning, spread)))
+                .collect()
+        };
+        Self {
+            combs: [combs(0), combs(STEREO_SPREAD)],
+            allpasses: [allpasses(0), allpasses(STEREO_SPREAD)],
+            wet_direct: 0.0,
+            wet_cross: 0.0,
+        }
+    }
+
+    fn configure(&mut self, params: &ReverbParams) {
+        let feedback = 0.7 + 0.28 * params.size.min(127) as f32 / 127.0;
+        let damping = 0.4 * params.damping.min(127) as f32 / 127.0;
+        for comb in self.combs.iter_mut().flatten() {
+            comb.feedback = feedback;
+            comb.damping = damping;
+        }
+        let width = params.width.min(127) as f32 / 127.0;
+        self.wet_direct = REVERB_WET_SCALE * (width / 2.0 + 0.5);
+        self.wet_cross = REVERB_WET_SCALE * (1.0 - width) / 2.0;
+    }
+
+    fn process(&mut self, input: f32) -> (f32, f32) {
+        let input = input * REVERB_INPUT_GAIN;
+        let mut outputs = [0.0f32; 2];
+        for (side, output) in outputs.iter_mut().enumerate() {
+            *output = self.combs[side].iter_mut().map(|comb| comb.process(input)).sum();
+            for allpass in self.allpasses[side].iter_mut() {
+                *output = allpass.process(*output);
+            }
+        }
+        let [left, right] = outputs;
+        (
+            left * self.wet_direct + right * self.wet_cross,
+            right * self.wet_direct + left * self.wet_cross,
+        )
+    }
+}
+
+#[derive(Clone, Debug)]
+struct CombFilter {
+    buffer: Vec<f32>,
+    index: usize,
+    feedback: f32,
+    damping: f32,
+    filter_store: f32,
+}
+
+impl CombFilter {
+    fn new(length: usize) -> Self {
+        Self {
+            buffer: vec![0.0; length],
+            index: 0,
+            feedback: 0.0,
+            damping: 0.0,
+            filter_store: 0.0,
+        }
+    }
+
+    fn process(&mut self, input: f32) -> f32 {
+        let output = self.buffer[self.index];
+        self.filter_store = output * (1.0 - self.damping) + self.filter_store * self.damping;
+        self.buffer[self.index] = input + self.filter_store * self.feedback;
+        self.index = (self.index + 1) % self.buffer.len();
+        output
+    }
+}
+
+#[derive(Clone, Debug)]
+struct AllpassFilter {
+    buffer: Vec<f32>,
+    index: usize,
+}
+
+impl AllpassFilter {
+    fn new(length: usize) -> Self {
+        Self {
+            buffer: vec![0.0; length],
+            index: 0,
+        }
+    }
+
+    fn process(&mut self, input: f32) -> f32 {
+        let buffered = self.buffer[self.index];
+        self.buffer[self.index] = input + buffered * ALLPASS_FEEDBACK;
+        self.index = (self.index + 1) % self.buffer.len();
+        buffered - input
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SendEffects;
+    use p9_core::model::{DelayParams, ReverbParams};
+
+    fn impulse_peaks(sends: &mut SendEffects, frames: usize) -> Vec<(usize, f32, f32)> {
+        (0..frames)
+            .map(|frame| {
+                let input = if frame == 0 { 1.0 } else { 0.0 };
+                let (left, right) = sends.process(input, 0.0);
+                (frame, left, right)
+            })
+            .filter(|(_, left, right)| left.abs() > 1e-3 || right.abs() > 1e-3)
+            .collect()
+    }
+
+    #[test]
+    fn delay_time_follows_steps_and_tempo() {
+        let mut sends = SendEffects::new(48_000);
+        let delay = DelayParams {
+            time_steps: 4,
+            ..DelayParams::default()
+        };
+        sends.configure(delay, ReverbParams::default(), 120);
+        // Four sixteenths at 120 BPM are half a second.
+        assert_eq!(sends.delay_frames(), 24_000);
+        sends.configure(delay, ReverbParams::default(), 60);
+        assert_eq!(sends.delay_frames(), 48_000);
+
+        let mut sends = SendEffects::new(48_000);
+        sends.configure(delay, ReverbParams::default(), 240);
+        let peaks = impulse_peaks(&mut sends, 12_001);
+        assert_eq!(peaks.first().map(|peak| peak.0), Some(12_000));
+    }
+
+    #[test]
+    fn tempo_changes_move_the_delay_time_without_reallocating() {
+        let mut sends = SendEffects::new(48_000);
+        let lines = (sends.delay.left.as_ptr(), sends.delay.right.as_ptr());
+        let capacity = sends.delay.left.capacity();
+        let delay = DelayParams {
+            time_steps: 32,
+            ..DelayParams::default()
+        };
+
+        for tempo in [1, 60, 120, 240, 1] {
+            sends.configure(delay, ReverbParams::default(), tempo);
+            assert_eq!((sends.delay.left.as_ptr(), sends.delay.right.as_ptr()), lines);
+            assert_eq!(sends.delay.left.capacity(), capacity);
+        }
+        // 32 steps at TPO 01 would be 24 minutes; the time stops at four seconds.
+        assert_eq!(sends.delay_frames(), 4 * 48_000);
+        sends.configure(delay, ReverbParams::default(), 240);
+        assert_eq!(sends.delay_frames(), 96_000);
+    }
+
+    #[test]
+    fn ping_pong_repeats_alternate_channels() {
+        let mut sends = SendEffects::new(8_000);
+        let delay = DelayParams {
+            time_steps: 1,
+            feedback: 127,
+            filter: 127,
+            ping_pong: true,
+        };
+        sends.configure(delay, ReverbParams::default(), 120);
+        let frames = sends.delay_frames();
+        let peaks = impulse_peaks(&mut sends, frames * 3 + 1);
+        let repeats: Vec<_> = peaks.iter().filter(|peak| peak.0 % frames == 0).collect();
+        assert_eq!(repeats.len(), 3);
+        assert!(repeats[0].1.abs() > 0.0 && repeats[0].2 == 0.0);
+        assert!(repeats[1].1 == 0.0 && repeats[1].2.abs() > 0.0);
+        assert!(repeats[2].1.abs() > 0.0 && repeats[2].2 == 0.0);
+    }
+
+    #[test]
+    fn reverb_tail_decays_and_width_spreads_channels() {
+        fn render(width: u8) -> Vec<(f32, f32)> {
+            let mut sends = SendEffects::new(44_100);
+            let reverb = ReverbParams {
+                width,
+                ..ReverbParams::default()
+            };
+            sends.configure(DelayParams::default(), reverb, 120);
+            (0..44_100)
+                .map(|frame| sends.process(0.0, if frame == 0 { 1.0 } else { 0.0 }))
+                .collect()
+        }
+        let energy = |frames: &[(f32, f32)]| -> f32 {
+            frames.iter().map(|(left, right)| left * left + right * right).sum()
+        };
+        let side = |frames: &[(f32, f32)]| -> f32 {
+            frames.iter().map(|(left, right)| (left - right).abs()).sum()
+        };
+
+        let wide = render(127);
+        assert!(energy(&wide[2_000..6_000]) > 0.0);
+        assert!(energy(&wide[2_000..6_000]) > energy(&wide[40_000..44_000]));
+
+        let mono = render(0);
+        assert!(side(&mono) < 1e-4);
+        assert!(side(&wide) > 0.01);
+    }
+}
@@ -1,18 +1,35 @@
 use std::f32::consts::{PI, TAU};
 use std::fs::File;
 use std::io::{self, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use p9_core::engine::Engine;
 use p9_core::events::{RenderEvent, RenderMode};
-use p9_core::model::{SamplerRenderVariant, SynthWaveform};
-use p9_core::scheduler::Scheduler;
-
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+use p9_core::model::{
+    DrumSound, DrumSynthParams, FilterMode, FilterParams, FmParams, InstrumentId,
+    SamplerRenderVariant, SynthWaveform, FM_OPERATOR_SLOTS, PAN_CENTER, PAN_MAX,
+    PULSE_WIDTH_MAX, PULSE_WIDTH_MIN,
+};
+use p9_core::scheduler::{frames_per_tick, Scheduler};
+
+use crate::dsp::{cutoff_steps_to_hz, SendEffects};
+use crate::sample::{SampleBank, SampleData, SamplePlayhead};
+
+const AMPLITUDE_SMOOTHING_MS: u16 = 5;
+/// Phase deviation in radians of a modulator at full level.
+const FM_MAX_MOD_INDEX: f32 = 4.0;
+const FM_MAX_FEEDBACK: f32 = 1.5;
+const EXPORT_CHANNELS: u16 = 2;
+const EXPORT_BLOCK_FRAMES: u32 = 256;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct OfflineRenderConfig {
     pub sample_rate_hz: u32,
     pub ppq: u16,
     pub ticks: u64,
+    /// Folder relative sample paths resolve against, normally the project file's folder.
+    pub sample_dir: Option<PathBuf>,
 }
 
 impl Default for OfflineRenderConfig {
@@ -21,23 +38,29 @@ impl Default for OfflineRenderConfig {
             sample_rate_hz: 48_000,
             ppq: 24,
             ticks: 96,
+            sample_dir: None,
         }
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ExportReport {
     pub sample_rate_hz: u32,
+    pub channels: u16,
     pub ticks_rendered: u64,
     pub events_rendered: usize,
+    /// Sample frames per channel.
     pub samples_rendered: u32,
     pub peak_abs_sample: i16,
+    /// Instruments whose sample file could not be loaded; they render silent.
+    pub failed_samples: Vec<InstrumentId>,
 }
 
 #[derive(Debug)]
 pub enum ExportError {
     Io(io::Error),
     InvalidTempo(u16),
+    InvalidSampleRate(u32),
     InvalidPpq(u16),
     InvalidTicks(u64),
     DataTooLarge(usize),
@@ -49,7 +72,7 @@ impl From<io::Error> for ExportError {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 struct ActiveVoice {
     track_id: u8,
     note: u8,
@@ -58,55 +81,201 @@ struct ActiveVoice {
     send_mfx: f32,
     send_delay: f32,
     send_reverb: f32,
+    pan_left: f32,
+    pan_right: f32,
     sampler_variant: SamplerRenderVariant,
     sampler_transient_level: f32,
     sampler_body_level: f32,
     phase: f32,
     phase_inc: f32,
+    base_phase_inc: f32,
+    noise_seed: u32,
     amplitude: f32,
+    target_amplitude: f32,
+    amplitude_step: f32,
+    velocity_gain: f32,
+    level_gain: f32,
     elapsed_samples: u32,
     attack_samples: u32,
+    decay_samples: u32,
+    sustain_level: f32,
     release_samples: u32,
     release_progress_samples: u32,
     releasing: bool,
+    filter: FilterParams,
+    filter_state: SvfState,
+    sample_rate_hz: f32,
+    fm: Option<FmVoice>,
+    sample: Option<(Arc<SampleData>, SamplePlayhead)>,
+    drum: Option<DrumVoice>,
+}
+
+/// Operator state of an FM voice; the carrier's envelope is the voice envelope.
+#[derive(Clone, Copy, Debug)]
+struct FmVoice {
+    operator_count: usize,
+    operators: [FmOperatorState; FM_OPERATOR_SLOTS],
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct FmOperatorState {
+    ratio: f32,
+    level: f32,
+    feedback: f32,
+    attack_samples: u32,
+    decay_samples: u32,
+    sustain_level: f32,
+    release_samples: u32,
+    phase: f32,
+    /// The last two outputs, averaged for feedback so it does not oscillate at Nyquist.
+    history: [f32; 2],
+}
+
+impl FmVoice {
+    fn new(params: &FmParams, sample_rate_hz: f32) -> Self {
+        let mut operators = [FmOperatorState::default(); FM_OPERATOR_SLOTS];
+        for (state, operator) in operators.iter_mut().zip(params.operators.iter()) {
+            *state = FmOperatorState {
+                ratio: operator.ratio.max(1) as f32 / 4.0,
+                level: operator.level.min(127) as f32 / 127.0,
+                feedback: operator.feedback.min(127) as f32 / 127.0,
+                attack_samples: ms_to_samples(operator.attack_ms, sample_rate_hz),
+                decay_samples: ms_to_samples(operator.decay_ms, sample_rate_hz),
+                sustain_level: operator.sustain.min(127) as f32 / 127.0,
+                release_samples: ms_to_samples(operator.release_ms, sample_rate_hz),
+                phase: 0.0,
+                history: [0.0; 2],
+            };
+        }
+        Self {
+            operator_count: (params.operator_count as usize).clamp(1, FM_OPERATOR_SLOTS),
+            operators,
+        }
+    }
+}
+
+/// One drum hit. Each part fades out on a squared ramp that reaches silence after its decay,
+/// so hits end on their own; oscillator steps are radians per sample.
+#[derive(Clone, Copy, Debug)]
+struct DrumVoice {
+    model: DrumModel,
+    level: f32,
+    phase: f32,
+    /// Last input and output of the hat's one-pole high-pass.
+    highpass: [f32; 2],
+}
+
+#[derive(Clone, Copy, Debug)]
+enum DrumModel {
+    Kick {
+        start_inc: f32,
+        end_inc: f32,
+        sweep_samples: u32,
+        decay_samples: u32,
+    },
+    Snare {
+        tone_inc: f32,
+        tone_decay_samples: u32,
+        noise_decay_samples: u32,
+        tone_mix: f32,
+    },
+    Hat {
+        coefficient: f32,
+        decay_samples: u32,
+    },
+}
+
+impl DrumVoice {
+    fn new(params: &DrumSynthParams, note: u8, sample_rate_hz: f32) -> Self {
+        let sample_rate_hz = sample_rate_hz.max(1.0);
+        let phase_inc = |hz: u16| TAU * hz as f32 / sample_rate_hz;
+        let (model, level) = match DrumSound::for_note(note) {
+            DrumSound::Kick => (
+                DrumModel::Kick {
+                    start_inc: phase_inc(params.kick.start_hz),
+                    end_inc: phase_inc(params.kick.end_hz),
+                    sweep_samples: ms_to_samples(params.kick.sweep_ms, sample_rate_hz),
+                    decay_samples: ms_to_samples(params.kick.decay_ms, sample_rate_hz),
+                },
+                params.kick.level,
+            ),
+            DrumSound::Snare => (
+                DrumModel::Snare {
+                    tone_inc: phase_inc(params.snare.tone_hz),
+                    tone_decay_samples: ms_to_samples(params.snare.tone_decay_ms, sample_rate_hz),
+                    noise_decay_samples: ms_to_samples(
+                        params.snare.noise_decay_ms,
+                        sample_rate_hz,
+                    ),
+                    tone_mix: params.snare.tone_mix.min(127) as f32 / 127.0,
+                },
+                params.snare.level,
+            ),
+            DrumSound::Hat => {
+                let cutoff_hz = cutoff_steps_to_hz(params.hat.cutoff as f32, sample_rate_hz);
+                (
+                    DrumModel::Hat {
+                        coefficient: 1.0 / (1.0 + TAU * cutoff_hz / sample_rate_hz),
+                        decay_samples: ms_to_samples(params.hat.decay_ms, sample_rate_hz),
+                    },
+                    params.hat.level,
+                )
+            }
+        };
+        Self {
+            model,
+            level: level.min(127) as f32 / 127.0,
+            phase: 0.0,
+            highpass: [0.0; 2],
+        }
+    }
+
+    fn length_samples(&self) -> u32 {
+        match self.model {
+            DrumModel::Kick { decay_samples, .. } | DrumModel::Hat { decay_samples, .. } => {
+                decay_samples
+            }
+            DrumModel::Snare {
+                tone_decay_samples,
+                noise_decay_samples,
+                ..
+            } => tone_decay_samples.max(noise_decay_samples),
+        }
+    }
+}
+
+/// Integrator memories of a topology-preserving state-variable filter.
+#[derive(Clone, Copy, Debug, Default)]
+struct SvfState {
+    ic1: f32,
+    ic2: f32,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum VoiceRenderMode {
     Standard,
     SamplerV1,
+    Sample,
+    Fm,
+    Drum,
 }
 
 #[derive(Clone, Debug)]
 struct RenderFxState {
-    delay_line: Vec<f32>,
-    delay_index: usize,
-    reverb_lp: f32,
+    sends: SendEffects,
 }
 
 impl RenderFxState {
     fn new(sample_rate_hz: u32) -> Self {
-        let delay_samples = (sample_rate_hz / 8).max(1) as usize;
         Self {
-            delay_line: vec![0.0; delay_samples],
-            delay_index: 0,
-            reverb_lp: 0.0,
+            sends: SendEffects::new(sample_rate_hz),
         }
     }
 
-    fn process_returns(&mut self, send_mfx: f32, send_delay: f32, send_reverb: f32) -> f32 {
+    fn process_returns(&mut self, send_mfx: f32, send_delay: f32, send_reverb: f32) -> (f32, f32) {
         let mfx = soft_clip(send_mfx * 1.8) * 0.42;
-
-        let delayed = self.delay_line[self.delay_index];
-        let delay_input = send_delay + delayed * 0.45;
-        self.delay_line[self.delay_index] = delay_input;
-        self.delay_index = (self.delay_index + 1) % self.delay_line.len();
-        let delay_out = delayed * 0.34;
-
-        self.reverb_lp = self.reverb_lp * 0.82 + send_reverb * 0.18;
-        let reverb_out = self.reverb_lp * 0.28;
-
-        (mfx + delay_out + reverb_out).clamp(-1.0, 1.0)
+        let (left, right) = self.sends.process(send_delay, send_reverb);
+        ((mfx + left).clamp(-1.0, 1.0), (mfx + right).clamp(-1.0, 1.0))
     }
 }
 
@@ -115,6 +284,9 @@ pub fn render_project_to_wav(
     path: impl AsRef<Path>,
     config: OfflineRenderConfig,
 ) -> Result<ExportReport, ExportError> {
+    if config.sample_rate_hz == 0 {
+        return Err(ExportError::InvalidSampleRate(config.sample_rate_hz));
+    }
     if config.ppq == 0 {
         return Err(ExportError::InvalidPpq(config.ppq));
     }
@@ -127,55 +299,89 @@ pub fn render_project_to_wav(
         return Err(ExportError::InvalidTempo(tempo));
     }
 
-    let samples_per_tick = samples_per_tick(config.sample_rate_hz, tempo, config.ppq);
+    let sample_bank = SampleBank::load_project(engine.snapshot(), config.sample_dir.as_deref());
+    let initial_samples_per_tick =
+        frames_per_tick(config.sample_rate_hz, tempo, config.ppq).ceil() as usize;
     let mut scheduler = Scheduler::new(config.ppq);
     let mut voices: Vec<ActiveVoice> = Vec::new();
     let mut fx_state = RenderFxState::new(config.sample_rate_hz);
     let mut samples = Vec::<i16>::with_capacity(
-        samples_per_tick
+        initial_samples_per_tick
             .saturating_mul(config.ticks as usize)
-            .max(samples_per_tick),
+            .max(initial_samples_per_tick)
+            .saturating_mul(EXPORT_CHANNELS as usize),
     );
     let mut events_rendered = 0usize;
     let mut peak_abs_sample = 0i16;
 
-    for _ in 0..config.ticks {
-        let events = scheduler.tick(engine);
+    loop {
+        // Blocks end just past the next tick, so the render stops where tick `ticks` would
+        // start.
+        let until_tick = scheduler.frames_until_next_tick();
+        let block_frames = if scheduler.current_tick < config.ticks {
+            EXPORT_BLOCK_FRAMES.min(until_tick as u32 + 1)
+        } else {
+            EXPORT_BLOCK_FRAMES.min(until_tick as u32)
+        };
+        if block_frames == 0 {
+            break;
+        }
+
+        let events = scheduler.render_block(engine, config.sample_rate_hz, block_frames);
         events_rendered = events_rendered.saturating_add(events.len());
 
-        for event in &events {
-            apply_event(&mut voices, event, config.sample_rate_hz as f32);
-        }
+        // TPO FX can change the tempo mid-song, so delay time follows the scheduler.
+        let tempo = scheduler.effective_tempo(engine);
+        let mixer = &engine.snapshot().mixer;
+        fx_state.sends.configure(mixer.delay, mixer.reverb, tempo);
+
+        let mut pending = events.iter().peekable();
+        for frame in 0..block_frames {
+            while let Some(timed) = pending.next_if(|timed| timed.frame_offset <= frame) {
+                apply_event_with_samples(
+                    &mut voices,
+                    &timed.event,
+                    config.sample_rate_hz as f32,
+                    &sample_bank,
+                );
+            }
 
-        for _ in 0..samples_per_tick {
-            let sample = synthesize_sample_routed(&mut voices, &mut fx_state);
-            let sample_i16 = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
-            peak_abs_sample = peak_abs_sample.max(sample_i16.saturating_abs());
-            samples.push(sample_i16);
+            let (left, right) = synthesize_sample_routed(&mut voices, &mut fx_state);
+            for sample in [left, right] {
+                let sample_i16 = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+                peak_abs_sample = peak_abs_sample.max(sample_i16.saturating_abs());
+                samples.push(sample_i16);
+            }
         }
     }
 
-    write_wav_mono_i16(path.as_ref(), config.sample_rate_hz, &samples)?;
+    write_wav_i16(path.as_ref(), config.sample_rate_hz, EXPORT_CHANNELS, &samples)?;
 
-    let samples_rendered =
-        u32::try_from(samples.len()).map_err(|_| ExportError::DataTooLarge(samples.len()))?;
+    let frames = samples.len() / EXPORT_CHANNELS as usize;
+    let samples_rendered = u32::try_from(frames).map_err(|_| ExportError::DataTooLarge(frames))?;
 
     Ok(ExportReport {
         sample_rate_hz: config.sample_rate_hz,
+        channels: EXPORT_CHANNELS,
         ticks_rendered: config.ticks,
         events_rendered,
         samples_rendered,
         peak_abs_sample,
+        failed_samples: sample_bank.failed().to_vec(),
     })
 }
 
-fn samples_per_tick(sample_rate_hz: u32, tempo: u16, ppq: u16) -> usize {
-    let ticks_per_second = (tempo as f32 * ppq as f32) / 60.0;
-    let per_tick = (sample_rate_hz as f32 / ticks_per_second).round();
-    per_tick.max(1.0) as usize
+#[cfg(test)]
+fn apply_event(voices: &mut Vec<ActiveVoice>, event: &RenderEvent, sample_rate_hz: f32) {
+    apply_event_with_samples(voices, event, sample_rate_hz, &SampleBank::default());
 }
 
-fn apply_event(voices: &mut Vec<ActiveVoice>, event: &RenderEvent, sample_rate_hz: f32) {
+fn apply_event_with_samples(
+    voices: &mut Vec<ActiveVoice>,
+    event: &RenderEvent,
+    sample_rate_hz: f32,
+    sample_bank: &SampleBank,
+) {
     match event {
         RenderEvent::NoteOn {
             track_id,
@@ -184,6 +390,7 @@ fn apply_event(voices: &mut Vec<ActiveVoice>, event: &RenderEvent, sample_rate_h
             render_mode,
             track_level,
             master_level,
+            pan,
             send_mfx,
             send_delay,
             send_reverb,
@@ -192,8 +399,14 @@ fn apply_event(voices: &mut Vec<ActiveVoice>, event: &RenderEvent, sample_rate_h
             sampler_body_level,
             waveform,
             attack_ms,
+            decay_ms,
+            sustain,
             release_ms,
             gain,
+            filter,
+            fm,
+            drum,
+            instrument_id,
             ..
         } => {
             voices.retain(|voice| !(voice.track_id == *track_id && voice.note == *note));
@@ -202,20 +415,33 @@ fn apply_event(voices: &mut Vec<ActiveVoice>, event: &RenderEvent, sample_rate_h
                 return;
             }
 
+            let sample = if matches!(render_mode, RenderMode::Sample) {
+                // A file that failed to load leaves the note silent instead of faking it.
+                let Some(loaded) = instrument_id.and_then(|id| sample_bank.get(id)) else {
+                    return;
+                };
+                let playhead = SamplePlayhead::new(loaded, *note, sample_rate_hz);
+                if playhead.is_finished() {
+                    return;
+                }
+                Some((Arc::clone(&loaded.data), playhead))
+            } else {
+                None
+            };
+
             let freq_hz = 440.0 * 2.0_f32.powf((*note as f32 - 69.0) / 12.0);
             let phase_inc = TAU * (freq_hz / sample_rate_hz.max(1.0));
             let velocity_gain = *velocity as f32 / 127.0;
-            let instrument_gain = *gain as f32 / 127.0;
-            let track_gain = (*track_level as f32 / 127.0).clamp(0.0, 1.0);
-            let master_gain = (*master_level as f32 / 127.0).clamp(0.0, 1.0);
             let mode = match render_mode {
                 RenderMode::SamplerV1 => VoiceRenderMode::SamplerV1,
+                RenderMode::Sample => VoiceRenderMode::Sample,
+                RenderMode::Fm => VoiceRenderMode::Fm,
+                RenderMode::Drum => VoiceRenderMode::Drum,
                 RenderMode::Synth | RenderMode::ExternalMuted => VoiceRenderMode::Standard,
             };
-            let mode_gain = match mode {
-                VoiceRenderMode::Standard => 0.22,
-                VoiceRenderMode::SamplerV1 => 0.28,
-            };
+            let level_gain = voice_level_gain(mode, *gain, *track_level, *master_level);
+            let amplitude = (velocity_gain * level_gain).clamp(0.0, 1.0);
+            let (pan_left, pan_right) = pan_gains(*pan);
 
             voices.push(ActiveVoice {
                 track_id: *track_id,
@@ -225,31 +451,108 @@ fn apply_event(voices: &mut Vec<ActiveVoice>, event: &RenderEvent, sample_rate_h
                 send_mfx: (*send_mfx as f32 / 127.0).clamp(0.0, 1.0),
                 send_delay: (*send_delay as f32 / 127.0).clamp(0.0, 1.0),
                 send_reverb: (*send_reverb as f32 / 127.0).clamp(0.0, 1.0),
+                pan_left,
+                pan_right,
                 sampler_variant: *sampler_variant,
                 sampler_transient_level: *sampler_transient_level as f32 / 127.0,
                 sampler_body_level: *sampler_body_level as f32 / 127.0,
                 phase: 0.0,
                 phase_inc,
-                amplitude: (velocity_gain * instrument_gain * track_gain * master_gain * mode_gain)
-                    .clamp(0.0, 1.0),
+                base_phase_inc: phase_inc,
+                noise_seed: ((*track_id as u32) << 8 | *note as u32).wrapping_mul(0x9E37_79B9),
+                amplitude,
+                target_amplitude: amplitude,
+                amplitude_step: 0.0,
+                velocity_gain,
+                level_gain,
                 elapsed_samples: 0,
                 attack_samples: ms_to_samples(*attack_ms, sample_rate_hz),
+                decay_samples: ms_to_samples(*decay_ms, sample_rate_hz),
+                sustain_level: (*sustain).min(127) as f32 / 127.0,
                 release_samples: ms_to_samples(*release_ms, sample_rate_hz),
                 release_progress_samples: 0,
                 releasing: false,
+                filter: *filter,
+                filter_state: SvfState::default(),
+                sample_rate_hz,
+                fm: matches!(mode, VoiceRenderMode::Fm).then(|| FmVoice::new(fm, sample_rate_hz)),
+                sample,
+                drum: matches!(mode, VoiceRenderMode::Drum)
+                    .then(|| DrumVoice::new(drum, *note, sample_rate_hz)),
             });
         }
         RenderEvent::NoteOff { track_id, note } => {
-            for voice in voices.iter_mut() {
+            // Drum hits are one-shots that ring out over their own decay.
+            for voice in voices.iter_mut().filter(|voice| voice.mode != VoiceRenderMode::Drum) {
                 if voice.track_id == *track_id && voice.note == *note {
                     voice.releasing = true;
                     voice.release_progress_samples = 0;
                 }
             }
         }
+        RenderEvent::PitchBend {
+            track_id,
+            note,
+            cents,
+        } => {
+            let ratio = 2.0_f32.powf(*cents as f32 / 1200.0);
+            for voice in voices.iter_mut() {
+                if voice.track_id == *track_id && voice.note == *note {
+                    voice.phase_inc = voice.base_phase_inc * ratio;
+                }
+            }
+        }
+        RenderEvent::NoteVolume {
+            track_id,
+            note,
+            velocity,
+        } => {
+            let velocity_gain = *velocity as f32 / 127.0;
+            for voice in voices.iter_mut() {
+                if voice.track_id == *track_id && voice.note == *note {
+                    voice.velocity_gain = velocity_gain;
+                    ramp_amplitude(voice, sample_rate_hz);
+                }
+            }
+        }
+        RenderEvent::TrackParams { track_id, params } => {
+            for voice in voices.iter_mut().filter(|voice| voice.track_id == *track_id) {
+                voice.level_gain = voice_level_gain(
+                    voice.mode,
+                    params.gain,
+                    params.track_level,
+                    params.master_level,
+                );
+                voice.send_mfx = (params.send_mfx as f32 / 127.0).clamp(0.0, 1.0);
+                voice.send_delay = (params.send_delay as f32 / 127.0).clamp(0.0, 1.0);
+                voice.send_reverb = (params.send_reverb as f32 / 127.0).clamp(0.0, 1.0);
+                voice.attack_samples = ms_to_samples(params.attack_ms, sample_rate_hz);
+                voice.release_samples = ms_to_samples(params.release_ms, sample_rate_hz);
+                ramp_amplitude(voice, sample_rate_hz);
+            }
+        }
     }
 }
 
+fn voice_level_gain(mode: VoiceRenderMode, gain: u8, track_level: u8, master_level: u8) -> f32 {
+    let instrument_gain = gain as f32 / 127.0;
+    let track_gain = (track_level as f32 / 127.0).clamp(0.0, 1.0);
+    let master_gain = (master_level as f32 / 127.0).clamp(0.0, 1.0);
+    let mode_gain = match mode {
+        VoiceRenderMode::Standard | VoiceRenderMode::Fm => 0.22,
+        VoiceRenderMode::SamplerV1 => 0.28,
+        VoiceRenderMode::Sample | VoiceRenderMode::Drum => 0.5,
+    };
+    instrument_gain * track_gain * master_gain * mode_gain
+}
+
+/// Glides towards the new velocity and level over `AMPLITUDE_SMOOTHING_MS` to avoid clicks.
+fn ramp_amplitude(voice: &mut ActiveVoice, sample_rate_hz: f32) {
+    let ramp_samples = ms_to_samples(AMPLITUDE_SMOOTHING_MS, sample_rate_hz).max(1);
+    voice.target_amplitude = (voice.velocity_gain * voice.level_gain).clamp(0.0, 1.0);
+    voice.amplitude_step = (voice.target_amplitude - voice.amplitude).abs() / ramp_samples as f32;
+}
+
 #[cfg(test)]
 fn synthesize_sample(voices: &mut Vec<ActiveVoice>) -> f32 {
     if voices.is_empty() {
@@ -259,8 +562,10 @@ fn synthesize_sample(voices: &mut Vec<ActiveVoice>) -> f32 {
     let mut mixed = 0.0f32;
 
     for voice in voices.iter_mut() {
-        let osc = oscillator_sample(voice);
         let env = envelope_sample(voice);
+        let osc = oscillator_sample(voice);
+        let osc = filter_sample(voice, osc, env);
+        smooth_amplitude(voice);
         mixed += osc * voice.amplitude * env;
         voice.phase += voice.phase_inc;
         if voice.phase >= TAU {
@@ -287,24 +592,31 @@ fn synthesize_sample(voices: &mut Vec<ActiveVoice>) -> f32 {
     mixed
 }
 
-fn synthesize_sample_routed(voices: &mut Vec<ActiveVoice>, fx_state: &mut RenderFxState) -> f32 {
+fn synthesize_sample_routed(
+    voices: &mut Vec<ActiveVoice>,
+    fx_state: &mut RenderFxState,
+) -> (f32, f32) {
     if voices.is_empty() {
         return fx_state.process_returns(0.0, 0.0, 0.0);
     }
 
-    let mut dry_mixed = 0.0f32;
+    let mut dry_left = 0.0f32;
+    let mut dry_right = 0.0f32;
     let mut send_mfx = 0.0f32;
     let mut send_delay = 0.0f32;
     let mut send_reverb = 0.0f32;
 
     for voice in voices.iter_mut() {
-        let osc = oscillator_sample(voice);
         let env = envelope_sample(voice);
+        let osc = oscillator_sample(voice);
+        let osc = filter_sample(voice, osc, env);
+        smooth_amplitude(voice);
         let sample = osc * voice.amplitude * env;
 
         let total_send = (voice.send_mfx + voice.send_delay + voice.send_reverb).clamp(0.0, 1.0);
         let dry_scale = (1.0 - total_send * 0.6).clamp(0.4, 1.0);
-        dry_mixed += sample * dry_scale;
+        dry_left += sample * dry_scale * voice.pan_left;
+        dry_right += sample * dry_scale * voice.pan_right;
         send_mfx += sample * voice.send_mfx;
         send_delay += sample * voice.send_delay;
         send_reverb += sample * voice.send_reverb;
@@ -331,15 +643,28 @@ fn synthesize_sample_routed(voices: &mut Vec<ActiveVoice>, fx_state: &mut Render
         voice.release_progress_samples < voice.release_samples
     });
 
-    let returns = fx_state.process_returns(send_mfx, send_delay, send_reverb);
-    (dry_mixed + returns).clamp(-1.0, 1.0)
+    // Sends are mono buses; the delay and reverb return them in stereo.
+    let (return_left, return_right) = fx_state.process_returns(send_mfx, send_delay, send_reverb);
+    (
+        (dry_left + return_left).clamp(-1.0, 1.0),
+        (dry_right + return_right).clamp(-1.0, 1.0),
+    )
+}
+
+/// Balance law: centre keeps both channels at unity, panning attenuates the far side.
+fn pan_gains(pan: u8) -> (f32, f32) {
+    let offset = (pan.min(PAN_MAX) as f32 - PAN_CENTER as f32) / PAN_CENTER as f32;
+    ((1.0 - offset).min(1.0), (1.0 + offset).min(1.0))
 }
 
-fn oscillator_sample(voice: &ActiveVoice) -> f32 {
+fn oscillator_sample(voice: &mut ActiveVoice) -> f32 {
     match voice.mode {
-        VoiceRenderMode::Standard => waveform_sample(voice.waveform, voice.phase),
+        VoiceRenderMode::Standard => waveform_sample(voice),
+        VoiceRenderMode::Fm => fm_sample(voice),
+        VoiceRenderMode::Sample => sample_playback(voice),
+        VoiceRenderMode::Drum => drum_sample(voice),
         VoiceRenderMode::SamplerV1 => {
-            let base = waveform_sample(voice.waveform, voice.phase);
+            let base = waveform_sample(voice);
             let sine = voice.phase.sin();
             let (variant_base_mix, variant_sine_mix, variant_transient_scale) = match voice
                 .sampler_variant
@@ -364,41 +689,257 @@ fn oscillator_sample(voice: &ActiveVoice) -> f32 {
     }
 }
 
-fn waveform_sample(waveform: SynthWaveform, phase: f32) -> f32 {
+fn waveform_sample(voice: &ActiveVoice) -> f32 {
+    let noise_index = voice.noise_seed.wrapping_add(voice.elapsed_samples);
+    band_limited_sample(voice.waveform, voice.phase, voice.phase_inc, noise_index)
+}
+
+/// Oscillator output at `phase` (radians), with PolyBLEP corrections smoothing the jumps of
+/// saw and pulse shapes so harmonics above Nyquist do not fold back into the audible band.
+fn band_limited_sample(
+    waveform: SynthWaveform,
+    phase: f32,
+    phase_inc: f32,
+    noise_index: u32,
+) -> f32 {
+    let t = phase / TAU;
+    let dt = (phase_inc / TAU).clamp(0.0, 0.5);
     match waveform {
         SynthWaveform::Sine => phase.sin(),
-        SynthWaveform::Square => {
-            if phase.sin() >= 0.0 {
-                1.0
-            } else {
-                -1.0
-            }
-        }
-        SynthWaveform::Saw => (phase / PI) - 1.0,
-        SynthWaveform::Triangle => {
-            let normalized = phase / TAU;
-            2.0 * (2.0 * (normalized - (normalized + 0.5).floor())).abs() - 1.0
+        SynthWaveform::Square => pulse_sample(t, dt, 0.5),
+        SynthWaveform::Saw => 2.0 * t - 1.0 - poly_blep(t, dt),
+        SynthWaveform::Triangle => 2.0 * (2.0 * (t - (t + 0.5).floor())).abs() - 1.0,
+        SynthWaveform::Noise => white_noise(noise_index),
+        SynthWaveform::Pulse { width } => {
+            let width = width.clamp(PULSE_WIDTH_MIN, PULSE_WIDTH_MAX);
+            pulse_sample(t, dt, width as f32 / 128.0)
         }
     }
 }
 
-fn envelope_sample(voice: &ActiveVoice) -> f32 {
-    let attack_env = if voice.attack_samples == 0 {
+fn pulse_sample(t: f32, dt: f32, duty: f32) -> f32 {
+    let naive = if t < duty { 1.0 } else { -1.0 };
+    let falling = (t - duty).rem_euclid(1.0);
+    naive + poly_blep(t, dt) - poly_blep(falling, dt)
+}
+
+/// Residual of a unit step convolved with a two-sample polynomial kernel around `t == 0`.
+fn poly_blep(t: f32, dt: f32) -> f32 {
+    if dt <= 0.0 {
+        0.0
+    } else if t < dt {
+        let x = t / dt;
+        x + x - x * x - 1.0
+    } else if t > 1.0 - dt {
+        let x = (t - 1.0) / dt;
+        x * x + x + x + 1.0
+    } else {
+        0.0
+    }
+}
+
+fn white_noise(index: u32) -> f32 {
+    let mut x = index.wrapping_mul(0x2C1B_3C6D);
+    x ^= x >> 15;
+    x = x.wrapping_mul(0x297A_2D39);
+    x ^= x >> 12;
+    (x as f32 / u32::MAX as f32) * 2.0 - 1.0
+}
+
+/// Reads the voice's sample and advances it, following pitch bends through `phase_inc`.
+fn sample_playback(voice: &mut ActiveVoice) -> f32 {
+    let pitch_ratio = if voice.base_phase_inc > 0.0 {
+        voice.phase_inc / voice.base_phase_inc
+    } else {
         1.0
+    };
+    let Some((data, playhead)) = voice.sample.as_mut() else {
+        return 0.0;
+    };
+    let value = playhead.read(data);
+    playhead.advance(pitch_ratio as f64);
+    if playhead.is_finished() {
+        // One-shot samples end the voice at their end point, whatever the envelope says.
+        voice.releasing = true;
+        voice.release_samples = 0;
+    }
+    value
+}
+
+/// Renders the voice's drum hit and ends the voice once every part has faded out.
+fn drum_sample(voice: &mut ActiveVoice) -> f32 {
+    let pitch_ratio = if voice.base_phase_inc > 0.0 {
+        voice.phase_inc / voice.base_phase_inc
     } else {
-        (voice.elapsed_samples as f32 / voice.attack_samples as f32).clamp(0.0, 1.0)
+        1.0
+    };
+    let elapsed = voice.elapsed_samples;
+    let noise = white_noise(voice.noise_seed.wrapping_add(elapsed));
+    let Some(drum) = voice.drum.as_mut() else {
+        return 0.0;
+    };
+    let fade = |length: u32| {
+        if elapsed >= length {
+            0.0
+        } else {
+            let remaining = 1.0 - elapsed as f32 / length as f32;
+            remaining * remaining
+        }
+    };
+
+    let output = match drum.model {
+        DrumModel::Kick {
+            start_inc,
+            end_inc,
+            sweep_samples,
+            decay_samples,
+        } => {
+            let sweep = fade(sweep_samples);
+            let phase_inc = end_inc + (start_inc - end_inc) * sweep;
+            let output = drum.phase.sin() * fade(decay_samples);
+            drum.phase = (drum.phase + phase_inc * pitch_ratio) % TAU;
+            output
+        }
+        DrumModel::Snare {
+            tone_inc,
+            tone_decay_samples,
+            noise_decay_samples,
+            tone_mix,
+        } => {
+            let tone = drum.phase.sin() * fade(tone_decay_samples);
+            drum.phase = (drum.phase + tone_inc * pitch_ratio) % TAU;
+            tone * tone_mix + noise * fade(noise_decay_samples) * (1.0 - tone_mix)
+        }
+        DrumModel::Hat {
+            coefficient,
+            decay_samples,
+        } => {
+            let [previous_input, previous_output] = drum.highpass;
+            let highpassed = coefficient * (previous_output + noise - previous_input);
+            drum.highpass = [noise, highpassed];
+            highpassed * fade(decay_samples)
+        }
+    };
+
+    let output = output * drum.level;
+    if elapsed.saturating_add(1) >= drum.length_samples() {
+        voice.releasing = true;
+        voice.release_samples = 0;
+    }
+    output
+}
+
+/// Runs the operator stack from the last modulator down to the carrier.
+fn fm_sample(voice: &mut ActiveVoice) -> f32 {
+    let phase_inc = voice.phase_inc;
+    let (elapsed, releasing, release_progress) = (
+        voice.elapsed_samples,
+        voice.releasing,
+        voice.release_progress_samples,
+    );
+    let Some(fm) = voice.fm.as_mut() else {
+        return 0.0;
     };
 
-    let release_env = if !voice.releasing {
+    let mut modulation = 0.0f32;
+    let mut output = 0.0f32;
+    for index in (0..fm.operator_count).rev() {
+        let operator = &mut fm.operators[index];
+        let feedback = operator.feedback
+            * FM_MAX_FEEDBACK
+            * (operator.history[0] + operator.history[1])
+            * 0.5;
+        let raw = (operator.phase + modulation + feedback).sin();
+        operator.history = [raw, operator.history[0]];
+        operator.phase = (operator.phase + phase_inc * operator.ratio) % TAU;
+
+        if index == 0 {
+            output = raw * operator.level;
+        } else {
+            let env = attack_decay_level(
+                elapsed,
+                operator.attack_samples,
+                operator.decay_samples,
+                operator.sustain_level,
+            ) * release_level(releasing, release_progress, operator.release_samples);
+            modulation = raw * operator.level * env * FM_MAX_MOD_INDEX;
+        }
+    }
+    output
+}
+
+fn envelope_sample(voice: &ActiveVoice) -> f32 {
+    attack_decay_level(
+        voice.elapsed_samples,
+        voice.attack_samples,
+        voice.decay_samples,
+        voice.sustain_level,
+    ) * release_level(
+        voice.releasing,
+        voice.release_progress_samples,
+        voice.release_samples,
+    )
+}
+
+fn attack_decay_level(elapsed: u32, attack: u32, decay: u32, sustain_level: f32) -> f32 {
+    if elapsed < attack {
+        return elapsed as f32 / attack as f32;
+    }
+    let decayed = elapsed - attack;
+    if decayed >= decay {
+        sustain_level
+    } else {
+        1.0 - (1.0 - sustain_level) * (decayed as f32 / decay as f32)
+    }
+}
+
+fn release_level(releasing: bool, progress: u32, release: u32) -> f32 {
+    if !releasing {
         1.0
-    } else if voice.release_samples == 0 {
+    } else if release == 0 {
         0.0
     } else {
-        (1.0 - (voice.release_progress_samples as f32 / voice.release_samples as f32))
-            .clamp(0.0, 1.0)
-    };
+        (1.0 - progress as f32 / release as f32).clamp(0.0, 1.0)
+    }
+}
+
+/// Zavalishin's trapezoidal SVF: stable at any cutoff, with the envelope sweeping the cutoff
+/// by `env_amount` steps.
+fn filter_sample(voice: &mut ActiveVoice, input: f32, env: f32) -> f32 {
+    let filter = voice.filter;
+    if filter.is_bypassed() {
+        return input;
+    }
 
-    attack_env * release_env
+    let steps = (filter.cutoff.min(127) as f32 + filter.env_amount as f32 * env).clamp(0.0, 127.0);
+    let cutoff_hz = cutoff_steps_to_hz(steps, voice.sample_rate_hz);
+    let g = (PI * cutoff_hz / voice.sample_rate_hz).tan();
+    // Damping 2.0 has no resonance; full resonance stops just short of self-oscillation.
+    let k = 2.0 - 1.9 * filter.resonance.min(127) as f32 / 127.0;
+    let a1 = 1.0 / (1.0 + g * (g + k));
+    let a2 = g * a1;
+    let a3 = g * a2;
+
+    let state = &mut voice.filter_state;
+    let v3 = input - state.ic2;
+    let v1 = a1 * state.ic1 + a2 * v3;
+    let v2 = state.ic2 + a2 * state.ic1 + a3 * v3;
+    state.ic1 = 2.0 * v1 - state.ic1;
+    state.ic2 = 2.0 * v2 - state.ic2;
+
+    match filter.mode {
+        FilterMode::LowPass => v2,
+        FilterMode::BandPass => v1,
+        FilterMode::HighPass => input - k * v1 - v2,
+    }
+}
+
+fn smooth_amplitude(voice: &mut ActiveVoice) {
+    if voice.amplitude < voice.target_amplitude {
+        voice.amplitude = (voice.amplitude + voice.amplitude_step).min(voice.target_amplitude);
+    } else if voice.amplitude > voice.target_amplitude {
+        voice.amplitude = (voice.amplitude - voice.amplitude_step).max(voice.target_amplitude);
+    }
 }
 
 fn ms_to_samples(ms: u16, sample_rate_hz: f32) -> u32 {
@@ -414,7 +955,12 @@ fn soft_clip(value: f32) -> f32 {
     value / (1.0 + value.abs())
 }
 
-fn write_wav_mono_i16(path: &Path, sample_rate_hz: u32, samples: &[i16]) -> Result<(), ExportError> {
+fn write_wav_i16(
+    path: &Path,
+    sample_rate_hz: u32,
+    channels: u16,
+    samples: &[i16],
+) -> Result<(), ExportError> {
     let data_len = samples
         .len()
         .checked_mul(2)
@@ -430,12 +976,13 @@ fn write_wav_mono_i16(path: &Path, sample_rate_hz: u32, samples: &[i16]) -> Resu
     file.write_all(b"fmt ")?;
     file.write_all(&16u32.to_le_bytes())?;
     file.write_all(&1u16.to_le_bytes())?;
-    file.write_all(&1u16.to_le_bytes())?;
+    file.write_all(&channels.to_le_bytes())?;
     file.write_all(&sample_rate_hz.to_le_bytes())?;
 
-    let byte_rate = sample_rate_hz.saturating_mul(2);
+    let block_align = channels.saturating_mul(2);
+    let byte_rate = sample_rate_hz.saturating_mul(block_align as u32);
     file.write_all(&byte_rate.to_le_bytes())?;
-    file.write_all(&2u16.to_le_bytes())?;
+    file.write_all(&block_align.to_le_bytes())?;
     file.write_all(&16u16.to_le_bytes())?;
 
     file.write_all(b"data")?;
@@ -451,12 +998,18 @@ fn write_wav_mono_i16(path: &Path, sample_rate_hz: u32, samples: &[i16]) -> Resu
 #[cfg(test)]
 mod tests {
     use super::{
-        apply_event, render_project_to_wav, synthesize_sample, synthesize_sample_routed,
-        OfflineRenderConfig, RenderFxState,
+        apply_event, band_limited_sample, ms_to_samples, render_project_to_wav, synthesize_sample,
+        synthesize_sample_routed, write_wav_i16, ExportError, ExportReport,
+        OfflineRenderConfig, RenderFxState, AMPLITUDE_SMOOTHING_MS,
     };
+    use crate::audio::{AudioBackend, AudioBackendConfig, NativeAudioBackend};
     use p9_core::engine::{Engine, EngineCommand};
-    use p9_core::events::{RenderEvent, RenderMode};
-    use p9_core::model::{Chain, Instrument, InstrumentType, Phrase};
+    use p9_core::events::{RenderEvent, RenderMode, VoiceParams};
+    use p9_core::model::{
+        Chain, DelayParams, DrumSound, DrumSynthParams, FilterMode, FilterParams, FmParams,
+        FxCommand, Instrument, InstrumentType, Phrase, ReverbParams, SampleParams, SynthWaveform,
+        Table,
+    };
     use std::fs;
     use std::path::PathBuf;
     use std::time::{SystemTime, UNIX_EPOCH};
@@ -501,6 +1054,98 @@ mod tests {
         path
     }
 
+    /// `setup_engine` with `instrument` saved as id 0 and played on both of its notes.
+    fn instrument_engine(instrument: Instrument) -> Engine {
+        let mut engine = setup_engine();
+        engine
+            .apply_command(EngineCommand::UpsertInstrument { instrument })
+            .unwrap();
+        let mut phrase = engine.snapshot().phrases[&0].clone();
+        phrase.steps[0].instrument_id = Some(0);
+        phrase.steps[4].instrument_id = Some(0);
+        engine
+            .apply_command(EngineCommand::UpsertPhrase { phrase })
+            .unwrap();
+        engine
+    }
+
+    /// `setup_engine` with `(step_index, fx_slot, code, value)` FX on its phrase.
+    fn fx_engine(fx: &[(usize, usize, &str, u8)]) -> Engine {
+        let mut engine = setup_engine();
+        for &(step_index, fx_slot, code, value) in fx {
+            engine
+                .apply_command(EngineCommand::SetStepFx {
+                    phrase_id: 0,
+                    step_index,
+                    fx_slot,
+                    fx: Some(FxCommand {
+                        code: code.to_string(),
+                        value,
+                    }),
+                })
+                .unwrap();
+        }
+        engine
+    }
+
+    /// Renders `engine` through a temporary WAV file and returns its stereo frames.
+    fn render_frames(engine: &Engine, cfg: &OfflineRenderConfig) -> (ExportReport, Vec<[i16; 2]>) {
+        let path = temp_file("p9_export_frames");
+        let report = render_project_to_wav(engine, &path, cfg.clone()).unwrap();
+        let bytes = fs::read(&path).unwrap();
+        let _ = fs::remove_file(path);
+        let frames = bytes[44..]
+            .chunks_exact(4)
+            .map(|frame| {
+                [
+                    i16::from_le_bytes([frame[0], frame[1]]),
+                    i16::from_le_bytes([frame[2], frame[3]]),
+                ]
+            })
+            .collect();
+        (report, frames)
+    }
+
+    /// Left-channel sign changes per frame; twice the pitch of a pure tone over the rate.
+    fn crossing_rate(frames: &[[i16; 2]]) -> f32 {
+        frames
+            .windows(2)
+            .filter(|pair| (pair[0][0] < 0) != (pair[1][0] < 0))
+            .count() as f32
+            / frames.len() as f32
+    }
+
+    /// Summed left-channel magnitude.
+    fn energy(frames: &[[i16; 2]]) -> i64 {
+        frames.iter().map(|frame| (frame[0] as i64).abs()).sum()
+    }
+
+    /// Frames between rising zero crossings on the left channel.
+    fn periods(frames: &[[i16; 2]]) -> Vec<usize> {
+        let rising: Vec<usize> = frames
+            .windows(2)
+            .enumerate()
+            .filter(|(_, pair)| pair[0][0] < 0 && pair[1][0] >= 0)
+            .map(|(index, _)| index)
+            .collect();
+        rising.windows(2).map(|pair| pair[1] - pair[0]).collect()
+    }
+
+    fn mean_period(frames: &[[i16; 2]]) -> f32 {
+        let periods = periods(frames);
+        periods.iter().sum::<usize>() as f32 / periods.len() as f32
+    }
+
+    /// How far the pitch of period `to` sits above that of period `from`.
+    fn semitones(from: f32, to: f32) -> f32 {
+        12.0 * (from / to).log2()
+    }
+
+    /// One tick is 1000 frames at 120 BPM, 24 PPQ and 48 kHz.
+    fn tick_frames(frames: &[[i16; 2]], tick: usize) -> &[[i16; 2]] {
+        &frames[tick * 1_000..(tick + 1) * 1_000]
+    }
+
     #[test]
     fn render_project_to_wav_writes_valid_riff_file() {
         let engine = setup_engine();
@@ -520,6 +1165,8 @@ mod tests {
         assert!(bytes.starts_with(b"RIFF"));
         assert_eq!(&bytes[8..12], b"WAVE");
         assert_eq!(&bytes[36..40], b"data");
+        assert_eq!(u16::from_le_bytes([bytes[22], bytes[23]]), 2);
+        assert_eq!(report.channels, 2);
         assert!(report.events_rendered > 0);
         assert!(report.samples_rendered > 0);
         assert!(report.peak_abs_sample > 0);
@@ -538,7 +1185,7 @@ mod tests {
             ..OfflineRenderConfig::default()
         };
 
-        let left = render_project_to_wav(&engine, &left_path, cfg).unwrap();
+        let left = render_project_to_wav(&engine, &left_path, cfg.clone()).unwrap();
         let right = render_project_to_wav(&engine, &right_path, cfg).unwrap();
 
         let left_bytes = fs::read(&left_path).unwrap();
@@ -552,50 +1199,275 @@ mod tests {
     }
 
     #[test]
-    fn render_project_to_wav_midiout_profile_is_silent() {
-        let mut engine = setup_engine();
-        let instrument = Instrument::new(0, InstrumentType::MidiOut, "MIDI Out");
-        engine
-            .apply_command(EngineCommand::UpsertInstrument { instrument })
-            .unwrap();
-        for (step, note, velocity) in [(0usize, Some(60u8), 100u8), (4usize, Some(64u8), 100u8)] {
-            engine
-                .apply_command(EngineCommand::SetPhraseStep {
-                    phrase_id: 0,
-                    step_index: step,
-                    note,
-                    velocity,
-                    instrument_id: Some(0),
-                })
-                .unwrap();
-        }
+    fn fm_synth_export_brightens_with_modulator_level() {
+        let fm_engine = |fm: FmParams| {
+            let mut bell = Instrument::new(0, InstrumentType::FmSynth, "Bell");
+            bell.fm_params = Some(fm);
+            instrument_engine(bell)
+        };
+        let carrier = FmParams {
+            operator_count: 1,
+            ..FmParams::default()
+        };
+        let mut stack = FmParams {
+            operator_count: 3,
+            ..FmParams::default()
+        };
+        stack.operators[1].ratio = 8;
+        stack.operators[2].ratio = 14;
+        stack.operators[2].feedback = 64;
+        let mut brighter = stack;
+        brighter.operators[1].level = 127;
+        let cfg = OfflineRenderConfig {
+            ticks: 6,
+            ..OfflineRenderConfig::default()
+        };
 
-        let output = temp_file("p9_export_midiout_silent");
-        let report = render_project_to_wav(
+        let (carrier_report, carrier_frames) = render_frames(&fm_engine(carrier), &cfg);
+        let (_, stack_frames) = render_frames(&fm_engine(stack), &cfg);
+        let (_, bright_frames) = render_frames(&fm_engine(brighter), &cfg);
+
+        // Modulators add sidebands above the carrier, and more so at a higher level.
+        assert!(carrier_report.peak_abs_sample > 0);
+        let carrier_rate = crossing_rate(&carrier_frames[..2_000]);
+        let stack_rate = crossing_rate(&stack_frames[..2_000]);
+        let bright_rate = crossing_rate(&bright_frames[..2_000]);
+        assert!(stack_rate > carrier_rate * 1.5, "{stack_rate} vs {carrier_rate}");
+        assert!(bright_rate > stack_rate * 1.5, "{bright_rate} vs {stack_rate}");
+    }
+
+    #[test]
+    fn sample_instruments_play_their_wav_pitched_by_note() {
+        let sample_engine = |sample: SampleParams| {
+            let mut drums = Instrument::new(0, InstrumentType::Sampler, "Drums");
+            drums.sample = Some(sample);
+            instrument_engine(drums)
+        };
+        let source_path = temp_file("p9_export_sample_source");
+        let tone: Vec<i16> = (0..4_800)
+            .map(|i| ((i as f32 * 0.05).sin() * 20_000.0) as i16)
+            .collect();
+        write_wav_i16(&source_path, 24_000, 1, &tone).unwrap();
+        let source = source_path.to_string_lossy().into_owned();
+        let cfg = OfflineRenderConfig {
+            ticks: 6,
+            ..OfflineRenderConfig::default()
+        };
+
+        let sample = SampleParams::new(source.clone());
+        let lower = SampleParams {
+            root_note: 72,
+            ..SampleParams::new(source)
+        };
+        let (_, root_frames) = render_frames(&sample_engine(sample), &cfg);
+        let (_, lower_frames) = render_frames(&sample_engine(lower), &cfg);
+        let missing = SampleParams::new("/nonexistent/p9_missing.wav");
+        let (silent, _) = render_frames(&sample_engine(missing), &cfg);
+
+        // The 24 kHz source repeats every 2π / 0.05 samples, twice that at 48 kHz.
+        let source_period = 2.0 * std::f32::consts::TAU / 0.05;
+        let root_period = mean_period(&root_frames[..6_000]);
+        assert!((root_period - source_period).abs() < 1.0, "{root_period}");
+        let lower_pitch = semitones(root_period, mean_period(&lower_frames[..6_000]));
+        assert!((lower_pitch + 12.0).abs() < 0.1, "{lower_pitch}");
+        assert_eq!(silent.peak_abs_sample, 0);
+        assert_eq!(silent.failed_samples, vec![0]);
+
+        let _ = fs::remove_file(source_path);
+    }
+
+    #[test]
+    fn relative_sample_paths_resolve_against_the_sample_dir_not_the_working_dir() {
+        let project_dir = temp_file("p9_export_project_dir");
+        fs::create_dir_all(&project_dir).unwrap();
+        assert_ne!(std::env::current_dir().unwrap(), project_dir);
+        let tone: Vec<i16> = (0..4_800)
+            .map(|i| ((i as f32 * 0.05).sin() * 20_000.0) as i16)
+            .collect();
+        write_wav_i16(&project_dir.join("tone.wav"), 48_000, 1, &tone).unwrap();
+        let mut drums = Instrument::new(0, InstrumentType::Sampler, "Drums");
+        drums.sample = Some(SampleParams::new("tone.wav"));
+        let engine = instrument_engine(drums);
+
+        let (in_project, _) = render_frames(
             &engine,
-            &output,
-            OfflineRenderConfig {
-                ticks: 48,
+            &OfflineRenderConfig {
+                ticks: 6,
+                sample_dir: Some(project_dir.clone()),
                 ..OfflineRenderConfig::default()
             },
-        )
-        .unwrap();
+        );
+        let (in_working_dir, _) = render_frames(
+            &engine,
+            &OfflineRenderConfig {
+                ticks: 6,
+                ..OfflineRenderConfig::default()
+            },
+        );
 
-        assert_eq!(report.peak_abs_sample, 0);
-        let _ = fs::remove_file(output);
+        assert!(in_project.failed_samples.is_empty());
+        assert!(in_project.peak_abs_sample > 1_000);
+        assert_eq!(in_working_dir.failed_samples, vec![0]);
+        assert_eq!(in_working_dir.peak_abs_sample, 0);
+
+        let _ = fs::remove_dir_all(project_dir);
     }
 
     #[test]
-    fn render_project_sampler_profile_differs_from_synth_profile() {
-        let mut synth_engine = setup_engine();
-        let synth = Instrument::new(0, InstrumentType::Synth, "Synth");
-        synth_engine
-            .apply_command(EngineCommand::UpsertInstrument { instrument: synth })
-            .unwrap();
-        synth_engine
-            .apply_command(EngineCommand::SetPhraseStep {
-                phrase_id: 0,
-                step_index: 0,
+    fn drum_synth_export_follows_kick_snare_and_hat_params() {
+        // Kick on step 0, snare on step 4 and a hat on step 8.
+        let drum_engine = |drums: DrumSynthParams| {
+            let mut kit = Instrument::new(0, InstrumentType::DrumSynth, "Kit");
+            kit.drum_params = Some(drums);
+            let mut engine = instrument_engine(kit);
+            let mut phrase = engine.snapshot().phrases[&0].clone();
+            phrase.steps[8].note = Some(66);
+            phrase.steps[8].velocity = 100;
+            phrase.steps[8].instrument_id = Some(0);
+            engine
+                .apply_command(EngineCommand::UpsertPhrase { phrase })
+                .unwrap();
+            engine
+        };
+        let mut tuned = DrumSynthParams::default();
+        tuned.kick.start_hz = 240;
+        tuned.snare.tone_mix = 127;
+        tuned.hat.decay_ms = 240;
+        let cfg = OfflineRenderConfig {
+            ticks: 72,
+            ..OfflineRenderConfig::default()
+        };
+
+        let (report, kit) = render_frames(&drum_engine(DrumSynthParams::default()), &cfg);
+        let (_, repeat) = render_frames(&drum_engine(DrumSynthParams::default()), &cfg);
+        let (_, tuned) = render_frames(&drum_engine(tuned), &cfg);
+        assert!(report.peak_abs_sample > 0);
+        assert!(kit == repeat, "drum noise must be seeded per render");
+
+        // The kick sweeps down to the same 50 Hz tail from a higher start.
+        let kick_attack = crossing_rate(&kit[..960]);
+        let kick_tail = crossing_rate(&kit[4_800..14_400]);
+        assert!(kick_attack > kick_tail * 2.0, "{kick_attack} vs {kick_tail}");
+        assert!(crossing_rate(&tuned[..960]) > kick_attack);
+        assert!((crossing_rate(&tuned[4_800..14_400]) - kick_tail).abs() < 0.000_5);
+
+        // The default snare is mostly noise; an all-tone mix drops to its 190 Hz body.
+        let noisy_snare = crossing_rate(&kit[24_000..28_800]);
+        let tonal_snare = crossing_rate(&tuned[24_000..28_800]);
+        assert!(noisy_snare > tonal_snare * 10.0, "{noisy_snare} vs {tonal_snare}");
+
+        // The hat is gone after its 60 ms decay unless the decay is lengthened.
+        assert!(energy(&kit[48_000..48_480]) > 0);
+        assert_eq!(energy(&kit[51_360..57_600]), 0);
+        assert!(energy(&tuned[51_360..57_600]) > 0);
+    }
+
+    #[test]
+    fn swing_moves_off_beat_notes_in_export() {
+        let mut engine = setup_engine();
+        let mut phrase = engine.snapshot().phrases[&0].clone();
+        phrase.steps[1].note = Some(67);
+        phrase.steps[1].velocity = 100;
+        engine
+            .apply_command(EngineCommand::UpsertPhrase { phrase })
+            .unwrap();
+        let cfg = OfflineRenderConfig {
+            ticks: 24,
+            ..OfflineRenderConfig::default()
+        };
+
+        let straight_path = temp_file("p9_export_straight");
+        let straight = render_project_to_wav(&engine, &straight_path, cfg.clone()).unwrap();
+        engine.apply_command(EngineCommand::SetSongSwing(70)).unwrap();
+        let swung_path = temp_file("p9_export_swung");
+        let swung = render_project_to_wav(&engine, &swung_path, cfg).unwrap();
+
+        assert_eq!(straight.events_rendered, swung.events_rendered);
+        assert_eq!(straight.samples_rendered, swung.samples_rendered);
+        assert_ne!(fs::read(&straight_path).unwrap(), fs::read(&swung_path).unwrap());
+
+        let _ = fs::remove_file(straight_path);
+        let _ = fs::remove_file(swung_path);
+    }
+
+    #[test]
+    fn sub_tick_swing_is_rendered_between_ticks() {
+        let mut engine = setup_engine();
+        let mut phrase = engine.snapshot().phrases[&0].clone();
+        phrase.steps[1].note = Some(67);
+        phrase.steps[1].velocity = 100;
+        engine
+            .apply_command(EngineCommand::UpsertPhrase { phrase })
+            .unwrap();
+        // At PPQ 4 every step is one tick, so this swing only exists below tick resolution.
+        let cfg = OfflineRenderConfig {
+            ppq: 4,
+            ticks: 4,
+            ..OfflineRenderConfig::default()
+        };
+
+        let straight_path = temp_file("p9_export_subtick_straight");
+        render_project_to_wav(&engine, &straight_path, cfg.clone()).unwrap();
+        engine.apply_command(EngineCommand::SetSongSwing(58)).unwrap();
+        let swung_path = temp_file("p9_export_subtick_swung");
+        render_project_to_wav(&engine, &swung_path, cfg).unwrap();
+
+        let straight = fs::read(&straight_path).unwrap();
+        let swung = fs::read(&swung_path).unwrap();
+        // The first tick (6000 frames, 4 bytes each after the 44-byte header) is unchanged.
+        let first_tick = 44 + 6_000 * 4;
+        assert_eq!(straight[..first_tick], swung[..first_tick]);
+        assert_ne!(straight, swung);
+
+        let _ = fs::remove_file(straight_path);
+        let _ = fs::remove_file(swung_path);
+    }
+
+    #[test]
+    fn render_project_to_wav_midiout_profile_is_silent() {
+        let mut engine = setup_engine();
+        let instrument = Instrument::new(0, InstrumentType::MidiOut, "MIDI Out");
+        engine
+            .apply_command(EngineCommand::UpsertInstrument { instrument })
+            .unwrap();
+        for (step, note, velocity) in [(0usize, Some(60u8), 100u8), (4usize, Some(64u8), 100u8)] {
+            engine
+                .apply_command(EngineCommand::SetPhraseStep {
+                    phrase_id: 0,
+                    step_index: step,
+                    note,
+                    velocity,
+                    instrument_id: Some(0),
+                })
+                .unwrap();
+        }
+
+        let output = temp_file("p9_export_midiout_silent");
+        let report = render_project_to_wav(
+            &engine,
+            &output,
+            OfflineRenderConfig {
+                ticks: 48,
+                ..OfflineRenderConfig::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(report.peak_abs_sample, 0);
+        let _ = fs::remove_file(output);
+    }
+
+    #[test]
+    fn render_project_sampler_profile_differs_from_synth_profile() {
+        let mut synth_engine = setup_engine();
+        let synth = Instrument::new(0, InstrumentType::Synth, "Synth");
+        synth_engine
+            .apply_command(EngineCommand::UpsertInstrument { instrument: synth })
+            .unwrap();
+        synth_engine
+            .apply_command(EngineCommand::SetPhraseStep {
+                phrase_id: 0,
+                step_index: 0,
                 note: Some(60),
                 velocity: 100,
                 instrument_id: Some(0),
@@ -642,7 +1514,7 @@ mod tests {
             ..OfflineRenderConfig::default()
         };
 
-        let synth_report = render_project_to_wav(&synth_engine, &synth_path, cfg).unwrap();
+        let synth_report = render_project_to_wav(&synth_engine, &synth_path, cfg.clone()).unwrap();
         let sampler_report = render_project_to_wav(&sampler_engine, &sampler_path, cfg).unwrap();
         let synth_bytes = fs::read(&synth_path).unwrap();
         let sampler_bytes = fs::read(&sampler_path).unwrap();
@@ -665,6 +1537,7 @@ mod tests {
             render_mode: RenderMode::ExternalMuted,
             track_level: 127,
             master_level: 127,
+            pan: 0x40,
             send_mfx: 0,
             send_delay: 0,
             send_reverb: 0,
@@ -672,10 +1545,15 @@ mod tests {
             waveform: p9_core::model::SynthWaveform::Saw,
             attack_ms: 5,
             release_ms: 80,
+            decay_ms: 0,
+            sustain: 127,
+            filter: p9_core::model::FilterParams::default(),
             gain: 100,
             sampler_variant: p9_core::model::SamplerRenderVariant::Classic,
             sampler_transient_level: 64,
             sampler_body_level: 96,
+            fm: p9_core::model::FmParams::default(),
+            drum: p9_core::model::DrumSynthParams::default(),
         };
 
         apply_event(&mut voices, &event, 48_000.0);
@@ -697,6 +1575,7 @@ mod tests {
             render_mode: RenderMode::Synth,
             track_level: 127,
             master_level: 127,
+            pan: 0x40,
             send_mfx: 0,
             send_delay: 0,
             send_reverb: 0,
@@ -704,10 +1583,15 @@ mod tests {
             waveform: p9_core::model::SynthWaveform::Saw,
             attack_ms: 9,
             release_ms: 9,
+            decay_ms: 0,
+            sustain: 127,
+            filter: p9_core::model::FilterParams::default(),
             gain: 100,
             sampler_variant: p9_core::model::SamplerRenderVariant::Classic,
             sampler_transient_level: 64,
             sampler_body_level: 96,
+            fm: p9_core::model::FmParams::default(),
+            drum: p9_core::model::DrumSynthParams::default(),
         };
         let sampler_event = RenderEvent::NoteOn {
             track_id: 0,
@@ -716,6 +1600,7 @@ mod tests {
             render_mode: RenderMode::SamplerV1,
             track_level: 127,
             master_level: 127,
+            pan: 0x40,
             send_mfx: 0,
             send_delay: 0,
             send_reverb: 0,
@@ -723,10 +1608,15 @@ mod tests {
             waveform: p9_core::model::SynthWaveform::Saw,
             attack_ms: 9,
             release_ms: 9,
+            decay_ms: 0,
+            sustain: 127,
+            filter: p9_core::model::FilterParams::default(),
             gain: 100,
             sampler_variant: p9_core::model::SamplerRenderVariant::Punch,
             sampler_transient_level: 110,
             sampler_body_level: 40,
+            fm: p9_core::model::FmParams::default(),
+            drum: p9_core::model::DrumSynthParams::default(),
         };
 
         apply_event(&mut synth_voices, &synth_event, 48_000.0);
@@ -760,6 +1650,7 @@ mod tests {
                 render_mode: RenderMode::Synth,
                 track_level: 127,
                 master_level: 127,
+                pan: 0x40,
                 send_mfx: 0,
                 send_delay: 0,
                 send_reverb: 0,
@@ -767,10 +1658,15 @@ mod tests {
                 waveform: p9_core::model::SynthWaveform::Saw,
                 attack_ms: 1,
                 release_ms: 48,
+                decay_ms: 0,
+                sustain: 127,
+                filter: p9_core::model::FilterParams::default(),
                 gain: 100,
                 sampler_variant: p9_core::model::SamplerRenderVariant::Classic,
                 sampler_transient_level: 64,
                 sampler_body_level: 96,
+                fm: p9_core::model::FmParams::default(),
+                drum: p9_core::model::DrumSynthParams::default(),
             },
             48_000.0,
         );
@@ -784,6 +1680,7 @@ mod tests {
                 render_mode: RenderMode::Synth,
                 track_level: 0,
                 master_level: 127,
+                pan: 0x40,
                 send_mfx: 0,
                 send_delay: 0,
                 send_reverb: 0,
@@ -791,10 +1688,15 @@ mod tests {
                 waveform: p9_core::model::SynthWaveform::Saw,
                 attack_ms: 1,
                 release_ms: 48,
+                decay_ms: 0,
+                sustain: 127,
+                filter: p9_core::model::FilterParams::default(),
                 gain: 100,
                 sampler_variant: p9_core::model::SamplerRenderVariant::Classic,
                 sampler_transient_level: 64,
                 sampler_body_level: 96,
+                fm: p9_core::model::FmParams::default(),
+                drum: p9_core::model::DrumSynthParams::default(),
             },
             48_000.0,
         );
@@ -802,14 +1704,273 @@ mod tests {
         let mut full_energy = 0.0f32;
         let mut muted_energy = 0.0f32;
         for _ in 0..64 {
-            full_energy += synthesize_sample_routed(&mut full_mix_voices, &mut full_fx).abs();
-            muted_energy += synthesize_sample_routed(&mut muted_mix_voices, &mut muted_fx).abs();
+            full_energy += synthesize_sample_routed(&mut full_mix_voices, &mut full_fx).0.abs();
+            muted_energy += synthesize_sample_routed(&mut muted_mix_voices, &mut muted_fx).0.abs();
         }
 
         assert!(full_energy > 0.1);
         assert!(muted_energy < full_energy * 0.05);
     }
 
+    fn shaped_synth_note_on(decay_ms: u16, sustain: u8, filter: FilterParams) -> RenderEvent {
+        RenderEvent::NoteOn {
+            track_id: 0,
+            note: 48,
+            velocity: 127,
+            render_mode: RenderMode::Synth,
+            track_level: 127,
+            master_level: 127,
+            pan: 0x40,
+            send_mfx: 0,
+            send_delay: 0,
+            send_reverb: 0,
+            instrument_id: Some(0),
+            waveform: SynthWaveform::Saw,
+            attack_ms: 0,
+            release_ms: 48,
+            decay_ms,
+            sustain,
+            filter,
+            gain: 127,
+            sampler_variant: p9_core::model::SamplerRenderVariant::Classic,
+            sampler_transient_level: 64,
+            sampler_body_level: 96,
+            fm: p9_core::model::FmParams::default(),
+            drum: p9_core::model::DrumSynthParams::default(),
+        }
+    }
+
+    fn drum_note_on(note: u8) -> RenderEvent {
+        let mut event = shaped_synth_note_on(0, 127, FilterParams::default());
+        if let RenderEvent::NoteOn {
+            note: event_note,
+            render_mode,
+            ..
+        } = &mut event
+        {
+            *event_note = note;
+            *render_mode = RenderMode::Drum;
+        }
+        event
+    }
+
+    fn peak_over(voices: &mut Vec<super::ActiveVoice>, samples: usize) -> f32 {
+        (0..samples)
+            .map(|_| synthesize_sample(voices).abs())
+            .fold(0.0, f32::max)
+    }
+
+    #[test]
+    fn decay_settles_held_synth_notes_on_the_sustain_level() {
+        let mut voices = Vec::new();
+        let event = shaped_synth_note_on(10, 32, FilterParams::default());
+        apply_event(&mut voices, &event, 48_000.0);
+
+        let early_peak = peak_over(&mut voices, 480);
+        // Skip the rest of the decay before measuring the sustained level.
+        peak_over(&mut voices, 960);
+        let sustained_peak = peak_over(&mut voices, 960);
+
+        assert!(early_peak > 0.2);
+        let ratio = sustained_peak / early_peak;
+        assert!((0.15..0.4).contains(&ratio), "sustain ratio {ratio}");
+    }
+
+    /// The pre-PolyBLEP oscillators, kept as the aliasing baseline.
+    fn naive_sample(waveform: SynthWaveform, phase: f32) -> f32 {
+        match waveform {
+            SynthWaveform::Saw => phase / std::f32::consts::PI - 1.0,
+            _ => {
+                if phase.sin() >= 0.0 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+        }
+    }
+
+    /// Energy in DFT bins that are not harmonics of `cycles`, i.e. folded-back partials.
+    fn alias_energy(samples: &[f32], cycles: usize) -> f64 {
+        let n = samples.len();
+        (1..n / 2)
+            .filter(|bin| bin % cycles != 0)
+            .map(|bin| {
+                let (mut re, mut im) = (0.0f64, 0.0f64);
+                for (index, sample) in samples.iter().enumerate() {
+                    let angle = std::f64::consts::TAU * (bin * index % n) as f64 / n as f64;
+                    re += *sample as f64 * angle.cos();
+                    im -= *sample as f64 * angle.sin();
+                }
+                re * re + im * im
+            })
+            .sum()
+    }
+
+    #[test]
+    fn band_limited_oscillators_alias_less_than_naive_ones() {
+        // 347 whole cycles in the window put a ~4 kHz fundamental exactly on a bin, so any
+        // energy between its harmonics is aliasing.
+        const WINDOW: usize = 2_048;
+        const CYCLES: usize = 347;
+        let phase_inc = std::f32::consts::TAU * CYCLES as f32 / WINDOW as f32;
+
+        for waveform in [SynthWaveform::Saw, SynthWaveform::Square] {
+            let mut naive = Vec::with_capacity(WINDOW);
+            let mut blep = Vec::with_capacity(WINDOW);
+            for index in 0..WINDOW {
+                let cycle = (index * CYCLES % WINDOW) as f32 / WINDOW as f32;
+                let phase = std::f32::consts::TAU * cycle;
+                naive.push(naive_sample(waveform, phase));
+                blep.push(band_limited_sample(waveform, phase, phase_inc, 0));
+            }
+
+            let naive_alias = alias_energy(&naive, CYCLES);
+            let blep_alias = alias_energy(&blep, CYCLES);
+            assert!(
+                blep_alias < naive_alias * 0.25,
+                "{waveform:?}: {blep_alias} vs naive {naive_alias}"
+            );
+        }
+    }
+
+    #[test]
+    fn noise_and_pulse_width_shape_the_oscillator() {
+        let noise: Vec<f32> = (0..512)
+            .map(|index| band_limited_sample(SynthWaveform::Noise, 0.0, 0.1, index))
+            .collect();
+        let mean = noise.iter().sum::<f32>() / noise.len() as f32;
+        assert!(noise.iter().all(|sample| (-1.0..=1.0).contains(sample)));
+        assert!(mean.abs() < 0.1);
+        assert!(noise.windows(2).filter(|pair| pair[0] != pair[1]).count() > 500);
+
+        // Average level of a pulse follows its duty cycle: 2 * width / 128 - 1.
+        let phase_inc = std::f32::consts::TAU / 256.0;
+        let mean_of = |width| {
+            (0..256)
+                .map(|index| {
+                    let phase = index as f32 * phase_inc;
+                    band_limited_sample(SynthWaveform::Pulse { width }, phase, phase_inc, 0)
+                })
+                .sum::<f32>()
+                / 256.0
+        };
+        assert!(mean_of(64).abs() < 0.02);
+        assert!((mean_of(32) + 0.5).abs() < 0.02);
+        assert!((mean_of(96) - 0.5).abs() < 0.02);
+        assert_eq!(
+            mean_of(0),
+            mean_of(1),
+            "widths below the minimum clamp to the narrowest pulse"
+        );
+    }
+
+    #[test]
+    fn drum_hits_sweep_and_ring_out_past_note_off() {
+        let crossings = |samples: &[f32]| {
+            samples
+                .windows(2)
+                .filter(|pair| (pair[0] < 0.0) != (pair[1] < 0.0))
+                .count() as f32
+                / samples.len() as f32
+        };
+
+        // The kick's pitch falls from 180 Hz towards 50 Hz.
+        let mut voices = Vec::new();
+        apply_event(&mut voices, &drum_note_on(36), 48_000.0);
+        apply_event(
+            &mut voices,
+            &RenderEvent::NoteOff {
+                track_id: 0,
+                note: 36,
+            },
+            48_000.0,
+        );
+        let kick: Vec<f32> = (0..14_400).map(|_| synthesize_sample(&mut voices)).collect();
+        assert!(crossings(&kick[..960]) > crossings(&kick[4_800..14_400]) * 2.0);
+        assert!(kick[4_800..].iter().any(|sample| sample.abs() > 0.01));
+        assert!(!voices.is_empty());
+        peak_over(&mut voices, 2_400);
+        assert!(voices.is_empty(), "the kick ends with its 350 ms decay");
+
+        // Hats are far brighter than the kick and gone after 60 ms.
+        apply_event(&mut voices, &drum_note_on(42), 48_000.0);
+        let hat: Vec<f32> = (0..2_880).map(|_| synthesize_sample(&mut voices)).collect();
+        assert!(crossings(&hat) > crossings(&kick[..960]) * 10.0);
+        assert!(voices.is_empty());
+
+        let mut snare_voices = Vec::new();
+        apply_event(&mut snare_voices, &drum_note_on(38), 48_000.0);
+        assert!(peak_over(&mut snare_voices, 480) > 0.01);
+        assert_eq!(DrumSound::for_note(38), DrumSound::Snare);
+    }
+
+    #[test]
+    fn realtime_drum_hits_ignore_note_off_for_as_long_as_export() {
+        let note_off = RenderEvent::NoteOff {
+            track_id: 0,
+            note: 36,
+        };
+        let mut voices = Vec::new();
+        apply_event(&mut voices, &drum_note_on(36), 48_000.0);
+        apply_event(&mut voices, &note_off, 48_000.0);
+        let mut export_frames = 0u32;
+        while !voices.is_empty() {
+            synthesize_sample(&mut voices);
+            export_frames += 1;
+        }
+
+        let mut backend = NativeAudioBackend::new(AudioBackendConfig::default());
+        backend.start_checked().unwrap();
+        backend.push_events(&[drum_note_on(36)]);
+        backend.push_events(std::slice::from_ref(&note_off));
+        let mut callbacks_after_note_on = 1u32;
+        while backend.metrics().active_voices > 0 {
+            backend.push_events(&[]);
+            callbacks_after_note_on += 1;
+        }
+
+        // The simulated backend releases in 10 ms steps; both keep the kick for 350 ms.
+        assert_eq!(backend.metrics().voice_release_deferred_total, 0);
+        assert_eq!(export_frames / 48, 350);
+        assert_eq!(callbacks_after_note_on * 10, export_frames / 48);
+    }
+
+    #[test]
+    fn low_pass_filter_removes_saw_harmonics() {
+        let mut open_voices = Vec::new();
+        let mut closed_voices = Vec::new();
+        let closed = FilterParams {
+            mode: FilterMode::LowPass,
+            cutoff: 30,
+            resonance: 0,
+            env_amount: 0,
+        };
+        apply_event(
+            &mut open_voices,
+            &shaped_synth_note_on(0, 127, FilterParams::default()),
+            48_000.0,
+        );
+        apply_event(&mut closed_voices, &shaped_synth_note_on(0, 127, closed), 48_000.0);
+
+        // Sample-to-sample differences weigh the high harmonics of the saw.
+        let mut open_edge = 0.0f32;
+        let mut closed_edge = 0.0f32;
+        let mut open_prev = 0.0f32;
+        let mut closed_prev = 0.0f32;
+        for _ in 0..4_800 {
+            let open = synthesize_sample(&mut open_voices);
+            let closed = synthesize_sample(&mut closed_voices);
+            open_edge += (open - open_prev).abs();
+            closed_edge += (closed - closed_prev).abs();
+            open_prev = open;
+            closed_prev = closed;
+        }
+
+        assert!(open_edge > 1.0);
+        assert!(closed_edge < open_edge * 0.5, "{closed_edge} vs {open_edge}");
+    }
+
     #[test]
     fn send_routing_changes_export_signature() {
         fn render_signature(send_mfx: u8, send_delay: u8, send_reverb: u8) -> i64 {
@@ -824,6 +1985,7 @@ mod tests {
                     render_mode: RenderMode::Synth,
                     track_level: 127,
                     master_level: 127,
+                    pan: 0x40,
                     send_mfx,
                     send_delay,
                     send_reverb,
@@ -831,17 +1993,22 @@ mod tests {
                     waveform: p9_core::model::SynthWaveform::Saw,
                     attack_ms: 1,
                     release_ms: 56,
+                    decay_ms: 0,
+                    sustain: 127,
+                    filter: p9_core::model::FilterParams::default(),
                     gain: 100,
                     sampler_variant: p9_core::model::SamplerRenderVariant::Classic,
                     sampler_transient_level: 64,
                     sampler_body_level: 96,
+                    fm: p9_core::model::FmParams::default(),
+                    drum: p9_core::model::DrumSynthParams::default(),
                 },
                 48_000.0,
             );
 
             let mut signature = 0.0f64;
             for frame in 0u32..192 {
-                let sample = synthesize_sample_routed(&mut voices, &mut fx);
+                let (sample, _) = synthesize_sample_routed(&mut voices, &mut fx);
                 signature += sample as f64 * (frame + 1) as f64;
             }
 
@@ -853,6 +2020,503 @@ mod tests {
         assert_ne!(dry, routed);
     }
 
+    #[test]
+    fn mixer_delay_and_reverb_params_shape_the_send_returns() {
+        let render = |name: &str, delay: DelayParams, reverb: ReverbParams| {
+            let mut engine = setup_engine();
+            let mut lead = Instrument::new(0, InstrumentType::Synth, "Lead");
+            lead.send_levels.delay = 127;
+            lead.send_levels.reverb = 127;
+            engine
+                .apply_command(EngineCommand::UpsertInstrument { instrument: lead })
+                .unwrap();
+            let mut phrase = engine.snapshot().phrases[&0].clone();
+            phrase.steps[0].instrument_id = Some(0);
+            phrase.steps[4].instrument_id = Some(0);
+            engine
+                .apply_command(EngineCommand::UpsertPhrase { phrase })
+                .unwrap();
+            engine
+                .apply_command(EngineCommand::SetMixerSends {
+                    mfx: 0,
+                    delay: 127,
+                    reverb: 127,
+                })
+                .unwrap();
+            engine
+                .apply_command(EngineCommand::SetMixerDelay { delay })
+                .unwrap();
+            engine
+                .apply_command(EngineCommand::SetMixerReverb { reverb })
+                .unwrap();
+            let path = temp_file(name);
+            let cfg = OfflineRenderConfig {
+                ticks: 48,
+                ..OfflineRenderConfig::default()
+            };
+            render_project_to_wav(&engine, &path, cfg).unwrap();
+            let bytes = fs::read(&path).unwrap();
+            let _ = fs::remove_file(path);
+            bytes
+        };
+
+        let base = render("p9_export_sends", DelayParams::default(), ReverbParams::default());
+        let again =
+            render("p9_export_sends_again", DelayParams::default(), ReverbParams::default());
+        let longer = DelayParams {
+            time_steps: 5,
+            ..DelayParams::default()
+        };
+        let ping_pong = DelayParams {
+            ping_pong: true,
+            ..DelayParams::default()
+        };
+        let small_room = ReverbParams {
+            size: 0,
+            ..ReverbParams::default()
+        };
+
+        assert_eq!(base, again);
+        assert_ne!(base, render("p9_export_delay_time", longer, ReverbParams::default()));
+        assert_ne!(base, render("p9_export_ping_pong", ping_pong, ReverbParams::default()));
+        assert_ne!(base, render("p9_export_reverb_size", DelayParams::default(), small_room));
+    }
+
+    #[test]
+    fn track_params_event_glides_level_and_sends_of_held_voices() {
+        let mut voices = Vec::new();
+        let mut fx = RenderFxState::new(48_000);
+        apply_event(
+            &mut voices,
+            &RenderEvent::NoteOn {
+                track_id: 0,
+                note: 60,
+                velocity: 127,
+                render_mode: RenderMode::Synth,
+                track_level: 127,
+                master_level: 127,
+                pan: 0x40,
+                send_mfx: 0,
+                send_delay: 0,
+                send_reverb: 0,
+                instrument_id: Some(0),
+                waveform: SynthWaveform::Saw,
+                attack_ms: 0,
+                release_ms: 48,
+                decay_ms: 0,
+                sustain: 127,
+                filter: p9_core::model::FilterParams::default(),
+                gain: 127,
+                sampler_variant: p9_core::model::SamplerRenderVariant::Classic,
+                sampler_transient_level: 64,
+                sampler_body_level: 96,
+                fm: p9_core::model::FmParams::default(),
+                drum: p9_core::model::DrumSynthParams::default(),
+            },
+            48_000.0,
+        );
+        let full = voices[0].amplitude;
+
+        apply_event(
+            &mut voices,
+            &RenderEvent::TrackParams {
+                track_id: 0,
+                params: VoiceParams {
+                    track_level: 32,
+                    master_level: 127,
+                    send_mfx: 0,
+                    send_delay: 127,
+                    send_reverb: 0,
+                    attack_ms: 0,
+                    release_ms: 48,
+                    gain: 127,
+                },
+            },
+            48_000.0,
+        );
+        assert_eq!(voices[0].amplitude, full);
+        assert_eq!(voices[0].send_delay, 1.0);
+
+        let ramp = ms_to_samples(AMPLITUDE_SMOOTHING_MS, 48_000.0);
+        for _ in 0..ramp {
+            synthesize_sample_routed(&mut voices, &mut fx);
+        }
+        assert!((voices[0].amplitude - full * 32.0 / 127.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn pitch_bend_event_retunes_matching_voice_only() {
+        fn note_on(track_id: u8, note: u8) -> RenderEvent {
+            RenderEvent::NoteOn {
+                track_id,
+                note,
+                velocity: 100,
+                render_mode: RenderMode::Synth,
+                track_level: 127,
+                master_level: 127,
+                pan: 0x40,
+                send_mfx: 0,
+                send_delay: 0,
+                send_reverb: 0,
+                instrument_id: Some(0),
+                waveform: p9_core::model::SynthWaveform::Saw,
+                attack_ms: 1,
+                release_ms: 48,
+                decay_ms: 0,
+                sustain: 127,
+                filter: p9_core::model::FilterParams::default(),
+                gain: 100,
+                sampler_variant: p9_core::model::SamplerRenderVariant::Classic,
+                sampler_transient_level: 64,
+                sampler_body_level: 96,
+                fm: p9_core::model::FmParams::default(),
+                drum: p9_core::model::DrumSynthParams::default(),
+            }
+        }
+
+        let mut voices = Vec::new();
+        apply_event(&mut voices, &note_on(0, 60), 48_000.0);
+        apply_event(&mut voices, &note_on(1, 60), 48_000.0);
+        let base = voices[0].phase_inc;
+
+        apply_event(
+            &mut voices,
+            &RenderEvent::PitchBend {
+                track_id: 0,
+                note: 60,
+                cents: -1200,
+            },
+            48_000.0,
+        );
+        assert!((voices[0].phase_inc - base * 0.5).abs() < 1e-6);
+        assert_eq!(voices[1].phase_inc, base);
+
+        apply_event(
+            &mut voices,
+            &RenderEvent::PitchBend {
+                track_id: 0,
+                note: 60,
+                cents: 0,
+            },
+            48_000.0,
+        );
+        assert_eq!(voices[0].phase_inc, base);
+    }
+
+    #[test]
+    fn render_project_slide_fx_glides_from_the_previous_note() {
+        let cfg = OfflineRenderConfig {
+            ticks: 48,
+            ..OfflineRenderConfig::default()
+        };
+        let (slide, slide_frames) = render_frames(&fx_engine(&[(4, 0, "SLD", 12)]), &cfg);
+        let (straight, straight_frames) = render_frames(&setup_engine(), &cfg);
+
+        // Step 4 starts at frame 24 000; note 60 repeats every 183.5 frames, note 64 every 145.6.
+        let straight_periods = periods(&straight_frames[24_000..30_000]);
+        assert!(straight_periods.iter().all(|period| (145..=146).contains(period)));
+        let glide = periods(&slide_frames[24_000..30_000]);
+        assert!((183..=184).contains(&glide[0]), "{glide:?}");
+        assert!(glide.windows(2).all(|pair| pair[1] <= pair[0] + 1), "{glide:?}");
+        assert!(glide[glide.len() - 1] + 10 < glide[0], "{glide:?}");
+        assert!(glide[glide.len() - 1] > 150, "{glide:?}");
+        assert!(slide.events_rendered > straight.events_rendered);
+    }
+
+    #[test]
+    fn render_project_arp_fx_cycles_offsets_every_tick() {
+        let cfg = OfflineRenderConfig {
+            ticks: 6,
+            ..OfflineRenderConfig::default()
+        };
+        let (arp, arp_frames) = render_frames(&fx_engine(&[(0, 0, "ARP", 0x37)]), &cfg);
+        let (straight, straight_frames) = render_frames(&setup_engine(), &cfg);
+
+        let root = mean_period(tick_frames(&arp_frames, 0));
+        let offsets: Vec<f32> = (0..6)
+            .map(|tick| semitones(root, mean_period(tick_frames(&arp_frames, tick))))
+            .collect();
+        for (offset, expected) in offsets.iter().zip([0.0, 3.0, 7.0, 0.0, 3.0, 7.0]) {
+            assert!((offset - expected).abs() < 0.15, "{offsets:?}");
+        }
+        for tick in 1..6 {
+            let held = semitones(root, mean_period(tick_frames(&straight_frames, tick)));
+            assert!(held.abs() < 0.15, "{held}");
+        }
+        assert!(arp.events_rendered > straight.events_rendered);
+    }
+
+    #[test]
+    fn render_project_tick_length_follows_tempo_fx() {
+        let mut engine = setup_engine();
+        engine
+            .apply_command(EngineCommand::SetStepFx {
+                phrase_id: 0,
+                step_index: 1,
+                fx_slot: 0,
+                fx: Some(FxCommand {
+                    code: "TPO".to_string(),
+                    value: 240,
+                }),
+            })
+            .unwrap();
+        let cfg = OfflineRenderConfig {
+            ticks: 48,
+            ..OfflineRenderConfig::default()
+        };
+
+        let path = temp_file("p9_export_tempo_fx");
+        let report = render_project_to_wav(&engine, &path, cfg).unwrap();
+
+        // 6 ticks of step 0 at 120 BPM, then 42 ticks at 240 BPM.
+        assert_eq!(engine.snapshot().song.tempo, 120);
+        assert_eq!(report.samples_rendered, 6 * 1_000 + 42 * 500);
+
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn render_project_fractional_tick_length_does_not_drift() {
+        let engine = setup_engine();
+        let cfg = OfflineRenderConfig {
+            sample_rate_hz: 44_100,
+            ppq: 96,
+            ticks: 96 * 8,
+            sample_dir: None,
+        };
+
+        let path = temp_file("p9_export_fractional_ticks");
+        let report = render_project_to_wav(&engine, &path, cfg).unwrap();
+
+        // 229.6875 frames per tick: eight beats are exactly four seconds, where rounding
+        // every tick to 230 frames would run 240 frames long.
+        assert_eq!(report.samples_rendered, 4 * 44_100);
+
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn render_project_rejects_zero_sample_rate() {
+        let engine = setup_engine();
+        let cfg = OfflineRenderConfig {
+            sample_rate_hz: 0,
+            ..OfflineRenderConfig::default()
+        };
+
+        let path = temp_file("p9_export_zero_rate");
+        let result = render_project_to_wav(&engine, &path, cfg);
+
+        assert!(matches!(result, Err(ExportError::InvalidSampleRate(0))));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn render_project_random_fx_is_bit_identical_per_seed() {
+        let random_engine = |seed: u32| {
+            let mut engine = fx_engine(&[
+                (0, 0, "RNN", 12),
+                (0, 1, "RTG", 3),
+                (4, 0, "CHA", 160),
+                (4, 1, "RNV", 40),
+            ]);
+            engine.apply_command(EngineCommand::SetSongSeed(seed)).unwrap();
+            engine
+        };
+        let cfg = OfflineRenderConfig {
+            ticks: 96,
+            ..OfflineRenderConfig::default()
+        };
+        let (_, left) = render_frames(&random_engine(11), &cfg);
+        let (_, right) = render_frames(&random_engine(11), &cfg);
+        let (_, other) = render_frames(&random_engine(12), &cfg);
+
+        assert!(left == right);
+        // RNN rolls within an octave of note 60, whose period is 183.5 frames.
+        let rolled = mean_period(&left[..3_000]);
+        let other_rolled = mean_period(&other[..3_000]);
+        for period in [rolled, other_rolled] {
+            assert!(semitones(183.5, period).abs() <= 12.1, "{period}");
+        }
+        assert!(semitones(rolled, other_rolled).abs() > 0.9);
+        // The RTG retrigger on tick 3 repeats the rolled note.
+        assert!(semitones(rolled, mean_period(&left[3_000..6_000])).abs() < 0.1);
+        // CHA drops the step 4 note for seed 11 and keeps it for seed 12.
+        assert_eq!(energy(&left[24_000..30_000]), 0);
+        assert!(energy(&other[24_000..30_000]) > 0);
+    }
+
+    #[test]
+    fn note_volume_event_rescales_matching_voice_only() {
+        let mut voices = Vec::new();
+        for track_id in [0u8, 1] {
+            apply_event(
+                &mut voices,
+                &RenderEvent::NoteOn {
+                    track_id,
+                    note: 60,
+                    velocity: 127,
+                    render_mode: RenderMode::Synth,
+                    track_level: 127,
+                    master_level: 127,
+                    pan: 0x40,
+                    send_mfx: 0,
+                    send_delay: 0,
+                    send_reverb: 0,
+                    instrument_id: None,
+                    waveform: SynthWaveform::Saw,
+                    attack_ms: 0,
+                    release_ms: 10,
+                    decay_ms: 0,
+                    sustain: 127,
+                    filter: p9_core::model::FilterParams::default(),
+                    gain: 127,
+                    sampler_variant: p9_core::model::SamplerRenderVariant::Classic,
+                    sampler_transient_level: 0,
+                    sampler_body_level: 0,
+                    fm: p9_core::model::FmParams::default(),
+                    drum: p9_core::model::DrumSynthParams::default(),
+                },
+                48_000.0,
+            );
+        }
+        let full = voices[0].amplitude;
+
+        apply_event(
+            &mut voices,
+            &RenderEvent::NoteVolume {
+                track_id: 0,
+                note: 60,
+                velocity: 32,
+            },
+            48_000.0,
+        );
+
+        let target = full * 32.0 / 127.0;
+        assert!((voices[0].target_amplitude - target).abs() < 1e-6);
+        assert_eq!(voices[0].amplitude, full);
+        assert_eq!(voices[1].target_amplitude, full);
+
+        for _ in 0..ms_to_samples(AMPLITUDE_SMOOTHING_MS, 48_000.0) / 2 {
+            let _ = synthesize_sample(&mut voices);
+        }
+        assert!(voices[0].amplitude < full && voices[0].amplitude > target);
+        for _ in 0..ms_to_samples(AMPLITUDE_SMOOTHING_MS, 48_000.0) {
+            let _ = synthesize_sample(&mut voices);
+        }
+        assert!((voices[0].amplitude - target).abs() < 1e-6);
+        assert_eq!(voices[1].amplitude, full);
+    }
+
+    #[test]
+    fn render_project_tick_table_steps_pitch_every_tick() {
+        fn table_engine(speed: u8) -> Engine {
+            let mut instrument = Instrument::new(0, InstrumentType::Synth, "Tbl");
+            instrument.table_id = Some(0);
+            let mut engine = instrument_engine(instrument);
+            let mut table = Table::new(0);
+            table.rows[0].volume = 127;
+            table.rows[1].note_offset = 12;
+            table.rows[1].volume = 96;
+            table.rows[2].note_offset = 7;
+            table.rows[2].volume = 127;
+            table.speed = speed;
+            table.loop_start = 0;
+            engine
+                .apply_command(EngineCommand::UpsertTable { table })
+                .unwrap();
+            engine
+        }
+
+        let cfg = OfflineRenderConfig {
+            ticks: 6,
+            ..OfflineRenderConfig::default()
+        };
+        let (ticked, ticked_frames) = render_frames(&table_engine(1), &cfg);
+        let (stepped, stepped_frames) = render_frames(&table_engine(0), &cfg);
+
+        let root = mean_period(tick_frames(&ticked_frames, 0));
+        for (tick, expected) in [(1, 12.0), (2, 7.0), (3, 0.0)] {
+            let offset = semitones(root, mean_period(tick_frames(&ticked_frames, tick)));
+            assert!((offset - expected).abs() < 0.15, "tick {tick}: {offset}");
+            let held = semitones(root, mean_period(tick_frames(&stepped_frames, tick)));
+            assert!(held.abs() < 0.15, "tick {tick}: {held}");
+        }
+        assert!(ticked.events_rendered > stepped.events_rendered);
+    }
+
+    #[test]
+    fn render_project_vibrato_bends_pitch_and_tremolo_dips_level() {
+        let cfg = OfflineRenderConfig {
+            ticks: 6,
+            ..OfflineRenderConfig::default()
+        };
+        let (vibrato, vibrato_frames) = render_frames(&fx_engine(&[(0, 0, "VIB", 0x38)]), &cfg);
+        let (tremolo, tremolo_frames) = render_frames(&fx_engine(&[(0, 0, "TRM", 0x26)]), &cfg);
+        let (straight, straight_frames) = render_frames(&setup_engine(), &cfg);
+
+        let spread = |periods: Vec<usize>| {
+            periods.iter().max().unwrap() - periods.iter().min().unwrap()
+        };
+        assert!(spread(periods(&straight_frames[..6_000])) <= 1);
+        assert!(spread(periods(&vibrato_frames[..6_000])) >= 4);
+
+        // Tremolo only lowers the level, starting from the unmodulated first tick.
+        let level = |frames: &[[i16; 2]]| energy(&frames[1_000..6_000]);
+        assert_eq!(energy(&tremolo_frames[..1_000]), energy(&straight_frames[..1_000]));
+        assert!(level(&tremolo_frames) * 20 < level(&straight_frames) * 19);
+        let pitch = semitones(
+            mean_period(&straight_frames[..6_000]),
+            mean_period(&tremolo_frames[..6_000]),
+        );
+        assert!(pitch.abs() < 0.05, "{pitch}");
+        assert!(vibrato.events_rendered > straight.events_rendered);
+        assert!(tremolo.events_rendered > straight.events_rendered);
+    }
+
+    #[test]
+    fn render_project_volume_slide_fades_out_smoothly() {
+        let cfg = OfflineRenderConfig {
+            ticks: 6,
+            ..OfflineRenderConfig::default()
+        };
+        let (_, fade) = render_frames(&fx_engine(&[(0, 0, "VSL", 0x0F)]), &cfg);
+        let (_, straight) = render_frames(&setup_engine(), &cfg);
+
+        assert_eq!(energy(tick_frames(&fade, 0)), energy(tick_frames(&straight, 0)));
+        assert!(energy(&fade[4_000..6_000]) * 2 < energy(&straight[4_000..6_000]));
+    }
+
+    #[test]
+    fn render_project_pan_places_voices_in_the_stereo_field() {
+        fn channel_energy(frames: &[[i16; 2]]) -> (i64, i64) {
+            frames.iter().fold((0, 0), |(left, right), frame| {
+                (left + (frame[0] as i64).abs(), right + (frame[1] as i64).abs())
+            })
+        }
+
+        let mut left_engine = setup_engine();
+        left_engine
+            .apply_command(EngineCommand::SetTrackPan {
+                track_index: 0,
+                pan: 0,
+            })
+            .unwrap();
+        let cfg = OfflineRenderConfig {
+            ticks: 6,
+            ..OfflineRenderConfig::default()
+        };
+
+        let (left_l, left_r) = channel_energy(&render_frames(&left_engine, &cfg).1);
+        let right_engine = fx_engine(&[(0, 0, "PAN", 0x80)]);
+        let (right_l, right_r) = channel_energy(&render_frames(&right_engine, &cfg).1);
+        let (centre_l, centre_r) = channel_energy(&render_frames(&setup_engine(), &cfg).1);
+
+        assert!(centre_l > 0);
+        assert_eq!(centre_l, centre_r);
+        assert_eq!((left_l, left_r), (centre_l, 0));
+        assert_eq!((right_l, right_r), (0, centre_r));
+    }
+
     #[test]
     fn long_mixed_mode_session_is_deterministic_and_variant_sensitive() {
         fn mixed_session_signature(
@@ -875,6 +2539,7 @@ mod tests {
                             render_mode: RenderMode::Synth,
                             track_level: 127,
                             master_level: 127,
+                            pan: 0x40,
                             send_mfx: 0,
                             send_delay: 0,
                             send_reverb: 0,
@@ -882,10 +2547,15 @@ mod tests {
                             waveform: p9_core::model::SynthWaveform::Saw,
                             attack_ms: 5,
                             release_ms: 64,
+                            decay_ms: 0,
+                            sustain: 127,
+                            filter: p9_core::model::FilterParams::default(),
                             gain: 100,
                             sampler_variant: p9_core::model::SamplerRenderVariant::Classic,
                             sampler_transient_level: 64,
                             sampler_body_level: 96,
+                            fm: p9_core::model::FmParams::default(),
+                            drum: p9_core::model::DrumSynthParams::default(),
                         },
                         48_000.0,
                     );
@@ -912,6 +2582,7 @@ mod tests {
                             render_mode: RenderMode::SamplerV1,
                             track_level: 127,
                             master_level: 127,
+                            pan: 0x40,
                             send_mfx: 0,
                             send_delay: 0,
                             send_reverb: 0,
@@ -919,10 +2590,15 @@ mod tests {
                             waveform: p9_core::model::SynthWaveform::Saw,
                             attack_ms: 1,
                             release_ms: 48,
+                            decay_ms: 0,
+                            sustain: 127,
+                            filter: p9_core::model::FilterParams::default(),
                             gain: 100,
                             sampler_variant,
                             sampler_transient_level,
                             sampler_body_level,
+                            fm: p9_core::model::FmParams::default(),
+                            drum: p9_core::model::DrumSynthParams::default(),
                         },
                         48_000.0,
                     );
@@ -949,6 +2625,7 @@ mod tests {
                             render_mode: RenderMode::ExternalMuted,
                             track_level: 127,
                             master_level: 127,
+                            pan: 0x40,
                             send_mfx: 0,
                             send_delay: 0,
                             send_reverb: 0,
@@ -956,10 +2633,15 @@ mod tests {
                             waveform: p9_core::model::SynthWaveform::Square,
                             attack_ms: 1,
                             release_ms: 1,
+                            decay_ms: 0,
+                            sustain: 127,
+                            filter: p9_core::model::FilterParams::default(),
                             gain: 110,
                             sampler_variant: p9_core::model::SamplerRenderVariant::Classic,
                             sampler_transient_level: 64,
                             sampler_body_level: 64,
+                            fm: p9_core::model::FmParams::default(),
+                            drum: p9_core::model::DrumSynthParams::default(),
                         },
                         48_000.0,
                     );
@@ -2,4 +2,5 @@ pub mod audio;
 pub mod dsp;
 pub mod export;
 pub mod midi;
+pub mod sample;
 pub mod voice;
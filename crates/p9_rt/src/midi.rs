@@ -2,6 +2,14 @@ use std::collections::VecDeque;
 
 use p9_core::events::RenderEvent;
 
+const PITCH_BEND_CENTER: i32 = 8192;
+const PITCH_BEND_RANGE_CENTS: i32 = 200;
+/// A song position pointer counts MIDI beats (sixteenth notes) of six clocks each.
+const MIDI_CLOCKS_PER_BEAT: u64 = 6;
+pub const MIDI_CLOCKS_PER_QUARTER: u64 = 24;
+const SONG_POSITION_MAX: u64 = 0x3FFF;
+const CC_CHANNEL_VOLUME: u8 = 7;
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct MidiMessage {
     pub status: u8,
@@ -25,6 +33,7 @@ pub enum DecodedMidi {
     Stop,
     Continue,
     Clock,
+    SongPosition(u16),
     Unknown,
 }
 
@@ -140,11 +149,29 @@ pub fn decode_message(msg: MidiMessage) -> DecodedMidi {
             0xFC => DecodedMidi::Stop,
             0xFB => DecodedMidi::Continue,
             0xF8 => DecodedMidi::Clock,
+            0xF2 => DecodedMidi::SongPosition(
+                ((msg.data2 as u16 & 0x7F) << 7) | (msg.data1 as u16 & 0x7F),
+            ),
             _ => DecodedMidi::Unknown,
         },
     }
 }
 
+/// MIDI clocks from the song start to a decoded song position pointer.
+pub fn song_position_clocks(beats: u16) -> u64 {
+    beats as u64 * MIDI_CLOCKS_PER_BEAT
+}
+
+/// Encodes the position as a song position pointer, rounding down to a whole MIDI beat.
+pub fn song_position_message(clocks: u64) -> MidiMessage {
+    let beats = (clocks / MIDI_CLOCKS_PER_BEAT).min(SONG_POSITION_MAX);
+    MidiMessage {
+        status: 0xF2,
+        data1: (beats & 0x7F) as u8,
+        data2: ((beats >> 7) & 0x7F) as u8,
+    }
+}
+
 pub fn render_event_to_midi(event: &RenderEvent) -> MidiMessage {
     match event {
         RenderEvent::NoteOn {
@@ -162,6 +189,33 @@ pub fn render_event_to_midi(event: &RenderEvent) -> MidiMessage {
             data1: *note,
             data2: 0,
         },
+        RenderEvent::PitchBend {
+            track_id, cents, ..
+        } => {
+            // Receivers are assumed to use the default +/-2 semitone bend range.
+            let offset = *cents as i32 * PITCH_BEND_CENTER / PITCH_BEND_RANGE_CENTS;
+            let value = (PITCH_BEND_CENTER + offset).clamp(0, 0x3FFF);
+            MidiMessage {
+                status: 0xE0 | (track_id & 0x0F),
+                data1: (value & 0x7F) as u8,
+                data2: ((value >> 7) & 0x7F) as u8,
+            }
+        }
+        RenderEvent::NoteVolume {
+            track_id,
+            note,
+            velocity,
+        } => MidiMessage {
+            status: 0xA0 | (track_id & 0x0F),
+            data1: *note,
+            data2: (*velocity).min(127),
+        },
+        // Sends and envelope times have no portable CC; the channel volume carries the mix.
+        RenderEvent::TrackParams { track_id, params } => MidiMessage {
+            status: 0xB0 | (track_id & 0x0F),
+            data1: CC_CHANNEL_VOLUME,
+            data2: (params.track_level as u16 * params.master_level as u16 / 127).min(127) as u8,
+        },
     }
 }
 
@@ -177,10 +231,11 @@ pub fn forward_render_events(events: &[RenderEvent], output: &mut dyn MidiOutput
 #[cfg(test)]
 mod tests {
     use super::{
-        decode_message, forward_render_events, render_event_to_midi, BufferedMidiInput,
-        BufferedMidiOutput, DecodedMidi, MidiInput, MidiMessage, MidiOutput, NoopMidiOutput,
+        decode_message, forward_render_events, render_event_to_midi, song_position_message,
+        BufferedMidiInput, BufferedMidiOutput, DecodedMidi, MidiInput, MidiMessage, MidiOutput,
+        NoopMidiOutput,
     };
-    use p9_core::events::{RenderEvent, RenderMode};
+    use p9_core::events::{RenderEvent, RenderMode, VoiceParams};
     use p9_core::model::SynthWaveform;
 
     fn note_on(track_id: u8, note: u8, velocity: u8) -> RenderEvent {
@@ -191,6 +246,7 @@ mod tests {
             render_mode: RenderMode::Synth,
             track_level: 127,
             master_level: 127,
+            pan: 0x40,
             send_mfx: 0,
             send_delay: 0,
             send_reverb: 0,
@@ -198,10 +254,15 @@ mod tests {
             waveform: SynthWaveform::Saw,
             attack_ms: 5,
             release_ms: 80,
+            decay_ms: 0,
+            sustain: 127,
+            filter: p9_core::model::FilterParams::default(),
             gain: 100,
             sampler_variant: p9_core::model::SamplerRenderVariant::Classic,
             sampler_transient_level: 64,
             sampler_body_level: 96,
+            fm: p9_core::model::FmParams::default(),
+            drum: p9_core::model::DrumSynthParams::default(),
         }
     }
 
@@ -256,6 +317,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn song_position_round_trips_in_midi_beats() {
+        let message = song_position_message(6 * 300 + 5);
+        assert_eq!(
+            message,
+            MidiMessage {
+                status: 0xF2,
+                data1: 44,
+                data2: 2,
+            }
+        );
+        assert_eq!(decode_message(message), DecodedMidi::SongPosition(300));
+        assert_eq!(
+            decode_message(song_position_message(u64::MAX)),
+            DecodedMidi::SongPosition(0x3FFF)
+        );
+    }
+
     #[test]
     fn render_event_maps_track_to_channel() {
         let msg = render_event_to_midi(&note_on(19, 72, 90));
@@ -270,6 +349,80 @@ mod tests {
         );
     }
 
+    #[test]
+    fn pitch_bend_maps_to_channel_bend_with_clamped_range() {
+        let centered = render_event_to_midi(&RenderEvent::PitchBend {
+            track_id: 2,
+            note: 60,
+            cents: 0,
+        });
+        assert_eq!(
+            centered,
+            MidiMessage {
+                status: 0xE2,
+                data1: 0x00,
+                data2: 0x40
+            }
+        );
+
+        let down_full = render_event_to_midi(&RenderEvent::PitchBend {
+            track_id: 2,
+            note: 60,
+            cents: -1200,
+        });
+        assert_eq!(down_full.data1, 0);
+        assert_eq!(down_full.data2, 0);
+
+        let up_semitone = render_event_to_midi(&RenderEvent::PitchBend {
+            track_id: 0,
+            note: 60,
+            cents: 100,
+        });
+        assert_eq!((up_semitone.data2 as i32) << 7 | up_semitone.data1 as i32, 12288);
+    }
+
+    #[test]
+    fn track_params_map_to_channel_volume() {
+        let message = render_event_to_midi(&RenderEvent::TrackParams {
+            track_id: 1,
+            params: VoiceParams {
+                track_level: 64,
+                master_level: 127,
+                send_mfx: 0,
+                send_delay: 0,
+                send_reverb: 0,
+                attack_ms: 5,
+                release_ms: 80,
+                gain: 100,
+            },
+        });
+        assert_eq!(
+            message,
+            MidiMessage {
+                status: 0xB1,
+                data1: 7,
+                data2: 64
+            }
+        );
+    }
+
+    #[test]
+    fn note_volume_maps_to_poly_pressure() {
+        let message = render_event_to_midi(&RenderEvent::NoteVolume {
+            track_id: 3,
+            note: 64,
+            velocity: 90,
+        });
+        assert_eq!(
+            message,
+            MidiMessage {
+                status: 0xA3,
+                data1: 64,
+                data2: 90
+            }
+        );
+    }
+
     #[test]
     fn forward_render_events_sends_all_messages() {
         let events = vec![
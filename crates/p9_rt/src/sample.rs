@@ -0,0 +1,780 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use p9_core::model::{
+    InstrumentId, InstrumentType, ProjectData, SampleLoopMode, SampleParams, SampleSlicing,
+    MAX_SAMPLE_SLICES,
+};
+
+const WAVE_FORMAT_PCM: u16 = 1;
+const WAVE_FORMAT_IEEE_FLOAT: u16 = 3;
+const WAVE_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+/// Playhead positions are frames in 32.32 fixed point.
+const FRACTION_BITS: u32 = 32;
+const FRACTION_ONE: f64 = (1u64 << FRACTION_BITS) as f64;
+/// Transient detection compares energy over 5 ms windows.
+const ONSET_WINDOWS_PER_SECOND: u32 = 200;
+/// Windows of history an onset must stand out against.
+const ONSET_HISTORY_WINDOWS: usize = 4;
+const ONSET_MIN_GAP_MS: u32 = 50;
+
+#[derive(Debug)]
+pub enum SampleError {
+    Io(io::Error),
+    InvalidWav(&'static str),
+    UnsupportedFormat {
+        format_tag: u16,
+        bits_per_sample: u16,
+        channels: u16,
+    },
+}
+
+impl From<io::Error> for SampleError {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+/// Decoded audio as stereo frames in -1.0..=1.0; mono files carry the same value on both sides.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SampleData {
+    pub sample_rate_hz: u32,
+    pub channels: u16,
+    frames: Vec<[f32; 2]>,
+}
+
+impl SampleData {
+    pub fn new(sample_rate_hz: u32, channels: u16, frames: Vec<[f32; 2]>) -> Self {
+        Self {
+            sample_rate_hz,
+            channels,
+            frames,
+        }
+    }
+
+    pub fn frame_count(&self) -> u32 {
+        self.frames.len().min(u32::MAX as usize) as u32
+    }
+
+    pub fn frame(&self, index: u32) -> [f32; 2] {
+        self.frames
+            .get(index as usize)
+            .copied()
+            .unwrap_or([0.0, 0.0])
+    }
+}
+
+pub fn load_wav(path: impl AsRef<Path>) -> Result<SampleData, SampleError> {
+    decode_wav(&fs::read(path)?)
+}
+
+/// Decodes RIFF/WAVE files holding 8/16/24/32-bit PCM or 32/64-bit float, mono or stereo.
+pub fn decode_wav(bytes: &[u8]) -> Result<SampleData, SampleError> {
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err(SampleError::InvalidWav("missing RIFF/WAVE header"));
+    }
+
+    let mut format = None;
+    let mut data = None;
+    let mut offset = 12usize;
+    while offset + 8 <= bytes.len() {
+        let id = &bytes[offset..offset + 4];
+        let size = read_u32(bytes, offset + 4) as usize;
+        let body_start = offset + 8;
+        let body_end = body_start.saturating_add(size).min(bytes.len());
+        let body = &bytes[body_start..body_end];
+        match id {
+            b"fmt " => format = Some(WavFormat::parse(body)?),
+            b"data" => data = Some(body),
+            _ => {}
+        }
+        // Chunks are padded to an even length.
+        offset = body_start.saturating_add(size).saturating_add(size & 1);
+    }
+
+    let format = format.ok_or(SampleError::InvalidWav("missing fmt chunk"))?;
+    let data = data.ok_or(SampleError::InvalidWav("missing data chunk"))?;
+    format.decode(data)
+}
+
+#[derive(Clone, Copy, Debug)]
+struct WavFormat {
+    format_tag: u16,
+    channels: u16,
+    sample_rate_hz: u32,
+    bits_per_sample: u16,
+}
+
+impl WavFormat {
+    fn parse(body: &[u8]) -> Result<Self, SampleError> {
+        if body.len() < 16 {
+            return Err(SampleError::InvalidWav("short fmt chunk"));
+        }
+        let mut format_tag = read_u16(body, 0);
+        if format_tag == WAVE_FORMAT_EXTENSIBLE && body.len() >= 26 {
+            // The sub-format GUID starts with the plain format tag.
+            format_tag = read_u16(body, 24);
+        }
+        Ok(Self {
+            format_tag,
+            channels: read_u16(body, 2),
+            sample_rate_hz: read_u32(body, 4),
+            bits_per_sample: read_u16(body, 14),
+        })
+    }
+
+    fn decode(self, data: &[u8]) -> Result<SampleData, SampleError> {
+        let unsupported = SampleError::UnsupportedFormat {
+            format_tag: self.format_tag,
+            bits_per_sample: self.bits_per_sample,
+            channels: self.channels,
+        };
+        let decode_one: fn(&[u8]) -> f32 = match (self.format_tag, self.bits_per_sample) {
+            (WAVE_FORMAT_PCM, 8) => |b| (b[0] as f32 - 128.0) / 128.0,
+            (WAVE_FORMAT_PCM, 16) => |b| i16::from_le_bytes([b[0], b[1]]) as f32 / 32_768.0,
+            (WAVE_FORMAT_PCM, 24) => {
+                |b| (i32::from_le_bytes([0, b[0], b[1], b[2]]) >> 8) as f32 / 8_388_608.0
+            }
+            (WAVE_FORMAT_PCM, 32) => {
+                |b| i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f32 / 2_147_483_648.0
+            }
+            (WAVE_FORMAT_IEEE_FLOAT, 32) => |b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+            (WAVE_FORMAT_IEEE_FLOAT, 64) => |b| {
+                f64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]) as f32
+            },
+            _ => return Err(unsupported),
+        };
+        if !(1..=2).contains(&self.channels) {
+            return Err(unsupported);
+        }
+        if self.sample_rate_hz == 0 {
+            return Err(SampleError::InvalidWav("zero sample rate"));
+        }
+
+        let sample_bytes = self.bits_per_sample as usize / 8;
+        let frame_bytes = sample_bytes * self.channels as usize;
+        let frames = data
+            .chunks_exact(frame_bytes)
+            .map(|frame| {
+                let left = decode_one(&frame[..sample_bytes]).clamp(-1.0, 1.0);
+                let right = if self.channels == 2 {
+                    decode_one(&frame[sample_bytes..]).clamp(-1.0, 1.0)
+                } else {
+                    left
+                };
+                [left, right]
+            })
+            .collect();
+
+        Ok(SampleData::new(self.sample_rate_hz, self.channels, frames))
+    }
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        bytes[offset],
+        bytes[offset + 1],
+        bytes[offset + 2],
+        bytes[offset + 3],
+    ])
+}
+
+#[derive(Clone, Debug)]
+pub struct LoadedSample {
+    pub data: Arc<SampleData>,
+    pub params: SampleParams,
+}
+
+/// Decoded samples of a project's Sampler instruments, keyed by instrument.
+#[derive(Clone, Debug, Default)]
+pub struct SampleBank {
+    samples: HashMap<InstrumentId, LoadedSample>,
+    failed: Vec<InstrumentId>,
+    /// Sources that failed to load, so an unchanged source is not read again.
+    failed_params: HashMap<InstrumentId, SampleParams>,
+    base_dir: Option<PathBuf>,
+}
+
+impl SampleBank {
+    /// Loads every Sampler instrument's file. Relative paths resolve against `base_dir`, the
+    /// project file's directory, or the working directory for a project without a file.
+    pub fn load_project(project: &ProjectData, base_dir: Option<&Path>) -> Self {
+        let mut bank = Self::default();
+        bank.sync_project(project, base_dir);
+        bank
+    }
+
+    /// Brings the bank in line with `project` after edits or a project load, and returns
+    /// whether it changed. Instruments whose sample changed pick up the new parameters, files
+    /// are decoded once however many instruments share them, and a file already decoded or
+    /// already failed for the same source is not read again.
+    pub fn sync_project(&mut self, project: &ProjectData, base_dir: Option<&Path>) -> bool {
+        let mut changed = false;
+        if self.base_dir.as_deref() != base_dir {
+            changed = !self.samples.is_empty() || !self.failed.is_empty();
+            *self = Self {
+                base_dir: base_dir.map(Path::to_path_buf),
+                ..Self::default()
+            };
+        }
+
+        let mut decoded: HashMap<&str, Arc<SampleData>> = self
+            .samples
+            .values()
+            .map(|loaded| (loaded.params.path.as_str(), Arc::clone(&loaded.data)))
+            .collect();
+        let mut samples = HashMap::new();
+        let mut failed_params = HashMap::new();
+        let mut instrument_ids: Vec<_> = project.instruments.keys().copied().collect();
+        instrument_ids.sort_unstable();
+
+        for instrument_id in instrument_ids {
+            let instrument = &project.instruments[&instrument_id];
+            let Some(params) = instrument.sample.as_ref() else {
+                continue;
+            };
+            if instrument.instrument_type != InstrumentType::Sampler {
+                continue;
+            }
+            if self.failed_params.get(&instrument_id) == Some(params) {
+                failed_params.insert(instrument_id, params.clone());
+                continue;
+            }
+
+            let data = match decoded.get(params.path.as_str()) {
+                Some(data) => Arc::clone(data),
+                None => match load_wav(resolve_sample_path(&params.path, base_dir)) {
+                    Ok(data) => {
+                        let data = Arc::new(data);
+                        decoded.insert(params.path.as_str(), Arc::clone(&data));
+                        data
+                    }
+                    Err(_) => {
+                        failed_params.insert(instrument_id, params.clone());
+                        continue;
+                    }
+                },
+            };
+            samples.insert(
+                instrument_id,
+                LoadedSample {
+                    data,
+                    params: params.clone(),
+                },
+            );
+        }
+
+        changed |= samples.len() != self.samples.len()
+            || samples.iter().any(|(instrument_id, loaded)| {
+                self.samples.get(instrument_id).is_none_or(|old| {
+                    old.params != loaded.params || !Arc::ptr_eq(&old.data, &loaded.data)
+                })
+            })
+            || failed_params != self.failed_params;
+
+        self.samples = samples;
+        self.failed = failed_params.keys().copied().collect();
+        self.failed.sort_unstable();
+        self.failed_params = failed_params;
+        changed
+    }
+
+    pub fn insert(
+        &mut self,
+        instrument_id: InstrumentId,
+        params: SampleParams,
+        data: Arc<SampleData>,
+    ) {
+        self.samples.insert(instrument_id, LoadedSample { data, params });
+    }
+
+    pub fn get(&self, instrument_id: InstrumentId) -> Option<&LoadedSample> {
+        self.samples.get(&instrument_id)
+    }
+
+    /// Instruments whose file could not be read or decoded.
+    pub fn failed(&self) -> &[InstrumentId] {
+        &self.failed
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+}
+
+fn resolve_sample_path(path: &str, base_dir: Option<&Path>) -> PathBuf {
+    match base_dir {
+        Some(dir) if Path::new(path).is_relative() => dir.join(path),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Play and loop bounds clamped to the decoded file, in frames.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct SampleSpan {
+    start: u32,
+    end: u32,
+    loop_start: u32,
+    loop_end: u32,
+    loop_mode: SampleLoopMode,
+}
+
+impl SampleSpan {
+    fn resolve(params: &SampleParams, frame_count: u32) -> Self {
+        let end = params.end.unwrap_or(frame_count).min(frame_count);
+        let start = params.start.min(end);
+        let loop_end = params.loop_end.unwrap_or(end).clamp(start, end);
+        let loop_start = params.loop_start.clamp(start, loop_end);
+        let loop_mode = if loop_end > loop_start {
+            params.loop_mode
+        } else {
+            SampleLoopMode::Off
+        };
+        Self {
+            start,
+            end,
+            loop_start,
+            loop_end,
+            loop_mode,
+        }
+    }
+
+    /// Narrows the span to one slice, which loops as a whole when the sample loops.
+    fn slice(self, slicing: &SampleSlicing, index: usize) -> Option<Self> {
+        let (start, end) = match slicing {
+            SampleSlicing::Equal(count) => {
+                let count = *count as u64;
+                if index as u64 >= count {
+                    return None;
+                }
+                let length = (self.end - self.start) as u64;
+                let offset = |slice: u64| self.start + (length * slice / count) as u32;
+                (offset(index as u64), offset(index as u64 + 1))
+            }
+            SampleSlicing::Points(points) => {
+                let mut bounds = vec![self.start];
+                bounds.extend(
+                    points
+                        .iter()
+                        .copied()
+                        .filter(|point| *point > self.start && *point < self.end),
+                );
+                bounds.push(self.end);
+                bounds.sort_unstable();
+                (*bounds.get(index)?, *bounds.get(index + 1)?)
+            }
+        };
+        let loop_mode = if end > start {
+            self.loop_mode
+        } else {
+            SampleLoopMode::Off
+        };
+        Some(Self {
+            start,
+            end,
+            loop_start: start,
+            loop_end: end,
+            loop_mode,
+        })
+    }
+}
+
+/// Read position of one sounding note. It owns no audio, so realtime voices can advance it
+/// per block and the offline renderer per frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SamplePlayhead {
+    span: SampleSpan,
+    position: u64,
+    increment: u64,
+    reverse: bool,
+    finished: bool,
+}
+
+impl SamplePlayhead {
+    /// Pitches the file by the distance of `note` from its root note, converting between the
+    /// file and output sample rates. Sliced samples play the note's slice at the recorded
+    /// pitch instead; notes without a slice give a playhead that has already finished.
+    pub fn new(sample: &LoadedSample, note: u8, output_rate_hz: f32) -> Self {
+        let mut span = SampleSpan::resolve(&sample.params, sample.data.frame_count());
+        let rate_ratio = sample.data.sample_rate_hz as f64 / output_rate_hz.max(1.0) as f64;
+        let step = match &sample.params.slicing {
+            Some(slicing) => {
+                let slice = note
+                    .checked_sub(sample.params.root_note)
+                    .and_then(|index| span.slice(slicing, index as usize));
+                span = slice.unwrap_or(SampleSpan {
+                    end: span.start,
+                    ..span
+                });
+                rate_ratio
+            }
+            None => {
+                let semitones = note as f64 - sample.params.root_note as f64;
+                2f64.powf(semitones / 12.0) * rate_ratio
+            }
+        };
+        Self {
+            span,
+            position: (span.start as u64) << FRACTION_BITS,
+            increment: (step * FRACTION_ONE) as u64,
+            reverse: false,
+            finished: span.end <= span.start,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    pub fn frame_position(&self) -> u32 {
+        (self.position >> FRACTION_BITS) as u32
+    }
+
+    /// Moves on by `output_frames`, which may be fractional when the note is pitch-bent.
+    pub fn advance(&mut self, output_frames: f64) {
+        if self.finished {
+            return;
+        }
+        let delta = (self.increment as f64 * output_frames.max(0.0)) as u64;
+        self.position = if self.reverse {
+            self.position.saturating_sub(delta)
+        } else {
+            self.position.saturating_add(delta)
+        };
+
+        let span = self.span;
+        let loop_start = (span.loop_start as u64) << FRACTION_BITS;
+        let loop_end = (span.loop_end as u64) << FRACTION_BITS;
+        match span.loop_mode {
+            SampleLoopMode::Off => {
+                self.finished = self.position >= (span.end as u64) << FRACTION_BITS;
+            }
+            SampleLoopMode::Forward => {
+                if self.position >= loop_end {
+                    let overshoot = (self.position - loop_end) % (loop_end - loop_start);
+                    self.position = loop_start + overshoot;
+                }
+            }
+            SampleLoopMode::PingPong => loop {
+                if !self.reverse && self.position >= loop_end {
+                    self.position = (2 * loop_end).saturating_sub(self.position);
+                    self.reverse = true;
+                } else if self.reverse && self.position < loop_start {
+                    self.position = 2 * loop_start - self.position;
+                    self.reverse = false;
+                } else {
+                    break;
+                }
+            },
+        }
+    }
+
+    /// Linear interpolation at the current position, folded to mono.
+    pub fn read(&self, data: &SampleData) -> f32 {
+        if self.finished {
+            return 0.0;
+        }
+        let last = self.span.end.saturating_sub(1);
+        let index = self.frame_position().min(last);
+        let next = index.saturating_add(1).min(last);
+        let fraction = (self.position & ((1u64 << FRACTION_BITS) - 1)) as f32 / FRACTION_ONE as f32;
+        let mono = |frame: [f32; 2]| (frame[0] + frame[1]) * 0.5;
+        let current = mono(data.frame(index));
+        current + (mono(data.frame(next)) - current) * fraction
+    }
+}
+
+/// Finds note onsets in the play region and returns them as cut points for
+/// `SampleSlicing::Points`. `sensitivity` 0..=127 trades missed hits for false ones.
+pub fn detect_slice_points(data: &SampleData, params: &SampleParams, sensitivity: u8) -> Vec<u32> {
+    let span = SampleSpan::resolve(params, data.frame_count());
+    let window = (data.sample_rate_hz / ONSET_WINDOWS_PER_SECOND).max(1);
+    let min_gap = (data.sample_rate_hz as u64 * ONSET_MIN_GAP_MS as u64 / 1_000) as u32;
+    let ratio = 4.0 - 2.5 * sensitivity.min(127) as f32 / 127.0;
+
+    let energies: Vec<(u32, f32)> = (span.start..span.end)
+        .step_by(window as usize)
+        .map(|window_start| {
+            let window_end = window_start.saturating_add(window).min(span.end);
+            let sum: f32 = (window_start..window_end)
+                .map(|index| {
+                    let [left, right] = data.frame(index);
+                    let mono = (left + right) * 0.5;
+                    mono * mono
+                })
+                .sum();
+            (window_start, sum / (window_end - window_start) as f32)
+        })
+        .collect();
+    // Ignore rises in the noise floor, 40 dB below the loudest window.
+    let floor = energies.iter().map(|(_, energy)| *energy).fold(0.0, f32::max) * 1e-4;
+
+    let mut points = Vec::new();
+    let mut last_onset = span.start;
+    for (index, &(position, energy)) in energies.iter().enumerate().skip(1) {
+        let history = &energies[index.saturating_sub(ONSET_HISTORY_WINDOWS)..index];
+        let average =
+            history.iter().map(|(_, energy)| *energy).sum::<f32>() / history.len() as f32;
+        if energy > floor
+            && energy > average * ratio
+            && position - last_onset >= min_gap
+            && points.len() < MAX_SAMPLE_SLICES - 1
+        {
+            points.push(position);
+            last_onset = position;
+        }
+    }
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        decode_wav, detect_slice_points, LoadedSample, SampleBank, SampleData, SampleError,
+        SamplePlayhead,
+    };
+    use p9_core::model::{
+        Instrument, InstrumentType, ProjectData, SampleLoopMode, SampleParams, SampleSlicing,
+    };
+    use std::sync::Arc;
+
+    fn wav_bytes(format_tag: u16, channels: u16, bits: u16, data: &[u8]) -> Vec<u8> {
+        let block_align = channels * bits / 8;
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(36 + data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(b"WAVE");
+        bytes.extend_from_slice(b"fmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        bytes.extend_from_slice(&format_tag.to_le_bytes());
+        bytes.extend_from_slice(&channels.to_le_bytes());
+        bytes.extend_from_slice(&44_100u32.to_le_bytes());
+        bytes.extend_from_slice(&(44_100 * block_align as u32).to_le_bytes());
+        bytes.extend_from_slice(&block_align.to_le_bytes());
+        bytes.extend_from_slice(&bits.to_le_bytes());
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    fn ramp(frames: u32) -> Arc<SampleData> {
+        let frames = (0..frames).map(|i| [i as f32 / 1000.0; 2]).collect();
+        Arc::new(SampleData::new(48_000, 1, frames))
+    }
+
+    fn loaded(params: SampleParams, data: Arc<SampleData>) -> LoadedSample {
+        LoadedSample { data, params }
+    }
+
+    #[test]
+    fn decodes_pcm_and_float_formats() {
+        let pcm8 = decode_wav(&wav_bytes(1, 1, 8, &[0, 128, 255])).unwrap();
+        assert_eq!(pcm8.frame_count(), 3);
+        assert_eq!(pcm8.frame(0), [-1.0, -1.0]);
+        assert_eq!(pcm8.frame(1), [0.0, 0.0]);
+
+        let pcm16 = decode_wav(&wav_bytes(1, 2, 16, &[0x00, 0x40, 0x00, 0xC0])).unwrap();
+        assert_eq!(pcm16.channels, 2);
+        assert_eq!(pcm16.frame(0), [0.5, -0.5]);
+
+        let pcm24 = decode_wav(&wav_bytes(1, 1, 24, &[0x00, 0x00, 0xC0])).unwrap();
+        assert_eq!(pcm24.frame(0), [-0.5, -0.5]);
+
+        let float = decode_wav(&wav_bytes(3, 1, 32, &0.25f32.to_le_bytes())).unwrap();
+        assert_eq!(float.frame(0), [0.25, 0.25]);
+        assert_eq!(float.sample_rate_hz, 44_100);
+    }
+
+    #[test]
+    fn rejects_malformed_and_unsupported_files() {
+        assert!(matches!(
+            decode_wav(b"RIFX0000WAVE"),
+            Err(SampleError::InvalidWav(_))
+        ));
+        assert!(matches!(
+            decode_wav(&wav_bytes(1, 3, 16, &[0; 6])),
+            Err(SampleError::UnsupportedFormat { channels: 3, .. })
+        ));
+        assert!(matches!(
+            decode_wav(&wav_bytes(2, 1, 4, &[0; 2])),
+            Err(SampleError::UnsupportedFormat { format_tag: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn playhead_pitches_from_the_root_note() {
+        let sample = loaded(SampleParams::new("ramp.wav"), ramp(1_000));
+
+        let mut root = SamplePlayhead::new(&sample, 60, 48_000.0);
+        root.advance(100.0);
+        assert_eq!(root.frame_position(), 100);
+
+        let mut octave_up = SamplePlayhead::new(&sample, 72, 48_000.0);
+        octave_up.advance(100.0);
+        assert_eq!(octave_up.frame_position(), 200);
+
+        // An octave down at twice the file rate moves a quarter frame per output frame.
+        let mut slow = SamplePlayhead::new(&sample, 48, 96_000.0);
+        slow.advance(2.0);
+        assert_eq!(slow.frame_position(), 0);
+        assert!((slow.read(&sample.data) - 0.0005).abs() < 1e-6);
+    }
+
+    #[test]
+    fn playhead_honours_start_end_and_loop_modes() {
+        let mut params = SampleParams::new("ramp.wav");
+        params.start = 100;
+        params.end = Some(300);
+        let one_shot = loaded(params.clone(), ramp(1_000));
+        let mut playhead = SamplePlayhead::new(&one_shot, 60, 48_000.0);
+        assert_eq!(playhead.frame_position(), 100);
+        playhead.advance(199.0);
+        assert!(!playhead.is_finished());
+        playhead.advance(1.0);
+        assert!(playhead.is_finished());
+        assert_eq!(playhead.read(&one_shot.data), 0.0);
+
+        params.loop_start = 200;
+        params.loop_end = Some(250);
+        params.loop_mode = SampleLoopMode::Forward;
+        let forward = loaded(params.clone(), ramp(1_000));
+        let mut playhead = SamplePlayhead::new(&forward, 60, 48_000.0);
+        playhead.advance(160.0);
+        assert_eq!(playhead.frame_position(), 210);
+        assert!(!playhead.is_finished());
+
+        params.loop_mode = SampleLoopMode::PingPong;
+        let ping_pong = loaded(params, ramp(1_000));
+        let mut playhead = SamplePlayhead::new(&ping_pong, 60, 48_000.0);
+        playhead.advance(160.0);
+        assert_eq!(playhead.frame_position(), 240);
+        playhead.advance(60.0);
+        assert_eq!(playhead.frame_position(), 220);
+    }
+
+    #[test]
+    fn sliced_samples_map_notes_to_slices_at_the_recorded_pitch() {
+        let mut params = SampleParams::new("break.wav");
+        params.root_note = 36;
+        params.start = 200;
+        params.slicing = Some(SampleSlicing::Equal(4));
+        let equal = loaded(params.clone(), ramp(1_000));
+
+        let mut third = SamplePlayhead::new(&equal, 38, 48_000.0);
+        assert_eq!(third.frame_position(), 600);
+        third.advance(199.0);
+        assert_eq!(third.frame_position(), 799);
+        third.advance(1.0);
+        assert!(third.is_finished());
+        assert!(SamplePlayhead::new(&equal, 35, 48_000.0).is_finished());
+        assert!(SamplePlayhead::new(&equal, 40, 48_000.0).is_finished());
+
+        params.slicing = Some(SampleSlicing::Points(vec![250, 700]));
+        params.loop_mode = SampleLoopMode::Forward;
+        let points = loaded(params, ramp(1_000));
+        let mut last = SamplePlayhead::new(&points, 38, 48_000.0);
+        assert_eq!(last.frame_position(), 700);
+        last.advance(350.0);
+        assert!(!last.is_finished());
+        assert_eq!(last.frame_position(), 750);
+        assert!(SamplePlayhead::new(&points, 39, 48_000.0).is_finished());
+    }
+
+    #[test]
+    fn transient_detection_finds_drum_hits() {
+        // Decaying noise bursts every 150 ms over silence, like a sparse break.
+        let hits = [0u32, 7_200, 14_400, 21_600];
+        let frames = (0..28_800u32)
+            .map(|index| {
+                let since_hit = hits.iter().rev().find(|hit| **hit <= index).map(|hit| index - hit);
+                let noise = ((index.wrapping_mul(2_654_435_761) >> 16) as f32 / 32_768.0) - 1.0;
+                let level = since_hit.map_or(0.0, |frames| (-(frames as f32) / 1_200.0).exp());
+                [noise * level; 2]
+            })
+            .collect();
+        let data = SampleData::new(48_000, 1, frames);
+        let params = SampleParams::new("break.wav");
+
+        let points = detect_slice_points(&data, &params, 64);
+        assert_eq!(points.len(), 3);
+        for (point, hit) in points.iter().zip(&hits[1..]) {
+            assert!(point.abs_diff(*hit) <= 240, "{point} is not near {hit}");
+        }
+
+        let mut late = params.clone();
+        late.start = 10_000;
+        assert_eq!(detect_slice_points(&data, &late, 64).len(), 2);
+        let silence = SampleData::new(48_000, 1, vec![[0.0; 2]; 4_800]);
+        assert!(detect_slice_points(&silence, &params, 127).is_empty());
+    }
+
+    #[test]
+    fn bank_records_instruments_whose_files_fail_to_load() {
+        let mut project = ProjectData::new("bank");
+        let mut sampler = Instrument::new(3, InstrumentType::Sampler, "Missing");
+        sampler.sample = Some(SampleParams::new("/nonexistent/p9_missing.wav"));
+        project.instruments.insert(3, sampler);
+        let mut synth = Instrument::new(4, InstrumentType::Synth, "Ignored");
+        synth.sample = Some(SampleParams::new("/nonexistent/p9_missing.wav"));
+        project.instruments.insert(4, synth);
+
+        let bank = SampleBank::load_project(&project, None);
+        assert!(bank.is_empty());
+        assert_eq!(bank.failed(), &[3]);
+    }
+
+    #[test]
+    fn bank_sync_follows_sample_edits_relative_to_the_project_dir() {
+        let dir = std::env::temp_dir().join(format!("p9_bank_sync_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("short.wav"), wav_bytes(1, 1, 16, &[0; 8])).unwrap();
+        std::fs::write(dir.join("long.wav"), wav_bytes(1, 1, 16, &[0; 32])).unwrap();
+
+        let mut project = ProjectData::new("bank");
+        let mut sampler = Instrument::new(1, InstrumentType::Sampler, "Kit");
+        sampler.sample = Some(SampleParams::new("short.wav"));
+        project.instruments.insert(1, sampler);
+
+        // Relative paths are looked up next to the project file, not in the working directory.
+        assert!(SampleBank::load_project(&project, None).get(1).is_none());
+        let mut bank = SampleBank::load_project(&project, Some(&dir));
+        assert_eq!(bank.get(1).unwrap().data.frame_count(), 4);
+        assert!(!bank.sync_project(&project, Some(&dir)));
+
+        let sample = project.instruments.get_mut(&1).unwrap().sample.as_mut().unwrap();
+        sample.root_note = 48;
+        assert!(bank.sync_project(&project, Some(&dir)));
+        assert_eq!(bank.get(1).unwrap().params.root_note, 48);
+
+        let sample = project.instruments.get_mut(&1).unwrap().sample.as_mut().unwrap();
+        sample.path = String::from("long.wav");
+        assert!(bank.sync_project(&project, Some(&dir)));
+        assert_eq!(bank.get(1).unwrap().data.frame_count(), 16);
+
+        let sample = project.instruments.get_mut(&1).unwrap().sample.as_mut().unwrap();
+        sample.path = String::from("missing.wav");
+        assert!(bank.sync_project(&project, Some(&dir)));
+        assert!(bank.get(1).is_none());
+        assert_eq!(bank.failed(), &[1]);
+        assert!(!bank.sync_project(&project, Some(&dir)));
+
+        project.instruments.get_mut(&1).unwrap().sample = None;
+        assert!(bank.sync_project(&project, Some(&dir)));
+        assert!(bank.is_empty());
+        assert!(bank.failed().is_empty());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
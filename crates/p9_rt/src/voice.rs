@@ -1,23 +1,50 @@
-use p9_core::model::{InstrumentId, SynthWaveform};
+use p9_core::model::{
+    FilterParams, FmParams, InstrumentId, SynthParams, SynthWaveform, PAN_CENTER,
+};
+
+use crate::sample::SamplePlayhead;
 
 const ZERO_ATTACK_THRESHOLD_MS: u16 = 1;
 const SHORT_RELEASE_THRESHOLD_MS: u16 = 2;
 const RELEASE_BLOCK_MS: u16 = 10;
 const MAX_RELEASE_BLOCKS: u16 = 64;
+const VELOCITY_SMOOTHING_STEP: u8 = 16;
+
+/// The instrument's sound for a new voice.
+#[derive(Clone, Copy, Debug)]
+pub struct NoteOnParams {
+    pub instrument_id: Option<InstrumentId>,
+    /// `synth.gain` is the routed gain after track and master levels.
+    pub synth: SynthParams,
+    pub fm: Option<FmParams>,
+    pub pan: u8,
+}
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Voice {
     pub track_id: u8,
     pub note: u8,
     pub velocity: u8,
+    pub target_velocity: u8,
     pub instrument_id: Option<InstrumentId>,
     pub waveform: SynthWaveform,
     pub attack_ms: u16,
+    pub decay_ms: u16,
+    pub sustain: u8,
     pub release_ms: u16,
     pub gain: u8,
+    pub filter: FilterParams,
+    /// Operator settings when the voice plays an FM instrument.
+    pub fm: Option<FmParams>,
+    /// Read position when the voice plays a Sampler instrument's file.
+    pub sample: Option<SamplePlayhead>,
+    pub pan: u8,
+    pub pitch_cents: i16,
     pub started_at: u64,
     pub is_releasing: bool,
     pub release_pending_blocks: u16,
+    /// Rings out over its own decay and ignores note-offs, like a drum hit.
+    pub one_shot: bool,
 }
 
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
@@ -35,6 +62,7 @@ pub struct VoiceLifecycleStats {
     pub steal_releasing_total: u64,
     pub steal_active_total: u64,
     pub polyphony_pressure_total: u64,
+    pub panned_note_on_total: u64,
 }
 
 pub struct VoiceAllocator {
@@ -54,6 +82,7 @@ pub struct VoiceAllocator {
     steal_releasing_total: u64,
     steal_active_total: u64,
     polyphony_pressure_total: u64,
+    panned_note_on_total: u64,
 }
 
 impl VoiceAllocator {
@@ -76,21 +105,22 @@ impl VoiceAllocator {
             steal_releasing_total: 0,
             steal_active_total: 0,
             polyphony_pressure_total: 0,
+            panned_note_on_total: 0,
         }
     }
 
-    pub fn note_on(
-        &mut self,
-        track_id: u8,
-        note: u8,
-        velocity: u8,
-        instrument_id: Option<InstrumentId>,
-        waveform: SynthWaveform,
-        attack_ms: u16,
-        release_ms: u16,
-        gain: u8,
-    ) {
+    pub fn note_on(&mut self, track_id: u8, note: u8, velocity: u8, params: NoteOnParams) {
+        let NoteOnParams {
+            instrument_id,
+            synth,
+            fm,
+            pan,
+        } = params;
+        let attack_ms = synth.attack_ms;
         self.note_on_total = self.note_on_total.saturating_add(1);
+        if pan != PAN_CENTER {
+            self.panned_note_on_total = self.panned_note_on_total.saturating_add(1);
+        }
         if attack_ms <= ZERO_ATTACK_THRESHOLD_MS {
             self.zero_attack_total = self.zero_attack_total.saturating_add(1);
             self.click_risk_total = self.click_risk_total.saturating_add(1);
@@ -101,14 +131,23 @@ impl VoiceAllocator {
             track_id,
             note,
             velocity,
+            target_velocity: velocity,
             instrument_id,
-            waveform,
+            waveform: synth.waveform,
             attack_ms,
-            release_ms,
-            gain,
+            decay_ms: synth.decay_ms,
+            sustain: synth.sustain,
+            release_ms: synth.release_ms,
+            gain: synth.gain,
+            filter: synth.filter,
+            fm,
+            sample: None,
+            pan,
+            pitch_cents: 0,
             started_at: self.activation_counter,
             is_releasing: false,
             release_pending_blocks: 0,
+            one_shot: false,
         };
 
         if let Some(index) = self.find_voice_slot(track_id, note) {
@@ -154,6 +193,10 @@ impl VoiceAllocator {
             return false;
         };
 
+        if voice.one_shot {
+            return true;
+        }
+
         if voice.release_ms <= SHORT_RELEASE_THRESHOLD_MS {
             self.short_release_total = self.short_release_total.saturating_add(1);
             self.click_risk_total = self.click_risk_total.saturating_add(1);
@@ -172,6 +215,146 @@ impl VoiceAllocator {
         true
     }
 
+    /// Turns the voice into a one-shot that frees itself once `decay_ms` has played,
+    /// whatever note-offs arrive meanwhile.
+    pub fn ring_out(&mut self, track_id: u8, note: u8, decay_ms: u16) -> bool {
+        let Some(index) = self.find_voice_slot(track_id, note) else {
+            return false;
+        };
+        let Some(voice) = self.slots[index].as_mut() else {
+            return false;
+        };
+
+        voice.one_shot = true;
+        voice.is_releasing = true;
+        voice.release_pending_blocks = release_blocks_for_ms(decay_ms);
+        true
+    }
+
+    pub fn set_pitch_cents(&mut self, track_id: u8, note: u8, cents: i16) -> bool {
+        let Some(index) = self.find_voice_slot(track_id, note) else {
+            return false;
+        };
+        let Some(voice) = self.slots[index].as_mut() else {
+            return false;
+        };
+
+        voice.pitch_cents = cents;
+        true
+    }
+
+    pub fn set_velocity(&mut self, track_id: u8, note: u8, velocity: u8) -> bool {
+        let Some(index) = self.find_voice_slot(track_id, note) else {
+            return false;
+        };
+        let Some(voice) = self.slots[index].as_mut() else {
+            return false;
+        };
+
+        voice.target_velocity = velocity;
+        true
+    }
+
+    /// Updates every voice on the track; returns how many were changed.
+    pub fn set_track_params(
+        &mut self,
+        track_id: u8,
+        gain: u8,
+        attack_ms: u16,
+        release_ms: u16,
+    ) -> usize {
+        let mut updated = 0usize;
+        for voice in self.slots.iter_mut().flatten() {
+            if voice.track_id == track_id {
+                voice.gain = gain;
+                voice.attack_ms = attack_ms;
+                voice.release_ms = release_ms;
+                updated += 1;
+            }
+        }
+        updated
+    }
+
+    pub fn voice_velocity(&self, track_id: u8, note: u8) -> Option<u8> {
+        let index = self.find_voice_slot(track_id, note)?;
+        self.slots[index].map(|voice| voice.velocity)
+    }
+
+    pub fn voice_pan(&self, track_id: u8, note: u8) -> Option<u8> {
+        let index = self.find_voice_slot(track_id, note)?;
+        self.slots[index].map(|voice| voice.pan)
+    }
+
+    pub fn voice_filter(&self, track_id: u8, note: u8) -> Option<FilterParams> {
+        let index = self.find_voice_slot(track_id, note)?;
+        self.slots[index].map(|voice| voice.filter)
+    }
+
+    pub fn voice_fm(&self, track_id: u8, note: u8) -> Option<FmParams> {
+        let index = self.find_voice_slot(track_id, note)?;
+        self.slots[index].and_then(|voice| voice.fm)
+    }
+
+    pub fn voice_pitch_cents(&self, track_id: u8, note: u8) -> Option<i16> {
+        let index = self.find_voice_slot(track_id, note)?;
+        self.slots[index].map(|voice| voice.pitch_cents)
+    }
+
+    pub fn voice_target_velocity(&self, track_id: u8, note: u8) -> Option<u8> {
+        let index = self.find_voice_slot(track_id, note)?;
+        self.slots[index].map(|voice| voice.target_velocity)
+    }
+
+    pub fn attach_sample(&mut self, track_id: u8, note: u8, playhead: SamplePlayhead) -> bool {
+        let Some(index) = self.find_voice_slot(track_id, note) else {
+            return false;
+        };
+        if let Some(voice) = self.slots[index].as_mut() {
+            voice.sample = Some(playhead);
+        }
+        true
+    }
+
+    pub fn voice_sample(&self, track_id: u8, note: u8) -> Option<SamplePlayhead> {
+        let index = self.find_voice_slot(track_id, note)?;
+        self.slots[index].and_then(|voice| voice.sample)
+    }
+
+    /// Moves sample voices on by one block, pitch bends included, and frees one-shot voices
+    /// that have played past their end point.
+    pub fn advance_sample_playheads(&mut self, frames: u32) {
+        for slot in &mut self.slots {
+            let Some(voice) = slot.as_mut() else {
+                continue;
+            };
+            let Some(playhead) = voice.sample.as_mut() else {
+                continue;
+            };
+
+            let pitch_ratio = 2f64.powf(voice.pitch_cents as f64 / 1_200.0);
+            playhead.advance(frames as f64 * pitch_ratio);
+            if playhead.is_finished() {
+                *slot = None;
+            }
+        }
+    }
+
+    pub fn advance_velocity_smoothing(&mut self) {
+        for voice in self.slots.iter_mut().flatten() {
+            voice.velocity = if voice.velocity < voice.target_velocity {
+                voice
+                    .velocity
+                    .saturating_add(VELOCITY_SMOOTHING_STEP)
+                    .min(voice.target_velocity)
+            } else {
+                voice
+                    .velocity
+                    .saturating_sub(VELOCITY_SMOOTHING_STEP)
+                    .max(voice.target_velocity)
+            };
+        }
+    }
+
     pub fn advance_release_envelopes(&mut self) {
         for slot in &mut self.slots {
             let Some(mut voice) = *slot else {
@@ -228,6 +411,7 @@ impl VoiceAllocator {
             steal_releasing_total: self.steal_releasing_total,
             steal_active_total: self.steal_active_total,
             polyphony_pressure_total: self.polyphony_pressure_total,
+            panned_note_on_total: self.panned_note_on_total,
         }
     }
 
@@ -284,14 +468,33 @@ fn release_blocks_for_ms(release_ms: u16) -> u16 {
 
 #[cfg(test)]
 mod tests {
-    use super::VoiceAllocator;
-    use p9_core::model::SynthWaveform;
+    use super::{NoteOnParams, VoiceAllocator};
+    use p9_core::model::{FilterMode, FilterParams, SynthParams, SynthWaveform};
+
+    fn synth(waveform: SynthWaveform, attack_ms: u16, release_ms: u16, gain: u8) -> SynthParams {
+        SynthParams {
+            waveform,
+            attack_ms,
+            release_ms,
+            gain,
+            ..SynthParams::default()
+        }
+    }
+
+    fn voice(synth: SynthParams, pan: u8) -> NoteOnParams {
+        NoteOnParams {
+            instrument_id: Some(0),
+            synth,
+            fm: None,
+            pan,
+        }
+    }
 
     #[test]
     fn note_off_enters_release_before_voice_is_cleared() {
         let mut allocator = VoiceAllocator::new(4);
 
-        allocator.note_on(0, 60, 100, Some(0), SynthWaveform::Saw, 5, 80, 90);
+        allocator.note_on(0, 60, 100, voice(synth(SynthWaveform::Saw, 5, 80, 90), 0x40));
         assert_eq!(allocator.active_voice_count(), 1);
 
         assert!(allocator.note_off(0, 60));
@@ -311,13 +514,32 @@ mod tests {
         assert_eq!(stats.release_pending_voices, 0);
     }
 
+    #[test]
+    fn one_shot_voices_ignore_note_off_and_free_after_their_decay() {
+        let mut allocator = VoiceAllocator::new(4);
+
+        allocator.note_on(0, 36, 100, voice(synth(SynthWaveform::Sine, 0, 1, 90), 0x40));
+        assert!(allocator.ring_out(0, 36, 30));
+        assert!(!allocator.ring_out(0, 38, 30));
+        assert!(allocator.note_off(0, 36));
+        assert_eq!(allocator.active_voice_count(), 1);
+
+        for _ in 0..2 {
+            allocator.advance_release_envelopes();
+            assert_eq!(allocator.active_voice_count(), 1);
+        }
+        allocator.advance_release_envelopes();
+        assert_eq!(allocator.active_voice_count(), 0);
+        assert_eq!(allocator.lifecycle_stats().short_release_total, 0);
+    }
+
     #[test]
     fn allocator_stays_bounded_and_steals_oldest() {
         let mut allocator = VoiceAllocator::new(2);
 
-        allocator.note_on(0, 60, 100, Some(0), SynthWaveform::Saw, 5, 80, 90);
-        allocator.note_on(0, 62, 100, Some(0), SynthWaveform::Saw, 5, 80, 90);
-        allocator.note_on(0, 64, 100, Some(0), SynthWaveform::Saw, 5, 80, 90);
+        allocator.note_on(0, 60, 100, voice(synth(SynthWaveform::Saw, 5, 80, 90), 0x40));
+        allocator.note_on(0, 62, 100, voice(synth(SynthWaveform::Saw, 5, 80, 90), 0x40));
+        allocator.note_on(0, 64, 100, voice(synth(SynthWaveform::Saw, 5, 80, 90), 0x40));
 
         assert_eq!(allocator.active_voice_count(), 2);
         assert_eq!(allocator.max_voices(), 2);
@@ -334,8 +556,9 @@ mod tests {
     fn retrigger_same_note_reuses_existing_slot() {
         let mut allocator = VoiceAllocator::new(2);
 
-        allocator.note_on(0, 60, 90, Some(0), SynthWaveform::Sine, 1, 20, 80);
-        allocator.note_on(0, 60, 120, Some(0), SynthWaveform::Square, 2, 30, 100);
+        allocator.note_on(0, 60, 90, voice(synth(SynthWaveform::Sine, 1, 20, 80), 0x40));
+        let square = synth(SynthWaveform::Square, 2, 30, 100);
+        allocator.note_on(0, 60, 120, voice(square, 0x40));
 
         assert_eq!(allocator.active_voice_count(), 1);
         assert_eq!(allocator.voices_stolen_total(), 0);
@@ -345,10 +568,10 @@ mod tests {
     fn lifecycle_counters_capture_click_risk_signals() {
         let mut allocator = VoiceAllocator::new(2);
 
-        allocator.note_on(0, 60, 100, Some(0), SynthWaveform::Saw, 0, 80, 90);
-        allocator.note_on(0, 60, 100, Some(0), SynthWaveform::Saw, 5, 80, 90);
-        allocator.note_on(0, 62, 100, Some(0), SynthWaveform::Saw, 5, 80, 90);
-        allocator.note_on(0, 63, 100, Some(0), SynthWaveform::Saw, 5, 1, 90);
+        allocator.note_on(0, 60, 100, voice(synth(SynthWaveform::Saw, 0, 80, 90), 0x40));
+        allocator.note_on(0, 60, 100, voice(synth(SynthWaveform::Saw, 5, 80, 90), 0x40));
+        allocator.note_on(0, 62, 100, voice(synth(SynthWaveform::Saw, 5, 80, 90), 0x40));
+        allocator.note_on(0, 63, 100, voice(synth(SynthWaveform::Saw, 5, 1, 90), 0x40));
 
         assert!(!allocator.note_off(0, 60));
         assert!(allocator.note_off(0, 63));
@@ -371,14 +594,110 @@ mod tests {
         assert_eq!(allocator.voices_stolen_total(), 1);
     }
 
+    #[test]
+    fn pitch_bend_updates_only_matching_voice_and_resets_on_retrigger() {
+        let mut allocator = VoiceAllocator::new(4);
+
+        allocator.note_on(0, 60, 100, voice(synth(SynthWaveform::Saw, 5, 80, 90), 0x40));
+        allocator.note_on(1, 60, 100, voice(synth(SynthWaveform::Saw, 5, 80, 90), 0x40));
+
+        assert!(allocator.set_pitch_cents(0, 60, -250));
+        assert!(!allocator.set_pitch_cents(0, 61, 100));
+        assert_eq!(allocator.voice_pitch_cents(0, 60), Some(-250));
+        assert_eq!(allocator.voice_pitch_cents(1, 60), Some(0));
+
+        allocator.note_on(0, 60, 100, voice(synth(SynthWaveform::Saw, 5, 80, 90), 0x40));
+        assert_eq!(allocator.voice_pitch_cents(0, 60), Some(0));
+    }
+
+    #[test]
+    fn pan_is_kept_per_voice_and_off_centre_notes_are_counted() {
+        let mut allocator = VoiceAllocator::new(4);
+
+        allocator.note_on(0, 60, 100, voice(synth(SynthWaveform::Saw, 5, 80, 90), 0x40));
+        allocator.note_on(1, 60, 100, voice(synth(SynthWaveform::Saw, 5, 80, 90), 0x00));
+        allocator.note_on(2, 60, 100, voice(synth(SynthWaveform::Saw, 5, 80, 90), 0x80));
+
+        assert_eq!(allocator.voice_pan(0, 60), Some(0x40));
+        assert_eq!(allocator.voice_pan(1, 60), Some(0x00));
+        assert_eq!(allocator.voice_pan(2, 60), Some(0x80));
+        assert_eq!(allocator.voice_pan(3, 60), None);
+        assert_eq!(allocator.lifecycle_stats().panned_note_on_total, 2);
+    }
+
+    #[test]
+    fn envelope_and_filter_settings_follow_each_voice() {
+        let mut allocator = VoiceAllocator::new(2);
+        let filter = FilterParams {
+            mode: FilterMode::HighPass,
+            cutoff: 70,
+            resonance: 20,
+            env_amount: 12,
+        };
+        let shaped = SynthParams {
+            decay_ms: 300,
+            sustain: 40,
+            filter,
+            ..synth(SynthWaveform::Saw, 5, 80, 90)
+        };
+
+        allocator.note_on(0, 60, 100, voice(shaped, 0x40));
+        allocator.note_on(0, 62, 100, voice(synth(SynthWaveform::Saw, 5, 80, 90), 0x40));
+
+        assert_eq!(allocator.voice_filter(0, 60), Some(filter));
+        assert_eq!(allocator.voice_filter(0, 62), Some(FilterParams::default()));
+        assert_eq!(allocator.voice_filter(1, 60), None);
+    }
+
+    #[test]
+    fn track_params_reach_every_voice_on_the_track() {
+        let mut allocator = VoiceAllocator::new(4);
+
+        allocator.note_on(0, 60, 100, voice(synth(SynthWaveform::Saw, 5, 80, 90), 0x40));
+        allocator.note_on(0, 64, 100, voice(synth(SynthWaveform::Saw, 5, 80, 90), 0x40));
+        allocator.note_on(1, 60, 100, voice(synth(SynthWaveform::Saw, 5, 80, 90), 0x40));
+        assert_eq!(allocator.set_track_params(0, 50, 5, 2), 2);
+        assert_eq!(allocator.set_track_params(3, 50, 5, 2), 0);
+
+        // The shortened release now cuts the voice instead of deferring it.
+        assert!(allocator.note_off(0, 60));
+        assert!(allocator.note_off(1, 60));
+        let stats = allocator.lifecycle_stats();
+        assert_eq!(stats.short_release_total, 1);
+        assert_eq!(stats.release_deferred_total, 1);
+    }
+
+    #[test]
+    fn velocity_changes_glide_towards_target_per_block() {
+        let mut allocator = VoiceAllocator::new(2);
+
+        allocator.note_on(0, 60, 100, voice(synth(SynthWaveform::Saw, 5, 80, 90), 0x40));
+        assert!(allocator.set_velocity(0, 60, 60));
+        assert!(!allocator.set_velocity(0, 61, 60));
+        assert_eq!(allocator.voice_velocity(0, 60), Some(100));
+        assert_eq!(allocator.voice_target_velocity(0, 60), Some(60));
+
+        allocator.advance_velocity_smoothing();
+        assert_eq!(allocator.voice_velocity(0, 60), Some(84));
+        allocator.advance_velocity_smoothing();
+        allocator.advance_velocity_smoothing();
+        assert_eq!(allocator.voice_velocity(0, 60), Some(60));
+
+        assert!(allocator.set_velocity(0, 60, 127));
+        for _ in 0..8 {
+            allocator.advance_velocity_smoothing();
+        }
+        assert_eq!(allocator.voice_velocity(0, 60), Some(127));
+    }
+
     #[test]
     fn stealing_prefers_releasing_voice_under_polyphony_pressure() {
         let mut allocator = VoiceAllocator::new(2);
 
-        allocator.note_on(0, 60, 100, Some(0), SynthWaveform::Saw, 5, 80, 90);
-        allocator.note_on(0, 62, 100, Some(0), SynthWaveform::Saw, 5, 80, 90);
+        allocator.note_on(0, 60, 100, voice(synth(SynthWaveform::Saw, 5, 80, 90), 0x40));
+        allocator.note_on(0, 62, 100, voice(synth(SynthWaveform::Saw, 5, 80, 90), 0x40));
         assert!(allocator.note_off(0, 60));
-        allocator.note_on(0, 64, 100, Some(0), SynthWaveform::Saw, 5, 80, 90);
+        allocator.note_on(0, 64, 100, voice(synth(SynthWaveform::Saw, 5, 80, 90), 0x40));
 
         assert_eq!(allocator.active_voice_count(), 2);
         assert!(!allocator.note_off(0, 60));
@@ -1,12 +1,17 @@
 use std::collections::HashMap;
 
 use p9_core::model::{
-    Chain, FxCommand, Groove, Instrument, InstrumentType, ProjectData, SamplerRenderVariant,
-    Scale, SynthWaveform, Table, CHAIN_ROW_COUNT, PHRASE_STEP_COUNT, SONG_ROW_COUNT, TRACK_COUNT,
+    Chain, DrumSynthParams, FilterMode, FxCommand, Groove, Instrument, InstrumentType,
+    ProjectData, SampleLoopMode, SampleParams, SampleSlicing, SamplerRenderVariant, Scale,
+    SynthWaveform, Table, CHAIN_ROW_COUNT, DELAY_MAX_STEPS, DELAY_MIN_STEPS, DRUM_FREQ_MAX_HZ,
+    DRUM_FREQ_MIN_HZ, FM_MIN_OPERATORS, FM_OPERATOR_SLOTS, MAX_PHRASE_STEP_COUNT,
+    MAX_SAMPLE_SLICES, MAX_SONG_ROW_COUNT, MAX_TRACK_COUNT, PAN_MAX, PHRASE_STEP_COUNT,
+    PULSE_WIDTH_MAX, PULSE_WIDTH_MIN, SONG_ROW_COUNT, SWING_MAX, SWING_STRAIGHT, TRACK_COUNT,
 };
 
-pub const FORMAT_VERSION: u16 = 2;
+pub const FORMAT_VERSION: u16 = 3;
 const FORMAT_VERSION_V1: u16 = 1;
+const FORMAT_VERSION_V2: u16 = 2;
 const FX_SLOT_COUNT: usize = 3;
 
 #[derive(Clone, Debug)]
@@ -49,16 +54,41 @@ struct InstrumentPatch {
     name: Option<String>,
     table_id: Option<Option<u8>>,
     note_length_steps: Option<u8>,
+    pan: Option<u8>,
     send_mfx: Option<u8>,
     send_delay: Option<u8>,
     send_reverb: Option<u8>,
     synth_waveform: Option<SynthWaveform>,
     synth_attack_ms: Option<u16>,
+    synth_decay_ms: Option<u16>,
+    synth_sustain: Option<u8>,
     synth_release_ms: Option<u16>,
     synth_gain: Option<u8>,
+    synth_filter_mode: Option<FilterMode>,
+    synth_filter_cutoff: Option<u8>,
+    synth_filter_resonance: Option<u8>,
+    synth_filter_env_amount: Option<i8>,
     sampler_variant: Option<SamplerRenderVariant>,
     sampler_transient_level: Option<u8>,
     sampler_body_level: Option<u8>,
+    fm_operator_count: Option<u8>,
+    fm_operator_values: Vec<(usize, FmOperatorField, u16)>,
+    sample_path: Option<String>,
+    sample_root_note: Option<u8>,
+    sample_start: Option<u32>,
+    sample_end: Option<Option<u32>>,
+    sample_loop_start: Option<u32>,
+    sample_loop_end: Option<Option<u32>>,
+    sample_loop_mode: Option<SampleLoopMode>,
+    sample_slicing: Option<Option<SampleSlicing>>,
+    drum_values: Vec<(DrumField, u16)>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct TablePatch {
+    speed: Option<u8>,
+    groove_id: Option<Option<u8>>,
+    loop_start: Option<u8>,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -71,10 +101,18 @@ struct TableRowPatch {
 #[derive(Clone, Debug, Default)]
 struct MixerPatch {
     track_levels: HashMap<usize, u8>,
+    track_pans: HashMap<usize, u8>,
     master_level: Option<u8>,
     send_mfx: Option<u8>,
     send_delay: Option<u8>,
     send_reverb: Option<u8>,
+    delay_time_steps: Option<u8>,
+    delay_feedback: Option<u8>,
+    delay_filter: Option<u8>,
+    delay_ping_pong: Option<bool>,
+    reverb_size: Option<u8>,
+    reverb_damping: Option<u8>,
+    reverb_width: Option<u8>,
 }
 
 impl ProjectEnvelope {
@@ -106,6 +144,13 @@ impl ProjectEnvelope {
             "song.default_scale={}",
             self.project.song.default_scale
         ));
+        lines.push(format!("song.seed={}", self.project.song.seed));
+        lines.push(format!("song.swing={}", self.project.song.swing));
+        lines.push(format!(
+            "song.track_count={}",
+            self.project.song.track_count()
+        ));
+        lines.push(format!("song.length={}", self.project.song.length()));
 
         for (track_idx, track) in self.project.song.tracks.iter().enumerate() {
             lines.push(format!(
@@ -128,6 +173,11 @@ impl ProjectEnvelope {
                 track_idx,
                 render_opt_u8(track.scale_override)
             ));
+            lines.push(format!(
+                "track.{}.swing_override={}",
+                track_idx,
+                render_opt_u8(track.swing_override)
+            ));
 
             for (row, slot) in track.song_rows.iter().enumerate() {
                 if let Some(chain_id) = slot {
@@ -164,6 +214,9 @@ impl ProjectEnvelope {
         phrase_ids.sort_unstable();
         for phrase_id in phrase_ids {
             if let Some(phrase) = self.project.phrases.get(&phrase_id) {
+                if phrase.length() != PHRASE_STEP_COUNT {
+                    lines.push(format!("phrase.{}.length={}", phrase_id, phrase.length()));
+                }
                 for step_idx in 0..phrase.steps.len() {
                     let step = &phrase.steps[step_idx];
 
@@ -224,6 +277,7 @@ impl ProjectEnvelope {
                     "instrument.{}.note_length_steps={}",
                     instrument_id, instrument.note_length_steps
                 ));
+                lines.push(format!("instrument.{}.pan={}", instrument_id, instrument.pan));
                 lines.push(format!(
                     "instrument.{}.send.mfx={}",
                     instrument_id, instrument.send_levels.mfx
@@ -245,6 +299,14 @@ impl ProjectEnvelope {
                     "instrument.{}.synth.attack_ms={}",
                     instrument_id, instrument.synth_params.attack_ms
                 ));
+                lines.push(format!(
+                    "instrument.{}.synth.decay_ms={}",
+                    instrument_id, instrument.synth_params.decay_ms
+                ));
+                lines.push(format!(
+                    "instrument.{}.synth.sustain={}",
+                    instrument_id, instrument.synth_params.sustain
+                ));
                 lines.push(format!(
                     "instrument.{}.synth.release_ms={}",
                     instrument_id, instrument.synth_params.release_ms
@@ -253,6 +315,24 @@ impl ProjectEnvelope {
                     "instrument.{}.synth.gain={}",
                     instrument_id, instrument.synth_params.gain
                 ));
+                let filter = instrument.synth_params.filter;
+                lines.push(format!(
+                    "instrument.{}.synth.filter_mode={}",
+                    instrument_id,
+                    render_filter_mode(filter.mode)
+                ));
+                lines.push(format!(
+                    "instrument.{}.synth.filter_cutoff={}",
+                    instrument_id, filter.cutoff
+                ));
+                lines.push(format!(
+                    "instrument.{}.synth.filter_resonance={}",
+                    instrument_id, filter.resonance
+                ));
+                lines.push(format!(
+                    "instrument.{}.synth.filter_env_amount={}",
+                    instrument_id, filter.env_amount
+                ));
                 if let Some(sampler_render) = instrument.sampler_render {
                     lines.push(format!(
                         "instrument.{}.sampler.variant={}",
@@ -268,6 +348,49 @@ impl ProjectEnvelope {
                         instrument_id, sampler_render.body_level
                     ));
                 }
+                if let Some(fm) = instrument.fm_params {
+                    lines.push(format!(
+                        "instrument.{}.fm.operator_count={}",
+                        instrument_id, fm.operator_count
+                    ));
+                    for (index, operator) in fm.operators.iter().enumerate() {
+                        let prefix = format!("instrument.{}.fm.op.{}", instrument_id, index);
+                        lines.push(format!("{}.ratio={}", prefix, operator.ratio));
+                        lines.push(format!("{}.level={}", prefix, operator.level));
+                        lines.push(format!("{}.feedback={}", prefix, operator.feedback));
+                        lines.push(format!("{}.attack_ms={}", prefix, operator.attack_ms));
+                        lines.push(format!("{}.decay_ms={}", prefix, operator.decay_ms));
+                        lines.push(format!("{}.sustain={}", prefix, operator.sustain));
+                        lines.push(format!("{}.release_ms={}", prefix, operator.release_ms));
+                    }
+                }
+                if let Some(drum) = instrument.drum_params {
+                    let prefix = format!("instrument.{}.drum", instrument_id);
+                    for field in DrumField::ALL {
+                        lines.push(format!("{}.{}={}", prefix, field.key(), field.get(&drum)));
+                    }
+                }
+                if let Some(sample) = &instrument.sample {
+                    let prefix = format!("instrument.{}.sample", instrument_id);
+                    lines.push(format!("{}.path={}", prefix, sample.path));
+                    lines.push(format!("{}.root_note={}", prefix, sample.root_note));
+                    lines.push(format!("{}.start={}", prefix, sample.start));
+                    lines.push(format!("{}.end={}", prefix, render_opt_u32(sample.end)));
+                    lines.push(format!("{}.loop_start={}", prefix, sample.loop_start));
+                    lines.push(format!(
+                        "{}.loop_end={}",
+                        prefix,
+                        render_opt_u32(sample.loop_end)
+                    ));
+                    lines.push(format!(
+                        "{}.loop_mode={}",
+                        prefix,
+                        render_sample_loop_mode(sample.loop_mode)
+                    ));
+                    if let Some(slicing) = &sample.slicing {
+                        lines.push(format!("{}.slices={}", prefix, render_sample_slicing(slicing)));
+                    }
+                }
             }
         }
 
@@ -275,6 +398,19 @@ impl ProjectEnvelope {
         table_ids.sort_unstable();
         for table_id in table_ids {
             if let Some(table) = self.project.tables.get(&table_id) {
+                if table.speed != 0 {
+                    lines.push(format!("table.{}.speed={}", table_id, table.speed));
+                }
+                if table.groove_id.is_some() {
+                    lines.push(format!(
+                        "table.{}.groove={}",
+                        table_id,
+                        render_opt_u8(table.groove_id)
+                    ));
+                }
+                if table.loop_start != 0 {
+                    lines.push(format!("table.{}.loop_start={}", table_id, table.loop_start));
+                }
                 for row_idx in 0..table.rows.len() {
                     let row = &table.rows[row_idx];
                     if row.note_offset != 0 {
@@ -327,11 +463,15 @@ impl ProjectEnvelope {
             }
         }
 
-        for track_idx in 0..TRACK_COUNT {
+        for track_idx in 0..self.project.mixer.track_levels.len() {
             lines.push(format!(
                 "mixer.track.{}.level={}",
                 track_idx, self.project.mixer.track_levels[track_idx]
             ));
+            lines.push(format!(
+                "mixer.track.{}.pan={}",
+                track_idx, self.project.mixer.track_pans[track_idx]
+            ));
         }
         lines.push(format!("mixer.master.level={}", self.project.mixer.master_level));
         lines.push(format!(
@@ -346,6 +486,18 @@ impl ProjectEnvelope {
             "mixer.send.reverb={}",
             self.project.mixer.send_levels.reverb
         ));
+        let delay = &self.project.mixer.delay;
+        lines.push(format!("mixer.delay.time_steps={}", delay.time_steps));
+        lines.push(format!("mixer.delay.feedback={}", delay.feedback));
+        lines.push(format!("mixer.delay.filter={}", delay.filter));
+        lines.push(format!(
+            "mixer.delay.ping_pong={}",
+            if delay.ping_pong { 1 } else { 0 }
+        ));
+        let reverb = &self.project.mixer.reverb;
+        lines.push(format!("mixer.reverb.size={}", reverb.size));
+        lines.push(format!("mixer.reverb.damping={}", reverb.damping));
+        lines.push(format!("mixer.reverb.width={}", reverb.width));
 
         lines.join("\n") + "\n"
     }
@@ -356,18 +508,25 @@ impl ProjectEnvelope {
         let mut tempo = None;
         let mut default_groove = None;
         let mut default_scale = None;
+        let mut seed = None;
+        let mut swing = None;
+        let mut track_count = None;
+        let mut song_length = None;
 
         let mut track_mute: HashMap<usize, bool> = HashMap::new();
         let mut track_solo: HashMap<usize, bool> = HashMap::new();
         let mut track_groove_override: HashMap<usize, Option<u8>> = HashMap::new();
         let mut track_scale_override: HashMap<usize, Option<u8>> = HashMap::new();
+        let mut track_swing_override: HashMap<usize, Option<u8>> = HashMap::new();
         let mut song_rows: HashMap<(usize, usize), u8> = HashMap::new();
 
         let mut chain_patches: HashMap<(u8, usize), ChainRowPatch> = HashMap::new();
+        let mut phrase_lengths: HashMap<u8, usize> = HashMap::new();
         let mut phrase_patches: HashMap<(u8, usize), StepPatch> = HashMap::new();
         let mut groove_map: HashMap<u8, Vec<u8>> = HashMap::new();
         let mut scale_patches: HashMap<u8, ScalePatch> = HashMap::new();
         let mut instrument_patches: HashMap<u8, InstrumentPatch> = HashMap::new();
+        let mut table_patches: HashMap<u8, TablePatch> = HashMap::new();
         let mut table_row_patches: HashMap<(u8, usize), TableRowPatch> = HashMap::new();
         let mut mixer_patch = MixerPatch::default();
 
@@ -404,6 +563,22 @@ impl ProjectEnvelope {
                     default_scale = Some(parse_u8(value, "song.default_scale")?);
                     continue;
                 }
+                "song.seed" => {
+                    seed = Some(parse_u32(value, "song.seed")?);
+                    continue;
+                }
+                "song.swing" => {
+                    swing = Some(parse_u8(value, "song.swing")?);
+                    continue;
+                }
+                "song.track_count" => {
+                    track_count = Some(parse_u16(value, "song.track_count")? as usize);
+                    continue;
+                }
+                "song.length" => {
+                    song_length = Some(parse_u16(value, "song.length")? as usize);
+                    continue;
+                }
                 _ => {}
             }
 
@@ -427,6 +602,12 @@ impl ProjectEnvelope {
                             parse_opt_u8(value, "track.scale_override")?,
                         );
                     }
+                    TrackField::SwingOverride => {
+                        track_swing_override.insert(
+                            track_idx,
+                            parse_opt_u8(value, "track.swing_override")?,
+                        );
+                    }
                     TrackField::SongRowChain(row) => {
                         let chain_id = parse_u8(value, "track.row.chain")?;
                         song_rows.insert((track_idx, row), chain_id);
@@ -448,6 +629,12 @@ impl ProjectEnvelope {
                 continue;
             }
 
+            if let Some(phrase_id) = parse_phrase_length_key(key)? {
+                let length = parse_u8(value, "phrase.length")? as usize;
+                phrase_lengths.insert(phrase_id, length);
+                continue;
+            }
+
             if let Some((phrase_id, step, field)) = parse_phrase_field(key)? {
                 let patch = phrase_patches.entry((phrase_id, step)).or_default();
                 match field {
@@ -485,6 +672,9 @@ impl ProjectEnvelope {
                     InstrumentField::NoteLengthSteps => {
                         patch.note_length_steps = Some(parse_u8(value, "instrument.note_length_steps")?);
                     }
+                    InstrumentField::Pan => {
+                        patch.pan = Some(parse_u8(value, "instrument.pan")?);
+                    }
                     InstrumentField::SendMfx => {
                         patch.send_mfx = Some(parse_u8(value, "instrument.send.mfx")?);
                     }
@@ -500,12 +690,33 @@ impl ProjectEnvelope {
                     InstrumentField::SynthAttackMs => {
                         patch.synth_attack_ms = Some(parse_u16(value, "instrument.synth.attack_ms")?);
                     }
+                    InstrumentField::SynthDecayMs => {
+                        patch.synth_decay_ms = Some(parse_u16(value, "instrument.synth.decay_ms")?);
+                    }
+                    InstrumentField::SynthSustain => {
+                        patch.synth_sustain = Some(parse_u8(value, "instrument.synth.sustain")?);
+                    }
                     InstrumentField::SynthReleaseMs => {
                         patch.synth_release_ms = Some(parse_u16(value, "instrument.synth.release_ms")?);
                     }
                     InstrumentField::SynthGain => {
                         patch.synth_gain = Some(parse_u8(value, "instrument.synth.gain")?);
                     }
+                    InstrumentField::SynthFilterMode => {
+                        patch.synth_filter_mode = Some(parse_filter_mode(value)?);
+                    }
+                    InstrumentField::SynthFilterCutoff => {
+                        patch.synth_filter_cutoff =
+                            Some(parse_u8(value, "instrument.synth.filter_cutoff")?);
+                    }
+                    InstrumentField::SynthFilterResonance => {
+                        patch.synth_filter_resonance =
+                            Some(parse_u8(value, "instrument.synth.filter_resonance")?);
+                    }
+                    InstrumentField::SynthFilterEnvAmount => {
+                        patch.synth_filter_env_amount =
+                            Some(parse_i8(value, "instrument.synth.filter_env_amount")?);
+                    }
                     InstrumentField::SamplerVariant => {
                         patch.sampler_variant = Some(parse_sampler_variant(value)?);
                     }
@@ -517,6 +728,68 @@ impl ProjectEnvelope {
                         patch.sampler_body_level =
                             Some(parse_u8(value, "instrument.sampler.body_level")?);
                     }
+                    InstrumentField::FmOperatorCount => {
+                        patch.fm_operator_count =
+                            Some(parse_u8(value, "instrument.fm.operator_count")?);
+                    }
+                    InstrumentField::FmOperator(index, field) => {
+                        let value = match field {
+                            FmOperatorField::AttackMs
+                            | FmOperatorField::DecayMs
+                            | FmOperatorField::ReleaseMs => parse_u16(value, "instrument.fm.op")?,
+                            _ => parse_u8(value, "instrument.fm.op")? as u16,
+                        };
+                        patch.fm_operator_values.push((index, field, value));
+                    }
+                    InstrumentField::SamplePath => {
+                        patch.sample_path = Some(value.to_string());
+                    }
+                    InstrumentField::SampleRootNote => {
+                        patch.sample_root_note =
+                            Some(parse_u8(value, "instrument.sample.root_note")?);
+                    }
+                    InstrumentField::SampleStart => {
+                        patch.sample_start = Some(parse_u32(value, "instrument.sample.start")?);
+                    }
+                    InstrumentField::SampleEnd => {
+                        patch.sample_end = Some(parse_opt_u32(value, "instrument.sample.end")?);
+                    }
+                    InstrumentField::SampleLoopStart => {
+                        patch.sample_loop_start =
+                            Some(parse_u32(value, "instrument.sample.loop_start")?);
+                    }
+                    InstrumentField::SampleLoopEnd => {
+                        patch.sample_loop_end =
+                            Some(parse_opt_u32(value, "instrument.sample.loop_end")?);
+                    }
+                    InstrumentField::SampleLoopMode => {
+                        patch.sample_loop_mode = Some(parse_sample_loop_mode(value)?);
+                    }
+                    InstrumentField::Drum(field) => {
+                        let value = if field.is_level() {
+                            parse_u8(value, "instrument.drum")? as u16
+                        } else {
+                            parse_u16(value, "instrument.drum")?
+                        };
+                        patch.drum_values.push((field, value));
+                    }
+                    InstrumentField::SampleSlices => {
+                        patch.sample_slicing = Some(parse_sample_slicing(value)?);
+                    }
+                }
+                continue;
+            }
+
+            if let Some((table_id, setting)) = parse_table_setting_key(key)? {
+                let patch = table_patches.entry(table_id).or_default();
+                match setting {
+                    TableSetting::Speed => patch.speed = Some(parse_u8(value, "table.speed")?),
+                    TableSetting::Groove => {
+                        patch.groove_id = Some(parse_opt_u8(value, "table.groove")?)
+                    }
+                    TableSetting::LoopStart => {
+                        patch.loop_start = Some(parse_u8(value, "table.loop_start")?)
+                    }
                 }
                 continue;
             }
@@ -547,6 +820,11 @@ impl ProjectEnvelope {
                             .track_levels
                             .insert(track_idx, parse_u8(value, "mixer.track.level")?);
                     }
+                    MixerField::TrackPan(track_idx) => {
+                        mixer_patch
+                            .track_pans
+                            .insert(track_idx, parse_u8(value, "mixer.track.pan")?);
+                    }
                     MixerField::MasterLevel => {
                         mixer_patch.master_level = Some(parse_u8(value, "mixer.master.level")?);
                     }
@@ -559,6 +837,29 @@ impl ProjectEnvelope {
                     MixerField::SendReverb => {
                         mixer_patch.send_reverb = Some(parse_u8(value, "mixer.send.reverb")?);
                     }
+                    MixerField::DelayTimeSteps => {
+                        mixer_patch.delay_time_steps =
+                            Some(parse_u8(value, "mixer.delay.time_steps")?);
+                    }
+                    MixerField::DelayFeedback => {
+                        mixer_patch.delay_feedback = Some(parse_u8(value, "mixer.delay.feedback")?);
+                    }
+                    MixerField::DelayFilter => {
+                        mixer_patch.delay_filter = Some(parse_u8(value, "mixer.delay.filter")?);
+                    }
+                    MixerField::DelayPingPong => {
+                        mixer_patch.delay_ping_pong =
+                            Some(parse_bool(value, "mixer.delay.ping_pong")?);
+                    }
+                    MixerField::ReverbSize => {
+                        mixer_patch.reverb_size = Some(parse_u8(value, "mixer.reverb.size")?);
+                    }
+                    MixerField::ReverbDamping => {
+                        mixer_patch.reverb_damping = Some(parse_u8(value, "mixer.reverb.damping")?);
+                    }
+                    MixerField::ReverbWidth => {
+                        mixer_patch.reverb_width = Some(parse_u8(value, "mixer.reverb.width")?);
+                    }
                 }
                 continue;
             }
@@ -588,7 +889,15 @@ impl ProjectEnvelope {
 
         let source_format_version =
             source_format_version.ok_or(StorageError::MissingField("format_version"))?;
-        if source_format_version != FORMAT_VERSION && source_format_version != FORMAT_VERSION_V1 {
+        // v2 files carry none of the v3 keys, and each missing part loads its default: seed 0,
+        // step-driven tables, centred pan, 16-step phrases, 8 tracks of 256 rows, straight
+        // swing, full sustain with the filter bypassed, no FM, sample, slicing or drum
+        // settings, and the default delay and reverb. v2 only names the four classic
+        // waveforms, which still parse unchanged.
+        if !matches!(
+            source_format_version,
+            FORMAT_VERSION | FORMAT_VERSION_V2 | FORMAT_VERSION_V1
+        ) {
             return Err(StorageError::UnsupportedFormat(source_format_version));
         }
 
@@ -598,7 +907,18 @@ impl ProjectEnvelope {
             return Err(StorageError::ParseError("tempo must be > 0".to_string()));
         }
 
+        let track_count = track_count.unwrap_or(TRACK_COUNT);
+        if !(1..=MAX_TRACK_COUNT).contains(&track_count) {
+            return Err(StorageError::InvalidIndex("track_count", track_count));
+        }
+        let song_length = song_length.unwrap_or(SONG_ROW_COUNT);
+        if !(1..=MAX_SONG_ROW_COUNT).contains(&song_length) {
+            return Err(StorageError::InvalidIndex("song_length", song_length));
+        }
+
         let mut project = ProjectData::new(song_name);
+        project.set_track_count(track_count);
+        project.song.set_length(song_length);
         project.song.tempo = tempo;
         if let Some(id) = default_groove {
             project.song.default_groove = id;
@@ -606,6 +926,12 @@ impl ProjectEnvelope {
         if let Some(id) = default_scale {
             project.song.default_scale = id;
         }
+        if let Some(seed) = seed {
+            project.song.seed = seed;
+        }
+        if let Some(swing) = swing {
+            project.song.swing = swing.clamp(SWING_STRAIGHT, SWING_MAX);
+        }
 
         for (track_idx, mute) in track_mute {
             let track = project
@@ -643,8 +969,17 @@ impl ProjectEnvelope {
             track.scale_override = override_id;
         }
 
+        for (track_idx, swing) in track_swing_override {
+            let track = project
+                .song
+                .tracks
+                .get_mut(track_idx)
+                .ok_or(StorageError::InvalidIndex("track", track_idx))?;
+            track.swing_override = swing.map(|swing| swing.clamp(SWING_STRAIGHT, SWING_MAX));
+        }
+
         for ((track_idx, row), chain_id) in song_rows {
-            if row >= SONG_ROW_COUNT {
+            if row >= song_length {
                 return Err(StorageError::InvalidIndex("song_row", row));
             }
             let track = project
@@ -673,15 +1008,26 @@ impl ProjectEnvelope {
             }
         }
 
-        for ((phrase_id, step_idx), patch) in phrase_patches {
-            if step_idx >= PHRASE_STEP_COUNT {
-                return Err(StorageError::InvalidIndex("phrase_step", step_idx));
+        for (phrase_id, length) in phrase_lengths {
+            if !(1..=MAX_PHRASE_STEP_COUNT).contains(&length) {
+                return Err(StorageError::InvalidIndex("phrase_length", length));
             }
+            project
+                .phrases
+                .entry(phrase_id)
+                .or_insert_with(|| p9_core::model::Phrase::new(phrase_id))
+                .set_length(length);
+        }
+
+        for ((phrase_id, step_idx), patch) in phrase_patches {
             let phrase = project
                 .phrases
                 .entry(phrase_id)
                 .or_insert_with(|| p9_core::model::Phrase::new(phrase_id));
-            let step = &mut phrase.steps[step_idx];
+            let step = phrase
+                .steps
+                .get_mut(step_idx)
+                .ok_or(StorageError::InvalidIndex("phrase_step", step_idx))?;
 
             if let Some(note) = patch.note {
                 step.note = note;
@@ -725,6 +1071,9 @@ impl ProjectEnvelope {
             if let Some(note_length_steps) = patch.note_length_steps {
                 instrument.note_length_steps = note_length_steps.max(1);
             }
+            if let Some(pan) = patch.pan {
+                instrument.pan = pan.min(PAN_MAX);
+            }
             if let Some(send_mfx) = patch.send_mfx {
                 instrument.send_levels.mfx = send_mfx;
             }
@@ -740,12 +1089,30 @@ impl ProjectEnvelope {
             if let Some(attack_ms) = patch.synth_attack_ms {
                 instrument.synth_params.attack_ms = attack_ms;
             }
+            if let Some(decay_ms) = patch.synth_decay_ms {
+                instrument.synth_params.decay_ms = decay_ms;
+            }
+            if let Some(sustain) = patch.synth_sustain {
+                instrument.synth_params.sustain = sustain.min(127);
+            }
             if let Some(release_ms) = patch.synth_release_ms {
                 instrument.synth_params.release_ms = release_ms;
             }
             if let Some(gain) = patch.synth_gain {
                 instrument.synth_params.gain = gain;
             }
+            if let Some(mode) = patch.synth_filter_mode {
+                instrument.synth_params.filter.mode = mode;
+            }
+            if let Some(cutoff) = patch.synth_filter_cutoff {
+                instrument.synth_params.filter.cutoff = cutoff.min(127);
+            }
+            if let Some(resonance) = patch.synth_filter_resonance {
+                instrument.synth_params.filter.resonance = resonance.min(127);
+            }
+            if let Some(env_amount) = patch.synth_filter_env_amount {
+                instrument.synth_params.filter.env_amount = env_amount;
+            }
             if patch.sampler_variant.is_some()
                 || patch.sampler_transient_level.is_some()
                 || patch.sampler_body_level.is_some()
@@ -762,6 +1129,105 @@ impl ProjectEnvelope {
                 }
                 instrument.sampler_render = Some(sampler_render);
             }
+            if patch.fm_operator_count.is_some() || !patch.fm_operator_values.is_empty() {
+                let mut fm = instrument.fm_params.unwrap_or_default();
+                if let Some(count) = patch.fm_operator_count {
+                    fm.operator_count = count.clamp(FM_MIN_OPERATORS, FM_OPERATOR_SLOTS as u8);
+                }
+                for (index, field, value) in patch.fm_operator_values {
+                    let operator = &mut fm.operators[index];
+                    let level = value.min(127) as u8;
+                    match field {
+                        FmOperatorField::Ratio => operator.ratio = value.min(u8::MAX as u16) as u8,
+                        FmOperatorField::Level => operator.level = level,
+                        FmOperatorField::Feedback => operator.feedback = level,
+                        FmOperatorField::AttackMs => operator.attack_ms = value,
+                        FmOperatorField::DecayMs => operator.decay_ms = value,
+                        FmOperatorField::Sustain => operator.sustain = level,
+                        FmOperatorField::ReleaseMs => operator.release_ms = value,
+                    }
+                }
+                instrument.fm_params = Some(fm);
+            }
+            if !patch.drum_values.is_empty() {
+                let mut drum = instrument.drum_params.unwrap_or_default();
+                for (field, value) in patch.drum_values {
+                    field.set(&mut drum, value);
+                }
+                instrument.drum_params = Some(drum);
+            }
+            if let Some(path) = patch.sample_path {
+                let mut sample = instrument
+                    .sample
+                    .take()
+                    .unwrap_or_else(|| SampleParams::new(String::new()));
+                sample.path = path;
+                instrument.sample = Some(sample);
+            }
+            if let Some(sample) = instrument.sample.as_mut() {
+                if let Some(root_note) = patch.sample_root_note {
+                    sample.root_note = root_note.min(127);
+                }
+                if let Some(start) = patch.sample_start {
+                    sample.start = start;
+                }
+                if let Some(end) = patch.sample_end {
+                    sample.end = end;
+                }
+                if let Some(loop_start) = patch.sample_loop_start {
+                    sample.loop_start = loop_start;
+                }
+                if let Some(loop_end) = patch.sample_loop_end {
+                    sample.loop_end = loop_end;
+                }
+                if let Some(loop_mode) = patch.sample_loop_mode {
+                    sample.loop_mode = loop_mode;
+                }
+                if let Some(slicing) = patch.sample_slicing {
+                    sample.slicing = slicing;
+                }
+                // Empty regions would be rejected by the engine; fall back to the file end.
+                if sample.end.is_some_and(|end| end <= sample.start) {
+                    sample.end = None;
+                }
+                if sample.loop_end.is_some_and(|end| end <= sample.loop_start) {
+                    sample.loop_end = None;
+                }
+                match sample.slicing.as_mut() {
+                    Some(SampleSlicing::Equal(count)) => {
+                        *count = (*count).clamp(1, MAX_SAMPLE_SLICES as u8);
+                    }
+                    Some(SampleSlicing::Points(points)) => {
+                        let (start, end) = (sample.start, sample.end);
+                        points.sort_unstable();
+                        points.dedup();
+                        points.retain(|point| {
+                            *point > start && end.is_none_or(|end| *point < end)
+                        });
+                        points.truncate(MAX_SAMPLE_SLICES - 1);
+                    }
+                    None => {}
+                }
+            }
+        }
+
+        for (table_id, patch) in table_patches {
+            let table = project
+                .tables
+                .entry(table_id)
+                .or_insert_with(|| Table::new(table_id));
+            if let Some(speed) = patch.speed {
+                table.speed = speed;
+            }
+            if let Some(groove_id) = patch.groove_id {
+                table.groove_id = groove_id;
+            }
+            if let Some(loop_start) = patch.loop_start {
+                if loop_start as usize >= table.rows.len() {
+                    return Err(StorageError::InvalidIndex("table_loop_start", loop_start as usize));
+                }
+                table.loop_start = loop_start;
+            }
         }
 
         for ((table_id, row_idx), patch) in table_row_patches {
@@ -816,12 +1282,19 @@ impl ProjectEnvelope {
         }
 
         for (track_idx, level) in mixer_patch.track_levels {
-            if track_idx >= TRACK_COUNT {
+            if track_idx >= track_count {
                 return Err(StorageError::InvalidIndex("mixer_track", track_idx));
             }
             project.mixer.track_levels[track_idx] = level;
         }
 
+        for (track_idx, pan) in mixer_patch.track_pans {
+            if track_idx >= track_count {
+                return Err(StorageError::InvalidIndex("mixer_track", track_idx));
+            }
+            project.mixer.track_pans[track_idx] = pan.min(PAN_MAX);
+        }
+
         if let Some(master_level) = mixer_patch.master_level {
             project.mixer.master_level = master_level;
         }
@@ -834,6 +1307,29 @@ impl ProjectEnvelope {
         if let Some(send_reverb) = mixer_patch.send_reverb {
             project.mixer.send_levels.reverb = send_reverb;
         }
+        let delay = &mut project.mixer.delay;
+        if let Some(time_steps) = mixer_patch.delay_time_steps {
+            delay.time_steps = time_steps.clamp(DELAY_MIN_STEPS, DELAY_MAX_STEPS);
+        }
+        if let Some(feedback) = mixer_patch.delay_feedback {
+            delay.feedback = feedback.min(127);
+        }
+        if let Some(filter) = mixer_patch.delay_filter {
+            delay.filter = filter.min(127);
+        }
+        if let Some(ping_pong) = mixer_patch.delay_ping_pong {
+            delay.ping_pong = ping_pong;
+        }
+        let reverb = &mut project.mixer.reverb;
+        if let Some(size) = mixer_patch.reverb_size {
+            reverb.size = size.min(127);
+        }
+        if let Some(damping) = mixer_patch.reverb_damping {
+            reverb.damping = damping.min(127);
+        }
+        if let Some(width) = mixer_patch.reverb_width {
+            reverb.width = width.min(127);
+        }
 
         Ok(Self {
             format_version: FORMAT_VERSION,
@@ -849,6 +1345,13 @@ fn render_opt_u8(value: Option<u8>) -> String {
     }
 }
 
+fn render_opt_u32(value: Option<u32>) -> String {
+    match value {
+        Some(v) => v.to_string(),
+        None => "none".to_string(),
+    }
+}
+
 fn parse_u8(value: &str, field: &str) -> Result<u8, StorageError> {
     value
         .parse::<u8>()
@@ -861,6 +1364,12 @@ fn parse_u16(value: &str, field: &str) -> Result<u16, StorageError> {
         .map_err(|_| StorageError::ParseError(field.to_string()))
 }
 
+fn parse_u32(value: &str, field: &str) -> Result<u32, StorageError> {
+    value
+        .parse::<u32>()
+        .map_err(|_| StorageError::ParseError(field.to_string()))
+}
+
 fn parse_i8(value: &str, field: &str) -> Result<i8, StorageError> {
     value
         .parse::<i8>()
@@ -883,6 +1392,14 @@ fn parse_opt_u8(value: &str, field: &str) -> Result<Option<u8>, StorageError> {
     }
 }
 
+fn parse_opt_u32(value: &str, field: &str) -> Result<Option<u32>, StorageError> {
+    if value.eq_ignore_ascii_case("none") {
+        Ok(None)
+    } else {
+        parse_u32(value, field).map(Some)
+    }
+}
+
 fn render_fx_command(command: &FxCommand) -> String {
     format!("{}:{}", command.code, command.value)
 }
@@ -910,6 +1427,8 @@ fn render_instrument_type(instrument_type: &InstrumentType) -> &'static str {
         InstrumentType::Sampler => "sampler",
         InstrumentType::MidiOut => "midi_out",
         InstrumentType::External => "external",
+        InstrumentType::FmSynth => "fm_synth",
+        InstrumentType::DrumSynth => "drum_synth",
     }
 }
 
@@ -920,29 +1439,108 @@ fn parse_instrument_type(value: &str) -> Result<InstrumentType, StorageError> {
         "sampler" => Ok(InstrumentType::Sampler),
         "midi_out" | "midiout" => Ok(InstrumentType::MidiOut),
         "external" => Ok(InstrumentType::External),
+        "fm_synth" => Ok(InstrumentType::FmSynth),
+        "drum_synth" => Ok(InstrumentType::DrumSynth),
         _ => Err(StorageError::ParseError("instrument.type".to_string())),
     }
 }
 
-fn render_waveform(waveform: SynthWaveform) -> &'static str {
+fn render_waveform(waveform: SynthWaveform) -> String {
     match waveform {
-        SynthWaveform::Sine => "sine",
-        SynthWaveform::Square => "square",
-        SynthWaveform::Saw => "saw",
-        SynthWaveform::Triangle => "triangle",
+        SynthWaveform::Sine => "sine".to_string(),
+        SynthWaveform::Square => "square".to_string(),
+        SynthWaveform::Saw => "saw".to_string(),
+        SynthWaveform::Triangle => "triangle".to_string(),
+        SynthWaveform::Noise => "noise".to_string(),
+        SynthWaveform::Pulse { width } => format!("pulse:{}", width),
     }
 }
 
 fn parse_waveform(value: &str) -> Result<SynthWaveform, StorageError> {
-    match value.to_ascii_lowercase().as_str() {
+    let value = value.to_ascii_lowercase();
+    if let Some(width) = value.strip_prefix("pulse:") {
+        let width = parse_u8(width, "instrument.synth.waveform")?;
+        return Ok(SynthWaveform::Pulse {
+            width: width.clamp(PULSE_WIDTH_MIN, PULSE_WIDTH_MAX),
+        });
+    }
+
+    match value.as_str() {
         "sine" => Ok(SynthWaveform::Sine),
         "square" => Ok(SynthWaveform::Square),
         "saw" => Ok(SynthWaveform::Saw),
         "triangle" => Ok(SynthWaveform::Triangle),
+        "noise" => Ok(SynthWaveform::Noise),
         _ => Err(StorageError::ParseError("instrument.synth.waveform".to_string())),
     }
 }
 
+fn render_filter_mode(mode: FilterMode) -> &'static str {
+    match mode {
+        FilterMode::LowPass => "lowpass",
+        FilterMode::HighPass => "highpass",
+        FilterMode::BandPass => "bandpass",
+    }
+}
+
+fn parse_filter_mode(value: &str) -> Result<FilterMode, StorageError> {
+    match value {
+        "lowpass" => Ok(FilterMode::LowPass),
+        "highpass" => Ok(FilterMode::HighPass),
+        "bandpass" => Ok(FilterMode::BandPass),
+        _ => Err(StorageError::ParseError("instrument.synth.filter_mode".to_string())),
+    }
+}
+
+fn render_sample_loop_mode(mode: SampleLoopMode) -> &'static str {
+    match mode {
+        SampleLoopMode::Off => "off",
+        SampleLoopMode::Forward => "forward",
+        SampleLoopMode::PingPong => "pingpong",
+    }
+}
+
+fn parse_sample_loop_mode(value: &str) -> Result<SampleLoopMode, StorageError> {
+    match value {
+        "off" => Ok(SampleLoopMode::Off),
+        "forward" => Ok(SampleLoopMode::Forward),
+        "pingpong" => Ok(SampleLoopMode::PingPong),
+        _ => Err(StorageError::ParseError("instrument.sample.loop_mode".to_string())),
+    }
+}
+
+fn render_sample_slicing(slicing: &SampleSlicing) -> String {
+    match slicing {
+        SampleSlicing::Equal(count) => format!("equal:{}", count),
+        SampleSlicing::Points(points) => {
+            let points: Vec<String> = points.iter().map(u32::to_string).collect();
+            format!("points:{}", points.join(","))
+        }
+    }
+}
+
+fn parse_sample_slicing(value: &str) -> Result<Option<SampleSlicing>, StorageError> {
+    if value.eq_ignore_ascii_case("none") {
+        return Ok(None);
+    }
+    let field = "instrument.sample.slices";
+    let Some((kind, rest)) = value.split_once(':') else {
+        return Err(StorageError::ParseError(field.to_string()));
+    };
+    match kind {
+        "equal" => Ok(Some(SampleSlicing::Equal(parse_u8(rest, field)?))),
+        "points" if rest.is_empty() => Ok(Some(SampleSlicing::Points(Vec::new()))),
+        "points" => {
+            let points = rest
+                .split(',')
+                .map(|point| parse_u32(point, field))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(Some(SampleSlicing::Points(points)))
+        }
+        _ => Err(StorageError::ParseError(field.to_string())),
+    }
+}
+
 fn render_sampler_variant(variant: SamplerRenderVariant) -> &'static str {
     match variant {
         SamplerRenderVariant::Classic => "classic",
@@ -967,6 +1565,7 @@ enum TrackField {
     Solo,
     GrooveOverride,
     ScaleOverride,
+    SwingOverride,
     SongRowChain(usize),
 }
 
@@ -984,7 +1583,7 @@ fn parse_track_field(key: &str) -> Result<Option<(usize, TrackField)>, StorageEr
         .parse::<usize>()
         .map_err(|_| StorageError::ParseError("track.index".to_string()))?;
 
-    if track_idx >= TRACK_COUNT {
+    if track_idx >= MAX_TRACK_COUNT {
         return Err(StorageError::InvalidIndex("track", track_idx));
     }
 
@@ -994,6 +1593,7 @@ fn parse_track_field(key: &str) -> Result<Option<(usize, TrackField)>, StorageEr
             "solo" => Ok(Some((track_idx, TrackField::Solo))),
             "groove_override" => Ok(Some((track_idx, TrackField::GrooveOverride))),
             "scale_override" => Ok(Some((track_idx, TrackField::ScaleOverride))),
+            "swing_override" => Ok(Some((track_idx, TrackField::SwingOverride))),
             _ => Ok(None),
         };
     }
@@ -1002,7 +1602,7 @@ fn parse_track_field(key: &str) -> Result<Option<(usize, TrackField)>, StorageEr
         let row = parts[3]
             .parse::<usize>()
             .map_err(|_| StorageError::ParseError("track.row".to_string()))?;
-        if row >= SONG_ROW_COUNT {
+        if row >= MAX_SONG_ROW_COUNT {
             return Err(StorageError::InvalidIndex("song_row", row));
         }
         return Ok(Some((track_idx, TrackField::SongRowChain(row))));
@@ -1051,6 +1651,20 @@ enum PhraseField {
     Fx(usize),
 }
 
+fn parse_phrase_length_key(key: &str) -> Result<Option<u8>, StorageError> {
+    if !key.starts_with("phrase.") {
+        return Ok(None);
+    }
+
+    let parts: Vec<&str> = key.split('.').collect();
+    if parts.len() != 3 || parts[2] != "length" {
+        return Ok(None);
+    }
+
+    let phrase_id = parse_u8(parts[1], "phrase.id")?;
+    Ok(Some(phrase_id))
+}
+
 fn parse_phrase_field(key: &str) -> Result<Option<(u8, usize, PhraseField)>, StorageError> {
     if !key.starts_with("phrase.") {
         return Ok(None);
@@ -1066,7 +1680,7 @@ fn parse_phrase_field(key: &str) -> Result<Option<(u8, usize, PhraseField)>, Sto
         .parse::<usize>()
         .map_err(|_| StorageError::ParseError("phrase.step".to_string()))?;
 
-    if step >= PHRASE_STEP_COUNT {
+    if step >= MAX_PHRASE_STEP_COUNT {
         return Err(StorageError::InvalidIndex("phrase_step", step));
     }
 
@@ -1109,16 +1723,149 @@ enum InstrumentField {
     Name,
     Table,
     NoteLengthSteps,
+    Pan,
     SendMfx,
     SendDelay,
     SendReverb,
     SynthWaveform,
     SynthAttackMs,
+    SynthDecayMs,
+    SynthSustain,
     SynthReleaseMs,
     SynthGain,
+    SynthFilterMode,
+    SynthFilterCutoff,
+    SynthFilterResonance,
+    SynthFilterEnvAmount,
     SamplerVariant,
     SamplerTransientLevel,
     SamplerBodyLevel,
+    FmOperatorCount,
+    FmOperator(usize, FmOperatorField),
+    SamplePath,
+    SampleRootNote,
+    SampleStart,
+    SampleEnd,
+    SampleLoopStart,
+    SampleLoopEnd,
+    SampleLoopMode,
+    SampleSlices,
+    Drum(DrumField),
+}
+
+#[derive(Clone, Copy, Debug)]
+enum DrumField {
+    KickStartHz,
+    KickEndHz,
+    KickSweepMs,
+    KickDecayMs,
+    KickLevel,
+    SnareToneHz,
+    SnareToneDecayMs,
+    SnareNoiseDecayMs,
+    SnareToneMix,
+    SnareLevel,
+    HatCutoff,
+    HatDecayMs,
+    HatLevel,
+}
+
+impl DrumField {
+    const ALL: [Self; 13] = [
+        Self::KickStartHz,
+        Self::KickEndHz,
+        Self::KickSweepMs,
+        Self::KickDecayMs,
+        Self::KickLevel,
+        Self::SnareToneHz,
+        Self::SnareToneDecayMs,
+        Self::SnareNoiseDecayMs,
+        Self::SnareToneMix,
+        Self::SnareLevel,
+        Self::HatCutoff,
+        Self::HatDecayMs,
+        Self::HatLevel,
+    ];
+
+    fn key(self) -> &'static str {
+        match self {
+            Self::KickStartHz => "kick.start_hz",
+            Self::KickEndHz => "kick.end_hz",
+            Self::KickSweepMs => "kick.sweep_ms",
+            Self::KickDecayMs => "kick.decay_ms",
+            Self::KickLevel => "kick.level",
+            Self::SnareToneHz => "snare.tone_hz",
+            Self::SnareToneDecayMs => "snare.tone_decay_ms",
+            Self::SnareNoiseDecayMs => "snare.noise_decay_ms",
+            Self::SnareToneMix => "snare.tone_mix",
+            Self::SnareLevel => "snare.level",
+            Self::HatCutoff => "hat.cutoff",
+            Self::HatDecayMs => "hat.decay_ms",
+            Self::HatLevel => "hat.level",
+        }
+    }
+
+    /// Levels, the tone mix and the cutoff are 0..=127 values stored as u8.
+    fn is_level(self) -> bool {
+        matches!(
+            self,
+            Self::KickLevel
+                | Self::SnareToneMix
+                | Self::SnareLevel
+                | Self::HatCutoff
+                | Self::HatLevel
+        )
+    }
+
+    fn get(self, drum: &DrumSynthParams) -> u16 {
+        match self {
+            Self::KickStartHz => drum.kick.start_hz,
+            Self::KickEndHz => drum.kick.end_hz,
+            Self::KickSweepMs => drum.kick.sweep_ms,
+            Self::KickDecayMs => drum.kick.decay_ms,
+            Self::KickLevel => drum.kick.level as u16,
+            Self::SnareToneHz => drum.snare.tone_hz,
+            Self::SnareToneDecayMs => drum.snare.tone_decay_ms,
+            Self::SnareNoiseDecayMs => drum.snare.noise_decay_ms,
+            Self::SnareToneMix => drum.snare.tone_mix as u16,
+            Self::SnareLevel => drum.snare.level as u16,
+            Self::HatCutoff => drum.hat.cutoff as u16,
+            Self::HatDecayMs => drum.hat.decay_ms,
+            Self::HatLevel => drum.hat.level as u16,
+        }
+    }
+
+    /// Stores a loaded value, clamping frequencies and 0..=127 values into range.
+    fn set(self, drum: &mut DrumSynthParams, value: u16) {
+        let hz = value.clamp(DRUM_FREQ_MIN_HZ, DRUM_FREQ_MAX_HZ);
+        let level = value.min(127) as u8;
+        match self {
+            Self::KickStartHz => drum.kick.start_hz = hz,
+            Self::KickEndHz => drum.kick.end_hz = hz,
+            Self::KickSweepMs => drum.kick.sweep_ms = value,
+            Self::KickDecayMs => drum.kick.decay_ms = value,
+            Self::KickLevel => drum.kick.level = level,
+            Self::SnareToneHz => drum.snare.tone_hz = hz,
+            Self::SnareToneDecayMs => drum.snare.tone_decay_ms = value,
+            Self::SnareNoiseDecayMs => drum.snare.noise_decay_ms = value,
+            Self::SnareToneMix => drum.snare.tone_mix = level,
+            Self::SnareLevel => drum.snare.level = level,
+            Self::HatCutoff => drum.hat.cutoff = level,
+            Self::HatDecayMs => drum.hat.decay_ms = value,
+            Self::HatLevel => drum.hat.level = level,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum FmOperatorField {
+    Ratio,
+    Level,
+    Feedback,
+    AttackMs,
+    DecayMs,
+    Sustain,
+    ReleaseMs,
 }
 
 fn parse_instrument_field(key: &str) -> Result<Option<(u8, InstrumentField)>, StorageError> {
@@ -1139,6 +1886,7 @@ fn parse_instrument_field(key: &str) -> Result<Option<(u8, InstrumentField)>, St
             "name" => InstrumentField::Name,
             "table" => InstrumentField::Table,
             "note_length_steps" => InstrumentField::NoteLengthSteps,
+            "pan" => InstrumentField::Pan,
             _ => return Ok(None),
         };
         return Ok(Some((instrument_id, field)));
@@ -1158,8 +1906,14 @@ fn parse_instrument_field(key: &str) -> Result<Option<(u8, InstrumentField)>, St
         let field = match parts[3] {
             "waveform" => InstrumentField::SynthWaveform,
             "attack_ms" => InstrumentField::SynthAttackMs,
+            "decay_ms" => InstrumentField::SynthDecayMs,
+            "sustain" => InstrumentField::SynthSustain,
             "release_ms" => InstrumentField::SynthReleaseMs,
             "gain" => InstrumentField::SynthGain,
+            "filter_mode" => InstrumentField::SynthFilterMode,
+            "filter_cutoff" => InstrumentField::SynthFilterCutoff,
+            "filter_resonance" => InstrumentField::SynthFilterResonance,
+            "filter_env_amount" => InstrumentField::SynthFilterEnvAmount,
             _ => return Ok(None),
         };
         return Ok(Some((instrument_id, field)));
@@ -1175,6 +1929,51 @@ fn parse_instrument_field(key: &str) -> Result<Option<(u8, InstrumentField)>, St
         return Ok(Some((instrument_id, field)));
     }
 
+    if parts.len() == 5 && parts[2] == "drum" {
+        let key = format!("{}.{}", parts[3], parts[4]);
+        let Some(field) = DrumField::ALL.into_iter().find(|field| field.key() == key) else {
+            return Ok(None);
+        };
+        return Ok(Some((instrument_id, InstrumentField::Drum(field))));
+    }
+
+    if parts.len() == 4 && parts[2] == "sample" {
+        let field = match parts[3] {
+            "path" => InstrumentField::SamplePath,
+            "root_note" => InstrumentField::SampleRootNote,
+            "start" => InstrumentField::SampleStart,
+            "end" => InstrumentField::SampleEnd,
+            "loop_start" => InstrumentField::SampleLoopStart,
+            "loop_end" => InstrumentField::SampleLoopEnd,
+            "loop_mode" => InstrumentField::SampleLoopMode,
+            "slices" => InstrumentField::SampleSlices,
+            _ => return Ok(None),
+        };
+        return Ok(Some((instrument_id, field)));
+    }
+
+    if parts.len() == 4 && parts[2] == "fm" && parts[3] == "operator_count" {
+        return Ok(Some((instrument_id, InstrumentField::FmOperatorCount)));
+    }
+
+    if parts.len() == 6 && parts[2] == "fm" && parts[3] == "op" {
+        let index = parse_u8(parts[4], "instrument.fm.op")? as usize;
+        if index >= FM_OPERATOR_SLOTS {
+            return Err(StorageError::ParseError("instrument.fm.op".to_string()));
+        }
+        let field = match parts[5] {
+            "ratio" => FmOperatorField::Ratio,
+            "level" => FmOperatorField::Level,
+            "feedback" => FmOperatorField::Feedback,
+            "attack_ms" => FmOperatorField::AttackMs,
+            "decay_ms" => FmOperatorField::DecayMs,
+            "sustain" => FmOperatorField::Sustain,
+            "release_ms" => FmOperatorField::ReleaseMs,
+            _ => return Ok(None),
+        };
+        return Ok(Some((instrument_id, InstrumentField::FmOperator(index, field))));
+    }
+
     Ok(None)
 }
 
@@ -1184,6 +1983,32 @@ enum TableField {
     Fx(usize),
 }
 
+enum TableSetting {
+    Speed,
+    Groove,
+    LoopStart,
+}
+
+fn parse_table_setting_key(key: &str) -> Result<Option<(u8, TableSetting)>, StorageError> {
+    if !key.starts_with("table.") {
+        return Ok(None);
+    }
+
+    let parts: Vec<&str> = key.split('.').collect();
+    if parts.len() != 3 {
+        return Ok(None);
+    }
+
+    let setting = match parts[2] {
+        "speed" => TableSetting::Speed,
+        "groove" => TableSetting::Groove,
+        "loop_start" => TableSetting::LoopStart,
+        _ => return Ok(None),
+    };
+    let table_id = parse_u8(parts[1], "table.id")?;
+    Ok(Some((table_id, setting)))
+}
+
 fn parse_table_field(key: &str) -> Result<Option<(u8, usize, TableField)>, StorageError> {
     if !key.starts_with("table.") {
         return Ok(None);
@@ -1237,10 +2062,18 @@ fn parse_table_field(key: &str) -> Result<Option<(u8, usize, TableField)>, Stora
 
 enum MixerField {
     TrackLevel(usize),
+    TrackPan(usize),
     MasterLevel,
     SendMfx,
     SendDelay,
     SendReverb,
+    DelayTimeSteps,
+    DelayFeedback,
+    DelayFilter,
+    DelayPingPong,
+    ReverbSize,
+    ReverbDamping,
+    ReverbWidth,
 }
 
 fn parse_mixer_field(key: &str) -> Result<Option<MixerField>, StorageError> {
@@ -1250,14 +2083,19 @@ fn parse_mixer_field(key: &str) -> Result<Option<MixerField>, StorageError> {
 
     let parts: Vec<&str> = key.split('.').collect();
 
-    if parts.len() == 4 && parts[1] == "track" && parts[3] == "level" {
+    if parts.len() == 4 && parts[1] == "track" {
+        let field: fn(usize) -> MixerField = match parts[3] {
+            "level" => MixerField::TrackLevel,
+            "pan" => MixerField::TrackPan,
+            _ => return Ok(None),
+        };
         let track_idx = parts[2]
             .parse::<usize>()
             .map_err(|_| StorageError::ParseError("mixer.track.index".to_string()))?;
-        if track_idx >= TRACK_COUNT {
+        if track_idx >= MAX_TRACK_COUNT {
             return Err(StorageError::InvalidIndex("mixer_track", track_idx));
         }
-        return Ok(Some(MixerField::TrackLevel(track_idx)));
+        return Ok(Some(field(track_idx)));
     }
 
     if parts.len() == 3 && parts[1] == "master" && parts[2] == "level" {
@@ -1274,6 +2112,27 @@ fn parse_mixer_field(key: &str) -> Result<Option<MixerField>, StorageError> {
         return Ok(Some(field));
     }
 
+    if parts.len() == 3 && parts[1] == "delay" {
+        let field = match parts[2] {
+            "time_steps" => MixerField::DelayTimeSteps,
+            "feedback" => MixerField::DelayFeedback,
+            "filter" => MixerField::DelayFilter,
+            "ping_pong" => MixerField::DelayPingPong,
+            _ => return Ok(None),
+        };
+        return Ok(Some(field));
+    }
+
+    if parts.len() == 3 && parts[1] == "reverb" {
+        let field = match parts[2] {
+            "size" => MixerField::ReverbSize,
+            "damping" => MixerField::ReverbDamping,
+            "width" => MixerField::ReverbWidth,
+            _ => return Ok(None),
+        };
+        return Ok(Some(field));
+    }
+
     Ok(None)
 }
 
@@ -1320,14 +2179,17 @@ fn parse_scale_field(key: &str) -> Result<Option<(u8, ScaleField)>, StorageError
 mod tests {
     use super::{ProjectEnvelope, StorageError, FORMAT_VERSION};
     use p9_core::model::{
-        Chain, FxCommand, Groove, Instrument, InstrumentType, ProjectData, SamplerRenderParams,
-        SamplerRenderVariant, Scale, SynthWaveform, Table,
+        Chain, DelayParams, DrumSynthParams, FilterMode, FilterParams, FmParams, FxCommand,
+        Groove, Instrument, InstrumentType, Phrase, ProjectData, ReverbParams, SampleLoopMode,
+        SampleParams, SampleSlicing, SamplerRenderParams, SamplerRenderVariant, Scale,
+        SynthWaveform, Table,
     };
 
     #[test]
     fn round_trip_text_preserves_song_basics() {
         let mut project = ProjectData::new("unit");
         project.song.tempo = 133;
+        project.song.seed = 0xDEAD_BEEF;
         let envelope = ProjectEnvelope::new(project);
 
         let text = envelope.to_text();
@@ -1336,6 +2198,240 @@ mod tests {
         assert_eq!(restored.format_version, FORMAT_VERSION);
         assert_eq!(restored.project.song.name, "unit");
         assert_eq!(restored.project.song.tempo, 133);
+        assert_eq!(restored.project.song.seed, 0xDEAD_BEEF);
+    }
+
+    #[test]
+    fn round_trip_preserves_song_and_track_swing() {
+        let mut project = ProjectData::new("swing");
+        project.song.swing = 62;
+        project.song.tracks[2].swing_override = Some(70);
+        let text = ProjectEnvelope::new(project).to_text();
+        assert!(text.contains("song.swing=62"));
+
+        let restored = ProjectEnvelope::from_text(&text).unwrap();
+        assert_eq!(restored.project.song.swing, 62);
+        assert_eq!(restored.project.song.tracks[2].swing_override, Some(70));
+        assert_eq!(restored.project.song.tracks[0].swing_override, None);
+
+        let text = text
+            .replace("song.swing=62", "song.swing=99")
+            .replace("track.0.swing_override=none", "track.0.swing_override=10");
+        let clamped = ProjectEnvelope::from_text(&text).unwrap();
+        assert_eq!(clamped.project.song.swing, 75);
+        assert_eq!(clamped.project.song.tracks[0].swing_override, Some(50));
+    }
+
+    #[test]
+    fn round_trip_preserves_synth_envelope_and_filter() {
+        let mut project = ProjectData::new("filter");
+        let mut instrument = Instrument::new(2, InstrumentType::Synth, "Bass");
+        instrument.synth_params.decay_ms = 340;
+        instrument.synth_params.sustain = 64;
+        instrument.synth_params.filter = FilterParams {
+            mode: FilterMode::BandPass,
+            cutoff: 40,
+            resonance: 90,
+            env_amount: -32,
+        };
+        project.instruments.insert(2, instrument);
+
+        let text = ProjectEnvelope::new(project).to_text();
+        assert!(text.contains("instrument.2.synth.filter_mode=bandpass"));
+        let restored = ProjectEnvelope::from_text(&text).unwrap();
+        let synth = restored.project.instruments.get(&2).unwrap().synth_params;
+        assert_eq!(synth.decay_ms, 340);
+        assert_eq!(synth.sustain, 64);
+        assert_eq!(synth.filter.mode, FilterMode::BandPass);
+        assert_eq!(synth.filter.cutoff, 40);
+        assert_eq!(synth.filter.resonance, 90);
+        assert_eq!(synth.filter.env_amount, -32);
+
+        let legacy = "format_version=2\nsong.name=x\nsong.tempo=120\n\
+                      instrument.1.type=synth\ninstrument.1.name=old\n";
+        let restored = ProjectEnvelope::from_text(legacy).unwrap();
+        assert_eq!(restored.format_version, FORMAT_VERSION);
+        let synth = restored.project.instruments.get(&1).unwrap().synth_params;
+        assert_eq!(synth.sustain, 127);
+        assert!(synth.filter.is_bypassed());
+
+        let invalid = text.replace("filter_mode=bandpass", "filter_mode=notch");
+        assert!(ProjectEnvelope::from_text(&invalid).is_err());
+    }
+
+    #[test]
+    fn round_trip_preserves_fm_operators() {
+        let mut project = ProjectData::new("fm");
+        let mut fm = FmParams {
+            operator_count: 3,
+            ..FmParams::default()
+        };
+        fm.operators[1].ratio = 14;
+        fm.operators[1].feedback = 33;
+        fm.operators[2].release_ms = 900;
+        let mut bell = Instrument::new(5, InstrumentType::FmSynth, "Bell");
+        bell.fm_params = Some(fm);
+        project.instruments.insert(5, bell);
+
+        let text = ProjectEnvelope::new(project).to_text();
+        assert!(text.contains("instrument.5.type=fm_synth"));
+        assert!(text.contains("instrument.5.fm.op.1.ratio=14"));
+        let restored = ProjectEnvelope::from_text(&text).unwrap();
+        let bell = restored.project.instruments.get(&5).unwrap();
+        assert_eq!(bell.instrument_type, InstrumentType::FmSynth);
+        assert_eq!(bell.fm_params, Some(fm));
+
+        let out_of_range = text.replace("fm.op.1.ratio=14", "fm.op.4.ratio=14");
+        assert!(ProjectEnvelope::from_text(&out_of_range).is_err());
+        let clamped = text.replace("fm.operator_count=3", "fm.operator_count=1");
+        let restored = ProjectEnvelope::from_text(&clamped).unwrap();
+        let fm = restored.project.instruments[&5].fm_params.unwrap();
+        assert_eq!(fm.operator_count, 2);
+    }
+
+    #[test]
+    fn round_trip_preserves_drum_synth_params() {
+        let mut project = ProjectData::new("drums");
+        let mut drums = DrumSynthParams::default();
+        drums.kick.sweep_ms = 75;
+        drums.snare.tone_mix = 20;
+        drums.hat.cutoff = 118;
+        let mut kit = Instrument::new(6, InstrumentType::DrumSynth, "Kit");
+        kit.drum_params = Some(drums);
+        project.instruments.insert(6, kit);
+
+        let text = ProjectEnvelope::new(project).to_text();
+        assert!(text.contains("instrument.6.type=drum_synth"));
+        assert!(text.contains("instrument.6.drum.kick.sweep_ms=75"));
+        assert!(text.contains("instrument.6.drum.snare.tone_mix=20"));
+        let restored = ProjectEnvelope::from_text(&text).unwrap();
+        let kit = &restored.project.instruments[&6];
+        assert_eq!(kit.instrument_type, InstrumentType::DrumSynth);
+        assert_eq!(kit.drum_params, Some(drums));
+
+        let clamped = text
+            .replace("kick.start_hz=180", "kick.start_hz=9000")
+            .replace("hat.cutoff=118", "hat.cutoff=200");
+        let restored = ProjectEnvelope::from_text(&clamped).unwrap();
+        let drums = restored.project.instruments[&6].drum_params.unwrap();
+        assert_eq!(drums.kick.start_hz, 5_000);
+        assert_eq!(drums.hat.cutoff, 127);
+    }
+
+    #[test]
+    fn round_trip_preserves_send_effect_params() {
+        let mut project = ProjectData::new("sends");
+        project.mixer.delay.time_steps = 6;
+        project.mixer.delay.feedback = 90;
+        project.mixer.delay.filter = 70;
+        project.mixer.delay.ping_pong = true;
+        project.mixer.reverb.size = 120;
+        project.mixer.reverb.damping = 10;
+        project.mixer.reverb.width = 40;
+        let (delay, reverb) = (project.mixer.delay, project.mixer.reverb);
+
+        let text = ProjectEnvelope::new(project).to_text();
+        assert!(text.contains("mixer.delay.time_steps=6\n"));
+        assert!(text.contains("mixer.delay.ping_pong=1\n"));
+        assert!(text.contains("mixer.reverb.width=40\n"));
+        let restored = ProjectEnvelope::from_text(&text).unwrap();
+        assert_eq!(restored.project.mixer.delay, delay);
+        assert_eq!(restored.project.mixer.reverb, reverb);
+
+        let clamped = text
+            .replace("time_steps=6", "time_steps=0")
+            .replace("reverb.size=120", "reverb.size=255");
+        let restored = ProjectEnvelope::from_text(&clamped).unwrap();
+        assert_eq!(restored.project.mixer.delay.time_steps, 1);
+        assert_eq!(restored.project.mixer.reverb.size, 127);
+    }
+
+    #[test]
+    fn round_trip_preserves_sample_regions() {
+        let mut project = ProjectData::new("samples");
+        let sample = SampleParams {
+            root_note: 48,
+            start: 120,
+            end: Some(44_100),
+            loop_start: 2_000,
+            loop_end: None,
+            loop_mode: SampleLoopMode::PingPong,
+            ..SampleParams::new("samples/amen break.wav")
+        };
+        let mut drums = Instrument::new(2, InstrumentType::Sampler, "Break");
+        drums.sample = Some(sample.clone());
+        project.instruments.insert(2, drums);
+
+        let text = ProjectEnvelope::new(project).to_text();
+        assert!(text.contains("instrument.2.sample.path=samples/amen break.wav"));
+        assert!(text.contains("instrument.2.sample.loop_end=none"));
+        let restored = ProjectEnvelope::from_text(&text).unwrap();
+        assert_eq!(restored.project.instruments[&2].sample, Some(sample));
+
+        let empty = text.replace("sample.end=44100", "sample.end=100");
+        let restored = ProjectEnvelope::from_text(&empty).unwrap();
+        assert_eq!(restored.project.instruments[&2].sample.as_ref().unwrap().end, None);
+        let invalid = text.replace("loop_mode=pingpong", "loop_mode=reverse");
+        assert!(ProjectEnvelope::from_text(&invalid).is_err());
+    }
+
+    #[test]
+    fn round_trip_preserves_sample_slices() {
+        let mut project = ProjectData::new("slices");
+        for (instrument_id, slicing) in [
+            (1, SampleSlicing::Equal(16)),
+            (2, SampleSlicing::Points(vec![1_200, 5_400, 9_000])),
+        ] {
+            let mut sample = SampleParams::new("breaks/think.wav");
+            sample.slicing = Some(slicing);
+            let mut instrument = Instrument::new(instrument_id, InstrumentType::Sampler, "Break");
+            instrument.sample = Some(sample);
+            project.instruments.insert(instrument_id, instrument);
+        }
+
+        let text = ProjectEnvelope::new(project.clone()).to_text();
+        assert!(text.contains("instrument.1.sample.slices=equal:16"));
+        assert!(text.contains("instrument.2.sample.slices=points:1200,5400,9000"));
+        let restored = ProjectEnvelope::from_text(&text).unwrap();
+        for instrument_id in [1, 2] {
+            assert_eq!(
+                restored.project.instruments[&instrument_id].sample,
+                project.instruments[&instrument_id].sample
+            );
+        }
+
+        let unordered = text
+            .replace("equal:16", "equal:0")
+            .replace("points:1200,5400,9000", "points:9000,0,1200,1200");
+        let restored = ProjectEnvelope::from_text(&unordered).unwrap();
+        let slicing = |id: u8| restored.project.instruments[&id].sample.clone().unwrap().slicing;
+        assert_eq!(slicing(1), Some(SampleSlicing::Equal(1)));
+        assert_eq!(slicing(2), Some(SampleSlicing::Points(vec![1_200, 9_000])));
+        let invalid = text.replace("equal:16", "halves:2");
+        assert!(ProjectEnvelope::from_text(&invalid).is_err());
+    }
+
+    #[test]
+    fn round_trip_preserves_noise_and_pulse_waveforms() {
+        let mut project = ProjectData::new("waves");
+        let mut noise = Instrument::new(1, InstrumentType::Synth, "Hiss");
+        noise.synth_params.waveform = SynthWaveform::Noise;
+        project.instruments.insert(1, noise);
+        let mut pulse = Instrument::new(2, InstrumentType::Synth, "Reed");
+        pulse.synth_params.waveform = SynthWaveform::Pulse { width: 24 };
+        project.instruments.insert(2, pulse);
+
+        let text = ProjectEnvelope::new(project).to_text();
+        assert!(text.contains("instrument.2.synth.waveform=pulse:24"));
+        let restored = ProjectEnvelope::from_text(&text).unwrap();
+        let waveform = |id| restored.project.instruments.get(&id).unwrap().synth_params.waveform;
+        assert_eq!(waveform(1), SynthWaveform::Noise);
+        assert_eq!(waveform(2), SynthWaveform::Pulse { width: 24 });
+
+        let text = text.replace("waveform=pulse:24", "waveform=pulse:0");
+        let clamped = ProjectEnvelope::from_text(&text).unwrap();
+        let instrument = clamped.project.instruments.get(&2).unwrap();
+        assert_eq!(instrument.synth_params.waveform, SynthWaveform::Pulse { width: 1 });
     }
 
     #[test]
@@ -1440,6 +2536,7 @@ mod tests {
         let mut instrument = Instrument::new(3, InstrumentType::Synth, "Lead");
         instrument.table_id = Some(7);
         instrument.note_length_steps = 4;
+        instrument.pan = 0x70;
         instrument.send_levels.mfx = 11;
         instrument.send_levels.delay = 22;
         instrument.send_levels.reverb = 33;
@@ -1479,6 +2576,7 @@ mod tests {
         });
 
         project.mixer.track_levels[0] = 98;
+        project.mixer.track_pans[3] = 0x10;
         project.mixer.master_level = 115;
         project.mixer.send_levels.mfx = 4;
         project.mixer.send_levels.delay = 5;
@@ -1491,6 +2589,7 @@ mod tests {
         assert_eq!(instrument.name, "Lead");
         assert_eq!(instrument.table_id, Some(7));
         assert_eq!(instrument.note_length_steps, 4);
+        assert_eq!(instrument.pan, 0x70);
         assert_eq!(instrument.send_levels.mfx, 11);
         assert_eq!(instrument.send_levels.delay, 22);
         assert_eq!(instrument.send_levels.reverb, 33);
@@ -1516,12 +2615,39 @@ mod tests {
         assert_eq!(phrase.steps[0].fx[0].as_ref().unwrap().value, 80);
 
         assert_eq!(restored.project.mixer.track_levels[0], 98);
+        assert_eq!(restored.project.mixer.track_pans[3], 0x10);
+        assert_eq!(restored.project.mixer.track_pans[0], 0x40);
         assert_eq!(restored.project.mixer.master_level, 115);
         assert_eq!(restored.project.mixer.send_levels.mfx, 4);
         assert_eq!(restored.project.mixer.send_levels.delay, 5);
         assert_eq!(restored.project.mixer.send_levels.reverb, 6);
     }
 
+    #[test]
+    fn round_trip_preserves_tick_table_settings() {
+        let mut project = ProjectData::new("tables");
+        let mut table = Table::new(2);
+        table.speed = 3;
+        table.groove_id = Some(4);
+        table.loop_start = 5;
+        project.tables.insert(2, table);
+
+        let text = ProjectEnvelope::new(project).to_text();
+        let restored = ProjectEnvelope::from_text(&text).unwrap();
+
+        let table = restored.project.tables.get(&2).unwrap();
+        assert_eq!(table.speed, 3);
+        assert_eq!(table.groove_id, Some(4));
+        assert_eq!(table.loop_start, 5);
+        assert!(table.runs_per_tick());
+
+        let invalid = "format_version=3\nsong.name=x\nsong.tempo=120\ntable.1.loop_start=16\n";
+        assert!(matches!(
+            ProjectEnvelope::from_text(invalid),
+            Err(StorageError::InvalidIndex("table_loop_start", 16))
+        ));
+    }
+
     #[test]
     fn from_text_migrates_v1_to_v2() {
         let input = "format_version=1\nname=legacy\ntempo=111\n";
@@ -1530,6 +2656,138 @@ mod tests {
         assert_eq!(restored.format_version, FORMAT_VERSION);
         assert_eq!(restored.project.song.name, "legacy");
         assert_eq!(restored.project.song.tempo, 111);
+        assert_eq!(restored.project.song.seed, 0);
+    }
+
+    #[test]
+    fn round_trip_preserves_phrase_length() {
+        let mut project = ProjectData::new("meter");
+        let mut short = Phrase::new(1);
+        short.set_length(5);
+        project.phrases.insert(1, short);
+        let mut long = Phrase::new(2);
+        long.set_length(64);
+        long.steps[63].note = Some(72);
+        project.phrases.insert(2, long);
+
+        let text = ProjectEnvelope::new(project).to_text();
+        assert!(text.contains("phrase.1.length=5\n"));
+        let restored = ProjectEnvelope::from_text(&text).unwrap();
+
+        assert_eq!(restored.project.phrases[&1].length(), 5);
+        assert_eq!(restored.project.phrases[&2].length(), 64);
+        assert_eq!(restored.project.phrases[&2].steps[63].note, Some(72));
+
+        let overflow = "format_version=3\nsong.name=x\nsong.tempo=120\n\
+                        phrase.1.length=4\nphrase.1.step.4.note=60\n";
+        assert!(matches!(
+            ProjectEnvelope::from_text(overflow),
+            Err(StorageError::InvalidIndex("phrase_step", 4))
+        ));
+        let invalid = "format_version=3\nsong.name=x\nsong.tempo=120\nphrase.1.length=65\n";
+        assert!(matches!(
+            ProjectEnvelope::from_text(invalid),
+            Err(StorageError::InvalidIndex("phrase_length", 65))
+        ));
+    }
+
+    #[test]
+    fn from_text_migrates_v2_to_v3_with_defaults() {
+        let input = "format_version=2\nsong.name=v2\nsong.tempo=120\n\
+                     track.7.row.255.chain=3\nphrase.1.step.15.note=60\n\
+                     table.1.row.0.note_offset=12\nmixer.track.1.level=90\n\
+                     mixer.send.delay=40\ninstrument.1.type=synth\n\
+                     instrument.1.synth.waveform=triangle\ninstrument.2.type=sampler\n";
+        let restored = ProjectEnvelope::from_text(input).unwrap();
+        let project = &restored.project;
+
+        assert_eq!(restored.format_version, FORMAT_VERSION);
+        assert_eq!(project.song.seed, 0);
+        assert_eq!(project.song.track_count(), 8);
+        assert_eq!(project.song.length(), 256);
+        assert_eq!(project.song.tracks[7].song_rows[255], Some(3));
+        assert_eq!(project.song.swing, 50);
+        assert!(project.song.tracks.iter().all(|track| track.swing_override.is_none()));
+        assert_eq!(project.phrases[&1].length(), 16);
+        assert_eq!(project.phrases[&1].steps[15].note, Some(60));
+
+        let table = project.tables.get(&1).unwrap();
+        assert_eq!(table.speed, 0);
+        assert_eq!(table.groove_id, None);
+        assert_eq!(table.loop_start, 0);
+        assert!(!table.runs_per_tick());
+
+        assert_eq!(project.mixer.track_levels[1], 90);
+        assert!(project.mixer.track_pans.iter().all(|pan| *pan == 0x40));
+        assert_eq!(project.mixer.send_levels.delay, 40);
+        assert_eq!(project.mixer.delay, DelayParams::default());
+        assert_eq!(project.mixer.reverb, ReverbParams::default());
+
+        let synth = &project.instruments[&1];
+        assert_eq!(synth.pan, 0x40);
+        assert_eq!(synth.synth_params.waveform, SynthWaveform::Triangle);
+        assert_eq!(synth.synth_params.sustain, 127);
+        assert!(synth.synth_params.filter.is_bypassed());
+        assert_eq!(synth.fm_params, None);
+        assert_eq!(synth.drum_params, None);
+        let sampler = &project.instruments[&2];
+        assert_eq!(sampler.instrument_type, InstrumentType::Sampler);
+        assert_eq!(sampler.sample, None);
+
+        let text = restored.to_text();
+        assert!(text.contains(&format!("format_version={}\n", FORMAT_VERSION)));
+        for line in [
+            "song.seed=0\n",
+            "song.swing=50\n",
+            "mixer.track.1.pan=64\n",
+            "instrument.1.pan=64\n",
+            "instrument.1.synth.waveform=triangle\n",
+            "mixer.delay.time_steps=3\n",
+        ] {
+            assert!(text.contains(line), "missing {line}");
+        }
+        for prefix in ["instrument.1.fm.", "instrument.1.drum.", "instrument.2.sample."] {
+            assert!(!text.contains(prefix), "unexpected {prefix}");
+        }
+    }
+
+    #[test]
+    fn round_trip_preserves_track_count_and_song_length() {
+        let mut project = ProjectData::new("layout");
+        project.set_track_count(12);
+        project.song.set_length(40);
+        project.song.tracks[11].song_rows[39] = Some(2);
+        project.mixer.track_levels[11] = 77;
+        project.mixer.track_pans[11] = 0x70;
+
+        let text = ProjectEnvelope::new(project).to_text();
+        assert!(text.contains("song.track_count=12\n"));
+        assert!(text.contains("song.length=40\n"));
+        let restored = ProjectEnvelope::from_text(&text).unwrap();
+
+        assert_eq!(restored.project.song.track_count(), 12);
+        assert_eq!(restored.project.song.length(), 40);
+        assert_eq!(restored.project.song.tracks[11].song_rows[39], Some(2));
+        assert_eq!(restored.project.mixer.track_levels[11], 77);
+        assert_eq!(restored.project.mixer.track_pans[11], 0x70);
+
+        let outside = "format_version=3\nsong.name=x\nsong.tempo=120\n\
+                       song.track_count=2\nsong.length=16\ntrack.0.row.16.chain=1\n";
+        assert!(matches!(
+            ProjectEnvelope::from_text(outside),
+            Err(StorageError::InvalidIndex("song_row", 16))
+        ));
+        let missing_track = "format_version=3\nsong.name=x\nsong.tempo=120\n\
+                             song.track_count=2\nmixer.track.2.level=10\n";
+        assert!(matches!(
+            ProjectEnvelope::from_text(missing_track),
+            Err(StorageError::InvalidIndex("mixer_track", 2))
+        ));
+        let invalid = "format_version=3\nsong.name=x\nsong.tempo=120\nsong.track_count=33\n";
+        assert!(matches!(
+            ProjectEnvelope::from_text(invalid),
+            Err(StorageError::InvalidIndex("track_count", 33))
+        ));
     }
 
     #[test]